@@ -1,10 +1,17 @@
 //! Wallet authentication utilities
-//! Handles wallet signature verification and nonce generation
+//! Handles wallet signature verification, nonce generation and session tokens
 
+use chrono::{DateTime, Duration, Utc};
 use ethers::{types::{Address, Signature}};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use thiserror::Error;
 
+/// How long an issued session token stays valid.
+pub const SESSION_TTL_HOURS: i64 = 24 * 7;
+
 #[derive(Error, Debug)]
 pub enum WalletVerificationError {
     #[error("Invalid signature format")]
@@ -61,3 +68,52 @@ pub fn is_valid_wallet_address(address: &str) -> bool {
     // Basic validation: starts with 0x and is 42 characters long
     address.starts_with("0x") && address.len() == 42 && address[2..].chars().all(|c| c.is_ascii_hexdigit())
 }
+
+/// Claims embedded in session JWTs.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionClaims {
+    /// User ID the token was issued for.
+    pub sub: Uuid,
+    /// Wallet address of the user.
+    pub wallet: String,
+    /// Issued-at timestamp (seconds since epoch).
+    pub iat: i64,
+    /// Expiry timestamp (seconds since epoch).
+    pub exp: i64,
+}
+
+/// Issues a signed session JWT for a user.
+/// Returns the token together with its expiry time.
+pub fn issue_session_token(
+    user_id: Uuid,
+    wallet_address: &str,
+    secret: &str,
+) -> Result<(String, DateTime<Utc>), jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let expires_at = now + Duration::hours(SESSION_TTL_HOURS);
+    let claims = SessionClaims {
+        sub: user_id,
+        wallet: wallet_address.to_string(),
+        iat: now.timestamp(),
+        exp: expires_at.timestamp(),
+    };
+
+    let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes()))?;
+    Ok((token, expires_at))
+}
+
+/// Verifies a session JWT signature and expiry and returns its claims.
+pub fn verify_session_token(token: &str, secret: &str) -> Result<SessionClaims, jsonwebtoken::errors::Error> {
+    let data = decode::<SessionClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )?;
+    Ok(data.claims)
+}
+
+/// Hashes a session token for storage in the sessions table.
+/// Tokens are never stored in plain text.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
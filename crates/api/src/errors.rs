@@ -9,6 +9,13 @@ use axum::{
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::services::clans::ClanError;
+use crate::services::duels::DuelError;
+use crate::services::quests::QuestError;
+use crate::services::seasons::SeasonError;
+use crate::services::tournaments::TournamentError;
+use crate::services::trading::TradingError;
+
 /// Main error type for all API operations.
 /// Represents different categories of errors that can occur in the application.
 #[derive(Error, Debug)]
@@ -19,6 +26,9 @@ pub enum ApiError {
     #[error("Authentication failed: {message}")]
     Authentication { message: String },
     
+    #[error("Access denied: {message}")]
+    Forbidden { message: String },
+    
     #[error("Resource not found: {resource}")]
     NotFound { resource: String },
     
@@ -27,6 +37,9 @@ pub enum ApiError {
     
     #[error("Bad request: {message}")]
     BadRequest { message: String },
+    
+    #[error("Conflict: {message}")]
+    Conflict { message: String },
 }
 
 /// Standardized error response structure.
@@ -48,9 +61,11 @@ impl IntoResponse for ApiError {
         let (status, error_code, message) = match self {
             ApiError::Validation { message } => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR", message),
             ApiError::Authentication { message } => (StatusCode::UNAUTHORIZED, "AUTH_ERROR", message),
+            ApiError::Forbidden { message } => (StatusCode::FORBIDDEN, "FORBIDDEN", message),
             ApiError::NotFound { resource } => (StatusCode::NOT_FOUND, "NOT_FOUND", format!("{} not found", resource)),
             ApiError::Internal { message } => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR", message),
             ApiError::BadRequest { message } => (StatusCode::BAD_REQUEST, "BAD_REQUEST", message),
+            ApiError::Conflict { message } => (StatusCode::CONFLICT, "CONFLICT", message),
         };
 
         let error_response = ErrorResponse {
@@ -63,6 +78,162 @@ impl IntoResponse for ApiError {
     }
 }
 
+impl From<TradingError> for ApiError {
+    /// Maps order execution failures to client or server errors.
+    fn from(error: TradingError) -> Self {
+        match error {
+            TradingError::UnknownSymbol(symbol) => ApiError::NotFound {
+                resource: format!("Market {}", symbol),
+            },
+            TradingError::NoPrice(symbol) => ApiError::NotFound {
+                resource: format!("Price for {}", symbol),
+            },
+            TradingError::UserNotFound => ApiError::NotFound {
+                resource: "User".to_string(),
+            },
+            TradingError::Database(_) => ApiError::Internal {
+                message: "Failed to execute order".to_string(),
+            },
+            other => ApiError::BadRequest {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+impl From<DuelError> for ApiError {
+    /// Maps duel failures to client or server errors.
+    fn from(error: DuelError) -> Self {
+        match error {
+            DuelError::NotFound => ApiError::NotFound {
+                resource: "Duel".to_string(),
+            },
+            DuelError::OpponentNotFound => ApiError::NotFound {
+                resource: "Opponent".to_string(),
+            },
+            DuelError::SelfChallenge
+            | DuelError::InvalidDuration
+            | DuelError::InvalidStake
+            | DuelError::UnknownSymbol(_)
+            | DuelError::InactiveSymbol(_)
+            | DuelError::NoSymbols => ApiError::Validation {
+                message: error.to_string(),
+            },
+            DuelError::AlreadyOpen | DuelError::NotPending => ApiError::Conflict {
+                message: error.to_string(),
+            },
+            DuelError::NotParticipant | DuelError::NotOpponent => ApiError::Forbidden {
+                message: error.to_string(),
+            },
+            DuelError::Trading(error) => ApiError::from(error),
+            DuelError::Database(_) => ApiError::Internal {
+                message: "Failed to process duel request".to_string(),
+            },
+            other => ApiError::BadRequest {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+impl From<QuestError> for ApiError {
+    /// Maps quest claim failures to client or server errors.
+    fn from(error: QuestError) -> Self {
+        match error {
+            QuestError::NotActive(quest_id) => ApiError::NotFound {
+                resource: format!("Active quest {}", quest_id),
+            },
+            QuestError::AlreadyClaimed => ApiError::Conflict {
+                message: error.to_string(),
+            },
+            QuestError::Database(_) => ApiError::Internal {
+                message: "Failed to claim quest".to_string(),
+            },
+            other => ApiError::BadRequest {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+impl From<SeasonError> for ApiError {
+    /// Maps season rollover failures to client or server errors.
+    fn from(error: SeasonError) -> Self {
+        match error {
+            SeasonError::NoCurrentSeason | SeasonError::AlreadyRolledOver => ApiError::Conflict {
+                message: error.to_string(),
+            },
+            SeasonError::InvalidLength => ApiError::Validation {
+                message: error.to_string(),
+            },
+            SeasonError::Database(_) => ApiError::Internal {
+                message: "Failed to roll over season".to_string(),
+            },
+        }
+    }
+}
+
+impl From<ClanError> for ApiError {
+    /// Maps clan failures to client or server errors.
+    fn from(error: ClanError) -> Self {
+        match error {
+            ClanError::InvalidInviteCode => ApiError::NotFound {
+                resource: "Invite code".to_string(),
+            },
+            ClanError::MemberNotFound => ApiError::NotFound {
+                resource: "Clan member".to_string(),
+            },
+            ClanError::TournamentNotFound => ApiError::NotFound {
+                resource: "Tournament".to_string(),
+            },
+            ClanError::InvalidTag => ApiError::Validation {
+                message: error.to_string(),
+            },
+            ClanError::AlreadyInClan | ClanError::NameTaken | ClanError::Full | ClanError::AlreadyEntered => {
+                ApiError::Conflict {
+                    message: error.to_string(),
+                }
+            }
+            ClanError::NotMember | ClanError::NotAllowed => ApiError::Forbidden {
+                message: error.to_string(),
+            },
+            ClanError::Database(_) | ClanError::InviteCodeUnavailable => ApiError::Internal {
+                message: "Failed to process clan request".to_string(),
+            },
+            other => ApiError::BadRequest {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+impl From<TournamentError> for ApiError {
+    /// Maps tournament failures to client or server errors.
+    fn from(error: TournamentError) -> Self {
+        match error {
+            TournamentError::NotFound => ApiError::NotFound {
+                resource: "Tournament".to_string(),
+            },
+            TournamentError::InvalidSchedule(_) | TournamentError::UnknownSymbol(_) => ApiError::Validation {
+                message: error.to_string(),
+            },
+            TournamentError::AlreadyJoined | TournamentError::Full => ApiError::Conflict {
+                message: error.to_string(),
+            },
+            TournamentError::NotJoined => ApiError::Forbidden {
+                message: error.to_string(),
+            },
+            TournamentError::Trading(error) => ApiError::from(error),
+            TournamentError::Database(_) => ApiError::Internal {
+                message: "Failed to process tournament request".to_string(),
+            },
+            other => ApiError::BadRequest {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
 /// Convenience type alias for API results.
 /// Simplifies function signatures throughout the application.
 pub type ApiResult<T> = Result<T, ApiError>;
\ No newline at end of file
@@ -0,0 +1,79 @@
+//! Request extractors shared by API handlers.
+//! Resolves the authenticated user from the session token and checks admin access.
+
+use axum::{
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use uuid::Uuid;
+
+use crate::auth_utils::{hash_token, verify_session_token};
+use crate::errors::ApiError;
+use crate::state::SharedState;
+use db::queries::sessions;
+
+/// Authenticated user making the request.
+/// Extracted from a `Authorization: Bearer <token>` header issued by wallet login.
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    /// ID of the authenticated user.
+    pub user_id: Uuid,
+    /// Wallet address of the authenticated user.
+    pub wallet_address: String,
+}
+
+impl FromRequestParts<SharedState> for AuthUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &SharedState) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ApiError::Authentication {
+                message: "Missing bearer token".to_string(),
+            })?;
+
+        let claims = verify_session_token(token, &state.jwt_secret).map_err(|_| ApiError::Authentication {
+            message: "Invalid or expired token".to_string(),
+        })?;
+
+        let session = sessions::find_active_session(&state.db_pool, &hash_token(token))
+            .await
+            .map_err(|_| ApiError::Internal {
+                message: "Failed to load session".to_string(),
+            })?;
+
+        match session {
+            Some(session) if session.user_id == claims.sub => Ok(AuthUser {
+                user_id: claims.sub,
+                wallet_address: claims.wallet,
+            }),
+            _ => Err(ApiError::Authentication {
+                message: "Session expired or revoked".to_string(),
+            }),
+        }
+    }
+}
+
+/// Authenticated user with admin access.
+/// Admins are configured through the ADMIN_WALLETS environment variable.
+#[derive(Debug, Clone)]
+pub struct AdminUser(pub AuthUser);
+
+impl FromRequestParts<SharedState> for AdminUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &SharedState) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+
+        if !state.is_admin(&user.wallet_address) {
+            return Err(ApiError::Forbidden {
+                message: "Admin access required".to_string(),
+            });
+        }
+
+        Ok(AdminUser(user))
+    }
+}
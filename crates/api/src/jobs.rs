@@ -0,0 +1,437 @@
+//! Background jobs started alongside the API.
+//! Runs periodic work such as advancing the simulated price oracle (or replaying
+//! imported history), persisting its ticks, evaluating price alerts, rolling ticks up into
+//! candles, running bots and scheduled rebalances, extending activity streaks and awarding XP,
+//! achievements and quest progress for user events, snapshotting portfolios, refreshing
+//! leaderboards, finalizing tournaments, settling duels, running weekly leagues, rolling seasons
+//! over and pruning expired idempotency keys and processed user events.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+use db::queries::{events, idempotency, market, xp};
+
+use crate::services::achievements::record_event;
+use crate::services::alerts::evaluate_tick;
+use crate::services::bots::run_due_bots;
+use crate::services::duels::settle_due_duels;
+use crate::services::events::UserEvent;
+use crate::services::leaderboards::refresh_leaderboards;
+use crate::services::leagues::update_leagues;
+use crate::services::market::{replay_step, rollup_recent_candles};
+use crate::services::notifications::PendingDeliveries;
+use crate::services::oracle::{PriceTick, DEFAULT_VOLATILITY_BPS};
+use crate::services::portfolio::capture_snapshots;
+use crate::services::quests;
+use crate::services::rebalance::run_due_rebalances;
+use crate::services::tournaments::finalize_due_tournaments;
+use crate::services::seasons::roll_over_due_season;
+use crate::services::streaks;
+use crate::services::xp::award_event;
+use crate::state::{AppState, SharedState};
+
+/// Default interval between simulated oracle ticks, in seconds.
+const DEFAULT_ORACLE_TICK_SECONDS: u64 = 5;
+
+/// How often buffered ticks are written to the database.
+const TICK_FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often candles are rolled up from ticks.
+const CANDLE_ROLLUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default interval between portfolio snapshots, in seconds.
+const DEFAULT_PORTFOLIO_SNAPSHOT_SECONDS: u64 = 300;
+
+/// Default interval between leaderboard refreshes, in seconds.
+const DEFAULT_LEADERBOARD_REFRESH_SECONDS: u64 = 300;
+
+/// How often trading bots are checked for due orders and crossed grid lines.
+const BOT_SCHEDULER_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often rebalance schedules are checked for due runs.
+const REBALANCE_SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often ended tournaments are checked for finalization.
+const TOURNAMENT_FINALIZER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often duels are checked for expired challenges and ended trading.
+const DUEL_SETTLER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often league scores are refreshed and ended league weeks finalized.
+const LEAGUE_SCHEDULER_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How often the current season is checked for its scheduled end.
+const SEASON_SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often expired idempotency keys are deleted.
+const IDEMPOTENCY_CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How long raw ticks are kept once they have been rolled up, in days.
+const TICK_RETENTION_DAYS: i64 = 7;
+
+/// How often stored user events are checked without a wake-up, e.g. for retries or after a failed claim.
+const PROGRESSION_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Delay before the first retry of a user event that failed to apply, in seconds; doubled on every further failure.
+const PROGRESSION_RETRY_SECONDS: i64 = 30;
+
+/// Longest delay between retries of a failing user event, in seconds.
+const PROGRESSION_MAX_RETRY_SECONDS: i64 = 3600;
+
+/// How long processed user events are kept, in days.
+const USER_EVENT_RETENTION_DAYS: i64 = 7;
+
+/// Spawns all background jobs on the Tokio runtime.
+pub fn spawn_background_jobs(state: SharedState) {
+    let tick_seconds = std::env::var("ORACLE_TICK_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_ORACLE_TICK_SECONDS);
+
+    let snapshot_seconds = std::env::var("PORTFOLIO_SNAPSHOT_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_PORTFOLIO_SNAPSHOT_SECONDS);
+
+    let leaderboard_seconds = std::env::var("LEADERBOARD_REFRESH_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_LEADERBOARD_REFRESH_SECONDS);
+
+    // Replay imported one-minute history from this time instead of simulating
+    let replay_from = std::env::var("ORACLE_REPLAY_FROM")
+        .ok()
+        .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+        .map(|value| value.with_timezone(&Utc));
+
+    // Subscribe before the ticker starts so no tick is missed
+    let ticks = state.oracle.subscribe();
+    tokio::spawn(run_tick_recorder(state.clone(), ticks));
+    let ticks = state.oracle.subscribe();
+    tokio::spawn(run_alert_evaluator(state.clone(), ticks));
+    tokio::spawn(run_progression(state.clone()));
+    tokio::spawn(run_candle_rollup(state.clone()));
+    tokio::spawn(run_bot_scheduler(state.clone()));
+    tokio::spawn(run_rebalance_scheduler(state.clone()));
+    tokio::spawn(run_leaderboard_refresh(state.clone(), Duration::from_secs(leaderboard_seconds)));
+    tokio::spawn(run_tournament_finalizer(state.clone()));
+    tokio::spawn(run_duel_settler(state.clone()));
+    tokio::spawn(run_league_scheduler(state.clone()));
+    tokio::spawn(run_season_scheduler(state.clone()));
+    tokio::spawn(run_idempotency_cleanup(state.clone()));
+    tokio::spawn(run_portfolio_snapshots(state.clone(), Duration::from_secs(snapshot_seconds)));
+    tokio::spawn(run_oracle_ticker(state, Duration::from_secs(tick_seconds), replay_from));
+}
+
+/// Restores the last persisted price of every symbol into the oracle.
+/// Keeps prices continuous across restarts.
+pub async fn restore_oracle_prices(state: &AppState) {
+    match market::latest_tick_prices(&state.db_pool).await {
+        Ok(prices) => {
+            for (symbol, price_cents) in &prices {
+                state.oracle.publish(symbol, *price_cents);
+            }
+            info!("📈 Restored {} oracle prices", prices.len());
+        }
+        Err(e) => warn!("⚠️ Failed to restore oracle prices: {}", e),
+    }
+}
+
+/// Brings stored user levels in line with the active level curve.
+/// Levels are stored with each XP grant, so a changed LEVEL_CURVE would otherwise only apply to new grants.
+pub async fn sync_user_levels(state: &AppState) {
+    match xp::sync_user_levels(&state.db_pool).await {
+        Ok(0) => {}
+        Ok(updated) => info!("🎚️ Recomputed levels of {} users for the active level curve", updated),
+        Err(e) => warn!("⚠️ Failed to recompute user levels: {}", e),
+    }
+}
+
+/// Advances the oracle on a fixed interval.
+/// Replays one candle of history per tick when a replay start is given and
+/// falls back to the random-walk simulation once the history runs out.
+async fn run_oracle_ticker(state: SharedState, period: Duration, mut replay_cursor: Option<DateTime<Utc>>) {
+    match replay_cursor {
+        Some(from) => info!("📈 Oracle replaying history from {} every {:?}", from, period),
+        None => info!("📈 Simulated oracle ticking every {:?}", period),
+    }
+
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+
+        if let Some(cursor) = replay_cursor {
+            match replay_step(&state.db_pool, &state.oracle, cursor).await {
+                Ok(Some(next)) => {
+                    replay_cursor = Some(next);
+                    continue;
+                }
+                Ok(None) => {
+                    info!("📈 Replay history exhausted, switching to simulation");
+                    replay_cursor = None;
+                }
+                Err(e) => {
+                    warn!("⚠️ Replay step failed: {}", e);
+                    continue;
+                }
+            }
+        }
+
+        state.oracle.simulate_step(DEFAULT_VOLATILITY_BPS);
+    }
+}
+
+/// Buffers oracle ticks and writes them to the database in batches.
+async fn run_tick_recorder(state: SharedState, mut ticks: broadcast::Receiver<PriceTick>) {
+    let mut flush = tokio::time::interval(TICK_FLUSH_INTERVAL);
+    let mut symbols: Vec<String> = Vec::new();
+    let mut prices: Vec<i64> = Vec::new();
+    let mut observed_at: Vec<DateTime<Utc>> = Vec::new();
+
+    loop {
+        tokio::select! {
+            received = ticks.recv() => match received {
+                Ok(tick) => {
+                    symbols.push(tick.symbol);
+                    prices.push(tick.price_cents);
+                    observed_at.push(tick.timestamp);
+                }
+                Err(RecvError::Lagged(skipped)) => warn!("⚠️ Tick recorder skipped {} ticks", skipped),
+                Err(RecvError::Closed) => break,
+            },
+            _ = flush.tick() => {
+                if symbols.is_empty() {
+                    continue;
+                }
+                if let Err(e) = market::insert_ticks(&state.db_pool, &symbols, &prices, &observed_at).await {
+                    warn!("⚠️ Failed to persist {} ticks: {}", symbols.len(), e);
+                }
+                symbols.clear();
+                prices.clear();
+                observed_at.clear();
+            }
+        }
+    }
+}
+
+/// Evaluates price alerts against every oracle tick.
+async fn run_alert_evaluator(state: SharedState, mut ticks: broadcast::Receiver<PriceTick>) {
+    loop {
+        match ticks.recv().await {
+            Ok(tick) => {
+                if let Err(e) = evaluate_tick(&state, &tick).await {
+                    warn!("⚠️ Alert evaluation for {} failed: {}", tick.symbol, e);
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => warn!("⚠️ Alert evaluator skipped {} ticks", skipped),
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Extends streaks, grants XP and achievements and advances quests for stored user events, in order.
+/// Drains the outbox whenever new events are committed, and periodically for retries and missed wake-ups.
+async fn run_progression(state: SharedState) {
+    loop {
+        match process_next_event(&state).await {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(e) => warn!("⚠️ Failed to process user events: {}", e),
+        }
+
+        let _ = tokio::time::timeout(PROGRESSION_POLL_INTERVAL, state.events.notified()).await;
+    }
+}
+
+/// Claims the next stored user event and applies it in one transaction, marking it processed on success.
+/// A failed event is rolled back and retried with backoff, so a failure or restart never loses progress.
+/// Returns `false` when no event is waiting.
+async fn process_next_event(state: &SharedState) -> Result<bool, sqlx::Error> {
+    let mut tx = state.db_pool.begin().await?;
+    let Some(record) = events::claim_next_user_event(&mut tx).await? else {
+        return Ok(false);
+    };
+    let (id, attempts) = (record.id, record.attempts);
+    let Some(event) = UserEvent::from_record(record) else {
+        warn!("⚠️ Skipping unreadable user event {}", id);
+        events::mark_user_event_processed(&mut tx, id).await?;
+        tx.commit().await?;
+        return Ok(true);
+    };
+
+    let mut deliveries = PendingDeliveries::new();
+    match apply_event(&mut tx, &event, &mut deliveries).await {
+        Ok(()) => {
+            events::mark_user_event_processed(&mut tx, id).await?;
+            tx.commit().await?;
+            deliveries.deliver(state);
+        }
+        Err(e) => {
+            tx.rollback().await?;
+            let delay_seconds = (PROGRESSION_RETRY_SECONDS << attempts.clamp(0, 16)).min(PROGRESSION_MAX_RETRY_SECONDS);
+            let retry_at = Utc::now() + chrono::Duration::seconds(delay_seconds);
+            events::record_user_event_failure(&state.db_pool, id, retry_at).await?;
+            warn!("⚠️ User event {} for user {} failed, retrying at {}: {}", id, event.user_id, retry_at, e);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Applies one user event to the user's progression in the caller's transaction.
+/// Events are handled one at a time so daily caps see every earlier grant. The streak is extended
+/// first so the day's activity counts towards the XP multiplier, and XP is granted before
+/// achievements so XP and level criteria see it.
+async fn apply_event(
+    conn: &mut PgConnection,
+    event: &UserEvent,
+    deliveries: &mut PendingDeliveries,
+) -> Result<(), sqlx::Error> {
+    let multiplier_percent = streaks::record_event(conn, event, deliveries).await?;
+    award_event(conn, event, multiplier_percent, deliveries).await?;
+    record_event(conn, event, deliveries).await?;
+    quests::record_event(conn, event).await
+}
+
+/// Rolls recent ticks up into candles and prunes old ticks.
+async fn run_candle_rollup(state: SharedState) {
+    let mut interval = tokio::time::interval(CANDLE_ROLLUP_INTERVAL);
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+
+        if let Err(e) = rollup_recent_candles(&state.db_pool, now).await {
+            warn!("⚠️ Candle rollup failed: {}", e);
+            continue;
+        }
+
+        let cutoff = now - chrono::Duration::days(TICK_RETENTION_DAYS);
+        if let Err(e) = market::delete_ticks_before(&state.db_pool, cutoff).await {
+            warn!("⚠️ Failed to prune old ticks: {}", e);
+        }
+    }
+}
+
+/// Places orders for due DCA bots and grid bots whose price crossed a grid line.
+async fn run_bot_scheduler(state: SharedState) {
+    let mut interval = tokio::time::interval(BOT_SCHEDULER_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = run_due_bots(&state, Utc::now()).await {
+            warn!("⚠️ Bot scheduler run failed: {}", e);
+        }
+    }
+}
+
+/// Deletes idempotency keys past their retention period, and processed user events.
+async fn run_idempotency_cleanup(state: SharedState) {
+    let mut interval = tokio::time::interval(IDEMPOTENCY_CLEANUP_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        match idempotency::delete_expired_idempotency_keys(&state.db_pool, Utc::now()).await {
+            Ok(0) => {}
+            Ok(deleted) => info!("🧹 Deleted {} expired idempotency keys", deleted),
+            Err(e) => warn!("⚠️ Failed to prune idempotency keys: {}", e),
+        }
+
+        let cutoff = Utc::now() - chrono::Duration::days(USER_EVENT_RETENTION_DAYS);
+        match events::delete_processed_user_events(&state.db_pool, cutoff).await {
+            Ok(0) => {}
+            Ok(deleted) => info!("🧹 Deleted {} processed user events", deleted),
+            Err(e) => warn!("⚠️ Failed to prune user events: {}", e),
+        }
+    }
+}
+
+/// Records every user's portfolio value for equity curves.
+async fn run_portfolio_snapshots(state: SharedState, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = capture_snapshots(&state, Utc::now()).await {
+            warn!("⚠️ Portfolio snapshot failed: {}", e);
+        }
+    }
+}
+
+/// Rebalances portfolios whose scheduled rebalance is due.
+async fn run_rebalance_scheduler(state: SharedState) {
+    let mut interval = tokio::time::interval(REBALANCE_SCHEDULER_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = run_due_rebalances(&state, Utc::now()).await {
+            warn!("⚠️ Rebalance scheduler run failed: {}", e);
+        }
+    }
+}
+
+/// Recomputes the materialized leaderboards.
+async fn run_leaderboard_refresh(state: SharedState, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = refresh_leaderboards(&state, Utc::now()).await {
+            warn!("⚠️ Leaderboard refresh failed: {}", e);
+        }
+    }
+}
+
+/// Records final rankings and grants prizes for tournaments that have ended.
+async fn run_tournament_finalizer(state: SharedState) {
+    let mut interval = tokio::time::interval(TOURNAMENT_FINALIZER_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = finalize_due_tournaments(&state, Utc::now()).await {
+            warn!("⚠️ Tournament finalization failed: {}", e);
+        }
+    }
+}
+
+/// Expires unanswered challenges and pays out stakes for duels that have ended.
+async fn run_duel_settler(state: SharedState) {
+    let mut interval = tokio::time::interval(DUEL_SETTLER_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = settle_due_duels(&state, Utc::now()).await {
+            warn!("⚠️ Duel settlement failed: {}", e);
+        }
+    }
+}
+
+/// Applies promotions and relegations for ended league weeks and keeps this week's tables current.
+async fn run_league_scheduler(state: SharedState) {
+    let mut interval = tokio::time::interval(LEAGUE_SCHEDULER_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = update_leagues(&state, Utc::now()).await {
+            warn!("⚠️ League update failed: {}", e);
+        }
+    }
+}
+
+/// Rolls the current season over once it reaches its scheduled end.
+async fn run_season_scheduler(state: SharedState) {
+    let mut interval = tokio::time::interval(SEASON_SCHEDULER_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = roll_over_due_season(&state, state.season_days, Utc::now()).await {
+            warn!("⚠️ Season rollover failed: {}", e);
+        }
+    }
+}
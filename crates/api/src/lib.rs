@@ -5,7 +5,6 @@ use std::sync::Arc;
 
 use axum::{extract::State, middleware as axum_middleware, Router};
 use tower_http::trace::TraceLayer;
-use sqlx;
 
 pub mod routes;
 pub mod types;
@@ -13,29 +12,77 @@ pub mod auth_utils;
 pub mod errors;
 pub mod middleware;
 pub mod state; 
+pub mod extractors;
+pub mod services;
+pub mod jobs;
 
-use state::{AppState, SharedState};
+use state::{AppState, ConfigError, SharedState};
 
 /// Creates the main API router with all endpoint groups and middleware.
 /// Configures CORS, logging, error handling and shared DB connection pool for all routes.
-pub async fn create_router(db_pool: sqlx::PgPool) -> Router {
+/// Fails when the environment holds invalid configuration.
+pub async fn create_router(db_pool: sqlx::PgPool) -> Result<Router, ConfigError> {
     // Create shared application state
-    let app_state = Arc::new(AppState::new(db_pool));
+    let app_state = Arc::new(AppState::new(db_pool)?);
 
-    Router::new()
+    // Continue from persisted prices and levels, then start oracle and other periodic work
+    jobs::restore_oracle_prices(&app_state).await;
+    jobs::sync_user_levels(&app_state).await;
+    jobs::spawn_background_jobs(app_state.clone());
+
+    let router = Router::new()
         .route("/", axum::routing::get(|| async { "Vectra DEX API v0.1" }))
         .route("/health", axum::routing::get(|| async { "API Health: OK" }))
         .route("/health/db", axum::routing::get(health_check_db))
         // Group authentication endpoints under /auth
         .nest("/auth", routes::auth::create_routes())
+        // Authenticated user's profile and activity streak
+        .nest("/users", routes::users::create_routes())
         // Group trading endpoints under /trading  
         .nest("/trading", routes::trading::create_routes())
+        // Asset catalog, with admin management under /admin/assets
+        .nest("/assets", routes::assets::create_routes())
+        .nest("/admin/assets", routes::assets::create_admin_routes())
+        // Group market data endpoints under /market
+        .nest("/market", routes::market::create_routes())
+        // Strategy backtests on stored candles
+        .nest("/backtests", routes::backtests::create_routes())
+        // DCA and grid trading bots
+        .nest("/bots", routes::bots::create_routes())
+        // Price alerts and the inbox they are delivered to
+        .nest("/alerts", routes::alerts::create_routes())
+        .nest("/notifications", routes::notifications::create_routes())
+        // Watchlists with live quotes
+        .nest("/watchlists", routes::watchlists::create_routes())
+        // XP totals, the ledger of XP events, achievements and quests
+        .nest("/xp", routes::xp::create_routes())
+        .nest("/achievements", routes::achievements::create_routes())
+        .nest("/quests", routes::quests::create_routes())
+        // Materialized leaderboards by XP, portfolio performance and skill
+        .nest("/leaderboards", routes::leaderboards::create_routes())
+        // Glicko-2 skill ratings and matchmaking
+        .nest("/ratings", routes::ratings::create_routes())
+        // Trading tournaments, created under /admin/tournaments
+        .nest("/tournaments", routes::tournaments::create_routes())
+        .nest("/admin/tournaments", routes::tournaments::create_admin_routes())
+        // Head-to-head duels between two users
+        .nest("/duels", routes::duels::create_routes())
+        // Weekly division leagues with promotion and relegation
+        .nest("/leagues", routes::leagues::create_routes())
+        // Clans with shared leaderboards and clan tournament entries
+        .nest("/clans", routes::clans::create_routes())
+        // Seasons and their archived standings, with rollover under /admin/seasons
+        .nest("/seasons", routes::seasons::create_routes())
+        .nest("/admin/seasons", routes::seasons::create_admin_routes())
         // Add middleware layers
+        .layer(axum_middleware::from_fn_with_state(app_state.clone(), middleware::idempotency))
         .layer(axum_middleware::from_fn(middleware::request_logger))
         .layer(middleware::create_cors_layer())
         .layer(TraceLayer::new_for_http())
         // Add shared application state
-        .with_state(app_state)
+        .with_state(app_state);
+
+    Ok(router)
 }
 
 /// Database health check endpoint.
@@ -1,12 +1,46 @@
 //! Middleware for request processing and validation.
-//! Handles CORS, request logging, and validation across all API endpoints.
+//! Handles CORS, request logging, idempotency keys and validation across all API endpoints.
 
-use axum::{extract::Request, http::Method, middleware::Next, response::Response};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, Request, State},
+    http::{header::CONTENT_TYPE, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use db::queries::idempotency;
+use sha2::{Digest, Sha256};
 use tower_http::cors::{Any, CorsLayer};
-use tracing::info;
+use tracing::{info, warn};
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::errors::ApiError;
+use crate::extractors::AuthUser;
+use crate::state::SharedState;
+
+/// Header carrying the client-supplied idempotency key.
+pub const IDEMPOTENCY_KEY_HEADER: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// Header set on responses replayed from a stored idempotency key.
+pub const IDEMPOTENT_REPLAYED_HEADER: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// How long a key is remembered, in hours.
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+/// How long a claimed key stays in flight before a retry may take it over, in seconds.
+/// Renewed while the request runs, so only requests whose server died lose their key.
+pub const IDEMPOTENCY_LEASE_SECONDS: i64 = 120;
+
+/// How often the lease of a running request is renewed, in seconds.
+const IDEMPOTENCY_RENEW_SECONDS: u64 = 30;
+
+/// Longest accepted idempotency key.
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// Largest request or response body buffered for idempotency, in bytes.
+const MAX_IDEMPOTENT_BODY_BYTES: usize = 1024 * 1024;
 
 /// Creates CORS layer for cross-origin requests.
 /// Allows frontend applications to communicate with the API from different domains.
@@ -15,6 +49,7 @@ pub fn create_cors_layer() -> CorsLayer {
         .allow_origin(Any) // TODO: Restrict to specific origins in production
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
         .allow_headers(Any)
+        .expose_headers([IDEMPOTENT_REPLAYED_HEADER])
 }
 
 /// Logs incoming requests for debugging and monitoring.
@@ -32,6 +67,209 @@ pub async fn request_logger(request: Request, next: Next) -> Response {
     response
 }
 
+/// Makes mutating requests with an `Idempotency-Key` header safe to retry.
+/// The first request with a key runs normally and its response is stored; retries with the
+/// same key and request replay that response, while reuse for a different request or while
+/// the original is still running is rejected with a conflict. The handler runs in its own task,
+/// renewing the key's lease until its response is stored, so neither a client disconnecting nor a
+/// slow handler lets a retry run the request a second time. Keys are scoped per user, so requests
+/// without a valid session are processed without idempotency.
+pub async fn idempotency(State(state): State<SharedState>, request: Request, next: Next) -> Response {
+    if ![Method::POST, Method::PUT, Method::PATCH, Method::DELETE].contains(request.method()) {
+        return next.run(request).await;
+    }
+    let Some(key) = request.headers().get(&IDEMPOTENCY_KEY_HEADER).cloned() else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH => key.to_string(),
+        _ => {
+            return ApiError::BadRequest {
+                message: format!("Idempotency-Key must be 1-{} visible ASCII characters", MAX_IDEMPOTENCY_KEY_LENGTH),
+            }
+            .into_response()
+        }
+    };
+
+    let (mut parts, body) = request.into_parts();
+    let Ok(user) = AuthUser::from_request_parts(&mut parts, &state).await else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+    let Ok(body) = to_bytes(body, MAX_IDEMPOTENT_BODY_BYTES).await else {
+        return ApiError::BadRequest {
+            message: "Request body too large".to_string(),
+        }
+        .into_response();
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(parts.method.as_str());
+    hasher.update(b" ");
+    hasher.update(parts.uri.to_string());
+    hasher.update(b"\n");
+    hasher.update(&body);
+    let request_hash = format!("{:x}", hasher.finalize());
+
+    let now = Utc::now();
+    let locked_until = now + Duration::seconds(IDEMPOTENCY_LEASE_SECONDS);
+    let expires_at = now + Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+    let claim_token = match idempotency::claim_idempotency_key(
+        &state.db_pool,
+        user.user_id,
+        &key,
+        &request_hash,
+        locked_until,
+        expires_at,
+    )
+    .await
+    {
+        Ok(Some(claim_token)) => claim_token,
+        Ok(None) => return replay_stored_response(&state, user.user_id, &key, &request_hash).await,
+        Err(_) => {
+            return ApiError::Internal {
+                message: "Failed to check idempotency key".to_string(),
+            }
+            .into_response()
+        }
+    };
+
+    let request = Request::from_parts(parts, Body::from(body));
+    let task_state = state.clone();
+    let handler = tokio::spawn(async move {
+        let response = run_holding_lease(&task_state, user.user_id, &key, claim_token, next.run(request)).await;
+        store_response(&task_state, user.user_id, &key, claim_token, response).await
+    });
+
+    match handler.await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("⚠️ Idempotent request handler failed: {}", e);
+            ApiError::Internal {
+                message: "Failed to process request".to_string(),
+            }
+            .into_response()
+        }
+    }
+}
+
+/// Runs a handler in its own task, renewing the key's lease until it finishes.
+/// A handler that panics yields a server error, since its side effects may already be committed.
+async fn run_holding_lease(
+    state: &SharedState,
+    user_id: Uuid,
+    key: &str,
+    claim_token: Uuid,
+    handler: impl Future<Output = Response> + Send + 'static,
+) -> Response {
+    let mut handler = tokio::spawn(handler);
+    let mut renewals = tokio::time::interval(std::time::Duration::from_secs(IDEMPOTENCY_RENEW_SECONDS));
+    renewals.tick().await;
+
+    let result = loop {
+        tokio::select! {
+            result = &mut handler => break result,
+            _ = renewals.tick() => {
+                let locked_until = Utc::now() + Duration::seconds(IDEMPOTENCY_LEASE_SECONDS);
+                match idempotency::renew_idempotency_key(&state.db_pool, user_id, key, claim_token, locked_until).await {
+                    Ok(true) => {}
+                    Ok(false) => warn!("⚠️ Lost the lease on an idempotency key while its request was running"),
+                    Err(e) => warn!("⚠️ Failed to renew idempotency key: {}", e),
+                }
+            }
+        }
+    };
+
+    result.unwrap_or_else(|e| {
+        warn!("⚠️ Idempotent request handler failed: {}", e);
+        ApiError::Internal {
+            message: "Failed to process request".to_string(),
+        }
+        .into_response()
+    })
+}
+
+/// Returns the stored response for a key already in use, or a conflict.
+async fn replay_stored_response(state: &SharedState, user_id: Uuid, key: &str, request_hash: &str) -> Response {
+    let record = match idempotency::find_idempotency_key(&state.db_pool, user_id, key).await {
+        Ok(Some(record)) => record,
+        // Released between claim and lookup; the client can simply retry
+        Ok(None) => {
+            return ApiError::Conflict {
+                message: "A request with this Idempotency-Key is still being processed".to_string(),
+            }
+            .into_response()
+        }
+        Err(_) => {
+            return ApiError::Internal {
+                message: "Failed to load idempotency key".to_string(),
+            }
+            .into_response()
+        }
+    };
+
+    if record.request_hash != request_hash {
+        return ApiError::Conflict {
+            message: "Idempotency-Key was already used for a different request".to_string(),
+        }
+        .into_response();
+    }
+
+    let (Some(status), Some(body)) = (record.response_status, record.response_body) else {
+        return ApiError::Conflict {
+            message: "A request with this Idempotency-Key is still being processed".to_string(),
+        }
+        .into_response();
+    };
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = StatusCode::from_u16(status as u16).unwrap_or(StatusCode::OK);
+    if let Some(content_type) = record.content_type.and_then(|value| HeaderValue::from_str(&value).ok()) {
+        response.headers_mut().insert(CONTENT_TYPE, content_type);
+    }
+    response
+        .headers_mut()
+        .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// Stores a handler's response under its key and passes it on.
+/// Every response is stored, server errors included, since the handler may have committed its
+/// side effects; a response too large to store is replaced by a server error, which is stored instead.
+async fn store_response(state: &SharedState, user_id: Uuid, key: &str, claim_token: Uuid, response: Response) -> Response {
+    let (parts, body) = response.into_parts();
+    let (parts, body) = match to_bytes(body, MAX_IDEMPOTENT_BODY_BYTES).await {
+        Ok(body) => (parts, body),
+        Err(_) => {
+            warn!("⚠️ Response too large to store for idempotency key");
+            let (parts, body) = ApiError::Internal {
+                message: "Failed to buffer response".to_string(),
+            }
+            .into_response()
+            .into_parts();
+            (parts, to_bytes(body, MAX_IDEMPOTENT_BODY_BYTES).await.unwrap_or_default())
+        }
+    };
+
+    let content_type = parts.headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok());
+    match idempotency::complete_idempotency_key(
+        &state.db_pool,
+        user_id,
+        key,
+        claim_token,
+        parts.status.as_u16() as i16,
+        &body,
+        content_type,
+    )
+    .await
+    {
+        Ok(true) => {}
+        Ok(false) => warn!("⚠️ Idempotency key was taken over before its response was stored"),
+        Err(e) => warn!("⚠️ Failed to store idempotent response: {}", e),
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
 /// Validates request data using the validator crate.
 /// Ensures all incoming data meets the defined validation rules.
 pub fn validate_request<T: Validate>(data: &T) -> Result<(), ApiError> {
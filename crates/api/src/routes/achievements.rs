@@ -0,0 +1,73 @@
+//! Achievement routes.
+//! Lists the achievement catalog and a user's progress towards each achievement.
+
+use axum::extract::State;
+use axum::{Json, Router, routing::get};
+use db::queries::achievements;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::services::achievements::player_progress;
+use crate::state::SharedState;
+use crate::types::{AchievementListResponse, AchievementProgressResponse, AchievementResponse, ApiResponse};
+
+/// Creates achievement route group.
+/// The catalog is public; progress requires an authenticated user.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_catalog))
+        .route("/me", get(list_progress))
+}
+
+/// Returns every achievement in the catalog.
+async fn list_catalog() -> Json<ApiResponse<Vec<AchievementResponse>>> {
+    Json(ApiResponse {
+        success: true,
+        data: Some(game::ACHIEVEMENTS.iter().map(AchievementResponse::from).collect()),
+        message: None,
+    })
+}
+
+/// Returns the user's progress towards every achievement and when unlocked ones were earned.
+async fn list_progress(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<AchievementListResponse>>> {
+    let mut conn = state.db_pool.acquire().await.map_err(|_| ApiError::Internal {
+        message: "Failed to load player statistics".to_string(),
+    })?;
+    let progress = player_progress(&mut conn, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load player statistics".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "User".to_string(),
+        })?;
+    let unlocked = achievements::list_user_achievements(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load achievements".to_string(),
+        })?;
+
+    let items: Vec<AchievementProgressResponse> = game::ACHIEVEMENTS
+        .iter()
+        .map(|achievement| {
+            let unlocked_at = unlocked
+                .iter()
+                .find(|unlock| unlock.achievement_id == achievement.id)
+                .map(|unlock| unlock.unlocked_at);
+            AchievementProgressResponse::new(achievement, &progress, unlocked_at)
+        })
+        .collect();
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(AchievementListResponse {
+            unlocked_count: items.iter().filter(|item| item.unlocked_at.is_some()).count(),
+            total_count: items.len(),
+            achievements: items,
+        }),
+        message: None,
+    }))
+}
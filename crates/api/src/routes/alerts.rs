@@ -0,0 +1,307 @@
+//! Price alert routes.
+//! Creates, lists, replaces and deletes a user's price alerts and the webhooks they are delivered to.
+
+use axum::extract::{Path, State};
+use axum::{Json, Router, routing::get};
+use db::queries::alerts::NewAlert;
+use db::queries::{alerts, assets, notifications};
+use uuid::Uuid;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::alerts::{initially_armed, MAX_ALERTS_PER_USER};
+use crate::services::notifications::{generate_webhook_secret, resolve_webhook_url, MAX_WEBHOOKS_PER_USER};
+use crate::state::SharedState;
+use crate::types::{
+    usd_to_cents, AlertCondition, AlertRequest, AlertResponse, ApiResponse, CreateWebhookRequest, WebhookResponse,
+};
+
+/// Largest percent move an alert can wait for.
+const MAX_ALERT_PERCENT: f64 = 1000.0;
+
+/// Creates price alert route group.
+/// All endpoints require an authenticated user and only touch the user's own alerts and webhooks.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_alerts).post(create_alert))
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/webhooks/{id}", axum::routing::delete(delete_webhook))
+        .route("/{id}", get(get_alert).put(replace_alert).delete(delete_alert))
+}
+
+/// Lists the user's alerts, newest first.
+async fn list_alerts(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<Vec<AlertResponse>>>> {
+    let alerts = alerts::list_alerts(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load alerts".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(alerts.into_iter().map(AlertResponse::from).collect()),
+        message: None,
+    }))
+}
+
+/// Returns a single alert.
+async fn get_alert(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<AlertResponse>>> {
+    let alert = alerts::find_alert(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load alert".to_string(),
+        })?
+        .ok_or_else(alert_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(AlertResponse::from(alert)),
+        message: None,
+    }))
+}
+
+/// Creates a price alert.
+/// Percent alerts measure moves from the current price.
+async fn create_alert(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<AlertRequest>,
+) -> ApiResult<Json<ApiResponse<AlertResponse>>> {
+    validate_request(&payload)?;
+
+    let count = alerts::count_alerts(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to count alerts".to_string(),
+        })?;
+    if count >= MAX_ALERTS_PER_USER {
+        return Err(ApiError::BadRequest {
+            message: format!("You can have at most {} alerts", MAX_ALERTS_PER_USER),
+        });
+    }
+
+    let symbol = payload.symbol.to_uppercase();
+    let new_alert = alert_settings(&state, auth.user_id, &symbol, &payload).await?;
+    let alert = alerts::create_alert(&state.db_pool, &new_alert)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to create alert".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(AlertResponse::from(alert)),
+        message: Some("Alert created".to_string()),
+    }))
+}
+
+/// Replaces an alert's settings and reactivates it.
+async fn replace_alert(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AlertRequest>,
+) -> ApiResult<Json<ApiResponse<AlertResponse>>> {
+    validate_request(&payload)?;
+
+    let symbol = payload.symbol.to_uppercase();
+    let new_alert = alert_settings(&state, auth.user_id, &symbol, &payload).await?;
+    let alert = alerts::replace_alert(&state.db_pool, id, &new_alert)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to update alert".to_string(),
+        })?
+        .ok_or_else(alert_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(AlertResponse::from(alert)),
+        message: Some("Alert updated".to_string()),
+    }))
+}
+
+/// Deletes an alert. Notifications it produced stay in the inbox.
+async fn delete_alert(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let deleted = alerts::delete_alert(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to delete alert".to_string(),
+        })?;
+
+    if !deleted {
+        return Err(alert_not_found());
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: Some("Alert deleted".to_string()),
+    }))
+}
+
+/// Lists the user's webhooks. Secrets are not returned.
+async fn list_webhooks(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<Vec<WebhookResponse>>>> {
+    let webhooks = notifications::list_webhooks(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load webhooks".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(webhooks.into_iter().map(WebhookResponse::from).collect()),
+        message: None,
+    }))
+}
+
+/// Registers a webhook that receives the user's notifications.
+/// The signing secret is only returned in this response.
+async fn create_webhook(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> ApiResult<Json<ApiResponse<WebhookResponse>>> {
+    validate_request(&payload)?;
+    resolve_webhook_url(&payload.url)
+        .await
+        .map_err(|e| ApiError::Validation {
+            message: format!("url: {}", e),
+        })?;
+
+    let count = notifications::count_webhooks(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to count webhooks".to_string(),
+        })?;
+    if count >= MAX_WEBHOOKS_PER_USER {
+        return Err(ApiError::BadRequest {
+            message: format!("You can have at most {} webhooks", MAX_WEBHOOKS_PER_USER),
+        });
+    }
+
+    let secret = generate_webhook_secret();
+    let webhook = notifications::create_webhook(&state.db_pool, auth.user_id, &payload.url, &secret)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to create webhook".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(WebhookResponse {
+            secret: Some(secret),
+            ..WebhookResponse::from(webhook)
+        }),
+        message: Some("Webhook registered".to_string()),
+    }))
+}
+
+/// Deletes a webhook.
+async fn delete_webhook(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let deleted = notifications::delete_webhook(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to delete webhook".to_string(),
+        })?;
+
+    if !deleted {
+        return Err(ApiError::NotFound {
+            resource: "Webhook".to_string(),
+        });
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: Some("Webhook deleted".to_string()),
+    }))
+}
+
+/// Validates an alert request against the asset catalog and current price.
+async fn alert_settings<'a>(
+    state: &SharedState,
+    user_id: Uuid,
+    symbol: &'a str,
+    payload: &'a AlertRequest,
+) -> ApiResult<NewAlert<'a>> {
+    assets::find_asset(&state.db_pool, symbol)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load asset".to_string(),
+        })?
+        .filter(|asset| asset.status != "delisted")
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("Asset {}", symbol),
+        })?;
+    let price = state.oracle.price(symbol);
+
+    let mut new_alert = NewAlert {
+        user_id,
+        symbol,
+        recurring: payload.recurring,
+        note: payload.note.as_deref(),
+        ..NewAlert::default()
+    };
+
+    match payload.condition {
+        AlertCondition::Above { price: target } | AlertCondition::Below { price: target } => {
+            let target_cents = usd_to_cents(target);
+            if target_cents <= 0 {
+                return Err(ApiError::Validation {
+                    message: "price: must be positive".to_string(),
+                });
+            }
+            new_alert.condition = if matches!(payload.condition, AlertCondition::Above { .. }) {
+                "above"
+            } else {
+                "below"
+            };
+            new_alert.target_price_cents = Some(target_cents);
+            new_alert.armed = initially_armed(new_alert.condition, Some(target_cents), price);
+        }
+        AlertCondition::Percent { percent } => {
+            let percent_bps = (percent * 100.0).round();
+            if percent_bps < 1.0 || percent > MAX_ALERT_PERCENT {
+                return Err(ApiError::Validation {
+                    message: format!("percent: must be between 0.01 and {}", MAX_ALERT_PERCENT),
+                });
+            }
+            let price = price.ok_or_else(|| ApiError::BadRequest {
+                message: format!("No price available for {}", symbol),
+            })?;
+
+            new_alert.condition = "percent";
+            new_alert.percent_bps = Some(percent_bps as i32);
+            new_alert.reference_price_cents = Some(price);
+            new_alert.armed = true;
+        }
+    }
+
+    Ok(new_alert)
+}
+
+/// Error returned for alerts that do not exist or belong to another user.
+fn alert_not_found() -> ApiError {
+    ApiError::NotFound {
+        resource: "Alert".to_string(),
+    }
+}
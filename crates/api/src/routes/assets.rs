@@ -0,0 +1,182 @@
+//! Asset catalog routes.
+//! Public listing of tradable assets and admin management of trading rules.
+
+use axum::extract::{Path, Query, State};
+use axum::{Json, Router, routing::get};
+use db::queries::assets::{self, AssetUpdate, NewAsset};
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AdminUser;
+use crate::middleware::validate_request;
+use crate::state::SharedState;
+use crate::types::{
+    units_to_micros, usd_to_cents, ApiResponse, AssetResponse, AssetStatus, CreateAssetRequest,
+    ListAssetsQuery, UpdateAssetRequest,
+};
+
+/// Creates public asset catalog routes.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_assets))
+        .route("/{symbol}", get(get_asset))
+}
+
+/// Creates admin routes for managing the asset catalog.
+pub fn create_admin_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", axum::routing::post(create_asset))
+        .route("/{symbol}", axum::routing::put(update_asset).delete(delete_asset))
+}
+
+/// Lists assets, optionally filtered by status.
+async fn list_assets(
+    State(state): State<SharedState>,
+    Query(query): Query<ListAssetsQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<AssetResponse>>>> {
+    let assets = assets::list_assets(&state.db_pool, query.status.map(|s| s.as_str()))
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load assets".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(assets.into_iter().map(AssetResponse::from).collect()),
+        message: None,
+    }))
+}
+
+/// Returns a single asset with its trading rules.
+async fn get_asset(
+    State(state): State<SharedState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<Json<ApiResponse<AssetResponse>>> {
+    let asset = find_asset_or_404(&state, &symbol.to_uppercase()).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(AssetResponse::from(asset)),
+        message: None,
+    }))
+}
+
+/// Adds an asset to the catalog and optionally seeds its oracle price.
+async fn create_asset(
+    State(state): State<SharedState>,
+    _admin: AdminUser,
+    Json(payload): Json<CreateAssetRequest>,
+) -> ApiResult<Json<ApiResponse<AssetResponse>>> {
+    validate_request(&payload)?;
+
+    let new_asset = NewAsset {
+        symbol: &payload.symbol,
+        name: &payload.name,
+        decimals: payload.decimals,
+        tick_size_cents: usd_to_cents(payload.tick_size),
+        lot_size: units_to_micros(payload.lot_size),
+        min_notional_cents: usd_to_cents(payload.min_notional),
+        status: payload.status.unwrap_or(AssetStatus::Active).as_str(),
+        chain: payload.chain.as_deref(),
+        contract_address: payload.contract_address.as_deref(),
+    };
+
+    let asset = assets::create_asset(&state.db_pool, &new_asset)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::BadRequest {
+                message: format!("Asset {} already exists", payload.symbol),
+            },
+            _ => ApiError::Internal {
+                message: "Failed to create asset".to_string(),
+            },
+        })?;
+
+    if let Some(price) = payload.initial_price {
+        state.oracle.publish(&asset.symbol, usd_to_cents(price));
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(AssetResponse::from(asset)),
+        message: Some("Asset created".to_string()),
+    }))
+}
+
+/// Updates an asset's trading rules or status.
+async fn update_asset(
+    State(state): State<SharedState>,
+    _admin: AdminUser,
+    Path(symbol): Path<String>,
+    Json(payload): Json<UpdateAssetRequest>,
+) -> ApiResult<Json<ApiResponse<AssetResponse>>> {
+    validate_request(&payload)?;
+
+    let update = AssetUpdate {
+        name: payload.name.as_deref(),
+        decimals: payload.decimals,
+        tick_size_cents: payload.tick_size.map(usd_to_cents),
+        lot_size: payload.lot_size.map(units_to_micros),
+        min_notional_cents: payload.min_notional.map(usd_to_cents),
+        status: payload.status.map(|s| s.as_str()),
+        chain: payload.chain.as_deref(),
+        contract_address: payload.contract_address.as_deref(),
+    };
+
+    let asset = assets::update_asset(&state.db_pool, &symbol.to_uppercase(), &update)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to update asset".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("Asset {}", symbol),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(AssetResponse::from(asset)),
+        message: Some("Asset updated".to_string()),
+    }))
+}
+
+/// Removes an asset that has never been traded.
+/// Assets with trading history must be delisted instead.
+async fn delete_asset(
+    State(state): State<SharedState>,
+    _admin: AdminUser,
+    Path(symbol): Path<String>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let deleted = assets::delete_asset(&state.db_pool, &symbol.to_uppercase())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => ApiError::BadRequest {
+                message: format!("Asset {} has trading history; delist it instead", symbol),
+            },
+            _ => ApiError::Internal {
+                message: "Failed to delete asset".to_string(),
+            },
+        })?;
+
+    if !deleted {
+        return Err(ApiError::NotFound {
+            resource: format!("Asset {}", symbol),
+        });
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: Some("Asset deleted".to_string()),
+    }))
+}
+
+/// Loads an asset or returns a not found error.
+async fn find_asset_or_404(state: &SharedState, symbol: &str) -> ApiResult<db::models::Asset> {
+    assets::find_asset(&state.db_pool, symbol)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load asset".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("Asset {}", symbol),
+        })
+}
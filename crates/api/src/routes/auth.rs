@@ -1,16 +1,21 @@
 //! Wallet authentication routes for MetaMask and other Web3 wallets.
 //! Handles wallet connection, nonce generation, and signature verification.
 
-use crate::auth_utils::{create_sign_message, generate_nonce, verify_wallet_signature};
+use crate::auth_utils::{
+    create_sign_message, generate_nonce, hash_token, issue_session_token, verify_wallet_signature,
+};
 use crate::errors::{ApiError, ApiResult};
 use crate::middleware::validate_request;
+use crate::services::events::store_event;
 use crate::state::SharedState;
 use crate::types::{
     ApiResponse, AuthResponse, NonceResponse, WalletConnectRequest, WalletLoginRequest,
 };
 use axum::extract::State;
 use axum::{Json, Router, routing::post};
-use db::queries::users;
+use db::queries::{sessions, users};
+use tracing::warn;
+use uuid::Uuid;
 
 /// Creates authentication route group for wallet-based auth.
 /// Provides endpoints for wallet connection and signature verification.
@@ -67,8 +72,9 @@ async fn wallet_login(
             match users::find_user_by_wallet(&state.db_pool, &payload.wallet_address).await {
                 Ok(Some(existing_user)) => {
                     // Existing user login
+                    let token = create_session(&state, existing_user.id, &existing_user.wallet_address).await?;
                     let auth_response = AuthResponse {
-                        token: format!("jwt_token_for_{}", &payload.wallet_address[..10]), // TODO: Real JWT
+                        token,
                         user_id: existing_user.id.to_string(),
                         wallet_address: existing_user.wallet_address,
                         level: existing_user.level as u32,
@@ -87,8 +93,9 @@ async fn wallet_login(
                     // Create new user
                     match users::create_user(&state.db_pool, &payload.wallet_address, None).await {
                         Ok(new_user) => {
+                            let token = create_session(&state, new_user.id, &new_user.wallet_address).await?;
                             let auth_response = AuthResponse {
-                                token: format!("jwt_token_for_{}", &payload.wallet_address[..10]), // TODO: Real JWT
+                                token,
                                 user_id: new_user.id.to_string(),
                                 wallet_address: new_user.wallet_address,
                                 level: new_user.level as u32,
@@ -128,3 +135,26 @@ async fn wallet_login(
         }
     }
 }
+
+/// Issues a session token for a user and records its hash in the sessions table.
+/// Also stores the sign-in event that earns daily login XP.
+async fn create_session(state: &SharedState, user_id: Uuid, wallet_address: &str) -> ApiResult<String> {
+    let (token, expires_at) = issue_session_token(user_id, wallet_address, &state.jwt_secret)
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to issue session token".to_string(),
+        })?;
+
+    sessions::create_session(&state.db_pool, user_id, &hash_token(&token), expires_at)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to create session".to_string(),
+        })?;
+
+    // Every sign-in counts towards the daily login XP, which is granted once per day
+    match store_event(&state.db_pool, user_id, &game::GameEvent::DailyLogin, None).await {
+        Ok(()) => state.events.notify(),
+        Err(e) => warn!("⚠️ Failed to store sign-in event for user {}: {}", user_id, e),
+    }
+
+    Ok(token)
+}
@@ -0,0 +1,121 @@
+//! Strategy backtesting routes.
+//! Runs declarative strategies against stored candle history.
+
+use axum::extract::State;
+use axum::{Json, Router, routing::post};
+use db::queries::{assets, fees, market, users};
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::backtest::{run_backtest, BacktestMarket};
+use crate::state::SharedState;
+use crate::types::{usd_to_cents, ApiResponse, BacktestRequest, BacktestResponse};
+
+/// Starting cash when the request gives none, in cents ($10,000).
+const DEFAULT_INITIAL_CASH_CENTS: i64 = 1_000_000;
+
+/// Maximum number of candles a single backtest may replay.
+const MAX_BACKTEST_CANDLES: i64 = 20_000;
+
+/// Seconds in a year, used to annualize per-candle returns.
+const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+/// Creates backtesting route group.
+/// All endpoints require an authenticated user.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new().route("/", post(create_backtest))
+}
+
+/// Backtests a strategy on a symbol's candles within a time range.
+/// Uses the live fee schedule, slippage model and the user's fee tier.
+async fn create_backtest(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(request): Json<BacktestRequest>,
+) -> ApiResult<Json<ApiResponse<BacktestResponse>>> {
+    validate_request(&request)?;
+    request
+        .strategy
+        .validate()
+        .map_err(|message| ApiError::Validation { message })?;
+    if request.from >= request.to {
+        return Err(ApiError::Validation {
+            message: "from: must be before to".to_string(),
+        });
+    }
+
+    let symbol = request.symbol.to_uppercase();
+    let asset = assets::find_asset(&state.db_pool, &symbol)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load asset".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "Asset".to_string(),
+        })?;
+    let schedule = fees::find_fee_schedule(&state.db_pool, &symbol)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load fee schedule".to_string(),
+        })?;
+    let user = users::find_user_by_id(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load user".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "User".to_string(),
+        })?;
+    let tier = fees::find_fee_tier(&state.db_pool, user.level)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load fee tier".to_string(),
+        })?;
+
+    // Fetch one extra candle to detect ranges over the limit
+    let candles = market::list_candles(
+        &state.db_pool,
+        &symbol,
+        request.interval.as_str(),
+        request.from,
+        request.to,
+        MAX_BACKTEST_CANDLES + 1,
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load candles".to_string(),
+    })?;
+    if candles.len() as i64 > MAX_BACKTEST_CANDLES {
+        return Err(ApiError::BadRequest {
+            message: format!(
+                "Range covers more than {} candles, use a shorter range or a coarser interval",
+                MAX_BACKTEST_CANDLES
+            ),
+        });
+    }
+    if candles.is_empty() {
+        return Err(ApiError::NotFound {
+            resource: "Candles".to_string(),
+        });
+    }
+
+    let market = BacktestMarket {
+        asset: &asset,
+        schedule: &schedule,
+        tier: &tier,
+    };
+    let initial_cash_cents = request
+        .initial_cash
+        .map(usd_to_cents)
+        .unwrap_or(DEFAULT_INITIAL_CASH_CENTS);
+    let periods_per_year = SECONDS_PER_YEAR / request.interval.seconds() as f64;
+
+    let result = run_backtest(&request.strategy, &candles, &market, initial_cash_cents, periods_per_year);
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(BacktestResponse::new(symbol, request.interval, candles.len(), result)),
+        message: None,
+    }))
+}
@@ -0,0 +1,307 @@
+//! Trading bot routes.
+//! Creates, lists, pauses, resumes and deletes a user's DCA and grid bots.
+
+use std::collections::HashMap;
+
+use axum::extract::{Path, State};
+use axum::{Json, Router, routing::{get, post}};
+use chrono::Utc;
+use db::models::Bot;
+use db::queries::{assets, bots};
+use db::queries::bots::{BotTradeStats, NewBot};
+use uuid::Uuid;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::bots::{
+    bot_pnl, grid_level_for_price, next_scheduled_run, MAX_BOTS_PER_USER, MIN_DCA_INTERVAL_SECONDS,
+};
+use crate::state::SharedState;
+use crate::types::{
+    units_to_micros, usd_to_cents, ApiResponse, BotConfig, BotResponse, CreateBotRequest,
+};
+
+/// Longest allowed time between DCA buys, in minutes (one year).
+const MAX_DCA_INTERVAL_MINUTES: u32 = 525_600;
+
+/// Maximum number of grid lines.
+const MAX_GRID_LEVELS: u16 = 100;
+
+/// Creates trading bot route group.
+/// All endpoints require an authenticated user and only touch the user's own bots.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_bots).post(create_bot))
+        .route("/{id}", get(get_bot).delete(delete_bot))
+        .route("/{id}/pause", post(pause_bot))
+        .route("/{id}/resume", post(resume_bot))
+}
+
+/// Lists the user's bots with their P&L.
+async fn list_bots(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<Vec<BotResponse>>>> {
+    let bots = bots::list_bots(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load bots".to_string(),
+        })?;
+    let mut stats = load_trade_stats(&state, auth.user_id).await?;
+
+    let bots = bots
+        .into_iter()
+        .map(|bot| {
+            let stats = stats.remove(&bot.id).unwrap_or_default();
+            bot_response(&state, bot, &stats)
+        })
+        .collect();
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(bots),
+        message: None,
+    }))
+}
+
+/// Returns a single bot with its P&L.
+async fn get_bot(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<BotResponse>>> {
+    let bot = bots::find_bot(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load bot".to_string(),
+        })?
+        .ok_or_else(bot_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_pnl(&state, bot).await?),
+        message: None,
+    }))
+}
+
+/// Creates a DCA or grid bot that starts running immediately.
+/// DCA bots place their first buy right away; grid bots start from the current price.
+async fn create_bot(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<CreateBotRequest>,
+) -> ApiResult<Json<ApiResponse<BotResponse>>> {
+    validate_request(&payload)?;
+
+    let symbol = payload.symbol.to_uppercase();
+    let asset = assets::find_asset(&state.db_pool, &symbol)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load asset".to_string(),
+        })?
+        .filter(|asset| asset.status != "delisted")
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("Asset {}", symbol),
+        })?;
+
+    let count = bots::count_bots(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to count bots".to_string(),
+        })?;
+    if count >= MAX_BOTS_PER_USER {
+        return Err(ApiError::BadRequest {
+            message: format!("You can have at most {} bots", MAX_BOTS_PER_USER),
+        });
+    }
+
+    let mut new_bot = NewBot {
+        user_id: auth.user_id,
+        name: &payload.name,
+        symbol: &symbol,
+        ..NewBot::default()
+    };
+
+    match payload.config {
+        BotConfig::Dca { amount, interval_minutes } => {
+            let amount_cents = usd_to_cents(amount);
+            if amount_cents < asset.min_notional_cents.max(1) {
+                return Err(ApiError::Validation {
+                    message: format!("amount: must be at least the minimum order value of {} cents", asset.min_notional_cents),
+                });
+            }
+            let interval_seconds = i64::from(interval_minutes) * 60;
+            if interval_seconds < MIN_DCA_INTERVAL_SECONDS || interval_minutes > MAX_DCA_INTERVAL_MINUTES {
+                return Err(ApiError::Validation {
+                    message: format!("interval_minutes: must be between 1 and {}", MAX_DCA_INTERVAL_MINUTES),
+                });
+            }
+
+            new_bot.kind = "dca";
+            new_bot.amount_cents = Some(amount_cents);
+            new_bot.interval_seconds = Some(interval_seconds as i32);
+            new_bot.next_run_at = Some(Utc::now());
+        }
+        BotConfig::Grid { lower_price, upper_price, levels, quantity_per_level } => {
+            let lower_cents = usd_to_cents(lower_price);
+            let upper_cents = usd_to_cents(upper_price);
+            if lower_cents <= 0 || upper_cents <= lower_cents {
+                return Err(ApiError::Validation {
+                    message: "upper_price: must be greater than lower_price, which must be positive".to_string(),
+                });
+            }
+            if !(2..=MAX_GRID_LEVELS).contains(&levels) {
+                return Err(ApiError::Validation {
+                    message: format!("levels: must be between 2 and {}", MAX_GRID_LEVELS),
+                });
+            }
+            let quantity = units_to_micros(quantity_per_level);
+            if quantity <= 0 || quantity % asset.lot_size != 0 {
+                return Err(ApiError::Validation {
+                    message: format!("quantity_per_level: must be a positive multiple of the lot size ({} micro units)", asset.lot_size),
+                });
+            }
+            let price = state.oracle.price(&symbol).ok_or_else(|| ApiError::BadRequest {
+                message: format!("No price available for {}", symbol),
+            })?;
+
+            new_bot.kind = "grid";
+            new_bot.grid_lower_cents = Some(lower_cents);
+            new_bot.grid_upper_cents = Some(upper_cents);
+            new_bot.grid_levels = Some(levels as i16);
+            new_bot.grid_quantity = Some(quantity);
+            new_bot.grid_level = Some(grid_level_for_price(lower_cents, upper_cents, levels as i16, price));
+        }
+    }
+
+    let bot = bots::create_bot(&state.db_pool, &new_bot)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to create bot".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(bot_response(&state, bot, &BotTradeStats::default())),
+        message: Some("Bot created".to_string()),
+    }))
+}
+
+/// Pauses a bot. Paused bots place no orders.
+async fn pause_bot(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<BotResponse>>> {
+    let bot = bots::pause_bot(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to pause bot".to_string(),
+        })?
+        .ok_or_else(bot_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_pnl(&state, bot).await?),
+        message: Some("Bot paused".to_string()),
+    }))
+}
+
+/// Resumes a paused bot.
+/// Buys missed while paused are skipped and grid bots continue from the current price.
+async fn resume_bot(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<BotResponse>>> {
+    let bot = bots::find_bot(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load bot".to_string(),
+        })?
+        .ok_or_else(bot_not_found)?;
+
+    let now = Utc::now();
+    let next_run_at = match (bot.next_run_at, bot.interval_seconds) {
+        (Some(due), Some(interval)) if due < now => Some(next_scheduled_run(due, interval, now)),
+        _ => None,
+    };
+    let grid_level = match (bot.grid_lower_cents, bot.grid_upper_cents, bot.grid_levels) {
+        (Some(lower), Some(upper), Some(levels)) => state
+            .oracle
+            .price(&bot.symbol)
+            .map(|price| grid_level_for_price(lower, upper, levels, price)),
+        _ => None,
+    };
+
+    let bot = bots::resume_bot(&state.db_pool, auth.user_id, id, next_run_at, grid_level)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to resume bot".to_string(),
+        })?
+        .ok_or_else(bot_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_pnl(&state, bot).await?),
+        message: Some("Bot resumed".to_string()),
+    }))
+}
+
+/// Deletes a bot. Its trades stay in the user's history.
+async fn delete_bot(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let deleted = bots::delete_bot(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to delete bot".to_string(),
+        })?;
+
+    if !deleted {
+        return Err(bot_not_found());
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: Some("Bot deleted".to_string()),
+    }))
+}
+
+/// Loads the trade statistics of all the user's bots, keyed by bot.
+async fn load_trade_stats(state: &SharedState, user_id: Uuid) -> ApiResult<HashMap<Uuid, BotTradeStats>> {
+    let stats = bots::list_bot_trade_stats(&state.db_pool, user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load bot trades".to_string(),
+        })?;
+
+    Ok(stats.into_iter().map(|s| (s.bot_id, s)).collect())
+}
+
+/// Builds the response for a single bot including its P&L.
+async fn with_pnl(state: &SharedState, bot: Bot) -> ApiResult<BotResponse> {
+    let stats = load_trade_stats(state, bot.user_id)
+        .await?
+        .remove(&bot.id)
+        .unwrap_or_default();
+
+    Ok(bot_response(state, bot, &stats))
+}
+
+/// Values a bot's trades at the current oracle price.
+fn bot_response(state: &SharedState, bot: Bot, stats: &BotTradeStats) -> BotResponse {
+    let pnl = bot_pnl(stats, state.oracle.price(&bot.symbol));
+    BotResponse::new(bot, pnl)
+}
+
+/// Error returned for bots that do not exist or belong to another user.
+fn bot_not_found() -> ApiError {
+    ApiError::NotFound {
+        resource: "Bot".to_string(),
+    }
+}
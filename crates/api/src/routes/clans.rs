@@ -0,0 +1,366 @@
+//! Clan routes.
+//! Lists and shows clans and their leaderboards, and manages the authenticated user's clan.
+
+use axum::extract::{Path, Query, State};
+use axum::{Json, Router, routing::{delete, get, post, put}};
+use chrono::Utc;
+use db::models::Clan;
+use db::queries::clans;
+use game::{ClanMetric, ClanRole, LeaderboardWindow};
+use uuid::Uuid;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::clans::{
+    create_clan, enter_tournament, join_clan, kick_member, leave_clan, member_role, regenerate_invite_code,
+    set_member_role,
+};
+use crate::state::SharedState;
+use crate::types::{
+    clan_score_display, ApiResponse, ClanDetailsResponse, ClanLeaderboardEntryResponse, ClanLeaderboardQuery,
+    ClanLeaderboardResponse, ClanMemberResponse, ClanRankResponse, ClanResponse, CreateClanRequest, JoinClanRequest,
+    LeaveClanResponse, ListClansQuery, SetClanRoleRequest, TournamentClanResponse,
+};
+
+/// Clans or leaderboard entries returned when no limit is given.
+const DEFAULT_CLANS_LIMIT: i64 = 50;
+
+/// Creates clan route group.
+/// Browsing clans and leaderboards is public; everything under /me and joining require authentication.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list).post(create))
+        .route("/me", get(get_my_clan))
+        .route("/join", post(join))
+        .route("/leave", post(leave))
+        .route("/me/invite-code", post(new_invite_code))
+        .route("/me/members/{user_id}", delete(kick))
+        .route("/me/members/{user_id}/role", put(set_role))
+        .route("/me/tournaments/{tournament_id}", post(enter))
+        .route("/leaderboards/{metric}", get(get_leaderboard))
+        .route("/{id}", get(get_clan))
+}
+
+/// Lists clans, largest first.
+async fn list(
+    State(state): State<SharedState>,
+    Query(query): Query<ListClansQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<ClanResponse>>>> {
+    validate_request(&query)?;
+
+    let summaries = clans::list_clans(
+        &state.db_pool,
+        query.search.as_deref(),
+        query.limit.unwrap_or(DEFAULT_CLANS_LIMIT),
+        query.offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load clans".to_string(),
+    })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(
+            summaries
+                .into_iter()
+                .map(|summary| ClanResponse::new(summary.clan, summary.members))
+                .collect(),
+        ),
+        message: None,
+    }))
+}
+
+/// Founds a clan with the user as its owner.
+async fn create(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<CreateClanRequest>,
+) -> ApiResult<Json<ApiResponse<ClanDetailsResponse>>> {
+    validate_request(&payload)?;
+
+    let clan = create_clan(
+        &state,
+        auth.user_id,
+        &payload.name,
+        &payload.tag,
+        payload.description.as_deref(),
+    )
+    .await?;
+    let response = details_response(&state, clan, Some(ClanRole::Owner)).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: Some("Clan created".to_string()),
+    }))
+}
+
+/// Returns the user's clan with its members and invite code for officers and the owner.
+async fn get_my_clan(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<ClanDetailsResponse>>> {
+    let (clan, role) = find_my_clan(&state, &auth).await?;
+    let response = details_response(&state, clan, Some(role)).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: None,
+    }))
+}
+
+/// Joins a clan by invite code.
+async fn join(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<JoinClanRequest>,
+) -> ApiResult<Json<ApiResponse<ClanDetailsResponse>>> {
+    validate_request(&payload)?;
+
+    let clan = join_clan(&state, auth.user_id, &payload.invite_code).await?;
+    let response = details_response(&state, clan, Some(ClanRole::Member)).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: Some("Joined clan".to_string()),
+    }))
+}
+
+/// Leaves the user's clan, handing ownership over or disbanding it when the owner leaves.
+async fn leave(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<LeaveClanResponse>>> {
+    let left = leave_clan(&state, auth.user_id).await?;
+    let message = if left.disbanded { "Clan disbanded" } else { "Left clan" };
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(LeaveClanResponse {
+            clan_id: left.clan_id.to_string(),
+            disbanded: left.disbanded,
+            new_owner_id: left.new_owner.map(|user_id| user_id.to_string()),
+        }),
+        message: Some(message.to_string()),
+    }))
+}
+
+/// Replaces the clan's invite code, invalidating the old one.
+async fn new_invite_code(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<ClanDetailsResponse>>> {
+    let clan = regenerate_invite_code(&state, auth.user_id).await?;
+    let (_, role) = find_my_clan(&state, &auth).await?;
+    let response = details_response(&state, clan, Some(role)).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: Some("Invite code replaced".to_string()),
+    }))
+}
+
+/// Removes a member from the user's clan.
+async fn kick(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(user_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    kick_member(&state, auth.user_id, user_id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: Some("Member removed".to_string()),
+    }))
+}
+
+/// Changes a member's role in the user's clan.
+async fn set_role(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SetClanRoleRequest>,
+) -> ApiResult<Json<ApiResponse<ClanMemberResponse>>> {
+    let role = ClanRole::parse(&payload.role).ok_or_else(|| ApiError::Validation {
+        message: "Role must be one of: owner, officer, member".to_string(),
+    })?;
+    set_member_role(&state, auth.user_id, user_id, role).await?;
+
+    let (clan, _) = find_my_clan(&state, &auth).await?;
+    let member = load_members(&state, clan.id)
+        .await?
+        .into_iter()
+        .find(|member| member.user_id == user_id.to_string())
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "Clan member".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(member),
+        message: Some("Role updated".to_string()),
+    }))
+}
+
+/// Enters the user's clan into a tournament.
+async fn enter(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(tournament_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<TournamentClanResponse>>> {
+    let entry = enter_tournament(&state, auth.user_id, tournament_id, Utc::now()).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(TournamentClanResponse::from(entry)),
+        message: Some("Clan entered in tournament".to_string()),
+    }))
+}
+
+/// Returns a page of a clan leaderboard, best first.
+async fn get_leaderboard(
+    State(state): State<SharedState>,
+    Path(metric): Path<String>,
+    Query(query): Query<ClanLeaderboardQuery>,
+) -> ApiResult<Json<ApiResponse<ClanLeaderboardResponse>>> {
+    validate_request(&query)?;
+
+    let metric = ClanMetric::parse(&metric).ok_or_else(|| ApiError::NotFound {
+        resource: format!("Clan leaderboard {}", metric),
+    })?;
+    let window = match query.window.as_deref() {
+        Some(window) => LeaderboardWindow::parse(window).ok_or_else(|| ApiError::Validation {
+            message: "Window must be one of: week, season, all".to_string(),
+        })?,
+        None => LeaderboardWindow::Season,
+    };
+
+    let rows = clans::list_clan_leaderboard(
+        &state.db_pool,
+        metric.as_str(),
+        window.as_str(),
+        query.limit.unwrap_or(DEFAULT_CLANS_LIMIT),
+        query.offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load clan leaderboard".to_string(),
+    })?;
+    let total = clans::count_clan_leaderboard_entries(&state.db_pool, metric.as_str(), window.as_str())
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load clan leaderboard".to_string(),
+        })?;
+
+    let (_, unit) = clan_score_display(metric, 0.0);
+    let computed_at = rows.first().map(|row| row.entry.computed_at);
+    let entries = rows
+        .into_iter()
+        .map(|row| ClanLeaderboardEntryResponse {
+            rank: row.entry.rank,
+            clan_id: row.entry.clan_id.to_string(),
+            name: row.name,
+            tag: row.tag,
+            members: row.entry.members,
+            score: clan_score_display(metric, row.entry.score).0,
+        })
+        .collect();
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(ClanLeaderboardResponse {
+            metric: metric.as_str(),
+            window: window.as_str(),
+            unit,
+            total,
+            computed_at,
+            entries,
+        }),
+        message: None,
+    }))
+}
+
+/// Returns a clan's public page.
+async fn get_clan(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<ClanDetailsResponse>>> {
+    let clan = clans::find_clan(&state.db_pool, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load clan".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "Clan".to_string(),
+        })?;
+    let response = details_response(&state, clan, None).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: None,
+    }))
+}
+
+/// Loads the user's clan and role or returns a not found error.
+async fn find_my_clan(state: &SharedState, auth: &AuthUser) -> ApiResult<(Clan, ClanRole)> {
+    let load_error = |_| ApiError::Internal {
+        message: "Failed to load clan".to_string(),
+    };
+    let not_found = || ApiError::NotFound {
+        resource: "Clan membership".to_string(),
+    };
+
+    let member = clans::find_membership(&state.db_pool, auth.user_id)
+        .await
+        .map_err(load_error)?
+        .ok_or_else(not_found)?;
+    let clan = clans::find_clan(&state.db_pool, member.clan_id)
+        .await
+        .map_err(load_error)?
+        .ok_or_else(not_found)?;
+
+    Ok((clan, member_role(&member)))
+}
+
+/// Loads a clan's members, owner and officers first.
+async fn load_members(state: &SharedState, clan_id: Uuid) -> ApiResult<Vec<ClanMemberResponse>> {
+    let rows = clans::list_members(&state.db_pool, clan_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load clan members".to_string(),
+        })?;
+
+    Ok(rows.into_iter().map(ClanMemberResponse::from).collect())
+}
+
+/// Builds a clan page. The invite code is only included for the owner and officers.
+async fn details_response(
+    state: &SharedState,
+    clan: Clan,
+    my_role: Option<ClanRole>,
+) -> ApiResult<ClanDetailsResponse> {
+    let members = load_members(state, clan.id).await?;
+    let ranks = clans::list_clan_ranks(&state.db_pool, clan.id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load clan ranks".to_string(),
+        })?;
+    let invite_code = my_role
+        .filter(|role| role.can_manage())
+        .map(|_| clan.invite_code.clone());
+
+    Ok(ClanDetailsResponse {
+        clan: ClanResponse::new(clan, members.len() as i64),
+        members,
+        ranks: ranks.into_iter().map(ClanRankResponse::from).collect(),
+        my_role: my_role.map(|role| role.as_str().to_string()),
+        invite_code,
+    })
+}
@@ -0,0 +1,258 @@
+//! Duel routes.
+//! Issues and answers challenges, compares live scores, trades duel accounts and reports win/loss records.
+
+use axum::extract::{Path, Query, State};
+use axum::{Json, Router, routing::{get, post}};
+use chrono::Utc;
+use db::models::Duel;
+use db::queries::duels;
+use db::queries::users::STARTING_BALANCE_CENTS;
+use game::duels::DEFAULT_DUEL_HOURS;
+use game::DuelStatus;
+use uuid::Uuid;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::duels::{
+    accept_duel, challenge, decline_duel, duel_account, duel_score, execute_duel_order, is_participant, DuelChallenge,
+};
+use crate::services::trading::MarketOrder;
+use crate::state::SharedState;
+use crate::types::{
+    units_to_micros, usd_to_cents, ApiResponse, CreateDuelRequest, DuelAccountResponse, DuelRecordResponse,
+    DuelResponse, DuelTradeResponse, ListDuelsQuery, PlaceOrderRequest,
+};
+
+/// Duels returned when no limit is given.
+const DEFAULT_DUELS_LIMIT: i64 = 50;
+
+/// Trades included with a duel account.
+const ACCOUNT_TRADES_LIMIT: i64 = 50;
+
+/// Creates duel route group.
+/// All endpoints require authentication; duels are only visible to their players.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_duels).post(create))
+        .route("/record", get(get_record))
+        .route("/{id}", get(get_duel))
+        .route("/{id}/accept", post(accept))
+        .route("/{id}/decline", post(decline))
+        .route("/{id}/me", get(get_account))
+        .route("/{id}/orders", post(place_order))
+}
+
+/// Lists the duels the user challenged or was challenged to, newest first.
+async fn list_duels(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<ListDuelsQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<DuelResponse>>>> {
+    validate_request(&query)?;
+    let status = query
+        .status
+        .as_deref()
+        .map(|value| {
+            DuelStatus::parse(value).ok_or_else(|| ApiError::Validation {
+                message: "Status must be one of: pending, declined, expired, active, settled".to_string(),
+            })
+        })
+        .transpose()?;
+
+    let list = duels::list_user_duels(
+        &state.db_pool,
+        auth.user_id,
+        status.map(|status| status.as_str()),
+        query.limit.unwrap_or(DEFAULT_DUELS_LIMIT),
+        query.offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load duels".to_string(),
+    })?;
+
+    let mut responses = Vec::with_capacity(list.len());
+    for duel in list {
+        responses.push(duel_response(&state, duel).await?);
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(responses),
+        message: None,
+    }))
+}
+
+/// Challenges another user to a duel.
+async fn create(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<CreateDuelRequest>,
+) -> ApiResult<Json<ApiResponse<DuelResponse>>> {
+    validate_request(&payload)?;
+
+    let mut allowed_symbols: Vec<String> = payload
+        .allowed_symbols
+        .unwrap_or_default()
+        .iter()
+        .map(|s| s.to_uppercase())
+        .collect();
+    allowed_symbols.sort();
+    allowed_symbols.dedup();
+
+    let terms = DuelChallenge {
+        opponent_wallet: payload.opponent_wallet,
+        starting_balance_cents: payload.starting_balance.map(usd_to_cents).unwrap_or(STARTING_BALANCE_CENTS),
+        allowed_symbols,
+        duration_hours: payload.duration_hours.unwrap_or(DEFAULT_DUEL_HOURS),
+        stake_xp: payload.stake_xp.unwrap_or(0),
+    };
+    let duel = challenge(&state, auth.user_id, &terms, Utc::now()).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(duel_response(&state, duel).await?),
+        message: Some("Challenge sent".to_string()),
+    }))
+}
+
+/// Returns the user's settled duel record.
+async fn get_record(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<DuelRecordResponse>>> {
+    let record = duels::duel_record(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load duel record".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(DuelRecordResponse::from(record)),
+        message: None,
+    }))
+}
+
+/// Returns a duel with the live or final score comparison.
+async fn get_duel(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DuelResponse>>> {
+    let duel = find_duel_for_player(&state, id, auth.user_id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(duel_response(&state, duel).await?),
+        message: None,
+    }))
+}
+
+/// Accepts a challenge, escrowing both stakes and starting the clock.
+async fn accept(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DuelResponse>>> {
+    let duel = accept_duel(&state, id, auth.user_id, Utc::now()).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(duel_response(&state, duel).await?),
+        message: Some("Duel accepted".to_string()),
+    }))
+}
+
+/// Declines a challenge.
+async fn decline(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DuelResponse>>> {
+    let duel = decline_duel(&state, id, auth.user_id, Utc::now()).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(duel_response(&state, duel).await?),
+        message: Some("Duel declined".to_string()),
+    }))
+}
+
+/// Returns the user's duel account with positions at current prices.
+async fn get_account(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DuelAccountResponse>>> {
+    let duel = find_duel_for_player(&state, id, auth.user_id).await?;
+    let view = duel_account(&state, &duel, auth.user_id).await?;
+    let trades = duels::list_trades(&state.db_pool, id, auth.user_id, ACCOUNT_TRADES_LIMIT)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load duel trades".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(DuelAccountResponse::new(
+            view,
+            trades,
+            duel.starting_balance_cents,
+            &state.oracle,
+        )),
+        message: None,
+    }))
+}
+
+/// Places a market order against the user's duel account.
+async fn place_order(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PlaceOrderRequest>,
+) -> ApiResult<Json<ApiResponse<DuelTradeResponse>>> {
+    validate_request(&payload)?;
+
+    let order = MarketOrder {
+        symbol: payload.symbol.to_uppercase(),
+        side: payload.side,
+        quantity: units_to_micros(payload.quantity),
+        bot_id: None,
+    };
+    let trade = execute_duel_order(&state, id, auth.user_id, &order, Utc::now()).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(DuelTradeResponse::from(trade)),
+        message: Some("Order filled".to_string()),
+    }))
+}
+
+/// Loads a duel the user plays in, or returns a not found error.
+async fn find_duel_for_player(state: &SharedState, id: Uuid, user_id: Uuid) -> ApiResult<Duel> {
+    duels::find_duel(&state.db_pool, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load duel".to_string(),
+        })?
+        .filter(|duel| is_participant(duel, user_id))
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "Duel".to_string(),
+        })
+}
+
+/// Builds a duel response with both players' profiles and the current score.
+async fn duel_response(state: &SharedState, duel: Duel) -> ApiResult<DuelResponse> {
+    let players = duels::find_players(&state.db_pool, &[duel.challenger_id, duel.opponent_id])
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load duel players".to_string(),
+        })?;
+    let score = duel_score(state, &duel).await.map_err(|_| ApiError::Internal {
+        message: "Failed to load duel score".to_string(),
+    })?;
+
+    Ok(DuelResponse::new(duel, &players, score))
+}
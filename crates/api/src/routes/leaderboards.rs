@@ -0,0 +1,139 @@
+//! Leaderboard routes.
+//! Serves pages of the materialized leaderboards and the authenticated user's position on them.
+
+use axum::extract::{Path, Query, State};
+use axum::{Json, Router, routing::get};
+use db::queries::leaderboards;
+use game::{LeaderboardMetric, LeaderboardWindow};
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::state::SharedState;
+use crate::types::{
+    leaderboard_score_display, ApiResponse, LeaderboardEntryResponse, LeaderboardQuery, LeaderboardRankQuery,
+    LeaderboardRankResponse, LeaderboardResponse,
+};
+
+/// Entries returned when no limit is given.
+const DEFAULT_LEADERBOARD_LIMIT: i64 = 50;
+
+/// Creates leaderboard route group.
+/// Pages are public; a user's own position requires authentication.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/{metric}", get(get_leaderboard))
+        .route("/{metric}/me", get(get_my_rank))
+}
+
+/// Returns a page of a leaderboard, best first.
+async fn get_leaderboard(
+    State(state): State<SharedState>,
+    Path(metric): Path<String>,
+    Query(query): Query<LeaderboardQuery>,
+) -> ApiResult<Json<ApiResponse<LeaderboardResponse>>> {
+    validate_request(&query)?;
+    let (metric, window) = parse_leaderboard(&metric, query.window.as_deref())?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT);
+    let rows = leaderboards::list_leaderboard(
+        &state.db_pool,
+        metric.as_str(),
+        window.as_str(),
+        limit,
+        query.offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load leaderboard".to_string(),
+    })?;
+    let total = count_entries(&state, metric, window).await?;
+
+    let (_, unit) = leaderboard_score_display(metric, 0.0);
+    let computed_at = rows.first().map(|row| row.entry.computed_at);
+    let entries = rows
+        .into_iter()
+        .map(|row| LeaderboardEntryResponse {
+            rank: row.entry.rank,
+            username: row.username,
+            wallet_address: row.wallet_address,
+            score: leaderboard_score_display(metric, row.entry.score).0,
+        })
+        .collect();
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(LeaderboardResponse {
+            metric: metric.as_str(),
+            window: window.as_str(),
+            unit,
+            total,
+            computed_at,
+            entries,
+        }),
+        message: None,
+    }))
+}
+
+/// Returns the user's rank and score on a leaderboard.
+async fn get_my_rank(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(metric): Path<String>,
+    Query(query): Query<LeaderboardRankQuery>,
+) -> ApiResult<Json<ApiResponse<LeaderboardRankResponse>>> {
+    let (metric, window) = parse_leaderboard(&metric, query.window.as_deref())?;
+
+    let entry = leaderboards::find_leaderboard_entry(&state.db_pool, metric.as_str(), window.as_str(), auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load leaderboard rank".to_string(),
+        })?;
+    let total = count_entries(&state, metric, window).await?;
+
+    let (_, unit) = leaderboard_score_display(metric, 0.0);
+    // Players ranked below the user, plus the user, as a share of all ranked players
+    let percentile = entry
+        .as_ref()
+        .filter(|_| total > 0)
+        .map(|entry| (total - i64::from(entry.rank) + 1) as f64 / total as f64 * 100.0);
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(LeaderboardRankResponse {
+            metric: metric.as_str(),
+            window: window.as_str(),
+            unit,
+            rank: entry.as_ref().map(|entry| entry.rank),
+            score: entry.as_ref().map(|entry| leaderboard_score_display(metric, entry.score).0),
+            total,
+            percentile,
+            computed_at: entry.as_ref().map(|entry| entry.computed_at),
+        }),
+        message: None,
+    }))
+}
+
+/// Parses the metric path segment and window query parameter, defaulting to the season window.
+fn parse_leaderboard(metric: &str, window: Option<&str>) -> ApiResult<(LeaderboardMetric, LeaderboardWindow)> {
+    let metric = LeaderboardMetric::parse(metric).ok_or_else(|| ApiError::NotFound {
+        resource: format!("Leaderboard {}", metric),
+    })?;
+    let window = match window {
+        Some(window) => LeaderboardWindow::parse(window).ok_or_else(|| ApiError::Validation {
+            message: "Window must be one of: week, season, all".to_string(),
+        })?,
+        None => LeaderboardWindow::Season,
+    };
+
+    Ok((metric, window))
+}
+
+/// Counts the players ranked on a leaderboard.
+async fn count_entries(state: &SharedState, metric: LeaderboardMetric, window: LeaderboardWindow) -> ApiResult<i64> {
+    leaderboards::count_leaderboard_entries(&state.db_pool, metric.as_str(), window.as_str())
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load leaderboard".to_string(),
+        })
+}
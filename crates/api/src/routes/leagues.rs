@@ -0,0 +1,81 @@
+//! League routes.
+//! Serves the user's weekly league table, the division ladder and the user's past league weeks.
+
+use axum::extract::{Query, State};
+use axum::{Json, Router, routing::get};
+use chrono::Utc;
+use db::queries::leagues;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::leagues::current_table;
+use crate::state::SharedState;
+use crate::types::{
+    ApiResponse, LeagueDivisionResponse, LeagueHistoryQuery, LeagueHistoryResponse, LeagueTableResponse,
+};
+
+/// League weeks returned when no limit is given.
+const DEFAULT_HISTORY_LIMIT: i64 = 20;
+
+/// Creates league route group.
+/// The division ladder is public; the table and history require authentication.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/current", get(get_current))
+        .route("/divisions", get(list_divisions))
+        .route("/history", get(get_history))
+}
+
+/// Returns the table of the user's group this week, placing the user in a group first if needed.
+async fn get_current(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<LeagueTableResponse>>> {
+    let table = current_table(&state, auth.user_id, Utc::now())
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load league table".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(LeagueTableResponse::new(table, auth.user_id)),
+        message: None,
+    }))
+}
+
+/// Lists the divisions from lowest to highest.
+async fn list_divisions() -> ApiResult<Json<ApiResponse<Vec<LeagueDivisionResponse>>>> {
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(
+            game::DIVISIONS
+                .iter()
+                .map(|division| LeagueDivisionResponse::new(division.tier))
+                .collect(),
+        ),
+        message: None,
+    }))
+}
+
+/// Returns the user's finished league weeks, newest first.
+async fn get_history(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<LeagueHistoryQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<LeagueHistoryResponse>>>> {
+    validate_request(&query)?;
+
+    let rows = leagues::list_history(&state.db_pool, auth.user_id, query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load league history".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(rows.into_iter().map(LeagueHistoryResponse::from).collect()),
+        message: None,
+    }))
+}
@@ -0,0 +1,139 @@
+//! Market data routes.
+//! Serves candle history, 24h tickers and the list of tradable symbols.
+
+use axum::extract::{Query, State};
+use axum::{Json, Router, routing::get};
+use chrono::{Duration, Utc};
+use db::queries::{assets, market};
+
+use crate::errors::{ApiError, ApiResult};
+use crate::middleware::validate_request;
+use crate::services::market::symbol_stats_24h;
+use crate::state::SharedState;
+use crate::types::{
+    cents_to_usd, ApiResponse, CandleResponse, CandlesQuery, MarketSymbol,
+    TickerQuery, TickerResponse,
+};
+
+/// Number of candles returned when no limit is given.
+const DEFAULT_CANDLE_LIMIT: i64 = 500;
+
+/// Creates market data route group.
+/// All endpoints are public.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/candles", get(get_candles))
+        .route("/ticker", get(get_ticker))
+        .route("/symbols", get(get_symbols))
+}
+
+/// Returns candles of a symbol and timeframe within a time range.
+async fn get_candles(
+    State(state): State<SharedState>,
+    Query(query): Query<CandlesQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<CandleResponse>>>> {
+    validate_request(&query)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_CANDLE_LIMIT);
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Duration::seconds(query.interval.seconds() * limit));
+    if from >= to {
+        return Err(ApiError::Validation {
+            message: "from: must be before to".to_string(),
+        });
+    }
+
+    let candles = market::list_candles(
+        &state.db_pool,
+        &query.symbol.to_uppercase(),
+        query.interval.as_str(),
+        from,
+        to,
+        limit,
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load candles".to_string(),
+    })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(candles.into_iter().map(CandleResponse::from).collect()),
+        message: None,
+    }))
+}
+
+/// Returns current price and 24h statistics for one or all tradable symbols.
+async fn get_ticker(
+    State(state): State<SharedState>,
+    Query(query): Query<TickerQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<TickerResponse>>>> {
+    let symbol = query.symbol.map(|s| s.to_uppercase());
+
+    let stats = symbol_stats_24h(&state.db_pool, symbol.as_deref(), Utc::now())
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load market statistics".to_string(),
+        })?;
+
+    let symbols: Vec<String> = match symbol {
+        Some(symbol) => vec![symbol],
+        None => tradable_assets(&state).await?.into_iter().map(|a| a.symbol).collect(),
+    };
+
+    let tickers: Vec<TickerResponse> = symbols
+        .into_iter()
+        .filter_map(|symbol| {
+            let price = state.oracle.price(&symbol)?;
+            let stats = stats.get(&symbol);
+            Some(TickerResponse::new(symbol, price, stats))
+        })
+        .collect();
+
+    if tickers.is_empty() {
+        return Err(ApiError::NotFound {
+            resource: "Ticker".to_string(),
+        });
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(tickers),
+        message: None,
+    }))
+}
+
+/// Lists tradable symbols with their current oracle price.
+async fn get_symbols(
+    State(state): State<SharedState>,
+) -> ApiResult<Json<ApiResponse<Vec<MarketSymbol>>>> {
+    let symbols = tradable_assets(&state)
+        .await?
+        .into_iter()
+        .map(|asset| MarketSymbol {
+            price: state.oracle.price(&asset.symbol).map(cents_to_usd),
+            symbol: asset.symbol,
+            name: asset.name,
+            status: asset.status,
+        })
+        .collect();
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(symbols),
+        message: None,
+    }))
+}
+
+/// Loads all assets that are not delisted.
+async fn tradable_assets(state: &SharedState) -> ApiResult<Vec<db::models::Asset>> {
+    let assets = assets::list_assets(&state.db_pool, None)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load assets".to_string(),
+        })?;
+
+    Ok(assets.into_iter().filter(|a| a.status != "delisted").collect())
+}
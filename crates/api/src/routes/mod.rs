@@ -1,2 +1,21 @@
+pub mod achievements;
+pub mod alerts;
+pub mod assets;
 pub mod auth;
-pub mod trading;
\ No newline at end of file
+pub mod backtests;
+pub mod bots;
+pub mod clans;
+pub mod duels;
+pub mod leaderboards;
+pub mod leagues;
+pub mod market;
+pub mod notifications;
+pub mod quests;
+pub mod ratings;
+pub mod rebalance;
+pub mod seasons;
+pub mod tournaments;
+pub mod trading;
+pub mod users;
+pub mod watchlists;
+pub mod xp;
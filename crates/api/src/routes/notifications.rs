@@ -0,0 +1,99 @@
+//! Notification inbox routes.
+//! Lists a user's notifications and marks them as read.
+
+use axum::extract::{Path, Query, State};
+use axum::{Json, Router, routing::{get, post}};
+use db::queries::notifications;
+use uuid::Uuid;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::state::SharedState;
+use crate::types::{ApiResponse, NotificationListQuery, NotificationListResponse, NotificationResponse};
+
+/// Notifications returned when no limit is given.
+const DEFAULT_NOTIFICATION_LIMIT: i64 = 50;
+
+/// Creates notification inbox route group.
+/// All endpoints require an authenticated user.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_notifications))
+        .route("/read-all", post(mark_all_read))
+        .route("/{id}/read", post(mark_read))
+}
+
+/// Lists the user's notifications, newest first, with the unread count.
+async fn list_notifications(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<NotificationListQuery>,
+) -> ApiResult<Json<ApiResponse<NotificationListResponse>>> {
+    validate_request(&query)?;
+
+    let items = notifications::list_notifications(
+        &state.db_pool,
+        auth.user_id,
+        query.unread_only,
+        query.limit.unwrap_or(DEFAULT_NOTIFICATION_LIMIT),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load notifications".to_string(),
+    })?;
+    let unread_count = notifications::count_unread_notifications(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to count notifications".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(NotificationListResponse {
+            unread_count,
+            notifications: items.into_iter().map(NotificationResponse::from).collect(),
+        }),
+        message: None,
+    }))
+}
+
+/// Marks a notification as read.
+async fn mark_read(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<NotificationResponse>>> {
+    let notification = notifications::mark_notification_read(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to update notification".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "Notification".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(NotificationResponse::from(notification)),
+        message: None,
+    }))
+}
+
+/// Marks all of the user's notifications as read.
+async fn mark_all_read(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let updated = notifications::mark_all_notifications_read(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to update notifications".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: Some(format!("Marked {} notifications as read", updated)),
+    }))
+}
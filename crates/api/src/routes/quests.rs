@@ -0,0 +1,54 @@
+//! Quest routes.
+//! Lists the current daily and weekly quests with the user's progress and claims completed ones.
+
+use axum::extract::{Path, State};
+use axum::{Json, Router, routing::{get, post}};
+use chrono::Utc;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::services::quests::{active_quest_statuses, claim_quest};
+use crate::state::SharedState;
+use crate::types::{ApiResponse, QuestResponse};
+
+/// Creates quest route group.
+/// All endpoints require an authenticated user.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_quests))
+        .route("/{id}/claim", post(claim))
+}
+
+/// Lists the active daily and weekly quests with the user's progress.
+async fn list_quests(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<Vec<QuestResponse>>>> {
+    let statuses = active_quest_statuses(&state, auth.user_id, Utc::now())
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load quests".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(statuses.into_iter().map(QuestResponse::from).collect()),
+        message: None,
+    }))
+}
+
+/// Claims the XP reward of a completed quest.
+async fn claim(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<String>,
+) -> ApiResult<Json<ApiResponse<QuestResponse>>> {
+    let status = claim_quest(&state, auth.user_id, &id, Utc::now()).await?;
+    let message = format!("Quest complete: +{} XP", status.xp_awarded.unwrap_or_default());
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(QuestResponse::from(status)),
+        message: Some(message),
+    }))
+}
@@ -0,0 +1,117 @@
+//! Skill rating routes.
+//! Serves the authenticated user's Glicko-2 rating, its history and rating-based duel matchmaking.
+
+use axum::extract::{Query, State};
+use axum::{Json, Router, routing::get};
+use chrono::Utc;
+use db::queries::ratings;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::ratings::{current_rating, match_candidates};
+use crate::state::SharedState;
+use crate::types::{
+    ApiResponse, MatchCandidateResponse, MatchmakingQuery, MatchmakingResponse, RatingEventResponse,
+    RatingHistoryQuery, RatingResponse,
+};
+
+/// Rating updates returned when no limit is given.
+const DEFAULT_HISTORY_LIMIT: i64 = 50;
+
+/// Match candidates returned when no limit is given.
+const DEFAULT_MATCHMAKING_LIMIT: i64 = 10;
+
+/// Creates skill rating route group.
+/// All endpoints require authentication. The skill leaderboard is served under /leaderboards/skill.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/me", get(get_my_rating))
+        .route("/me/history", get(get_my_history))
+        .route("/matchmaking", get(get_matchmaking))
+}
+
+/// Returns the user's current rating; unrated users get the starting rating.
+async fn get_my_rating(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<RatingResponse>>> {
+    let stored = ratings::find_rating(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load rating".to_string(),
+        })?;
+    let rating = stored
+        .as_ref()
+        .map(|stored| current_rating(stored, Utc::now()))
+        .unwrap_or_default();
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(RatingResponse::new(rating, stored.as_ref())),
+        message: None,
+    }))
+}
+
+/// Returns the user's most recent rating updates, newest first.
+async fn get_my_history(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<RatingHistoryQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<RatingEventResponse>>>> {
+    validate_request(&query)?;
+
+    let events = ratings::list_rating_events(&state.db_pool, auth.user_id, query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load rating history".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(events.into_iter().map(RatingEventResponse::from).collect()),
+        message: None,
+    }))
+}
+
+/// Suggests duel opponents rated closest to the user, leaving out players already in a duel with them.
+async fn get_matchmaking(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<MatchmakingQuery>,
+) -> ApiResult<Json<ApiResponse<MatchmakingResponse>>> {
+    validate_request(&query)?;
+
+    let (rating, candidates) = match_candidates(
+        &state,
+        auth.user_id,
+        query.limit.unwrap_or(DEFAULT_MATCHMAKING_LIMIT),
+        Utc::now(),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to find opponents".to_string(),
+    })?;
+
+    let candidates = candidates
+        .into_iter()
+        .map(|(candidate, candidate_rating)| MatchCandidateResponse {
+            user_id: candidate.user_id.to_string(),
+            username: candidate.username,
+            wallet_address: candidate.wallet_address,
+            rating: candidate_rating.rating,
+            deviation: candidate_rating.deviation,
+            win_probability: game::expected_score(&rating, &candidate_rating),
+            match_quality: game::match_quality(&rating, &candidate_rating),
+        })
+        .collect();
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(MatchmakingResponse {
+            rating: rating.rating,
+            candidates,
+        }),
+        message: None,
+    }))
+}
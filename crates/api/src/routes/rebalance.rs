@@ -0,0 +1,184 @@
+//! Portfolio rebalancing routes.
+//! Previews and executes rebalances to target weights and manages the user's rebalance schedule.
+
+use std::collections::HashSet;
+
+use axum::extract::State;
+use axum::{Json, Router, routing::{get, post}};
+use chrono::{Duration, Utc};
+use db::queries::rebalance;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::rebalance::{execute_rebalance, preview_rebalance, TargetWeight, WEIGHT_DENOMINATOR_BPS};
+use crate::state::SharedState;
+use crate::types::{
+    ApiResponse, RebalanceExecutionResponse, RebalancePreviewResponse, RebalanceRequest,
+    RebalanceScheduleRequest, RebalanceScheduleResponse, TargetWeightRequest, Trade,
+};
+
+/// Creates rebalancing route group, nested under /trading/rebalance.
+/// All endpoints require an authenticated user.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", post(rebalance_portfolio))
+        .route("/preview", post(preview))
+        .route(
+            "/schedule",
+            get(get_schedule).put(put_schedule).delete(delete_schedule),
+        )
+}
+
+/// Returns the orders, fees and resulting allocations of a rebalance without trading.
+async fn preview(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<RebalanceRequest>,
+) -> ApiResult<Json<ApiResponse<RebalancePreviewResponse>>> {
+    validate_request(&payload)?;
+    let targets = target_weights(&payload.targets)?;
+
+    let plan = preview_rebalance(&state, auth.user_id, &targets).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(RebalancePreviewResponse::from(plan)),
+        message: None,
+    }))
+}
+
+/// Rebalances the portfolio to target weights.
+/// Orders are re-planned at current prices and executed atomically.
+async fn rebalance_portfolio(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<RebalanceRequest>,
+) -> ApiResult<Json<ApiResponse<RebalanceExecutionResponse>>> {
+    validate_request(&payload)?;
+    let targets = target_weights(&payload.targets)?;
+
+    let (plan, trades) = execute_rebalance(&state, auth.user_id, &targets).await?;
+    let message = format!("Rebalanced with {} trades", trades.len());
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(RebalanceExecutionResponse {
+            plan: RebalancePreviewResponse::from(plan),
+            trades: trades.into_iter().map(Trade::from).collect(),
+        }),
+        message: Some(message),
+    }))
+}
+
+/// Returns the user's rebalance schedule.
+async fn get_schedule(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<RebalanceScheduleResponse>>> {
+    let schedule = rebalance::find_rebalance_schedule(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load rebalance schedule".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "Rebalance schedule".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(RebalanceScheduleResponse::from(schedule)),
+        message: None,
+    }))
+}
+
+/// Creates or replaces the user's rebalance schedule.
+/// The first scheduled run is one interval from now.
+async fn put_schedule(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<RebalanceScheduleRequest>,
+) -> ApiResult<Json<ApiResponse<RebalanceScheduleResponse>>> {
+    validate_request(&payload)?;
+    let targets = target_weights(&payload.targets)?;
+
+    // Catch unknown symbols now rather than on the first scheduled run
+    preview_rebalance(&state, auth.user_id, &targets).await?;
+
+    let interval_seconds = payload.interval_hours as i32 * 3600;
+    let (symbols, weights): (Vec<String>, Vec<i32>) = targets
+        .into_iter()
+        .map(|t| (t.symbol, t.weight_bps as i32))
+        .unzip();
+    let drift_threshold_bps = (payload.drift_threshold_percent.unwrap_or(0.0) * 100.0).round() as i32;
+
+    let schedule = rebalance::upsert_rebalance_schedule(
+        &state.db_pool,
+        auth.user_id,
+        &symbols,
+        &weights,
+        interval_seconds,
+        drift_threshold_bps,
+        Utc::now() + Duration::seconds(i64::from(interval_seconds)),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to save rebalance schedule".to_string(),
+    })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(RebalanceScheduleResponse::from(schedule)),
+        message: Some("Rebalance schedule saved".to_string()),
+    }))
+}
+
+/// Stops scheduled rebalancing.
+async fn delete_schedule(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let deleted = rebalance::delete_rebalance_schedule(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to delete rebalance schedule".to_string(),
+        })?;
+
+    if !deleted {
+        return Err(ApiError::NotFound {
+            resource: "Rebalance schedule".to_string(),
+        });
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: Some("Rebalance schedule deleted".to_string()),
+    }))
+}
+
+/// Converts requested weights into basis points.
+/// Rejects duplicate symbols and weights that add up to more than 100%.
+fn target_weights(targets: &[TargetWeightRequest]) -> ApiResult<Vec<TargetWeight>> {
+    let mut seen = HashSet::new();
+    let targets: Vec<TargetWeight> = targets
+        .iter()
+        .map(|t| TargetWeight {
+            symbol: t.symbol.to_uppercase(),
+            weight_bps: (t.weight_percent * 100.0).round() as i64,
+        })
+        .collect();
+
+    if let Some(duplicate) = targets.iter().find(|t| !seen.insert(t.symbol.clone())) {
+        return Err(ApiError::Validation {
+            message: format!("targets: {} is listed more than once", duplicate.symbol),
+        });
+    }
+    if targets.iter().map(|t| t.weight_bps).sum::<i64>() > WEIGHT_DENOMINATOR_BPS {
+        return Err(ApiError::Validation {
+            message: "targets: weights must add up to at most 100".to_string(),
+        });
+    }
+
+    Ok(targets)
+}
@@ -0,0 +1,161 @@
+//! Season routes.
+//! Browses current and past seasons with their final standings and lets admins roll a season over.
+
+use axum::extract::{Path, Query, State};
+use axum::{Json, Router, routing::{get, post}};
+use chrono::Utc;
+use db::models::Season;
+use db::queries::seasons;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::{AdminUser, AuthUser};
+use crate::middleware::validate_request;
+use crate::services::seasons::rollover_season;
+use crate::state::SharedState;
+use crate::types::{
+    ApiResponse, SeasonDetailResponse, SeasonResponse, SeasonRolloverRequest, SeasonRolloverResponse,
+    SeasonStandingResponse, SeasonStandingsQuery,
+};
+
+/// Standings returned when no limit is given.
+const DEFAULT_STANDINGS_LIMIT: i64 = 50;
+
+/// Creates season route group.
+/// Browsing is public; a user's own standing requires authentication.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_seasons))
+        .route("/current", get(get_current_season))
+        .route("/{number}", get(get_season))
+        .route("/{number}/me", get(get_my_standing))
+}
+
+/// Creates admin routes for managing seasons.
+pub fn create_admin_routes() -> Router<SharedState> {
+    Router::new().route("/rollover", post(rollover))
+}
+
+/// Lists all seasons, newest first.
+async fn list_seasons(
+    State(state): State<SharedState>,
+) -> ApiResult<Json<ApiResponse<Vec<SeasonResponse>>>> {
+    let seasons = seasons::list_seasons(&state.db_pool)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load seasons".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(seasons.into_iter().map(SeasonResponse::from).collect()),
+        message: None,
+    }))
+}
+
+/// Returns the season in progress.
+async fn get_current_season(
+    State(state): State<SharedState>,
+) -> ApiResult<Json<ApiResponse<SeasonResponse>>> {
+    let season = seasons::find_current_season(&state.db_pool)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load season".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "Current season".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(SeasonResponse::from(season)),
+        message: None,
+    }))
+}
+
+/// Returns a season with a page of its final standings.
+async fn get_season(
+    State(state): State<SharedState>,
+    Path(number): Path<i32>,
+    Query(query): Query<SeasonStandingsQuery>,
+) -> ApiResult<Json<ApiResponse<SeasonDetailResponse>>> {
+    validate_request(&query)?;
+
+    let season = find_season_or_404(&state, number).await?;
+    let standings = seasons::list_season_standings(
+        &state.db_pool,
+        season.id,
+        query.limit.unwrap_or(DEFAULT_STANDINGS_LIMIT),
+        query.offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load season standings".to_string(),
+    })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(SeasonDetailResponse {
+            season: SeasonResponse::from(season),
+            standings: standings.into_iter().map(SeasonStandingResponse::from).collect(),
+        }),
+        message: None,
+    }))
+}
+
+/// Returns the user's final standing in a season.
+async fn get_my_standing(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(number): Path<i32>,
+) -> ApiResult<Json<ApiResponse<SeasonStandingResponse>>> {
+    let season = find_season_or_404(&state, number).await?;
+    let standing = seasons::find_season_standing(&state.db_pool, season.id, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load season standing".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("Standing in season {}", number),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(SeasonStandingResponse::from(standing)),
+        message: None,
+    }))
+}
+
+/// Ends the current season now and starts the next one.
+async fn rollover(
+    State(state): State<SharedState>,
+    _admin: AdminUser,
+    Json(payload): Json<SeasonRolloverRequest>,
+) -> ApiResult<Json<ApiResponse<SeasonRolloverResponse>>> {
+    validate_request(&payload)?;
+
+    let length_days = payload.length_days.unwrap_or(state.season_days);
+    let rollover = rollover_season(&state, None, payload.name.as_deref(), length_days, Utc::now()).await?;
+    let message = format!("{} closed, {} started", rollover.closed.name, rollover.started.name);
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(SeasonRolloverResponse {
+            closed: SeasonResponse::from(rollover.closed),
+            started: SeasonResponse::from(rollover.started),
+            rewarded: rollover.rewarded,
+        }),
+        message: Some(message),
+    }))
+}
+
+/// Loads a season by number or returns a not found error.
+async fn find_season_or_404(state: &SharedState, number: i32) -> ApiResult<Season> {
+    seasons::find_season_by_number(&state.db_pool, number)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load season".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: format!("Season {}", number),
+        })
+}
@@ -0,0 +1,271 @@
+//! Tournament routes.
+//! Lists tournaments and their standings, registers entrants and trades their tournament accounts.
+
+use axum::extract::{Path, Query, State};
+use axum::{Json, Router, routing::{get, post}};
+use chrono::Utc;
+use db::models::Tournament;
+use db::queries::tournaments::{self, NewTournament};
+use uuid::Uuid;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::{AdminUser, AuthUser};
+use crate::middleware::validate_request;
+use crate::services::tournaments::{
+    clan_standings, create_tournament, execute_tournament_order, join_tournament, tournament_account,
+    tournament_standings,
+};
+use crate::services::trading::MarketOrder;
+use crate::state::SharedState;
+use crate::types::{
+    units_to_micros, usd_to_cents, ApiResponse, CreateTournamentRequest, ListTournamentsQuery, PlaceOrderRequest,
+    TournamentAccountResponse, TournamentClanStandingResponse, TournamentResponse, TournamentStandingResponse,
+    TournamentStandingsQuery, TournamentTradeResponse,
+};
+
+/// Standings returned when no limit is given.
+const DEFAULT_STANDINGS_LIMIT: i64 = 50;
+
+/// Trades included with a tournament account.
+const ACCOUNT_TRADES_LIMIT: i64 = 50;
+
+/// Creates tournament route group.
+/// Browsing is public; registering and trading require authentication.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_tournaments))
+        .route("/{id}", get(get_tournament))
+        .route("/{id}/standings", get(get_standings))
+        .route("/{id}/clans", get(get_clan_standings))
+        .route("/{id}/join", post(join))
+        .route("/{id}/me", get(get_account))
+        .route("/{id}/orders", post(place_order))
+}
+
+/// Creates admin routes for managing tournaments.
+pub fn create_admin_routes() -> Router<SharedState> {
+    Router::new().route("/", post(create))
+}
+
+/// Lists tournaments, most recent first.
+async fn list_tournaments(
+    State(state): State<SharedState>,
+    Query(query): Query<ListTournamentsQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<TournamentResponse>>>> {
+    let list = tournaments::list_tournaments(&state.db_pool, query.include_finalized.unwrap_or(false))
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load tournaments".to_string(),
+        })?;
+
+    let now = Utc::now();
+    let mut responses = Vec::with_capacity(list.len());
+    for tournament in list {
+        let participants = count_participants(&state, tournament.id).await?;
+        responses.push(TournamentResponse::new(tournament, participants, now));
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(responses),
+        message: None,
+    }))
+}
+
+/// Returns a single tournament.
+async fn get_tournament(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<TournamentResponse>>> {
+    let tournament = find_tournament_or_404(&state, id).await?;
+    let participants = count_participants(&state, id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(TournamentResponse::new(tournament, participants, Utc::now())),
+        message: None,
+    }))
+}
+
+/// Returns a page of standings: live values while running, the final ranking once finalized.
+async fn get_standings(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<TournamentStandingsQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<TournamentStandingResponse>>>> {
+    validate_request(&query)?;
+
+    let tournament = find_tournament_or_404(&state, id).await?;
+    let standings = tournament_standings(
+        &state,
+        &tournament,
+        query.limit.unwrap_or(DEFAULT_STANDINGS_LIMIT),
+        query.offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|_| ApiError::Internal {
+        message: "Failed to load standings".to_string(),
+    })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(
+            standings
+                .into_iter()
+                .map(|standing| TournamentStandingResponse::new(standing, tournament.starting_balance_cents))
+                .collect(),
+        ),
+        message: None,
+    }))
+}
+
+/// Returns the clans entered in a tournament ranked by their entrants' mean account value.
+async fn get_clan_standings(
+    State(state): State<SharedState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<TournamentClanStandingResponse>>>> {
+    let tournament = find_tournament_or_404(&state, id).await?;
+    let standings = clan_standings(&state, &tournament).await.map_err(|_| ApiError::Internal {
+        message: "Failed to load clan standings".to_string(),
+    })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(
+            standings
+                .into_iter()
+                .map(|standing| TournamentClanStandingResponse::new(standing, tournament.starting_balance_cents))
+                .collect(),
+        ),
+        message: None,
+    }))
+}
+
+/// Registers the user for a tournament.
+async fn join(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<TournamentAccountResponse>>> {
+    join_tournament(&state, id, auth.user_id, Utc::now()).await?;
+    let response = account_response(&state, id, auth.user_id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: Some("Registered for tournament".to_string()),
+    }))
+}
+
+/// Returns the user's tournament account with positions at current prices.
+async fn get_account(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<TournamentAccountResponse>>> {
+    let response = account_response(&state, id, auth.user_id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(response),
+        message: None,
+    }))
+}
+
+/// Places a market order against the user's tournament account.
+async fn place_order(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PlaceOrderRequest>,
+) -> ApiResult<Json<ApiResponse<TournamentTradeResponse>>> {
+    validate_request(&payload)?;
+
+    let order = MarketOrder {
+        symbol: payload.symbol.to_uppercase(),
+        side: payload.side,
+        quantity: units_to_micros(payload.quantity),
+        bot_id: None,
+    };
+    let trade = execute_tournament_order(&state, id, auth.user_id, &order, Utc::now()).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(TournamentTradeResponse::from(trade)),
+        message: Some("Order filled".to_string()),
+    }))
+}
+
+/// Creates a tournament.
+async fn create(
+    State(state): State<SharedState>,
+    _admin: AdminUser,
+    Json(payload): Json<CreateTournamentRequest>,
+) -> ApiResult<Json<ApiResponse<TournamentResponse>>> {
+    validate_request(&payload)?;
+
+    let mut allowed_symbols: Vec<String> = payload.allowed_symbols.iter().map(|s| s.to_uppercase()).collect();
+    allowed_symbols.sort();
+    allowed_symbols.dedup();
+
+    let tournament = create_tournament(
+        &state,
+        &NewTournament {
+            name: &payload.name,
+            description: payload.description.as_deref(),
+            starting_balance_cents: usd_to_cents(payload.starting_balance),
+            allowed_symbols: &allowed_symbols,
+            max_participants: payload.max_participants,
+            registration_opens_at: payload.registration_opens_at,
+            registration_closes_at: payload.registration_closes_at,
+            starts_at: payload.starts_at,
+            ends_at: payload.ends_at,
+        },
+    )
+    .await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(TournamentResponse::new(tournament, 0, Utc::now())),
+        message: Some("Tournament created".to_string()),
+    }))
+}
+
+/// Loads a tournament by id or returns a not found error.
+async fn find_tournament_or_404(state: &SharedState, id: Uuid) -> ApiResult<Tournament> {
+    tournaments::find_tournament(&state.db_pool, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load tournament".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "Tournament".to_string(),
+        })
+}
+
+/// Counts a tournament's entrants.
+async fn count_participants(state: &SharedState, id: Uuid) -> ApiResult<i64> {
+    tournaments::count_entries(&state.db_pool, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load tournament".to_string(),
+        })
+}
+
+/// Builds the user's account response with recent trades.
+async fn account_response(state: &SharedState, id: Uuid, user_id: Uuid) -> ApiResult<TournamentAccountResponse> {
+    let tournament = find_tournament_or_404(state, id).await?;
+    let account = tournament_account(state, id, user_id).await?;
+    let trades = tournaments::list_trades(&state.db_pool, id, user_id, ACCOUNT_TRADES_LIMIT)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load tournament trades".to_string(),
+        })?;
+
+    Ok(TournamentAccountResponse::new(
+        account,
+        trades,
+        tournament.starting_balance_cents,
+        &state.oracle,
+    ))
+}
@@ -1,17 +1,172 @@
-use axum::{routing::get, Router};
+//! Paper trading routes.
+//! Handles order placement, portfolio overview and history, and trade history and export.
 
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router, routing::{get, post}};
+use chrono::Utc;
+use db::queries::{trading, users};
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::execution;
+use crate::services::export::spawn_export;
+use crate::services::portfolio::portfolio_history;
+use crate::services::trading::{execute_market_order, positions_value_cents, MarketOrder};
 use crate::state::SharedState;
+use crate::types::{
+    cents_to_usd, micros_to_units, units_to_micros, ApiResponse, PlaceOrderRequest, Portfolio,
+    PortfolioHistoryQuery, PortfolioHistoryResponse, Position, Trade, TradeExportQuery,
+};
+
+/// Maximum number of trades returned by the history endpoint.
+const TRADE_HISTORY_LIMIT: i64 = 100;
 
+/// Creates trading route group for paper trading.
+/// All endpoints require an authenticated user.
 pub fn create_routes() -> Router<SharedState> {
     Router::new()
         .route("/portfolio", get(get_portfolio))
+        .route("/portfolio/history", get(get_portfolio_history))
         .route("/trades", get(get_trades))
+        .route("/trades/export", get(export_trades))
+        .route("/orders", post(place_order))
+        .nest("/rebalance", super::rebalance::create_routes())
+}
+
+/// Returns the user's cash, positions and total value at current oracle prices.
+async fn get_portfolio(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<Portfolio>>> {
+    let user = users::find_user_by_id(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load user".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "User".to_string(),
+        })?;
+
+    let positions = trading::list_positions(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load positions".to_string(),
+        })?;
+
+    let positions_value = positions_value_cents(&positions, &state.oracle);
+    let portfolio = Portfolio {
+        total_value: cents_to_usd(user.cash_balance_cents + positions_value),
+        cash_balance: cents_to_usd(user.cash_balance_cents),
+        positions: positions
+            .into_iter()
+            .map(|p| {
+                let price = state.oracle.price(&p.symbol).unwrap_or(p.average_price);
+                Position {
+                    quantity: micros_to_units(p.quantity),
+                    avg_price: cents_to_usd(p.average_price),
+                    current_value: cents_to_usd(execution::notional_cents(p.quantity, price)),
+                    symbol: p.symbol,
+                }
+            })
+            .collect(),
+    };
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(portfolio),
+        message: None,
+    }))
+}
+
+/// Returns the user's equity curve with returns and drawdown over a time range.
+async fn get_portfolio_history(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<PortfolioHistoryQuery>,
+) -> ApiResult<Json<ApiResponse<PortfolioHistoryResponse>>> {
+    let range = query.range.unwrap_or_default();
+    let curve = portfolio_history(&state, auth.user_id, range, Utc::now())
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load portfolio history".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(PortfolioHistoryResponse::new(range, curve)),
+        message: None,
+    }))
 }
 
-async fn get_portfolio() -> &'static str {
-    "Portfolio endpoint - Paper trading portfolio"
+/// Returns the user's most recent trades with fee and slippage line items.
+async fn get_trades(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<Vec<Trade>>>> {
+    let trades = trading::list_trades(&state.db_pool, auth.user_id, TRADE_HISTORY_LIMIT)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load trades".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(trades.into_iter().map(Trade::from).collect()),
+        message: None,
+    }))
+}
+
+/// Downloads the user's full trade history as CSV, JSON or a Koinly import file.
+/// Sells carry realized P&L per FIFO lot. The file is streamed from a database cursor;
+/// a failure mid-stream aborts the connection so a truncated file is never taken as complete.
+async fn export_trades(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<TradeExportQuery>,
+) -> ApiResult<Response> {
+    let format = query.format.unwrap_or_default();
+    let chunks = spawn_export(state.db_pool.clone(), auth.user_id, format);
+    let body = Body::from_stream(futures_util::stream::unfold(chunks, |mut chunks| async move {
+        chunks.recv().await.map(|chunk| (chunk, chunks))
+    }));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.file_name(Utc::now())),
+            ),
+        ],
+        body,
+    )
+        .into_response())
 }
 
-async fn get_trades() -> &'static str {
-    "Trades endpoint - Paper trading history"
-}
\ No newline at end of file
+/// Places a market order that fills immediately at the oracle price.
+async fn place_order(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<PlaceOrderRequest>,
+) -> ApiResult<Json<ApiResponse<Trade>>> {
+    validate_request(&payload)?;
+
+    let order = MarketOrder {
+        symbol: payload.symbol.to_uppercase(),
+        side: payload.side,
+        quantity: units_to_micros(payload.quantity),
+        bot_id: None,
+    };
+
+    let trade = execute_market_order(&state, auth.user_id, &order).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(Trade::from(trade)),
+        message: Some("Order filled".to_string()),
+    }))
+}
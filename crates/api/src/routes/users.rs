@@ -0,0 +1,78 @@
+//! User profile routes.
+//! Serves the authenticated user's profile with their activity streak and updates profile settings.
+
+use axum::extract::State;
+use axum::{Json, Router, routing::get};
+use chrono::Utc;
+use db::queries::{streaks, users};
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::streaks::parse_time_zone;
+use crate::state::SharedState;
+use crate::types::{ApiResponse, ProfileResponse, UpdateProfileRequest};
+
+/// Creates user profile route group.
+/// All endpoints require authentication.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new().route("/me", get(get_profile).patch(update_profile))
+}
+
+/// Returns the user's profile, XP, balances and activity streak.
+async fn get_profile(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<ProfileResponse>>> {
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(load_profile(&state, &auth).await?),
+        message: None,
+    }))
+}
+
+/// Updates the user's profile settings.
+/// Changing the time zone moves the boundary of the user's streak days from the next activity on.
+async fn update_profile(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<UpdateProfileRequest>,
+) -> ApiResult<Json<ApiResponse<ProfileResponse>>> {
+    validate_request(&payload)?;
+
+    if let Some(time_zone) = payload.time_zone.as_deref() {
+        let tz = parse_time_zone(time_zone).ok_or_else(|| ApiError::Validation {
+            message: format!("Unknown time zone {}", time_zone),
+        })?;
+        streaks::set_time_zone(&state.db_pool, auth.user_id, tz.name())
+            .await
+            .map_err(|_| ApiError::Internal {
+                message: "Failed to update time zone".to_string(),
+            })?;
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(load_profile(&state, &auth).await?),
+        message: Some("Profile updated".to_string()),
+    }))
+}
+
+/// Loads the user's profile with their streak as of now.
+async fn load_profile(state: &SharedState, auth: &AuthUser) -> ApiResult<ProfileResponse> {
+    let user = users::find_user_by_id(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load profile".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "User".to_string(),
+        })?;
+    let streak = streaks::find_streak(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load streak".to_string(),
+        })?;
+
+    Ok(ProfileResponse::new(user, streak.as_ref(), Utc::now()))
+}
@@ -0,0 +1,302 @@
+//! Watchlist routes.
+//! Creates, renames, reorders and deletes a user's watchlists and returns them with live quotes.
+
+use std::collections::HashSet;
+
+use axum::extract::{Path, State};
+use axum::{Json, Router, routing::{delete, get, post}};
+use chrono::Utc;
+use db::models::Watchlist;
+use db::queries::{assets, watchlists};
+use uuid::Uuid;
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::services::market::symbol_stats_24h;
+use crate::state::SharedState;
+use crate::types::{
+    AddWatchlistSymbolRequest, ApiResponse, CreateWatchlistRequest, TickerResponse, UpdateWatchlistRequest,
+    WatchlistQuote, WatchlistResponse,
+};
+
+/// Maximum number of watchlists per user.
+const MAX_WATCHLISTS_PER_USER: i64 = 20;
+
+/// Maximum number of symbols on a watchlist.
+const MAX_WATCHLIST_SYMBOLS: usize = 50;
+
+/// Creates watchlist route group.
+/// All endpoints require an authenticated user and only touch the user's own watchlists.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(list_watchlists).post(create_watchlist))
+        .route("/{id}", get(get_watchlist).put(update_watchlist).delete(delete_watchlist))
+        .route("/{id}/symbols", post(add_symbol))
+        .route("/{id}/symbols/{symbol}", delete(remove_symbol))
+}
+
+/// Lists the user's watchlists with quotes, oldest first.
+async fn list_watchlists(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<Vec<WatchlistResponse>>>> {
+    let watchlists = watchlists::list_watchlists(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load watchlists".to_string(),
+        })?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_quotes(&state, watchlists).await?),
+        message: None,
+    }))
+}
+
+/// Returns a single watchlist with quotes.
+async fn get_watchlist(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<WatchlistResponse>>> {
+    let watchlist = watchlists::find_watchlist(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load watchlist".to_string(),
+        })?
+        .ok_or_else(watchlist_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_quote(&state, watchlist).await?),
+        message: None,
+    }))
+}
+
+/// Creates a watchlist.
+async fn create_watchlist(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Json(payload): Json<CreateWatchlistRequest>,
+) -> ApiResult<Json<ApiResponse<WatchlistResponse>>> {
+    validate_request(&payload)?;
+
+    let count = watchlists::count_watchlists(&state.db_pool, auth.user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to count watchlists".to_string(),
+        })?;
+    if count >= MAX_WATCHLISTS_PER_USER {
+        return Err(ApiError::BadRequest {
+            message: format!("You can have at most {} watchlists", MAX_WATCHLISTS_PER_USER),
+        });
+    }
+
+    let symbols = watchable_symbols(&state, &payload.symbols).await?;
+    let watchlist = watchlists::create_watchlist(&state.db_pool, auth.user_id, &payload.name, &symbols)
+        .await
+        .map_err(|e| name_taken_or(e, &payload.name, "Failed to create watchlist"))?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_quote(&state, watchlist).await?),
+        message: Some("Watchlist created".to_string()),
+    }))
+}
+
+/// Renames a watchlist and/or replaces its symbols, which also reorders them.
+async fn update_watchlist(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWatchlistRequest>,
+) -> ApiResult<Json<ApiResponse<WatchlistResponse>>> {
+    validate_request(&payload)?;
+
+    let symbols = match &payload.symbols {
+        Some(symbols) => Some(watchable_symbols(&state, symbols).await?),
+        None => None,
+    };
+    let name = payload.name.as_deref();
+
+    let watchlist = watchlists::update_watchlist(&state.db_pool, auth.user_id, id, name, symbols.as_deref())
+        .await
+        .map_err(|e| name_taken_or(e, name.unwrap_or_default(), "Failed to update watchlist"))?
+        .ok_or_else(watchlist_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_quote(&state, watchlist).await?),
+        message: Some("Watchlist updated".to_string()),
+    }))
+}
+
+/// Deletes a watchlist.
+async fn delete_watchlist(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let deleted = watchlists::delete_watchlist(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to delete watchlist".to_string(),
+        })?;
+
+    if !deleted {
+        return Err(watchlist_not_found());
+    }
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: None,
+        message: Some("Watchlist deleted".to_string()),
+    }))
+}
+
+/// Appends a symbol to a watchlist. Symbols already on the list keep their place.
+async fn add_symbol(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AddWatchlistSymbolRequest>,
+) -> ApiResult<Json<ApiResponse<WatchlistResponse>>> {
+    validate_request(&payload)?;
+
+    let symbol = watchable_symbols(&state, std::slice::from_ref(&payload.symbol))
+        .await?
+        .remove(0);
+    let watchlist = watchlists::find_watchlist(&state.db_pool, auth.user_id, id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load watchlist".to_string(),
+        })?
+        .ok_or_else(watchlist_not_found)?;
+    if !watchlist.symbols.contains(&symbol) && watchlist.symbols.len() >= MAX_WATCHLIST_SYMBOLS {
+        return Err(ApiError::BadRequest {
+            message: format!("A watchlist can hold at most {} symbols", MAX_WATCHLIST_SYMBOLS),
+        });
+    }
+
+    let watchlist = watchlists::add_watchlist_symbol(&state.db_pool, auth.user_id, id, &symbol)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to update watchlist".to_string(),
+        })?
+        .ok_or_else(watchlist_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_quote(&state, watchlist).await?),
+        message: Some(format!("{} added to watchlist", symbol)),
+    }))
+}
+
+/// Removes a symbol from a watchlist.
+async fn remove_symbol(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Path((id, symbol)): Path<(Uuid, String)>,
+) -> ApiResult<Json<ApiResponse<WatchlistResponse>>> {
+    let symbol = symbol.to_uppercase();
+    let watchlist = watchlists::remove_watchlist_symbol(&state.db_pool, auth.user_id, id, &symbol)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to update watchlist".to_string(),
+        })?
+        .ok_or_else(watchlist_not_found)?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(with_quote(&state, watchlist).await?),
+        message: Some(format!("{} removed from watchlist", symbol)),
+    }))
+}
+
+/// Uppercases symbols and checks that each is listed and appears once.
+async fn watchable_symbols(state: &SharedState, symbols: &[String]) -> ApiResult<Vec<String>> {
+    let listed: HashSet<String> = assets::list_assets(&state.db_pool, None)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load assets".to_string(),
+        })?
+        .into_iter()
+        .filter(|asset| asset.status != "delisted")
+        .map(|asset| asset.symbol)
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut watchable = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        let symbol = symbol.to_uppercase();
+        if !listed.contains(&symbol) {
+            return Err(ApiError::NotFound {
+                resource: format!("Asset {}", symbol),
+            });
+        }
+        if !seen.insert(symbol.clone()) {
+            return Err(ApiError::Validation {
+                message: format!("symbols: {} is listed more than once", symbol),
+            });
+        }
+        watchable.push(symbol);
+    }
+
+    Ok(watchable)
+}
+
+/// Adds live quotes to watchlists, loading 24h statistics once for all of them.
+async fn with_quotes(state: &SharedState, watchlists: Vec<Watchlist>) -> ApiResult<Vec<WatchlistResponse>> {
+    let stats = symbol_stats_24h(&state.db_pool, None, Utc::now())
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load market statistics".to_string(),
+        })?;
+
+    Ok(watchlists
+        .into_iter()
+        .map(|watchlist| WatchlistResponse {
+            id: watchlist.id.to_string(),
+            name: watchlist.name,
+            symbols: watchlist
+                .symbols
+                .into_iter()
+                .map(|symbol| {
+                    let ticker = state
+                        .oracle
+                        .price(&symbol)
+                        .map(|price| TickerResponse::new(symbol.clone(), price, stats.get(&symbol)));
+                    WatchlistQuote::new(symbol, ticker)
+                })
+                .collect(),
+            created_at: watchlist.created_at,
+            updated_at: watchlist.updated_at,
+        })
+        .collect())
+}
+
+/// Adds live quotes to a single watchlist.
+async fn with_quote(state: &SharedState, watchlist: Watchlist) -> ApiResult<WatchlistResponse> {
+    let mut responses = with_quotes(state, vec![watchlist]).await?;
+    Ok(responses.remove(0))
+}
+
+/// Maps a duplicate-name violation to a conflict and any other error to an internal one.
+fn name_taken_or(error: sqlx::Error, name: &str, message: &str) -> ApiError {
+    match error {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::Conflict {
+            message: format!("You already have a watchlist named {}", name),
+        },
+        _ => ApiError::Internal {
+            message: message.to_string(),
+        },
+    }
+}
+
+/// Error returned for watchlists that do not exist or belong to another user.
+fn watchlist_not_found() -> ApiError {
+    ApiError::NotFound {
+        resource: "Watchlist".to_string(),
+    }
+}
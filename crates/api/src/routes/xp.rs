@@ -0,0 +1,85 @@
+//! XP routes.
+//! Returns a user's XP total, level progress and the history of XP events behind it.
+
+use axum::extract::{Query, State};
+use axum::{Json, Router, routing::get};
+use db::queries::{users, xp};
+
+use crate::errors::{ApiError, ApiResult};
+use crate::extractors::AuthUser;
+use crate::middleware::validate_request;
+use crate::state::SharedState;
+use crate::types::{ApiResponse, XpEventResponse, XpHistoryQuery, XpHistoryResponse, XpSummaryResponse};
+
+/// XP events returned when no limit is given.
+const DEFAULT_XP_HISTORY_LIMIT: i64 = 50;
+
+/// Creates XP route group.
+/// All endpoints require an authenticated user.
+pub fn create_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(get_xp))
+        .route("/history", get(get_xp_history))
+}
+
+/// Returns the user's XP total and progress towards the next level.
+async fn get_xp(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+) -> ApiResult<Json<ApiResponse<XpSummaryResponse>>> {
+    let xp_points = user_xp(&state, auth.user_id).await?;
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(XpSummaryResponse::new(xp_points)),
+        message: None,
+    }))
+}
+
+/// Lists the user's XP events, newest first.
+async fn get_xp_history(
+    State(state): State<SharedState>,
+    auth: AuthUser,
+    Query(query): Query<XpHistoryQuery>,
+) -> ApiResult<Json<ApiResponse<XpHistoryResponse>>> {
+    validate_request(&query)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_XP_HISTORY_LIMIT);
+    let cursor = query.before.map(|before| (before, query.before_id));
+    let events = xp::list_xp_events(&state.db_pool, auth.user_id, cursor, limit)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load XP history".to_string(),
+        })?;
+    let xp_points = user_xp(&state, auth.user_id).await?;
+
+    // A full page may be followed by older events
+    let next = (events.len() as i64 == limit)
+        .then(|| events.last().map(|event| (event.created_at, event.id)))
+        .flatten();
+
+    Ok(Json(ApiResponse {
+        success: true,
+        data: Some(XpHistoryResponse {
+            summary: XpSummaryResponse::new(xp_points),
+            events: events.into_iter().map(XpEventResponse::from).collect(),
+            next_before: next.map(|(created_at, _)| created_at),
+            next_before_id: next.map(|(_, id)| id.to_string()),
+        }),
+        message: None,
+    }))
+}
+
+/// Loads the user's stored XP total.
+async fn user_xp(state: &SharedState, user_id: uuid::Uuid) -> ApiResult<i32> {
+    let user = users::find_user_by_id(&state.db_pool, user_id)
+        .await
+        .map_err(|_| ApiError::Internal {
+            message: "Failed to load user".to_string(),
+        })?
+        .ok_or_else(|| ApiError::NotFound {
+            resource: "User".to_string(),
+        })?;
+
+    Ok(user.xp_points)
+}
@@ -0,0 +1,158 @@
+//! Achievement service.
+//! Updates player statistics from user events and unlocks the achievements they qualify for.
+
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use db::queries::{achievements, notifications, streaks, users, xp};
+use game::{Achievement, GameEvent, PlayerProgress};
+
+use crate::services::events::UserEvent;
+use crate::services::notifications::PendingDeliveries;
+use crate::services::xp::grant_xp_in;
+
+/// Notification kind used for unlocked achievements.
+pub const ACHIEVEMENT_KIND: &str = "achievement";
+
+/// Event-specific data included in achievement webhook payloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct AchievementData {
+    /// Catalog key of the achievement.
+    pub achievement_id: &'static str,
+    /// Display name.
+    pub name: &'static str,
+    /// Badge rarity.
+    pub badge: &'static str,
+    /// XP granted for the unlock.
+    pub xp_reward: u32,
+}
+
+/// Adds a user event to the player's statistics and unlocks any achievements now earned, all in the
+/// caller's transaction. Unlock notifications are queued for delivery once it commits.
+pub async fn record_event(
+    conn: &mut PgConnection,
+    event: &UserEvent,
+    deliveries: &mut PendingDeliveries,
+) -> Result<(), sqlx::Error> {
+    match event.event {
+        GameEvent::TradeExecuted { notional_cents } => {
+            achievements::record_trade_stats(&mut *conn, event.user_id, notional_cents).await?;
+        }
+        GameEvent::PositionClosed { realized_pnl_cents, cost_cents, held_seconds } => {
+            let return_bps = if cost_cents > 0 {
+                (realized_pnl_cents as i128 * 10_000 / cost_cents as i128) as i64
+            } else {
+                0
+            };
+            achievements::record_close_stats(&mut *conn, event.user_id, realized_pnl_cents, return_bps, held_seconds)
+                .await?;
+        }
+        // Sign-ins count through the activity streak, which is recorded before achievements are checked
+        GameEvent::DailyLogin => {}
+    }
+
+    unlock_achievements(conn, event.user_id, deliveries).await
+}
+
+/// Loads the statistics achievement criteria are checked against.
+/// Returns `None` when the user does not exist.
+pub async fn player_progress(conn: &mut PgConnection, user_id: Uuid) -> Result<Option<PlayerProgress>, sqlx::Error> {
+    let Some(user) = users::find_user_by_id(&mut *conn, user_id).await? else {
+        return Ok(None);
+    };
+    let stats = achievements::find_player_stats(&mut *conn, user_id).await?.unwrap_or_default();
+    let longest_streak_days = streaks::find_streak(&mut *conn, user_id)
+        .await?
+        .map(|streak| streak.longest_days)
+        .unwrap_or(0);
+
+    Ok(Some(PlayerProgress {
+        trade_count: stats.trade_count,
+        volume_cents: stats.volume_cents,
+        profitable_closes: stats.profitable_closes,
+        realized_pnl_cents: stats.realized_pnl_cents,
+        best_return_bps: stats.best_return_bps,
+        longest_hold_seconds: stats.longest_hold_seconds,
+        longest_login_streak_days: i64::from(longest_streak_days),
+        xp: i64::from(user.xp_points),
+        level: i64::from(user.level),
+    }))
+}
+
+/// Unlocks every achievement the user qualifies for, granting XP rewards and queuing notifications.
+/// Repeats until no more unlock, since reward XP can complete XP and level achievements.
+async fn unlock_achievements(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    deliveries: &mut PendingDeliveries,
+) -> Result<(), sqlx::Error> {
+    loop {
+        let Some(progress) = player_progress(conn, user_id).await? else {
+            return Ok(());
+        };
+        let unlocked: Vec<String> = achievements::list_user_achievements(&mut *conn, user_id)
+            .await?
+            .into_iter()
+            .map(|unlock| unlock.achievement_id)
+            .collect();
+
+        let earned = game::newly_unlocked(&progress, &unlocked);
+        if earned.is_empty() {
+            return Ok(());
+        }
+        for achievement in earned {
+            unlock_achievement(conn, user_id, achievement, deliveries).await?;
+        }
+    }
+}
+
+/// Unlocks an achievement, grants its XP reward and adds the unlock to the user's inbox, queuing it for
+/// the user's webhooks. Does nothing when the achievement was already unlocked.
+async fn unlock_achievement(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    achievement: &'static Achievement,
+    deliveries: &mut PendingDeliveries,
+) -> Result<(), sqlx::Error> {
+    if !achievements::unlock_achievement(&mut *conn, user_id, achievement.id, Utc::now()).await? {
+        return Ok(());
+    }
+
+    if achievement.xp_reward > 0 {
+        let key = format!("{}:{}", game::achievements::ACHIEVEMENT_XP_SOURCE, achievement.id);
+        let grant = xp::XpGrant {
+            source: game::achievements::ACHIEVEMENT_XP_SOURCE,
+            amount: achievement.xp_reward as i32,
+            reference_id: Some(achievement.id),
+            idempotency_key: Some(&key),
+        };
+        if let Some(granted) = grant_xp_in(&mut *conn, user_id, &grant).await? {
+            granted.queue(deliveries);
+        }
+    }
+
+    let message = match achievement.xp_reward {
+        0 => achievement.description.to_string(),
+        xp_reward => format!("{} (+{} XP)", achievement.description, xp_reward),
+    };
+    let notification = notifications::create_notification(
+        &mut *conn,
+        user_id,
+        ACHIEVEMENT_KIND,
+        &format!("Achievement unlocked: {}", achievement.name),
+        &message,
+        None,
+    )
+    .await?;
+    let data = AchievementData {
+        achievement_id: achievement.id,
+        name: achievement.name,
+        badge: achievement.badge,
+        xp_reward: achievement.xp_reward,
+    };
+    deliveries.push(notification, data);
+
+    Ok(())
+}
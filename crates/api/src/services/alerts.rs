@@ -0,0 +1,204 @@
+//! Price alert service.
+//! Evaluates alerts against oracle ticks and delivers the ones that fire.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::warn;
+use uuid::Uuid;
+
+use db::models::Alert;
+use db::queries::{alerts, notifications};
+
+use crate::services::notifications::deliver_to_webhooks;
+use crate::services::oracle::PriceTick;
+use crate::state::SharedState;
+use crate::types::cents_to_usd;
+
+/// Maximum number of alerts per user.
+pub const MAX_ALERTS_PER_USER: i64 = 50;
+
+/// Notification kind of fired price alerts.
+pub const PRICE_ALERT_KIND: &str = "price_alert";
+
+/// What a tick does to an alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertOutcome {
+    /// Nothing changes.
+    Unchanged,
+    /// A disarmed level alert moved back to the near side of its level.
+    Arm,
+    /// The alert fires.
+    Trigger,
+}
+
+/// Details of a fired price alert sent to webhooks.
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceAlertData {
+    /// Alert that fired.
+    pub alert_id: Uuid,
+    /// Watched symbol.
+    pub symbol: String,
+    /// Alert condition: "above", "below" or "percent".
+    pub condition: String,
+    /// Level crossed by "above" and "below" alerts, in USD.
+    pub target_price: Option<f64>,
+    /// Price the move was measured from for "percent" alerts, in USD.
+    pub reference_price: Option<f64>,
+    /// Price that triggered the alert, in USD.
+    pub price: f64,
+    /// Whether the alert stays active.
+    pub recurring: bool,
+}
+
+/// Whether a new level alert can fire right away.
+/// Level alerts only fire on a crossing, so one created past its level waits for the price to come back.
+pub fn initially_armed(condition: &str, target_price_cents: Option<i64>, price_cents: Option<i64>) -> bool {
+    match (condition, target_price_cents, price_cents) {
+        ("above", Some(target), Some(price)) => price < target,
+        ("below", Some(target), Some(price)) => price > target,
+        _ => true,
+    }
+}
+
+/// Evaluates an alert against a new price.
+pub fn evaluate_alert(alert: &Alert, price_cents: i64) -> AlertOutcome {
+    match (alert.condition.as_str(), alert.target_price_cents) {
+        ("above", Some(target)) if alert.armed && price_cents >= target => AlertOutcome::Trigger,
+        ("above", Some(target)) if !alert.armed && price_cents < target => AlertOutcome::Arm,
+        ("below", Some(target)) if alert.armed && price_cents <= target => AlertOutcome::Trigger,
+        ("below", Some(target)) if !alert.armed && price_cents > target => AlertOutcome::Arm,
+        ("percent", _) => match (alert.reference_price_cents, alert.percent_bps) {
+            (Some(reference), Some(percent_bps)) if reference > 0 => {
+                let moved_bps = (price_cents - reference).abs() as i128 * 10_000;
+                if moved_bps >= i128::from(percent_bps) * reference as i128 {
+                    AlertOutcome::Trigger
+                } else {
+                    AlertOutcome::Unchanged
+                }
+            }
+            _ => AlertOutcome::Unchanged,
+        },
+        _ => AlertOutcome::Unchanged,
+    }
+}
+
+/// Describes a fired alert for the user's inbox.
+/// Returns the notification title and message.
+pub fn alert_message(alert: &Alert, price_cents: i64) -> (String, String) {
+    let price = cents_to_usd(price_cents);
+    let (title, mut message) = match alert.condition.as_str() {
+        "percent" => {
+            let reference = alert.reference_price_cents.unwrap_or(price_cents);
+            let change = if reference > 0 {
+                (price_cents - reference) as f64 / reference as f64 * 100.0
+            } else {
+                0.0
+            };
+            (
+                format!("{} moved {:+.2}%", alert.symbol, change),
+                format!("{} is at ${:.2}, {:+.2}% from ${:.2}.", alert.symbol, price, change, cents_to_usd(reference)),
+            )
+        }
+        condition => {
+            let target = cents_to_usd(alert.target_price_cents.unwrap_or_default());
+            (
+                format!("{} {} ${:.2}", alert.symbol, condition, target),
+                format!("{} crossed {} ${:.2} and is at ${:.2}.", alert.symbol, condition, target, price),
+            )
+        }
+    };
+
+    if let Some(note) = &alert.note {
+        message.push(' ');
+        message.push_str(note);
+    }
+
+    (title, message)
+}
+
+/// Evaluates the active alerts on a tick's symbol.
+/// Fired alerts are recorded with their inbox notification in one transaction, then sent to webhooks.
+pub async fn evaluate_tick(state: &SharedState, tick: &PriceTick) -> Result<(), sqlx::Error> {
+    let active = alerts::list_active_alerts_for_symbol(&state.db_pool, &tick.symbol).await?;
+
+    let mut to_arm = Vec::new();
+    for alert in active {
+        match evaluate_alert(&alert, tick.price_cents) {
+            AlertOutcome::Unchanged => {}
+            AlertOutcome::Arm => to_arm.push(alert.id),
+            AlertOutcome::Trigger => {
+                if let Err(e) = fire_alert(state, &alert, tick.price_cents, tick.timestamp).await {
+                    warn!("⚠️ Failed to fire alert {}: {}", alert.id, e);
+                }
+            }
+        }
+    }
+
+    if !to_arm.is_empty() {
+        alerts::arm_alerts(&state.db_pool, &to_arm).await?;
+    }
+
+    Ok(())
+}
+
+/// Records a fired alert, adds it to the user's inbox and sends it to their webhooks.
+/// One-shot alerts deactivate, recurring level alerts disarm until the price comes back
+/// and recurring percent alerts measure the next move from the trigger price.
+async fn fire_alert(
+    state: &SharedState,
+    alert: &Alert,
+    price_cents: i64,
+    triggered_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let is_percent = alert.condition == "percent";
+    let reference_price_cents = if is_percent && alert.recurring {
+        Some(price_cents)
+    } else {
+        alert.reference_price_cents
+    };
+
+    let mut tx = state.db_pool.begin().await?;
+    let triggered = alerts::trigger_alert(
+        &mut tx,
+        alert.id,
+        price_cents,
+        triggered_at,
+        alert.recurring,
+        is_percent,
+        reference_price_cents,
+    )
+    .await?;
+    if triggered.is_none() {
+        // Changed or deleted since it was loaded
+        return Ok(());
+    }
+
+    // Describe the move from the reference the alert fired against, not the one it moves to
+    let (title, message) = alert_message(alert, price_cents);
+    let notification =
+        notifications::create_notification(&mut *tx, alert.user_id, PRICE_ALERT_KIND, &title, &message, Some(alert.id))
+            .await?;
+    tx.commit().await?;
+
+    let data = PriceAlertData {
+        alert_id: alert.id,
+        symbol: alert.symbol.clone(),
+        condition: alert.condition.clone(),
+        target_price: alert.target_price_cents.map(cents_to_usd),
+        reference_price: if is_percent {
+            alert.reference_price_cents.map(cents_to_usd)
+        } else {
+            None
+        },
+        price: cents_to_usd(price_cents),
+        recurring: alert.recurring,
+    };
+
+    // Slow endpoints must not hold up evaluation of the next tick
+    let state = state.clone();
+    tokio::spawn(async move {
+        deliver_to_webhooks(&state, &notification, data).await;
+    });
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Performance analytics shared by backtests, portfolio history and leaderboards.
+//! Computes returns, drawdowns and risk-adjusted ratios from value series.
+
+/// Calculates period-over-period simple returns of a value series.
+/// Periods starting from a non-positive value are skipped.
+pub fn simple_returns(values: &[f64]) -> Vec<f64> {
+    values
+        .windows(2)
+        .filter(|pair| pair[0] > 0.0)
+        .map(|pair| pair[1] / pair[0] - 1.0)
+        .collect()
+}
+
+/// Calculates the drawdown of every point from the running peak, as a fraction (0.0 to 1.0).
+pub fn drawdowns(values: &[f64]) -> Vec<f64> {
+    let mut peak = f64::MIN;
+    values
+        .iter()
+        .map(|&value| {
+            peak = peak.max(value);
+            if peak > 0.0 { (peak - value) / peak } else { 0.0 }
+        })
+        .collect()
+}
+
+/// Calculates the largest peak-to-trough decline, as a fraction (0.0 to 1.0).
+pub fn max_drawdown(values: &[f64]) -> f64 {
+    drawdowns(values).into_iter().fold(0.0, f64::max)
+}
+
+/// Calculates the annualized Sharpe ratio of periodic returns with a zero risk-free rate.
+/// Returns `None` when there are fewer than two returns or no volatility.
+pub fn sharpe_ratio(returns: &[f64], periods_per_year: f64) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let std_dev = variance.sqrt();
+    if std_dev == 0.0 || !std_dev.is_finite() {
+        return None;
+    }
+
+    Some(mean / std_dev * periods_per_year.sqrt())
+}
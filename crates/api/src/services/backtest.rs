@@ -0,0 +1,485 @@
+//! Backtesting engine for declarative trading strategies.
+//! Replays stored candles through the same execution, fee and order-size code used live.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use db::models::{Asset, Candle, FeeSchedule, FeeTier};
+use serde::{Deserialize, Serialize};
+
+use crate::services::analytics;
+use crate::services::execution::{self, Liquidity, OrderSide};
+use crate::services::indicators;
+use crate::services::trading::check_order_size;
+
+/// Longest indicator lookback a strategy may use.
+pub const MAX_INDICATOR_PERIOD: u32 = 500;
+
+/// Maximum number of rules per entry or exit block.
+pub const MAX_RULES: usize = 10;
+
+/// Indicator computed from candle closes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Indicator {
+    /// Close price in USD.
+    Price,
+    /// Simple moving average of the close in USD.
+    Sma { period: u32 },
+    /// Exponential moving average of the close in USD.
+    Ema { period: u32 },
+    /// Relative strength index (0 to 100).
+    Rsi { period: u32 },
+}
+
+/// Side of a rule comparison: an indicator or a constant.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Operand {
+    Indicator(Indicator),
+    Value(f64),
+}
+
+/// How the two operands of a rule are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// Left is greater than right.
+    Above,
+    /// Left is less than right.
+    Below,
+    /// Left moved from at or below right to above it on this candle.
+    CrossesAbove,
+    /// Left moved from at or above right to below it on this candle.
+    CrossesBelow,
+}
+
+/// Single comparison evaluated on every candle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub left: Operand,
+    pub condition: Condition,
+    pub right: Operand,
+}
+
+/// Declarative long-only strategy.
+/// Enters when all entry rules hold and exits when any exit rule holds
+/// or the stop loss / take profit is hit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategySpec {
+    /// Rules that must all hold to open a position.
+    pub entry: Vec<Rule>,
+    /// Rules of which any closes the position.
+    #[serde(default)]
+    pub exit: Vec<Rule>,
+    /// Close the position after this loss from the entry price, in percent.
+    pub stop_loss_percent: Option<f64>,
+    /// Close the position after this gain from the entry price, in percent.
+    pub take_profit_percent: Option<f64>,
+    /// Share of available cash used per entry, in percent.
+    #[serde(default = "default_position_size_percent")]
+    pub position_size_percent: f64,
+}
+
+fn default_position_size_percent() -> f64 {
+    100.0
+}
+
+impl StrategySpec {
+    /// Checks that the strategy is well formed.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.entry.is_empty() {
+            return Err("entry: at least one rule is required".to_string());
+        }
+        if self.entry.len() > MAX_RULES || self.exit.len() > MAX_RULES {
+            return Err(format!("entry/exit: at most {} rules are allowed", MAX_RULES));
+        }
+        if !(self.position_size_percent > 0.0 && self.position_size_percent <= 100.0) {
+            return Err("position_size_percent: must be greater than 0 and at most 100".to_string());
+        }
+        for percent in [self.stop_loss_percent, self.take_profit_percent].into_iter().flatten() {
+            if !(percent > 0.0 && percent < 1000.0) {
+                return Err("stop_loss_percent/take_profit_percent: must be between 0 and 1000".to_string());
+            }
+        }
+        for indicator in self.indicators() {
+            let period = match indicator {
+                Indicator::Price => continue,
+                Indicator::Sma { period } | Indicator::Ema { period } | Indicator::Rsi { period } => period,
+            };
+            if period == 0 || period > MAX_INDICATOR_PERIOD {
+                return Err(format!("period: must be between 1 and {}", MAX_INDICATOR_PERIOD));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns every indicator referenced by the strategy's rules.
+    fn indicators(&self) -> Vec<Indicator> {
+        self.entry
+            .iter()
+            .chain(self.exit.iter())
+            .flat_map(|rule| [rule.left, rule.right])
+            .filter_map(|operand| match operand {
+                Operand::Indicator(indicator) => Some(indicator),
+                Operand::Value(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Market conditions a backtest trades under.
+#[derive(Debug, Clone)]
+pub struct BacktestMarket<'a> {
+    /// Asset whose lot size and minimum notional apply.
+    pub asset: &'a Asset,
+    /// Fee and slippage schedule of the asset.
+    pub schedule: &'a FeeSchedule,
+    /// Fee tier of the user running the backtest.
+    pub tier: &'a FeeTier,
+}
+
+/// Fill made during a backtest.
+#[derive(Debug, Clone)]
+pub struct BacktestTrade {
+    /// Buy or sell.
+    pub side: OrderSide,
+    /// Open time of the candle the fill happened on.
+    pub time: DateTime<Utc>,
+    /// Why the trade was made: "entry", "exit", "stop_loss", "take_profit" or "end_of_test".
+    pub reason: &'static str,
+    /// Fill details from the execution model.
+    pub fill: execution::Fill,
+    /// Profit or loss of the round trip including fees, set on closing trades. In cents.
+    pub pnl_cents: Option<i64>,
+}
+
+/// Portfolio value at the close of a candle.
+#[derive(Debug, Clone, Copy)]
+pub struct EquityPoint {
+    /// Open time of the candle.
+    pub time: DateTime<Utc>,
+    /// Cash plus position value at the close, in cents.
+    pub equity_cents: i64,
+}
+
+/// Outcome of a backtest.
+#[derive(Debug, Clone)]
+pub struct BacktestResult {
+    /// Cash at the start, in cents.
+    pub initial_cash_cents: i64,
+    /// Equity after the last candle, in cents.
+    pub final_equity_cents: i64,
+    /// Total return as a fraction.
+    pub total_return: f64,
+    /// Largest peak-to-trough decline of equity as a fraction.
+    pub max_drawdown: f64,
+    /// Annualized Sharpe ratio of per-candle returns.
+    pub sharpe_ratio: Option<f64>,
+    /// Share of closed round trips with a profit.
+    pub win_rate: Option<f64>,
+    /// Fees paid, in cents.
+    pub total_fees_cents: i64,
+    /// Slippage paid, in cents.
+    pub total_slippage_cents: i64,
+    /// All fills in order.
+    pub trades: Vec<BacktestTrade>,
+    /// Equity after every candle.
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+/// Open position during a backtest.
+struct OpenPosition {
+    quantity: i64,
+    entry_price: i64,
+    cost_cents: i64,
+}
+
+/// Runs a strategy over candles ordered oldest first.
+/// Orders fill at the candle close (or the stop / target level, or the open when a stop gaps through)
+/// through the live execution model.
+pub fn run_backtest(
+    spec: &StrategySpec,
+    candles: &[Candle],
+    market: &BacktestMarket,
+    initial_cash_cents: i64,
+    periods_per_year: f64,
+) -> BacktestResult {
+    let closes: Vec<f64> = candles.iter().map(|c| c.close as f64 / 100.0).collect();
+    let series: HashMap<Indicator, Vec<Option<f64>>> = spec
+        .indicators()
+        .into_iter()
+        .map(|indicator| (indicator, indicator_series(indicator, &closes)))
+        .collect();
+
+    let mut cash = initial_cash_cents;
+    let mut position: Option<OpenPosition> = None;
+    let mut trades = Vec::new();
+    let mut equity_curve = Vec::with_capacity(candles.len());
+
+    for (i, candle) in candles.iter().enumerate() {
+        let is_last = i + 1 == candles.len();
+
+        if let Some(open) = &position {
+            let stop = spec
+                .stop_loss_percent
+                .map(|p| (open.entry_price as f64 * (1.0 - p / 100.0)).round() as i64);
+            let target = spec
+                .take_profit_percent
+                .map(|p| (open.entry_price as f64 * (1.0 + p / 100.0)).round() as i64);
+
+            // Stops are checked before targets so intrabar ambiguity resolves conservatively.
+            // A bar that opens below the stop gapped through it and fills at the open.
+            let exit = match (stop, target) {
+                (Some(stop), _) if candle.low <= stop => Some(("stop_loss", candle.open.min(stop))),
+                (_, Some(target)) if candle.high >= target => Some(("take_profit", target.max(candle.low))),
+                _ if spec.exit.iter().any(|rule| rule_holds(rule, i, &series)) => Some(("exit", candle.close)),
+                _ if is_last => Some(("end_of_test", candle.close)),
+                _ => None,
+            };
+
+            if let Some((reason, reference_price)) = exit {
+                let fill = simulate(OrderSide::Sell, open.quantity, reference_price, market);
+                cash += fill.cash_delta_cents(OrderSide::Sell);
+                trades.push(BacktestTrade {
+                    side: OrderSide::Sell,
+                    time: candle.open_time,
+                    reason,
+                    pnl_cents: Some(fill.notional_cents - fill.fee_cents - open.cost_cents),
+                    fill,
+                });
+                position = None;
+            }
+        } else if !is_last && spec.entry.iter().all(|rule| rule_holds(rule, i, &series)) {
+            let budget = (cash as f64 * spec.position_size_percent / 100.0) as i64;
+            let quantity = execution::affordable_quantity(budget, candle.close, market.asset.lot_size, market.schedule);
+
+            if check_order_size(market.asset, quantity, candle.close).is_ok() {
+                let fill = simulate(OrderSide::Buy, quantity, candle.close, market);
+                if cash + fill.cash_delta_cents(OrderSide::Buy) >= 0 {
+                    cash += fill.cash_delta_cents(OrderSide::Buy);
+                    position = Some(OpenPosition {
+                        quantity,
+                        entry_price: fill.price,
+                        cost_cents: fill.notional_cents + fill.fee_cents,
+                    });
+                    trades.push(BacktestTrade {
+                        side: OrderSide::Buy,
+                        time: candle.open_time,
+                        reason: "entry",
+                        fill,
+                        pnl_cents: None,
+                    });
+                }
+            }
+        }
+
+        let position_value = position
+            .as_ref()
+            .map(|p| execution::notional_cents(p.quantity, candle.close))
+            .unwrap_or(0);
+        equity_curve.push(EquityPoint {
+            time: candle.open_time,
+            equity_cents: cash + position_value,
+        });
+    }
+
+    summarize(initial_cash_cents, trades, equity_curve, periods_per_year)
+}
+
+/// Computes performance metrics from the trades and equity curve.
+fn summarize(
+    initial_cash_cents: i64,
+    trades: Vec<BacktestTrade>,
+    equity_curve: Vec<EquityPoint>,
+    periods_per_year: f64,
+) -> BacktestResult {
+    let equity: Vec<f64> = equity_curve.iter().map(|p| p.equity_cents as f64).collect();
+    let final_equity_cents = equity_curve.last().map(|p| p.equity_cents).unwrap_or(initial_cash_cents);
+
+    let closed: Vec<i64> = trades.iter().filter_map(|t| t.pnl_cents).collect();
+    let win_rate = if closed.is_empty() {
+        None
+    } else {
+        Some(closed.iter().filter(|pnl| **pnl > 0).count() as f64 / closed.len() as f64)
+    };
+
+    BacktestResult {
+        initial_cash_cents,
+        final_equity_cents,
+        total_return: if initial_cash_cents > 0 {
+            final_equity_cents as f64 / initial_cash_cents as f64 - 1.0
+        } else {
+            0.0
+        },
+        max_drawdown: analytics::max_drawdown(&equity),
+        sharpe_ratio: analytics::sharpe_ratio(&analytics::simple_returns(&equity), periods_per_year),
+        win_rate,
+        total_fees_cents: trades.iter().map(|t| t.fill.fee_cents).sum(),
+        total_slippage_cents: trades.iter().map(|t| t.fill.slippage_cents).sum(),
+        trades,
+        equity_curve,
+    }
+}
+
+/// Simulates a taker fill with the asset's tick size and the user's fee tier.
+fn simulate(side: OrderSide, quantity: i64, reference_price: i64, market: &BacktestMarket) -> execution::Fill {
+    execution::simulate_fill(
+        side,
+        quantity,
+        reference_price,
+        market.asset.tick_size_cents,
+        market.schedule,
+        Liquidity::Taker,
+        market.tier,
+    )
+}
+
+/// Computes an indicator's value for every candle.
+fn indicator_series(indicator: Indicator, closes: &[f64]) -> Vec<Option<f64>> {
+    match indicator {
+        Indicator::Price => closes.iter().map(|c| Some(*c)).collect(),
+        Indicator::Sma { period } => indicators::sma(closes, period as usize),
+        Indicator::Ema { period } => indicators::ema(closes, period as usize),
+        Indicator::Rsi { period } => indicators::rsi(closes, period as usize),
+    }
+}
+
+/// Returns an operand's value at a candle, if available.
+fn operand_value(operand: Operand, i: usize, series: &HashMap<Indicator, Vec<Option<f64>>>) -> Option<f64> {
+    match operand {
+        Operand::Value(value) => Some(value),
+        Operand::Indicator(indicator) => series.get(&indicator).and_then(|values| values[i]),
+    }
+}
+
+/// Evaluates a rule at a candle. Rules with missing data never hold.
+fn rule_holds(rule: &Rule, i: usize, series: &HashMap<Indicator, Vec<Option<f64>>>) -> bool {
+    let current = (
+        operand_value(rule.left, i, series),
+        operand_value(rule.right, i, series),
+    );
+    let (Some(left), Some(right)) = current else {
+        return false;
+    };
+
+    let previous = || {
+        if i == 0 {
+            return None;
+        }
+        Some((
+            operand_value(rule.left, i - 1, series)?,
+            operand_value(rule.right, i - 1, series)?,
+        ))
+    };
+
+    match rule.condition {
+        Condition::Above => left > right,
+        Condition::Below => left < right,
+        Condition::CrossesAbove => left > right && previous().is_some_and(|(l, r)| l <= r),
+        Condition::CrossesBelow => left < right && previous().is_some_and(|(l, r)| l >= r),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::fixtures::{asset, base_tier, candle, free_schedule};
+
+    /// Quantity bought by the always-on strategy: all cash at the $100 entry close.
+    const QUANTITY: i64 = 100_000_000;
+
+    /// Enters on the first candle and holds until a stop, target or the end of the test.
+    fn always_in(stop_loss_percent: Option<f64>, take_profit_percent: Option<f64>) -> StrategySpec {
+        StrategySpec {
+            entry: vec![Rule {
+                left: Operand::Indicator(Indicator::Price),
+                condition: Condition::Above,
+                right: Operand::Value(0.0),
+            }],
+            exit: Vec::new(),
+            stop_loss_percent,
+            take_profit_percent,
+            position_size_percent: 100.0,
+        }
+    }
+
+    fn run(spec: &StrategySpec, candles: &[Candle]) -> BacktestResult {
+        let asset = asset("ETH", 1_000, 100);
+        let schedule = free_schedule();
+        let tier = base_tier();
+        let market = BacktestMarket {
+            asset: &asset,
+            schedule: &schedule,
+            tier: &tier,
+        };
+        run_backtest(spec, candles, &market, 1_000_000, 8760.0)
+    }
+
+    /// Reason and fill price of the closing trade.
+    fn exit(result: &BacktestResult) -> (&'static str, i64) {
+        let trade = result.trades.last().unwrap();
+        assert_eq!(trade.side, OrderSide::Sell);
+        (trade.reason, trade.fill.price)
+    }
+
+    #[test]
+    fn stop_wins_when_a_candle_reaches_both_stop_and_target() {
+        let spec = always_in(Some(10.0), Some(20.0));
+        let result = run(&spec, &[candle(0, 10_000, 10_000, 10_000, 10_000), candle(1, 10_000, 13_000, 8_500, 11_000)]);
+
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[0].fill.quantity, QUANTITY);
+        assert_eq!(exit(&result), ("stop_loss", 9_000));
+        assert_eq!(result.trades[1].pnl_cents, Some(-100_000));
+        assert_eq!(result.final_equity_cents, 900_000);
+    }
+
+    #[test]
+    fn stop_fills_at_the_open_when_a_candle_gaps_through_it() {
+        let spec = always_in(Some(10.0), Some(20.0));
+        let result = run(&spec, &[candle(0, 10_000, 10_000, 10_000, 10_000), candle(1, 8_000, 8_200, 7_800, 8_100)]);
+
+        assert_eq!(exit(&result), ("stop_loss", 8_000));
+        assert_eq!(result.trades[1].pnl_cents, Some(-200_000));
+    }
+
+    #[test]
+    fn target_fills_at_its_level_or_the_low_of_a_gap_up() {
+        let spec = always_in(Some(10.0), Some(20.0));
+        let result = run(&spec, &[candle(0, 10_000, 10_000, 10_000, 10_000), candle(1, 10_500, 12_500, 10_000, 12_200)]);
+        assert_eq!(exit(&result), ("take_profit", 12_000));
+        assert_eq!(result.trades[1].pnl_cents, Some(200_000));
+
+        let result = run(&spec, &[candle(0, 10_000, 10_000, 10_000, 10_000), candle(1, 13_000, 13_500, 12_800, 13_200)]);
+        assert_eq!(exit(&result), ("take_profit", 12_800));
+    }
+
+    #[test]
+    fn open_position_is_closed_on_the_last_candle() {
+        let spec = always_in(None, None);
+        let candles = [
+            candle(0, 10_000, 10_000, 10_000, 10_000),
+            candle(1, 10_000, 10_600, 9_800, 10_500),
+            candle(2, 10_500, 11_200, 10_400, 11_000),
+        ];
+        let result = run(&spec, &candles);
+
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[1].time, candles[2].open_time);
+        assert_eq!(exit(&result), ("end_of_test", 11_000));
+        assert_eq!(result.final_equity_cents, 1_100_000);
+        assert_eq!(result.equity_curve.len(), 3);
+        assert_eq!(result.equity_curve[1].equity_cents, 1_050_000);
+        assert_eq!(result.win_rate, Some(1.0));
+    }
+
+    #[test]
+    fn no_position_is_opened_on_the_last_candle() {
+        let result = run(&always_in(None, None), &[candle(0, 10_000, 10_000, 10_000, 10_000)]);
+
+        assert!(result.trades.is_empty());
+        assert_eq!(result.final_equity_cents, 1_000_000);
+        assert_eq!(result.win_rate, None);
+    }
+}
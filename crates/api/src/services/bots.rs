@@ -0,0 +1,217 @@
+//! Server-side trading bots.
+//! Places due DCA buys and grid trades through the trading service and computes per-bot P&L.
+
+use chrono::{DateTime, Duration, Utc};
+use tracing::warn;
+
+use db::models::Bot;
+use db::queries::{assets, bots};
+use db::queries::bots::BotTradeStats;
+
+use crate::services::execution::{self, OrderSide, MICRO_UNITS};
+use crate::services::trading::{execute_market_order, MarketOrder, TradingError};
+use crate::state::AppState;
+
+/// Maximum number of bots a user may own.
+pub const MAX_BOTS_PER_USER: i64 = 20;
+
+/// Shortest allowed time between DCA buys, in seconds.
+pub const MIN_DCA_INTERVAL_SECONDS: i64 = 60;
+
+/// Number of grid lines at or below a price.
+/// Lines are spaced evenly from `lower_cents` to `upper_cents` inclusive.
+pub fn grid_level_for_price(lower_cents: i64, upper_cents: i64, levels: i16, price_cents: i64) -> i16 {
+    if price_cents < lower_cents {
+        return 0;
+    }
+    if price_cents >= upper_cents {
+        return levels;
+    }
+
+    let steps = (levels - 1) as i128;
+    let offset = (price_cents - lower_cents) as i128 * steps / (upper_cents - lower_cents) as i128;
+    offset as i16 + 1
+}
+
+/// Returns the first run time after `now` on an interval schedule that was due at `due`.
+/// Runs missed while the server was down or the schedule was paused are skipped.
+pub fn next_scheduled_run(due: DateTime<Utc>, interval_seconds: i32, now: DateTime<Utc>) -> DateTime<Utc> {
+    let interval = i64::from(interval_seconds).max(MIN_DCA_INTERVAL_SECONDS);
+    if due > now {
+        return due;
+    }
+
+    let missed = (now - due).num_seconds() / interval + 1;
+    due + Duration::seconds(missed * interval)
+}
+
+/// Profit and loss of a bot's own trades.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BotPnl {
+    /// Number of trades placed.
+    pub trade_count: i64,
+    /// Quantity still held from the bot's buys, in micro units.
+    pub held_quantity: i64,
+    /// Cash spent on buys including fees, in cents.
+    pub invested_cents: i64,
+    /// P&L of sold quantity against the average buy cost, in cents.
+    pub realized_pnl_cents: i64,
+    /// P&L of held quantity at the current price, in cents.
+    pub unrealized_pnl_cents: i64,
+    /// Fees paid, in cents.
+    pub fees_cents: i64,
+}
+
+/// Computes a bot's P&L from its trade statistics.
+/// Held quantity is valued at `price_cents`, or at cost when no price is known.
+pub fn bot_pnl(stats: &BotTradeStats, price_cents: Option<i64>) -> BotPnl {
+    let held_quantity = stats.bought_quantity - stats.sold_quantity;
+    // Average buy cost per micro unit, fees included
+    let cost_of = |quantity: i64| {
+        if stats.bought_quantity == 0 {
+            return 0;
+        }
+        (quantity as i128 * stats.buy_cost_cents as i128 / stats.bought_quantity as i128) as i64
+    };
+
+    let held_cost = cost_of(held_quantity);
+    let held_value = price_cents
+        .map(|price| execution::notional_cents(held_quantity, price))
+        .unwrap_or(held_cost);
+
+    BotPnl {
+        trade_count: stats.trade_count,
+        held_quantity,
+        invested_cents: stats.buy_cost_cents,
+        realized_pnl_cents: stats.sell_proceeds_cents - cost_of(stats.sold_quantity),
+        unrealized_pnl_cents: held_value - held_cost,
+        fees_cents: stats.fees_cents,
+    }
+}
+
+/// Runs every due DCA bot and every active grid bot once.
+/// A bot that fails is logged and skipped so it cannot hold up other users' bots.
+pub async fn run_due_bots(state: &AppState, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    for bot in bots::list_due_dca_bots(&state.db_pool, now).await? {
+        if let Err(e) = run_dca_bot(state, &bot, now).await {
+            warn!("⚠️ DCA bot {} run failed: {}", bot.id, e);
+        }
+    }
+    for bot in bots::list_active_grid_bots(&state.db_pool).await? {
+        if let Err(e) = run_grid_bot(state, &bot).await {
+            warn!("⚠️ Grid bot {} run failed: {}", bot.id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Buys the bot's fixed amount at the current price and schedules the next buy.
+async fn run_dca_bot(state: &AppState, bot: &Bot, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    let (Some(amount_cents), Some(interval_seconds), Some(due)) =
+        (bot.amount_cents, bot.interval_seconds, bot.next_run_at)
+    else {
+        return Ok(());
+    };
+
+    let result = match dca_quantity(state, &bot.symbol, amount_cents).await {
+        Ok(quantity) => place_bot_order(state, bot, OrderSide::Buy, quantity).await,
+        Err(e) => Err(e),
+    };
+    let error = order_error(bot, result)?;
+
+    bots::record_bot_run(
+        &state.db_pool,
+        bot.id,
+        Some(next_scheduled_run(due, interval_seconds, now)),
+        None,
+        error.as_deref(),
+    )
+    .await
+}
+
+/// Trades one grid quantity per grid line the price crossed since the last run.
+/// Buys on the way down and sells the bot's own holdings on the way up. The grid position only
+/// moves once the order filled, so a failed order is retried on the next run.
+async fn run_grid_bot(state: &AppState, bot: &Bot) -> Result<(), sqlx::Error> {
+    let (Some(lower), Some(upper), Some(levels), Some(grid_quantity), Some(current_level)) = (
+        bot.grid_lower_cents,
+        bot.grid_upper_cents,
+        bot.grid_levels,
+        bot.grid_quantity,
+        bot.grid_level,
+    ) else {
+        return Ok(());
+    };
+    let Some(price) = state.oracle.price(&bot.symbol) else {
+        return Ok(());
+    };
+
+    let level = grid_level_for_price(lower, upper, levels, price);
+    if level == current_level {
+        return Ok(());
+    }
+
+    let crossed = i64::from((level - current_level).abs());
+    let (side, quantity) = if level < current_level {
+        (OrderSide::Buy, crossed * grid_quantity)
+    } else {
+        let held = bots::bot_held_quantity(&state.db_pool, bot.id).await?;
+        (OrderSide::Sell, (crossed * grid_quantity).min(held))
+    };
+
+    // Nothing to sell yet, only move the grid position
+    let error = if quantity > 0 {
+        order_error(bot, place_bot_order(state, bot, side, quantity).await)?
+    } else {
+        None
+    };
+
+    let grid_level = error.is_none().then_some(level);
+    bots::record_bot_run(&state.db_pool, bot.id, None, grid_level, error.as_deref()).await
+}
+
+/// Converts a DCA amount into a lot-aligned quantity at the current price.
+async fn dca_quantity(state: &AppState, symbol: &str, amount_cents: i64) -> Result<i64, TradingError> {
+    let asset = assets::find_asset(&state.db_pool, symbol)
+        .await?
+        .ok_or_else(|| TradingError::UnknownSymbol(symbol.to_string()))?;
+    let price = state
+        .oracle
+        .price(symbol)
+        .ok_or_else(|| TradingError::NoPrice(symbol.to_string()))?;
+
+    let quantity = (amount_cents as i128 * MICRO_UNITS as i128 / price as i128) as i64;
+    Ok(quantity / asset.lot_size * asset.lot_size)
+}
+
+/// Places a market order on behalf of a bot.
+async fn place_bot_order(
+    state: &AppState,
+    bot: &Bot,
+    side: OrderSide,
+    quantity: i64,
+) -> Result<(), TradingError> {
+    let order = MarketOrder {
+        symbol: bot.symbol.clone(),
+        side,
+        quantity,
+        bot_id: Some(bot.id),
+    };
+    execute_market_order(state, bot.user_id, &order).await?;
+
+    Ok(())
+}
+
+/// Turns an order outcome into the error recorded on the bot.
+/// Database failures abort the scheduler run instead.
+fn order_error(bot: &Bot, result: Result<(), TradingError>) -> Result<Option<String>, sqlx::Error> {
+    match result {
+        Ok(()) => Ok(None),
+        Err(TradingError::Database(e)) => Err(e),
+        Err(e) => {
+            warn!("⚠️ Bot {} order failed: {}", bot.id, e);
+            Ok(Some(e.to_string()))
+        }
+    }
+}
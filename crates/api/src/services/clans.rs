@@ -0,0 +1,441 @@
+//! Clan service.
+//! Manages clan membership and roles, invite codes, clan leaderboards and clan tournament entries.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::Serialize;
+use sqlx::{Connection, PgConnection};
+use thiserror::Error;
+use tracing::info;
+use uuid::Uuid;
+
+use db::models::{Clan, ClanMember, Notification, TournamentClan};
+use db::queries::clans::{self, NewClan, RankedClan};
+use db::queries::{notifications, tournaments};
+use game::clans::{INVITE_CODE_LENGTH, MAX_CLAN_MEMBERS};
+use game::seasons::SEASON_RESET_XP_SOURCE;
+use game::{ClanMetric, ClanRole, LeaderboardWindow, TraderActivity};
+
+use crate::services::notifications::deliver_to_webhooks;
+use crate::services::tournaments::schedule;
+use crate::state::SharedState;
+
+/// Notification kind used for membership and role changes.
+pub const CLAN_KIND: &str = "clan";
+
+/// Characters invite codes are made of, leaving out easily confused ones.
+const INVITE_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Invite codes generated before giving up when each one is already held by another clan.
+const INVITE_CODE_ATTEMPTS: usize = 5;
+
+/// Errors that can occur while managing a clan.
+#[derive(Error, Debug)]
+pub enum ClanError {
+    #[error("Not a member of a clan")]
+    NotMember,
+    #[error("Already a member of a clan")]
+    AlreadyInClan,
+    #[error("Clan name or tag is already taken")]
+    NameTaken,
+    #[error("Tag must be 2-5 letters or digits")]
+    InvalidTag,
+    #[error("Invalid invite code")]
+    InvalidInviteCode,
+    #[error("No unused invite code could be generated")]
+    InviteCodeUnavailable,
+    #[error("Clan is full")]
+    Full,
+    #[error("Member not found")]
+    MemberNotFound,
+    #[error("Your role does not allow this")]
+    NotAllowed,
+    #[error("Cannot do this to yourself")]
+    SelfTarget,
+    #[error("Tournament not found")]
+    TournamentNotFound,
+    #[error("Tournament registration is not open")]
+    RegistrationClosed,
+    #[error("Clan is already entered in this tournament")]
+    AlreadyEntered,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Event-specific data included in clan webhook payloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClanEventData {
+    /// Clan the change happened in.
+    pub clan_id: Uuid,
+    /// "removed" or "role_changed".
+    pub event: &'static str,
+    /// Member's role afterwards, absent once removed.
+    pub role: Option<&'static str>,
+}
+
+/// Result of leaving a clan.
+#[derive(Debug, Clone, Copy)]
+pub struct LeftClan {
+    /// Clan left.
+    pub clan_id: Uuid,
+    /// Whether the clan was deleted because its last member left.
+    pub disbanded: bool,
+    /// Member who took over when the owner left.
+    pub new_owner: Option<Uuid>,
+}
+
+/// User's locked clan and their role in it.
+struct Membership {
+    clan: Clan,
+    role: ClanRole,
+}
+
+/// Generates a random invite code.
+pub fn generate_invite_code() -> String {
+    let mut rng = rand::rng();
+    (0..INVITE_CODE_LENGTH)
+        .map(|_| INVITE_CODE_ALPHABET[rng.random_range(0..INVITE_CODE_ALPHABET.len())] as char)
+        .collect()
+}
+
+/// Returns a member's role, treating unknown stored values as a regular member.
+pub fn member_role(member: &ClanMember) -> ClanRole {
+    ClanRole::parse(&member.role).unwrap_or(ClanRole::Member)
+}
+
+/// Founds a clan with the user as its owner.
+pub async fn create_clan(
+    state: &SharedState,
+    user_id: Uuid,
+    name: &str,
+    tag: &str,
+    description: Option<&str>,
+) -> Result<Clan, ClanError> {
+    let tag = tag.trim().to_uppercase();
+    if !(2..=5).contains(&tag.len()) || !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(ClanError::InvalidTag);
+    }
+
+    let mut tx = state.db_pool.begin().await?;
+    if clans::find_membership(&mut *tx, user_id).await?.is_some() {
+        return Err(ClanError::AlreadyInClan);
+    }
+
+    let mut created = None;
+    for _ in 0..INVITE_CODE_ATTEMPTS {
+        let new_clan = NewClan {
+            name: name.trim(),
+            tag: &tag,
+            description,
+            invite_code: &generate_invite_code(),
+            created_by: user_id,
+        };
+        match clans::create_clan(&mut tx, &new_clan).await {
+            Ok(Some(clan)) => {
+                created = Some(clan);
+                break;
+            }
+            // The invite code is held by another clan; try a fresh one
+            Ok(None) => continue,
+            Err(e) if violates(&e, &clans::CLAN_NAME_INDEXES) => return Err(ClanError::NameTaken),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let clan = created.ok_or(ClanError::InviteCodeUnavailable)?;
+    clans::add_member(&mut tx, clan.id, user_id, ClanRole::Owner.as_str())
+        .await?
+        .ok_or(ClanError::AlreadyInClan)?;
+    tx.commit().await?;
+
+    info!("🛡️ User {} founded clan [{}] {}", user_id, clan.tag, clan.name);
+    Ok(clan)
+}
+
+/// Joins the clan an invite code belongs to.
+pub async fn join_clan(state: &SharedState, user_id: Uuid, invite_code: &str) -> Result<Clan, ClanError> {
+    let invite_code = invite_code.trim().to_uppercase();
+
+    let mut tx = state.db_pool.begin().await?;
+    let clan = clans::lock_clan_by_invite_code(&mut tx, &invite_code)
+        .await?
+        .ok_or(ClanError::InvalidInviteCode)?;
+    if clans::count_members(&mut *tx, clan.id).await? >= MAX_CLAN_MEMBERS {
+        return Err(ClanError::Full);
+    }
+    clans::add_member(&mut tx, clan.id, user_id, ClanRole::Member.as_str())
+        .await?
+        .ok_or(ClanError::AlreadyInClan)?;
+    tx.commit().await?;
+
+    Ok(clan)
+}
+
+/// Leaves the user's clan.
+/// When the owner leaves, the longest-standing officer, or member if there are none, takes over;
+/// the clan is deleted when its last member leaves.
+pub async fn leave_clan(state: &SharedState, user_id: Uuid) -> Result<LeftClan, ClanError> {
+    let mut tx = state.db_pool.begin().await?;
+    let Membership { clan, role } = lock_membership(&mut tx, user_id).await?;
+
+    let successor = match role {
+        ClanRole::Owner => clans::find_successor(&mut *tx, clan.id, user_id).await?,
+        _ => None,
+    };
+    clans::remove_member(&mut tx, clan.id, user_id).await?;
+
+    let mut announcement = None;
+    let disbanded = role == ClanRole::Owner && successor.is_none();
+    if disbanded {
+        clans::delete_clan(&mut tx, clan.id).await?;
+    } else if let Some(successor) = &successor {
+        clans::set_role(&mut tx, clan.id, successor.user_id, ClanRole::Owner.as_str()).await?;
+        let message = format!("The owner left [{}] {} and you are now its owner", clan.tag, clan.name);
+        let notification =
+            notifications::create_notification(&mut *tx, successor.user_id, CLAN_KIND, "Clan ownership", &message, None)
+                .await?;
+        announcement = Some((notification, role_changed(&clan, ClanRole::Owner)));
+    }
+    tx.commit().await?;
+
+    if disbanded {
+        info!("🛡️ Clan [{}] {} disbanded", clan.tag, clan.name);
+    }
+    announce(state, announcement);
+
+    Ok(LeftClan {
+        clan_id: clan.id,
+        disbanded,
+        new_owner: successor.map(|member| member.user_id),
+    })
+}
+
+/// Removes a member from the user's clan.
+/// The owner can remove anyone; officers can only remove regular members.
+pub async fn kick_member(state: &SharedState, user_id: Uuid, target_id: Uuid) -> Result<(), ClanError> {
+    if user_id == target_id {
+        return Err(ClanError::SelfTarget);
+    }
+
+    let mut tx = state.db_pool.begin().await?;
+    let Membership { clan, role } = lock_membership(&mut tx, user_id).await?;
+    let target = find_clan_member(&mut tx, &clan, target_id).await?;
+    if !role.can_kick(member_role(&target)) {
+        return Err(ClanError::NotAllowed);
+    }
+    clans::remove_member(&mut tx, clan.id, target_id).await?;
+
+    let message = format!("You were removed from [{}] {}", clan.tag, clan.name);
+    let notification =
+        notifications::create_notification(&mut *tx, target_id, CLAN_KIND, "Removed from clan", &message, None).await?;
+    tx.commit().await?;
+
+    let data = ClanEventData {
+        clan_id: clan.id,
+        event: "removed",
+        role: None,
+    };
+    announce(state, Some((notification, data)));
+    Ok(())
+}
+
+/// Changes a member's role. Only the owner can assign roles; making someone the owner hands over
+/// ownership and makes the previous owner an officer.
+pub async fn set_member_role(
+    state: &SharedState,
+    user_id: Uuid,
+    target_id: Uuid,
+    new_role: ClanRole,
+) -> Result<ClanMember, ClanError> {
+    if user_id == target_id {
+        return Err(ClanError::SelfTarget);
+    }
+
+    let mut tx = state.db_pool.begin().await?;
+    let Membership { clan, role } = lock_membership(&mut tx, user_id).await?;
+    if !role.can_assign_roles() {
+        return Err(ClanError::NotAllowed);
+    }
+    find_clan_member(&mut tx, &clan, target_id).await?;
+
+    // Demote first so the clan never has two owners
+    if new_role == ClanRole::Owner {
+        clans::set_role(&mut tx, clan.id, user_id, ClanRole::Officer.as_str()).await?;
+    }
+    let member = clans::set_role(&mut tx, clan.id, target_id, new_role.as_str())
+        .await?
+        .ok_or(ClanError::MemberNotFound)?;
+
+    let message = format!("You are now {} of [{}] {}", role_title(new_role), clan.tag, clan.name);
+    let notification =
+        notifications::create_notification(&mut *tx, target_id, CLAN_KIND, "Clan role changed", &message, None).await?;
+    tx.commit().await?;
+
+    announce(state, Some((notification, role_changed(&clan, new_role))));
+    Ok(member)
+}
+
+/// Replaces the invite code of the user's clan, invalidating the old one.
+pub async fn regenerate_invite_code(state: &SharedState, user_id: Uuid) -> Result<Clan, ClanError> {
+    let mut tx = state.db_pool.begin().await?;
+    let Membership { clan, role } = lock_membership(&mut tx, user_id).await?;
+    if !role.can_manage() {
+        return Err(ClanError::NotAllowed);
+    }
+
+    for _ in 0..INVITE_CODE_ATTEMPTS {
+        // A collision aborts the statement, so each attempt runs in a savepoint that can be rolled back
+        let mut attempt = tx.begin().await?;
+        match clans::set_invite_code(&mut attempt, clan.id, &generate_invite_code()).await {
+            Ok(clan) => {
+                attempt.commit().await?;
+                tx.commit().await?;
+                return Ok(clan);
+            }
+            Err(e) if violates(&e, &[clans::INVITE_CODE_CONSTRAINT]) => attempt.rollback().await?,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(ClanError::InviteCodeUnavailable)
+}
+
+/// Enters the user's clan into a tournament while registration is open.
+/// Members who registered already play for the clan, as do members who register later.
+pub async fn enter_tournament(
+    state: &SharedState,
+    user_id: Uuid,
+    tournament_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<TournamentClan, ClanError> {
+    let mut tx = state.db_pool.begin().await?;
+    let Membership { clan, role } = lock_membership(&mut tx, user_id).await?;
+    if !role.can_manage() {
+        return Err(ClanError::NotAllowed);
+    }
+
+    let tournament = tournaments::lock_tournament(&mut tx, tournament_id)
+        .await?
+        .ok_or(ClanError::TournamentNotFound)?;
+    if tournament.finalized_at.is_some() || !schedule(&tournament).is_registration_open(now.timestamp()) {
+        return Err(ClanError::RegistrationClosed);
+    }
+
+    let entry = clans::enter_tournament(&mut tx, tournament_id, clan.id, user_id)
+        .await?
+        .ok_or(ClanError::AlreadyEntered)?;
+    tx.commit().await?;
+
+    info!("🛡️ Clan [{}] {} entered tournament {}", clan.tag, clan.name, tournament.name);
+    Ok(entry)
+}
+
+/// Recomputes the clan leaderboards of a window starting at `since` from members' activity in it.
+/// Members only add the XP they earned since joining their clan.
+/// Returns the number of entries written.
+pub async fn refresh_clan_leaderboards(
+    state: &SharedState,
+    window: LeaderboardWindow,
+    since: Option<DateTime<Utc>>,
+    activity: &HashMap<Uuid, TraderActivity>,
+    now: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let mut members: HashMap<Uuid, Vec<TraderActivity>> = HashMap::new();
+    for (clan_id, user_id, xp) in
+        clans::list_members_with_xp(&state.db_pool, since, now, SEASON_RESET_XP_SOURCE).await?
+    {
+        let member = TraderActivity {
+            xp,
+            ..activity.get(&user_id).cloned().unwrap_or_default()
+        };
+        members.entry(clan_id).or_default().push(member);
+    }
+
+    let mut written = 0;
+    for metric in ClanMetric::ALL {
+        let scored: Vec<((Uuid, i32), f64)> = members
+            .iter()
+            .filter_map(|(clan_id, members)| Some(((*clan_id, members.len() as i32), metric.score(members)?)))
+            .collect();
+        let ranked: Vec<RankedClan> = game::rank_scores(scored)
+            .into_iter()
+            .map(|((clan_id, members), rank, score)| RankedClan {
+                clan_id,
+                rank,
+                score,
+                members,
+            })
+            .collect();
+        written +=
+            clans::replace_clan_leaderboard(&state.db_pool, metric.as_str(), window.as_str(), &ranked, now).await?;
+    }
+
+    Ok(written)
+}
+
+/// Finds the user's membership and locks their clan, serializing changes to it.
+async fn lock_membership(conn: &mut PgConnection, user_id: Uuid) -> Result<Membership, ClanError> {
+    let member = clans::find_membership(&mut *conn, user_id)
+        .await?
+        .ok_or(ClanError::NotMember)?;
+    let clan = clans::lock_clan(conn, member.clan_id)
+        .await?
+        .ok_or(ClanError::NotMember)?;
+
+    // Re-read under the lock in case the membership changed in between
+    let member = clans::find_membership(&mut *conn, user_id)
+        .await?
+        .filter(|member| member.clan_id == clan.id)
+        .ok_or(ClanError::NotMember)?;
+    let role = member_role(&member);
+
+    Ok(Membership { clan, role })
+}
+
+/// Returns whether an error is a unique violation of one of the given constraints or indexes.
+fn violates(error: &sqlx::Error, constraints: &[&str]) -> bool {
+    match error {
+        sqlx::Error::Database(db_err) => {
+            db_err.is_unique_violation() && db_err.constraint().is_some_and(|name| constraints.contains(&name))
+        }
+        _ => false,
+    }
+}
+
+/// Finds a member of a locked clan.
+async fn find_clan_member(conn: &mut PgConnection, clan: &Clan, user_id: Uuid) -> Result<ClanMember, ClanError> {
+    clans::find_membership(conn, user_id)
+        .await?
+        .filter(|member| member.clan_id == clan.id)
+        .ok_or(ClanError::MemberNotFound)
+}
+
+/// Returns the webhook data for a role change.
+fn role_changed(clan: &Clan, role: ClanRole) -> ClanEventData {
+    ClanEventData {
+        clan_id: clan.id,
+        event: "role_changed",
+        role: Some(role.as_str()),
+    }
+}
+
+/// Returns a role as used in notification messages.
+fn role_title(role: ClanRole) -> &'static str {
+    match role {
+        ClanRole::Owner => "the owner",
+        ClanRole::Officer => "an officer",
+        ClanRole::Member => "a member",
+    }
+}
+
+/// Delivers a clan notification to webhooks in the background.
+fn announce(state: &SharedState, announcement: Option<(Notification, ClanEventData)>) {
+    let Some((notification, data)) = announcement else {
+        return;
+    };
+    let state = state.clone();
+    tokio::spawn(async move {
+        deliver_to_webhooks(&state, &notification, data).await;
+    });
+}
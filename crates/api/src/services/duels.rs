@@ -0,0 +1,574 @@
+//! Duel service.
+//! Issues and answers challenges, trades the players' duel accounts and settles ended duels.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use db::models::{Duel, DuelAccount, DuelPosition, DuelTrade, Notification};
+use db::queries::{assets, duels, notifications, users, xp};
+use game::duels::{CHALLENGE_TTL_HOURS, DUEL_XP_SOURCE, MAX_DUEL_HOURS, MAX_DUEL_STAKE_XP, MIN_DUEL_HOURS};
+use game::{DuelResult, DuelStatus};
+
+use crate::services::isolated::{self, IsolatedAccount};
+use crate::services::notifications::deliver_to_webhooks;
+use crate::services::ratings;
+use crate::services::trading::{MarketOrder, TradingError};
+use crate::state::SharedState;
+
+/// Notification kind used for challenges received.
+pub const DUEL_CHALLENGE_KIND: &str = "duel_challenge";
+
+/// Notification kind used when a challenge is accepted or declined.
+pub const DUEL_RESPONSE_KIND: &str = "duel_response";
+
+/// Notification kind used for duel results.
+pub const DUEL_RESULT_KIND: &str = "duel_result";
+
+/// Errors that can occur while challenging, trading or settling a duel.
+#[derive(Error, Debug)]
+pub enum DuelError {
+    #[error("Duel not found")]
+    NotFound,
+    #[error("Opponent not found")]
+    OpponentNotFound,
+    #[error("You cannot challenge yourself")]
+    SelfChallenge,
+    #[error("Duel length must be between {MIN_DUEL_HOURS} and {MAX_DUEL_HOURS} hours")]
+    InvalidDuration,
+    #[error("Stake must be between 0 and {MAX_DUEL_STAKE_XP} XP")]
+    InvalidStake,
+    #[error("Unknown symbol {0}")]
+    UnknownSymbol(String),
+    #[error("{0} is not open for trading")]
+    InactiveSymbol(String),
+    #[error("No symbols are available to trade")]
+    NoSymbols,
+    #[error("Not enough XP to stake: {required} required, {available} available")]
+    InsufficientXp { required: i32, available: i32 },
+    #[error("These players already have an open duel")]
+    AlreadyOpen,
+    #[error("Not a player in this duel")]
+    NotParticipant,
+    #[error("Only the challenged player can answer")]
+    NotOpponent,
+    #[error("Challenge is no longer pending")]
+    NotPending,
+    #[error("Duel is not active")]
+    NotActive,
+    #[error("{0} cannot be traded in this duel")]
+    SymbolNotAllowed(String),
+    #[error(transparent)]
+    Trading(#[from] TradingError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Terms of a challenge.
+#[derive(Debug, Clone)]
+pub struct DuelChallenge {
+    /// Wallet address of the user being challenged.
+    pub opponent_wallet: String,
+    /// Paper cash both players start with, in cents.
+    pub starting_balance_cents: i64,
+    /// Symbols both players may trade. Empty for every active asset.
+    pub allowed_symbols: Vec<String>,
+    /// Trading time once accepted, in hours.
+    pub duration_hours: i32,
+    /// XP each player puts up.
+    pub stake_xp: i32,
+}
+
+/// Event-specific data included in duel webhook payloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuelEventData {
+    /// Duel the event belongs to.
+    pub duel_id: Uuid,
+    /// Duel status after the event.
+    pub status: &'static str,
+    /// XP each player puts up.
+    pub stake_xp: i32,
+    /// XP paid out to the recipient, once settled.
+    pub xp_payout: Option<i32>,
+}
+
+/// Both players' account values in a duel.
+#[derive(Debug, Clone, Copy)]
+pub struct DuelScore {
+    /// Challenger's account value in cents.
+    pub challenger_value_cents: i64,
+    /// Opponent's account value in cents.
+    pub opponent_value_cents: i64,
+}
+
+/// Player's account in a duel.
+#[derive(Debug, Clone)]
+pub struct DuelAccountView {
+    /// Account with the cash balance.
+    pub account: DuelAccount,
+    /// Open positions.
+    pub positions: Vec<DuelPosition>,
+    /// Cash plus positions at current oracle prices, or the final value once settled, in cents.
+    pub value_cents: i64,
+}
+
+/// Returns the duel's status, treating unknown values as pending.
+pub fn status(duel: &Duel) -> DuelStatus {
+    DuelStatus::parse(&duel.status).unwrap_or(DuelStatus::Pending)
+}
+
+/// Returns whether a user is one of the duel's players.
+pub fn is_participant(duel: &Duel, user_id: Uuid) -> bool {
+    duel.challenger_id == user_id || duel.opponent_id == user_id
+}
+
+/// Issues a challenge to another user and notifies them.
+/// The challenger must hold the stake now; both stakes are escrowed when the opponent accepts.
+pub async fn challenge(
+    state: &SharedState,
+    challenger_id: Uuid,
+    terms: &DuelChallenge,
+    now: DateTime<Utc>,
+) -> Result<Duel, DuelError> {
+    if !(MIN_DUEL_HOURS..=MAX_DUEL_HOURS).contains(&terms.duration_hours) {
+        return Err(DuelError::InvalidDuration);
+    }
+    if !(0..=MAX_DUEL_STAKE_XP).contains(&terms.stake_xp) {
+        return Err(DuelError::InvalidStake);
+    }
+
+    let opponent = users::find_user_by_wallet(&state.db_pool, &terms.opponent_wallet)
+        .await?
+        .ok_or(DuelError::OpponentNotFound)?;
+    if opponent.id == challenger_id {
+        return Err(DuelError::SelfChallenge);
+    }
+    let challenger = users::find_user_by_id(&state.db_pool, challenger_id)
+        .await?
+        .ok_or(DuelError::NotFound)?;
+    if challenger.xp_points < terms.stake_xp {
+        return Err(DuelError::InsufficientXp {
+            required: terms.stake_xp,
+            available: challenger.xp_points,
+        });
+    }
+
+    let allowed_symbols = if terms.allowed_symbols.is_empty() {
+        assets::list_assets(&state.db_pool, Some("active"))
+            .await?
+            .into_iter()
+            .map(|asset| asset.symbol)
+            .collect()
+    } else {
+        for symbol in &terms.allowed_symbols {
+            match assets::find_asset(&state.db_pool, symbol).await? {
+                Some(asset) if asset.status == "active" => {}
+                Some(_) => return Err(DuelError::InactiveSymbol(symbol.clone())),
+                None => return Err(DuelError::UnknownSymbol(symbol.clone())),
+            }
+        }
+        terms.allowed_symbols.clone()
+    };
+    if allowed_symbols.is_empty() {
+        return Err(DuelError::NoSymbols);
+    }
+
+    let duel = duels::create_duel(
+        &state.db_pool,
+        &duels::NewDuel {
+            challenger_id,
+            opponent_id: opponent.id,
+            starting_balance_cents: terms.starting_balance_cents,
+            allowed_symbols: &allowed_symbols,
+            duration_hours: terms.duration_hours,
+            stake_xp: terms.stake_xp,
+            expires_at: now + Duration::hours(CHALLENGE_TTL_HOURS),
+        },
+    )
+    .await?
+    .ok_or(DuelError::AlreadyOpen)?;
+
+    let challenger_name = challenger.username.unwrap_or(challenger.wallet_address);
+    let notification = notifications::create_notification(
+        &state.db_pool,
+        opponent.id,
+        DUEL_CHALLENGE_KIND,
+        "New duel challenge",
+        &format!(
+            "{} challenged you to a {}h duel for {} XP",
+            challenger_name, duel.duration_hours, duel.stake_xp
+        ),
+        None,
+    )
+    .await?;
+    announce(state, vec![(notification, event_data(&duel, None))]);
+
+    Ok(duel)
+}
+
+/// Accepts a pending challenge: escrows both stakes, opens both duel accounts and starts the clock.
+pub async fn accept_duel(
+    state: &SharedState,
+    duel_id: Uuid,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Duel, DuelError> {
+    let mut tx = state.db_pool.begin().await?;
+    let duel = lock_pending_for_opponent(&mut tx, duel_id, user_id, now).await?;
+
+    let reference_id = duel_id.to_string();
+    let stake_key = format!("{}:{}:stake", DUEL_XP_SOURCE, duel_id);
+    if duel.stake_xp > 0 {
+        // Lock both players in a fixed order so concurrent acceptances cannot deadlock
+        let mut players = [duel.challenger_id, duel.opponent_id];
+        players.sort();
+        for player in players {
+            let available = duels::lock_user_xp(&mut tx, player).await?.unwrap_or(0);
+            if available < duel.stake_xp {
+                return Err(DuelError::InsufficientXp {
+                    required: duel.stake_xp,
+                    available,
+                });
+            }
+        }
+
+        let grant = xp::XpGrant {
+            source: DUEL_XP_SOURCE,
+            amount: -duel.stake_xp,
+            reference_id: Some(&reference_id),
+            idempotency_key: Some(&stake_key),
+        };
+        for player in players {
+            xp::grant_xp(&mut tx, player, &grant).await?;
+        }
+    }
+
+    for player in [duel.challenger_id, duel.opponent_id] {
+        duels::create_account(&mut tx, duel_id, player, duel.starting_balance_cents).await?;
+    }
+    let duel = duels::activate_duel(&mut tx, duel_id, now, now + Duration::hours(i64::from(duel.duration_hours))).await?;
+
+    let notification = notifications::create_notification(
+        &mut *tx,
+        duel.challenger_id,
+        DUEL_RESPONSE_KIND,
+        "Duel accepted",
+        &format!("Your duel challenge was accepted. Trading closes in {}h", duel.duration_hours),
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    announce(state, vec![(notification, event_data(&duel, None))]);
+    Ok(duel)
+}
+
+/// Declines a pending challenge and notifies the challenger.
+pub async fn decline_duel(
+    state: &SharedState,
+    duel_id: Uuid,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Duel, DuelError> {
+    let mut tx = state.db_pool.begin().await?;
+    lock_pending_for_opponent(&mut tx, duel_id, user_id, now).await?;
+    let duel = duels::set_duel_status(&mut tx, duel_id, DuelStatus::Declined.as_str(), now).await?;
+
+    let notification = notifications::create_notification(
+        &mut *tx,
+        duel.challenger_id,
+        DUEL_RESPONSE_KIND,
+        "Duel declined",
+        "Your duel challenge was declined",
+        None,
+    )
+    .await?;
+    tx.commit().await?;
+
+    announce(state, vec![(notification, event_data(&duel, None))]);
+    Ok(duel)
+}
+
+/// Executes a market order against the user's duel account at the current oracle price.
+/// Fees and slippage follow the regular schedule at the base fee tier, so levels give no edge.
+pub async fn execute_duel_order(
+    state: &SharedState,
+    duel_id: Uuid,
+    user_id: Uuid,
+    order: &MarketOrder,
+    now: DateTime<Utc>,
+) -> Result<DuelTrade, DuelError> {
+    let duel = duels::find_duel(&state.db_pool, duel_id)
+        .await?
+        .ok_or(DuelError::NotFound)?;
+    if !is_participant(&duel, user_id) {
+        return Err(DuelError::NotParticipant);
+    }
+    if status(&duel) != DuelStatus::Active || duel.ends_at.is_some_and(|ends_at| now >= ends_at) {
+        return Err(DuelError::NotActive);
+    }
+    if !duel.allowed_symbols.contains(&order.symbol) {
+        return Err(DuelError::SymbolNotAllowed(order.symbol.clone()));
+    }
+
+    let fill = isolated::simulate_order(state, order).await?;
+
+    let mut tx = state.db_pool.begin().await?;
+    let account = IsolatedAccount::Duel { duel_id, user_id };
+    if !isolated::apply_fill(&mut tx, account, order, &fill).await? {
+        return Err(DuelError::NotParticipant);
+    }
+
+    let trade = duels::insert_trade(
+        &mut tx,
+        &duels::NewDuelTrade {
+            duel_id,
+            user_id,
+            symbol: &order.symbol,
+            trade_type: order.side.as_str(),
+            quantity: fill.quantity,
+            price: fill.price,
+            total_value: fill.notional_cents,
+            fee_cents: fill.fee_cents,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(trade)
+}
+
+/// Loads the user's duel account valued at current oracle prices, or at its final value once settled.
+pub async fn duel_account(state: &SharedState, duel: &Duel, user_id: Uuid) -> Result<DuelAccountView, DuelError> {
+    let account = duels::find_account(&state.db_pool, duel.id, user_id)
+        .await?
+        .ok_or(DuelError::NotActive)?;
+    let positions = duels::list_positions(&state.db_pool, duel.id, user_id).await?;
+    let final_value_cents = if user_id == duel.challenger_id {
+        duel.challenger_final_value_cents
+    } else {
+        duel.opponent_final_value_cents
+    };
+    let open_value_cents = isolated::positions_value_cents(
+        positions.iter().map(|p| (p.symbol.as_str(), p.quantity, p.average_price)),
+        &state.oracle,
+    );
+    let value_cents = final_value_cents.unwrap_or(account.cash_balance_cents + open_value_cents);
+
+    Ok(DuelAccountView {
+        account,
+        positions,
+        value_cents,
+    })
+}
+
+/// Returns both players' account values: final values once settled, live values while active.
+/// Returns `None` for challenges that never started.
+pub async fn duel_score(state: &SharedState, duel: &Duel) -> Result<Option<DuelScore>, sqlx::Error> {
+    if let (Some(challenger_value_cents), Some(opponent_value_cents)) =
+        (duel.challenger_final_value_cents, duel.opponent_final_value_cents)
+    {
+        return Ok(Some(DuelScore {
+            challenger_value_cents,
+            opponent_value_cents,
+        }));
+    }
+    if status(duel) != DuelStatus::Active {
+        return Ok(None);
+    }
+
+    let (symbols, prices): (Vec<String>, Vec<i64>) = state.oracle.snapshot().into_iter().unzip();
+    live_score(&state.db_pool, duel, &symbols, &prices).await.map(Some)
+}
+
+/// Expires unanswered challenges and settles every active duel whose trading has ended.
+/// Returns the number of duels settled.
+pub async fn settle_due_duels(state: &SharedState, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+    let expired = duels::expire_pending_duels(&state.db_pool, now).await?;
+    if expired > 0 {
+        info!("⌛ Expired {} unanswered duel challenges", expired);
+    }
+
+    let due = duels::list_due_duels(&state.db_pool, now).await?;
+    let mut settled = 0;
+
+    for duel in due {
+        match settle_duel(state, duel.id, now).await {
+            Ok(true) => settled += 1,
+            Ok(false) => {}
+            Err(e) => warn!("⚠️ Failed to settle duel {}: {}", duel.id, e),
+        }
+    }
+
+    Ok(settled)
+}
+
+/// Records both final values at current oracle prices, rates both players and pays the escrowed stakes out.
+/// Returns `false` when the duel was already settled or has not ended.
+async fn settle_duel(state: &SharedState, duel_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+    let mut tx = state.db_pool.begin().await?;
+    let Some(duel) = duels::lock_duel(&mut tx, duel_id).await? else {
+        return Ok(false);
+    };
+    if status(&duel) != DuelStatus::Active || duel.ends_at.is_none_or(|ends_at| now < ends_at) {
+        return Ok(false);
+    }
+
+    let (symbols, prices): (Vec<String>, Vec<i64>) = state.oracle.snapshot().into_iter().unzip();
+    let announcements = settle_locked_duel(&mut tx, &duel, &symbols, &prices, now).await?;
+    tx.commit().await?;
+
+    announce(state, announcements);
+    Ok(true)
+}
+
+/// Settles active duels the caller has locked at the given prices, ended or not.
+/// Season rollovers run this before the reset so escrowed stakes are paid out in the season they were
+/// taken from. Returns the result notifications to `announce` once committed.
+pub async fn settle_locked_duels(
+    conn: &mut sqlx::PgConnection,
+    open: &[Duel],
+    symbols: &[String],
+    prices: &[i64],
+    now: DateTime<Utc>,
+) -> Result<Vec<(Notification, DuelEventData)>, sqlx::Error> {
+    let mut announcements = Vec::new();
+    for duel in open {
+        announcements.extend(settle_locked_duel(&mut *conn, duel, symbols, prices, now).await?);
+    }
+
+    Ok(announcements)
+}
+
+/// Settles a locked active duel at the given prices and returns its result notifications.
+async fn settle_locked_duel(
+    conn: &mut sqlx::PgConnection,
+    duel: &Duel,
+    symbols: &[String],
+    prices: &[i64],
+    now: DateTime<Utc>,
+) -> Result<Vec<(Notification, DuelEventData)>, sqlx::Error> {
+    let duel_id = duel.id;
+    let score = live_score(&mut *conn, duel, symbols, prices).await?;
+    let result = DuelResult::decide(score.challenger_value_cents, score.opponent_value_cents);
+    let winner_id = match result {
+        DuelResult::ChallengerWon => Some(duel.challenger_id),
+        DuelResult::OpponentWon => Some(duel.opponent_id),
+        DuelResult::Draw => None,
+    };
+    let duel = duels::settle_duel(
+        &mut *conn,
+        duel_id,
+        winner_id,
+        score.challenger_value_cents,
+        score.opponent_value_cents,
+        now,
+    )
+    .await?;
+
+    ratings::rate_duel(
+        &mut *conn,
+        duel_id,
+        duel.challenger_id,
+        duel.opponent_id,
+        result.challenger_score(),
+        now,
+    )
+    .await?;
+
+    let (challenger_payout, opponent_payout) = result.payouts(duel.stake_xp);
+    let reference_id = duel_id.to_string();
+    let payout_key = format!("{}:{}:payout", DUEL_XP_SOURCE, duel_id);
+    let mut announcements: Vec<(Notification, DuelEventData)> = Vec::new();
+
+    for (player, payout) in [(duel.challenger_id, challenger_payout), (duel.opponent_id, opponent_payout)] {
+        if payout > 0 {
+            let grant = xp::XpGrant {
+                source: DUEL_XP_SOURCE,
+                amount: payout,
+                reference_id: Some(&reference_id),
+                idempotency_key: Some(&payout_key),
+            };
+            xp::grant_xp(&mut *conn, player, &grant).await?;
+        }
+
+        let (title, message) = match winner_id {
+            Some(winner) if winner == player => (
+                "Duel won",
+                format!("You won the duel and your opponent's {} XP stake", duel.stake_xp),
+            ),
+            Some(_) => ("Duel lost", format!("You lost the duel and your {} XP stake", duel.stake_xp)),
+            None => ("Duel drawn", "The duel ended in a draw and stakes were returned".to_string()),
+        };
+        let notification =
+            notifications::create_notification(&mut *conn, player, DUEL_RESULT_KIND, title, &message, None).await?;
+        announcements.push((notification, event_data(&duel, Some(payout))));
+    }
+
+    info!("⚔️ Settled duel {} ({:?})", duel_id, result);
+    Ok(announcements)
+}
+
+/// Locks a pending challenge for its opponent to answer.
+async fn lock_pending_for_opponent(
+    conn: &mut sqlx::PgConnection,
+    duel_id: Uuid,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Duel, DuelError> {
+    let duel = duels::lock_duel(conn, duel_id).await?.ok_or(DuelError::NotFound)?;
+    if !is_participant(&duel, user_id) {
+        return Err(DuelError::NotFound);
+    }
+    if duel.opponent_id != user_id {
+        return Err(DuelError::NotOpponent);
+    }
+    if status(&duel) != DuelStatus::Pending || now >= duel.expires_at {
+        return Err(DuelError::NotPending);
+    }
+    Ok(duel)
+}
+
+/// Values both duel accounts at the given prices.
+async fn live_score(
+    executor: impl sqlx::PgExecutor<'_>,
+    duel: &Duel,
+    symbols: &[String],
+    prices: &[i64],
+) -> Result<DuelScore, sqlx::Error> {
+    let values = duels::account_values(executor, duel.id, symbols, prices).await?;
+    let value_of = |player: Uuid| {
+        values
+            .iter()
+            .find(|(user_id, _)| *user_id == player)
+            .map(|(_, value)| *value)
+            .unwrap_or(duel.starting_balance_cents)
+    };
+
+    Ok(DuelScore {
+        challenger_value_cents: value_of(duel.challenger_id),
+        opponent_value_cents: value_of(duel.opponent_id),
+    })
+}
+
+/// Builds the webhook payload for a duel event.
+fn event_data(duel: &Duel, xp_payout: Option<i32>) -> DuelEventData {
+    DuelEventData {
+        duel_id: duel.id,
+        status: status(duel).as_str(),
+        stake_xp: duel.stake_xp,
+        xp_payout,
+    }
+}
+
+/// Delivers duel notifications to webhooks in the background.
+pub fn announce(state: &SharedState, announcements: Vec<(Notification, DuelEventData)>) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        for (notification, data) in announcements {
+            deliver_to_webhooks(&state, &notification, data).await;
+        }
+    });
+}
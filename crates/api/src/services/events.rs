@@ -0,0 +1,109 @@
+//! User event outbox.
+//! Stores gamification events from trading and sign-in for background consumers such as XP awards.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use db::models::UserEventRecord;
+use db::queries::events::{self, NewUserEvent};
+use game::GameEvent;
+use sqlx::PgExecutor;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// Gamification event raised by a user's action.
+#[derive(Debug, Clone)]
+pub struct UserEvent {
+    /// User who acted.
+    pub user_id: Uuid,
+    /// What happened.
+    pub event: GameEvent,
+    /// Entity behind the event, e.g. a trade id.
+    pub reference_id: Option<String>,
+    /// When the event happened.
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl UserEvent {
+    /// Rebuilds an event from its stored record, or returns `None` for an unknown kind.
+    pub fn from_record(record: UserEventRecord) -> Option<Self> {
+        let event = match record.kind.as_str() {
+            "trade_executed" => GameEvent::TradeExecuted {
+                notional_cents: record.notional_cents?,
+            },
+            "position_closed" => GameEvent::PositionClosed {
+                realized_pnl_cents: record.realized_pnl_cents?,
+                cost_cents: record.cost_cents?,
+                held_seconds: record.held_seconds?,
+            },
+            "daily_login" => GameEvent::DailyLogin,
+            _ => return None,
+        };
+
+        Some(Self {
+            user_id: record.user_id,
+            event,
+            reference_id: record.reference_id,
+            occurred_at: record.occurred_at,
+        })
+    }
+}
+
+/// Stores an event that happened now.
+/// Run it in the transaction of the action behind the event, then wake consumers with `EventBus::notify`
+/// once it has been committed.
+pub async fn store_event(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+    event: &GameEvent,
+    reference_id: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let record = match *event {
+        GameEvent::TradeExecuted { notional_cents } => NewUserEvent {
+            kind: "trade_executed",
+            notional_cents: Some(notional_cents),
+            ..Default::default()
+        },
+        GameEvent::PositionClosed {
+            realized_pnl_cents,
+            cost_cents,
+            held_seconds,
+        } => NewUserEvent {
+            kind: "position_closed",
+            realized_pnl_cents: Some(realized_pnl_cents),
+            cost_cents: Some(cost_cents),
+            held_seconds: Some(held_seconds),
+            ..Default::default()
+        },
+        GameEvent::DailyLogin => NewUserEvent {
+            kind: "daily_login",
+            ..Default::default()
+        },
+    };
+
+    events::insert_user_event(executor, user_id, &NewUserEvent { reference_id, ..record }, Utc::now()).await
+}
+
+/// Wakes the consumer of stored events.
+/// Events themselves live in the database, so a missed or merged wake-up never loses one.
+#[derive(Debug, Clone, Default)]
+pub struct EventBus {
+    stored: Arc<Notify>,
+}
+
+impl EventBus {
+    /// Creates a bus with no pending wake-up.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signals that new events have been committed.
+    pub fn notify(&self) {
+        self.stored.notify_one();
+    }
+
+    /// Waits until events are committed, returning at once if that happened since the last wait.
+    pub async fn notified(&self) {
+        self.stored.notified().await;
+    }
+}
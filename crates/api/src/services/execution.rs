@@ -0,0 +1,167 @@
+//! Order execution math shared by live trading and simulations.
+//! Computes fill prices, fees and slippage without touching the database.
+
+use db::models::{FeeSchedule, FeeTier};
+use serde::{Deserialize, Serialize};
+
+/// Number of micro units in one whole token.
+pub const MICRO_UNITS: i64 = 1_000_000;
+
+/// Basis points in 100%.
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Side of an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    /// Returns the trade type stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+}
+
+/// Whether a fill adds or removes liquidity, which selects the maker or taker fee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liquidity {
+    Maker,
+    Taker,
+}
+
+/// Result of simulating a fill against a reference price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fill {
+    /// Quantity filled in micro units.
+    pub quantity: i64,
+    /// Oracle price before slippage in cents.
+    pub reference_price: i64,
+    /// Fill price including slippage in cents.
+    pub price: i64,
+    /// Quantity times fill price in cents, excluding fees.
+    pub notional_cents: i64,
+    /// Fee charged in cents.
+    pub fee_cents: i64,
+    /// Cost of slippage versus the reference price in cents.
+    pub slippage_cents: i64,
+    /// Slippage applied to the fill in basis points.
+    pub slippage_bps: i64,
+}
+
+impl Fill {
+    /// Cash that leaves (buy) or enters (sell) the account, in cents.
+    pub fn cash_delta_cents(&self, side: OrderSide) -> i64 {
+        match side {
+            OrderSide::Buy => -(self.notional_cents + self.fee_cents),
+            OrderSide::Sell => self.notional_cents - self.fee_cents,
+        }
+    }
+}
+
+/// Calculates the value of a quantity at a price, in cents.
+/// Rounds to the nearest cent.
+pub fn notional_cents(quantity_micros: i64, price_cents: i64) -> i64 {
+    let value = quantity_micros as i128 * price_cents as i128;
+    ((value + MICRO_UNITS as i128 / 2) / MICRO_UNITS as i128) as i64
+}
+
+/// Calculates slippage for an order of the given notional, in basis points.
+/// Grows linearly with order size and is capped by the schedule's maximum.
+pub fn slippage_bps(schedule: &FeeSchedule, notional_cents: i64) -> i64 {
+    let impact = schedule.slippage_impact_bps as i128 * notional_cents.max(0) as i128
+        / schedule.slippage_depth_cents.max(1) as i128;
+    let total = schedule.slippage_base_bps as i128 + impact;
+    total.min(schedule.max_slippage_bps as i128) as i64
+}
+
+/// Calculates the fee for a fill, in cents.
+/// Applies the tier discount first and then the minimum fee. Fills whose effective rate is zero,
+/// from a zero fee or a full discount, are free and pay no minimum either.
+pub fn fee_cents(
+    schedule: &FeeSchedule,
+    liquidity: Liquidity,
+    notional_cents: i64,
+    tier: &FeeTier,
+) -> i64 {
+    let fee_bps = match liquidity {
+        Liquidity::Maker => schedule.maker_fee_bps,
+        Liquidity::Taker => schedule.taker_fee_bps,
+    };
+    let discount_percent = tier.discount_percent.clamp(0, 100);
+    if fee_bps == 0 || discount_percent == 100 {
+        return 0;
+    }
+    let discounted = notional_cents as i128 * fee_bps as i128 * (100 - discount_percent) as i128
+        / (BPS_DENOMINATOR * 100);
+    (discounted as i64).max(schedule.min_fee_cents)
+}
+
+/// Rounds a price to the asset's tick size.
+/// Buys round up and sells round down so rounding never favours the trader.
+pub fn round_to_tick(price_cents: i64, tick_size_cents: i64, side: OrderSide) -> i64 {
+    let tick = tick_size_cents.max(1);
+    let rounded = match side {
+        OrderSide::Buy => (price_cents + tick - 1) / tick * tick,
+        OrderSide::Sell => price_cents / tick * tick,
+    };
+    rounded.max(tick)
+}
+
+/// Largest lot-aligned quantity whose taker cost, including worst-case fees and slippage,
+/// fits within a budget.
+pub fn affordable_quantity(budget_cents: i64, price_cents: i64, lot_size: i64, schedule: &FeeSchedule) -> i64 {
+    let buffer_bps = (schedule.taker_fee_bps + schedule.max_slippage_bps) as i128;
+    let worst_price = price_cents as i128 * (BPS_DENOMINATOR + buffer_bps) / BPS_DENOMINATOR;
+    if worst_price <= 0 || lot_size <= 0 {
+        return 0;
+    }
+
+    let budget = (budget_cents - schedule.min_fee_cents).max(0) as i128;
+    let quantity = (budget * MICRO_UNITS as i128 / worst_price) as i64;
+    quantity / lot_size * lot_size
+}
+
+/// Simulates filling an order at a reference price.
+/// Buys fill above and sells fill below the reference price by the slippage amount,
+/// rounded to the asset's tick size.
+pub fn simulate_fill(
+    side: OrderSide,
+    quantity_micros: i64,
+    reference_price: i64,
+    tick_size_cents: i64,
+    schedule: &FeeSchedule,
+    liquidity: Liquidity,
+    tier: &FeeTier,
+) -> Fill {
+    let reference_notional = notional_cents(quantity_micros, reference_price);
+    // Makers rest at their price and do not pay slippage
+    let slippage_bps = match liquidity {
+        Liquidity::Maker => 0,
+        Liquidity::Taker => slippage_bps(schedule, reference_notional),
+    };
+
+    let adjustment = reference_price as i128 * slippage_bps as i128;
+    let price = match side {
+        OrderSide::Buy => reference_price as i128 + (adjustment + BPS_DENOMINATOR - 1) / BPS_DENOMINATOR,
+        OrderSide::Sell => reference_price as i128 - adjustment / BPS_DENOMINATOR,
+    }
+    .max(1) as i64;
+    let price = round_to_tick(price, tick_size_cents, side);
+
+    let notional = notional_cents(quantity_micros, price);
+    Fill {
+        quantity: quantity_micros,
+        reference_price,
+        price,
+        notional_cents: notional,
+        fee_cents: fee_cents(schedule, liquidity, notional, tier),
+        slippage_cents: (notional - reference_notional).abs(),
+        slippage_bps,
+    }
+}
@@ -0,0 +1,550 @@
+//! Trade history export service.
+//! Matches sells against buy lots first-in first-out and encodes trades as CSV, JSON or Koinly rows.
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tracing::warn;
+use uuid::Uuid;
+
+use db::models::Trade;
+use db::queries::trading;
+
+/// Encoded chunks buffered between the database cursor and the response body.
+const EXPORT_CHANNEL_CAPACITY: usize = 64;
+
+/// Columns of the CSV export. Sells have one row per matched lot.
+const CSV_HEADER: [&str; 16] = [
+    "trade_id",
+    "executed_at",
+    "symbol",
+    "side",
+    "quantity",
+    "price",
+    "reference_price",
+    "gross_value",
+    "fee",
+    "slippage",
+    "net_value",
+    "lot_trade_id",
+    "lot_acquired_at",
+    "cost_basis",
+    "realized_pnl",
+    "bot_id",
+];
+
+/// Columns of Koinly's universal import template.
+const KOINLY_HEADER: [&str; 12] = [
+    "Date",
+    "Sent Amount",
+    "Sent Currency",
+    "Received Amount",
+    "Received Currency",
+    "Fee Amount",
+    "Fee Currency",
+    "Net Worth Amount",
+    "Net Worth Currency",
+    "Label",
+    "Description",
+    "TxHash",
+];
+
+/// Currency paper trades settle in.
+const QUOTE_CURRENCY: &str = "USD";
+
+/// Export file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One row per buy and per matched sell lot.
+    #[default]
+    Csv,
+    /// Array of trades with their lot breakdown.
+    Json,
+    /// Koinly universal import template.
+    Koinly,
+}
+
+impl ExportFormat {
+    /// Content type of the encoded export.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv | ExportFormat::Koinly => "text/csv; charset=utf-8",
+        }
+    }
+
+    /// File name suggested to the browser.
+    pub fn file_name(&self, now: DateTime<Utc>) -> String {
+        let date = now.format("%Y%m%d");
+        match self {
+            ExportFormat::Csv => format!("vectra-trades-{}.csv", date),
+            ExportFormat::Json => format!("vectra-trades-{}.json", date),
+            ExportFormat::Koinly => format!("vectra-trades-koinly-{}.csv", date),
+        }
+    }
+}
+
+/// Open quantity of a buy not yet matched by sells.
+#[derive(Debug, Clone)]
+struct Lot {
+    /// Buy that opened the lot.
+    trade_id: Uuid,
+    /// When the lot was bought.
+    acquired_at: DateTime<Utc>,
+    /// Unsold quantity in micro units.
+    quantity: i64,
+    /// Cost of the unsold quantity including fees, in cents.
+    cost_cents: i64,
+}
+
+/// Part of a sell matched against one buy lot.
+/// Amounts of the sell are split across its lots in proportion to quantity.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LotMatch {
+    /// Buy that opened the lot, `None` for quantity sold without a recorded buy.
+    pub lot_trade_id: Option<Uuid>,
+    /// When the lot was bought.
+    pub acquired_at: Option<DateTime<Utc>>,
+    /// Matched quantity in micro units.
+    pub quantity: i64,
+    /// Share of the sell's value before fees, in cents.
+    pub gross_value_cents: i64,
+    /// Share of the sell's fee, in cents.
+    pub fee_cents: i64,
+    /// Share of the sell's slippage, in cents.
+    pub slippage_cents: i64,
+    /// Cost of the matched quantity including buy fees, in cents.
+    pub cost_basis_cents: i64,
+    /// Proceeds after fees minus cost basis, in cents.
+    pub realized_pnl_cents: i64,
+}
+
+/// Tracks open lots per symbol while trades are replayed oldest first.
+#[derive(Debug, Default)]
+pub struct LotTracker {
+    /// Open lots per symbol, oldest first.
+    open: HashMap<String, VecDeque<Lot>>,
+}
+
+impl LotTracker {
+    /// Records a trade. Buys open a lot; sells close lots first-in first-out and return the matches.
+    pub fn record(&mut self, trade: &Trade) -> Vec<LotMatch> {
+        let lots = self.open.entry(trade.symbol.clone()).or_default();
+
+        if trade.trade_type == "buy" {
+            lots.push_back(Lot {
+                trade_id: trade.id,
+                acquired_at: trade.executed_at,
+                quantity: trade.quantity,
+                cost_cents: trade.total_value + trade.fee_cents,
+            });
+            return Vec::new();
+        }
+
+        // Take quantity from the oldest lots, leaving any unmatched remainder without a basis
+        let mut matches: Vec<LotMatch> = Vec::new();
+        let mut remaining = trade.quantity;
+        for lot in lots.iter() {
+            if remaining == 0 {
+                break;
+            }
+            let quantity = remaining.min(lot.quantity);
+            matches.push(LotMatch {
+                lot_trade_id: Some(lot.trade_id),
+                acquired_at: Some(lot.acquired_at),
+                quantity,
+                cost_basis_cents: share(lot.cost_cents, quantity, lot.quantity),
+                ..LotMatch::default()
+            });
+            remaining -= quantity;
+        }
+        if remaining > 0 {
+            matches.push(LotMatch {
+                quantity: remaining,
+                ..LotMatch::default()
+            });
+        }
+
+        // Drop fully sold lots and shrink a partially sold one
+        for matched in matches.iter().filter(|m| m.lot_trade_id.is_some()) {
+            if let Some(lot) = lots.front_mut() {
+                if matched.quantity == lot.quantity {
+                    lots.pop_front();
+                } else {
+                    lot.quantity -= matched.quantity;
+                    lot.cost_cents -= matched.cost_basis_cents;
+                }
+            }
+        }
+
+        let quantities: Vec<i64> = matches.iter().map(|m| m.quantity).collect();
+        let gross = split(trade.total_value, &quantities);
+        let fees = split(trade.fee_cents, &quantities);
+        let slippage = split(trade.slippage_cents, &quantities);
+        for (i, matched) in matches.iter_mut().enumerate() {
+            matched.gross_value_cents = gross[i];
+            matched.fee_cents = fees[i];
+            matched.slippage_cents = slippage[i];
+            matched.realized_pnl_cents = gross[i] - fees[i] - matched.cost_basis_cents;
+        }
+
+        matches
+    }
+}
+
+/// Portion of `total` belonging to `part` out of `whole`, rounded down.
+fn share(total: i64, part: i64, whole: i64) -> i64 {
+    if whole == 0 {
+        return 0;
+    }
+    (i128::from(total) * i128::from(part) / i128::from(whole)) as i64
+}
+
+/// Splits `total` in proportion to `weights`. The last part absorbs rounding so parts sum to `total`.
+fn split(total: i64, weights: &[i64]) -> Vec<i64> {
+    let whole: i64 = weights.iter().sum();
+    let mut parts: Vec<i64> = weights.iter().map(|weight| share(total, *weight, whole)).collect();
+    let assigned: i64 = parts.iter().sum();
+    if let Some(last) = parts.last_mut() {
+        *last += total - assigned;
+    }
+    parts
+}
+
+/// Formats cents as a decimal USD amount without floating point rounding.
+pub fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{}{}.{:02}", sign, cents / 100, cents % 100)
+}
+
+/// Formats micro units as a decimal token amount without floating point rounding.
+pub fn format_micros(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let micros = micros.unsigned_abs();
+    format!("{}{}.{:06}", sign, micros / 1_000_000, micros % 1_000_000)
+}
+
+/// Trade with its lot breakdown as written to JSON exports.
+#[derive(Serialize)]
+struct JsonExportRow<'a> {
+    id: Uuid,
+    executed_at: DateTime<Utc>,
+    symbol: &'a str,
+    side: &'a str,
+    quantity: String,
+    price: String,
+    reference_price: String,
+    gross_value: String,
+    fee: String,
+    slippage: String,
+    net_value: String,
+    cost_basis: Option<String>,
+    realized_pnl: Option<String>,
+    bot_id: Option<Uuid>,
+    lots: Vec<JsonExportLot>,
+}
+
+/// Matched lot of a sell as written to JSON exports.
+#[derive(Serialize)]
+struct JsonExportLot {
+    lot_trade_id: Option<Uuid>,
+    acquired_at: Option<DateTime<Utc>>,
+    quantity: String,
+    gross_value: String,
+    fee: String,
+    slippage: String,
+    cost_basis: String,
+    realized_pnl: String,
+}
+
+/// Value of a trade after fees: cash paid for buys, cash received for sells.
+fn net_value_cents(trade: &Trade) -> i64 {
+    if trade.trade_type == "buy" {
+        trade.total_value + trade.fee_cents
+    } else {
+        trade.total_value - trade.fee_cents
+    }
+}
+
+/// Encodes CSV records into bytes.
+fn csv_records(records: &[Vec<String>]) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        writer.write_record(record)?;
+    }
+    writer.into_inner().map_err(|e| e.into_error().into())
+}
+
+/// Encodes the start of an export.
+pub fn encode_header(format: ExportFormat) -> Result<Vec<u8>, csv::Error> {
+    match format {
+        ExportFormat::Csv => csv_records(&[CSV_HEADER.iter().map(|c| c.to_string()).collect()]),
+        ExportFormat::Koinly => csv_records(&[KOINLY_HEADER.iter().map(|c| c.to_string()).collect()]),
+        ExportFormat::Json => Ok(b"[".to_vec()),
+    }
+}
+
+/// Encodes the end of an export.
+pub fn encode_footer(format: ExportFormat) -> Vec<u8> {
+    match format {
+        ExportFormat::Json => b"]".to_vec(),
+        ExportFormat::Csv | ExportFormat::Koinly => Vec::new(),
+    }
+}
+
+/// Encodes one trade and its matched lots.
+/// `first` is whether the trade is the first of the export, for JSON separators.
+pub fn encode_trade(
+    format: ExportFormat,
+    trade: &Trade,
+    matches: &[LotMatch],
+    first: bool,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let cost_basis: Option<i64> = (trade.trade_type == "sell").then(|| matches.iter().map(|m| m.cost_basis_cents).sum());
+    let realized_pnl: Option<i64> =
+        (trade.trade_type == "sell").then(|| matches.iter().map(|m| m.realized_pnl_cents).sum());
+
+    match format {
+        ExportFormat::Csv => {
+            let row = |quantity: i64, gross: i64, fee: i64, slippage: i64, net: i64, lot: Option<&LotMatch>| {
+                vec![
+                    trade.id.to_string(),
+                    trade.executed_at.to_rfc3339(),
+                    trade.symbol.clone(),
+                    trade.trade_type.clone(),
+                    format_micros(quantity),
+                    format_cents(trade.price),
+                    format_cents(trade.reference_price),
+                    format_cents(gross),
+                    format_cents(fee),
+                    format_cents(slippage),
+                    format_cents(net),
+                    lot.and_then(|l| l.lot_trade_id).map(|id| id.to_string()).unwrap_or_default(),
+                    lot.and_then(|l| l.acquired_at).map(|t| t.to_rfc3339()).unwrap_or_default(),
+                    lot.map(|l| format_cents(l.cost_basis_cents)).unwrap_or_default(),
+                    lot.map(|l| format_cents(l.realized_pnl_cents)).unwrap_or_default(),
+                    trade.bot_id.map(|id| id.to_string()).unwrap_or_default(),
+                ]
+            };
+
+            let records: Vec<Vec<String>> = if matches.is_empty() {
+                vec![row(
+                    trade.quantity,
+                    trade.total_value,
+                    trade.fee_cents,
+                    trade.slippage_cents,
+                    net_value_cents(trade),
+                    None,
+                )]
+            } else {
+                matches
+                    .iter()
+                    .map(|m| {
+                        let net = m.gross_value_cents - m.fee_cents;
+                        row(m.quantity, m.gross_value_cents, m.fee_cents, m.slippage_cents, net, Some(m))
+                    })
+                    .collect()
+            };
+            Ok(csv_records(&records)?)
+        }
+        ExportFormat::Koinly => {
+            let quantity = format_micros(trade.quantity);
+            let gross = format_cents(trade.total_value);
+            let (sent, sent_currency, received, received_currency) = if trade.trade_type == "buy" {
+                (gross.clone(), QUOTE_CURRENCY, quantity, trade.symbol.as_str())
+            } else {
+                (quantity, trade.symbol.as_str(), gross.clone(), QUOTE_CURRENCY)
+            };
+            let description = match realized_pnl {
+                Some(pnl) => format!("Vectra paper trade, FIFO realized P&L {} USD", format_cents(pnl)),
+                None => "Vectra paper trade".to_string(),
+            };
+
+            Ok(csv_records(&[vec![
+                trade.executed_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                sent,
+                sent_currency.to_string(),
+                received,
+                received_currency.to_string(),
+                format_cents(trade.fee_cents),
+                QUOTE_CURRENCY.to_string(),
+                gross,
+                QUOTE_CURRENCY.to_string(),
+                String::new(),
+                description,
+                trade.id.to_string(),
+            ]])?)
+        }
+        ExportFormat::Json => {
+            let row = JsonExportRow {
+                id: trade.id,
+                executed_at: trade.executed_at,
+                symbol: &trade.symbol,
+                side: &trade.trade_type,
+                quantity: format_micros(trade.quantity),
+                price: format_cents(trade.price),
+                reference_price: format_cents(trade.reference_price),
+                gross_value: format_cents(trade.total_value),
+                fee: format_cents(trade.fee_cents),
+                slippage: format_cents(trade.slippage_cents),
+                net_value: format_cents(net_value_cents(trade)),
+                cost_basis: cost_basis.map(format_cents),
+                realized_pnl: realized_pnl.map(format_cents),
+                bot_id: trade.bot_id,
+                lots: matches
+                    .iter()
+                    .map(|m| JsonExportLot {
+                        lot_trade_id: m.lot_trade_id,
+                        acquired_at: m.acquired_at,
+                        quantity: format_micros(m.quantity),
+                        gross_value: format_cents(m.gross_value_cents),
+                        fee: format_cents(m.fee_cents),
+                        slippage: format_cents(m.slippage_cents),
+                        cost_basis: format_cents(m.cost_basis_cents),
+                        realized_pnl: format_cents(m.realized_pnl_cents),
+                    })
+                    .collect(),
+            };
+
+            let mut bytes = if first { Vec::new() } else { b",".to_vec() };
+            serde_json::to_writer(&mut bytes, &row)?;
+            Ok(bytes)
+        }
+    }
+}
+
+/// Streams a user's encoded trade history into a channel, oldest trade first.
+/// Only open lots are held in memory. A failure mid-export is logged and sent as the last item,
+/// so the response is aborted instead of ending like a complete file.
+pub fn spawn_export(
+    pool: PgPool,
+    user_id: Uuid,
+    format: ExportFormat,
+) -> mpsc::Receiver<Result<Vec<u8>, io::Error>> {
+    let (sender, receiver) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        if let Err(e) = write_export(&pool, user_id, format, &sender).await {
+            warn!("⚠️ Trade export for user {} stopped: {}", user_id, e);
+            let _ = sender.send(Err(io::Error::other(e))).await;
+        }
+    });
+
+    receiver
+}
+
+/// Encodes trades from a database cursor and sends them as chunks.
+/// Stops quietly when the client disconnects.
+async fn write_export(
+    pool: &PgPool,
+    user_id: Uuid,
+    format: ExportFormat,
+    sender: &mpsc::Sender<Result<Vec<u8>, io::Error>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if sender.send(Ok(encode_header(format)?)).await.is_err() {
+        return Ok(());
+    }
+
+    let mut tracker = LotTracker::default();
+    let mut trades = trading::stream_trades(pool, user_id);
+    let mut first = true;
+    while let Some(trade) = trades.try_next().await? {
+        let matches = tracker.record(&trade);
+        let chunk = encode_trade(format, &trade, &matches, first)?;
+        first = false;
+        if sender.send(Ok(chunk)).await.is_err() {
+            return Ok(());
+        }
+    }
+
+    let _ = sender.send(Ok(encode_footer(format))).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::fixtures::trade;
+
+    /// One token in micro units.
+    const TOKEN: i64 = 1_000_000;
+
+    /// Quantity, cost basis and realized PnL of every match.
+    fn summary(matches: &[LotMatch]) -> Vec<(i64, i64, i64)> {
+        matches
+            .iter()
+            .map(|m| (m.quantity, m.cost_basis_cents, m.realized_pnl_cents))
+            .collect()
+    }
+
+    #[test]
+    fn sells_consume_lots_oldest_first_across_buys() {
+        let mut tracker = LotTracker::default();
+        let first = trade(0, "ETH", "buy", TOKEN, 10_000, 30);
+        let second = trade(1, "ETH", "buy", 2 * TOKEN, 22_000, 60);
+        assert!(tracker.record(&first).is_empty());
+        assert!(tracker.record(&second).is_empty());
+
+        let matches = tracker.record(&trade(2, "ETH", "sell", 2 * TOKEN, 30_000, 90));
+        assert_eq!(matches[0].lot_trade_id, Some(first.id));
+        assert_eq!(matches[0].acquired_at, Some(first.executed_at));
+        assert_eq!(matches[1].lot_trade_id, Some(second.id));
+        assert_eq!(summary(&matches), vec![(TOKEN, 10_030, 4_925), (TOKEN, 11_030, 3_925)]);
+
+        // The rest of the second lot keeps the remainder of its cost
+        let matches = tracker.record(&trade(3, "ETH", "sell", TOKEN, 12_000, 36));
+        assert_eq!(matches[0].lot_trade_id, Some(second.id));
+        assert_eq!(summary(&matches), vec![(TOKEN, 11_030, 934)]);
+    }
+
+    #[test]
+    fn quantity_sold_beyond_open_lots_has_no_basis() {
+        let mut tracker = LotTracker::default();
+        let buy = trade(0, "ETH", "buy", TOKEN, 10_000, 0);
+        tracker.record(&buy);
+
+        let matches = tracker.record(&trade(1, "ETH", "sell", 3 * TOKEN, 36_000, 90));
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].lot_trade_id, Some(buy.id));
+        assert_eq!(matches[1].lot_trade_id, None);
+        assert_eq!(matches[1].acquired_at, None);
+        assert_eq!(summary(&matches), vec![(TOKEN, 10_000, 1_970), (2 * TOKEN, 0, 23_940)]);
+
+        let matches = tracker.record(&trade(2, "ETH", "sell", TOKEN, 12_000, 0));
+        assert_eq!(summary(&matches), vec![(TOKEN, 0, 12_000)]);
+        assert_eq!(matches[0].lot_trade_id, None);
+    }
+
+    #[test]
+    fn sell_amounts_are_split_by_quantity_and_sum_to_the_trade() {
+        let mut tracker = LotTracker::default();
+        for hour in 0..3 {
+            tracker.record(&trade(hour, "ETH", "buy", TOKEN, 10_000, 10));
+        }
+        tracker.record(&trade(3, "BTC", "buy", TOKEN, 50_000, 0));
+
+        let mut sell = trade(4, "ETH", "sell", 3 * TOKEN, 33_001, 100);
+        sell.slippage_cents = 5;
+        let matches = tracker.record(&sell);
+
+        let fees: Vec<i64> = matches.iter().map(|m| m.fee_cents).collect();
+        let gross: Vec<i64> = matches.iter().map(|m| m.gross_value_cents).collect();
+        let slippage: Vec<i64> = matches.iter().map(|m| m.slippage_cents).collect();
+        assert_eq!(fees, vec![33, 33, 34]);
+        assert_eq!(gross, vec![11_000, 11_000, 11_001]);
+        assert_eq!(slippage, vec![1, 1, 3]);
+        assert!(matches.iter().all(|m| m.cost_basis_cents == 10_010));
+        assert_eq!(matches.iter().map(|m| m.realized_pnl_cents).sum::<i64>(), 33_001 - 100 - 30_030);
+
+        // Lots are tracked per symbol
+        let matches = tracker.record(&trade(5, "BTC", "sell", TOKEN, 55_000, 0));
+        assert_eq!(summary(&matches), vec![(TOKEN, 50_000, 5_000)]);
+    }
+}
@@ -0,0 +1,100 @@
+//! Fixtures shared by the service unit tests.
+//! Builds assets, fee schedules, candles, positions and trades with round numbers.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use uuid::Uuid;
+
+use db::models::{Asset, Candle, FeeSchedule, FeeTier, Position, Trade};
+
+/// Fixed time the fixtures are dated from.
+pub fn time(hours: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 5, 0, 0, 0).unwrap() + Duration::hours(hours)
+}
+
+/// Active asset with a one cent tick.
+pub fn asset(symbol: &str, lot_size: i64, min_notional_cents: i64) -> Asset {
+    Asset {
+        symbol: symbol.to_string(),
+        name: symbol.to_string(),
+        decimals: 18,
+        tick_size_cents: 1,
+        lot_size,
+        min_notional_cents,
+        status: "active".to_string(),
+        chain: None,
+        contract_address: None,
+        created_at: time(0),
+        updated_at: time(0),
+    }
+}
+
+/// Schedule charging no fees or slippage, so fills happen at the reference price.
+pub fn free_schedule() -> FeeSchedule {
+    FeeSchedule {
+        symbol: "*".to_string(),
+        maker_fee_bps: 0,
+        taker_fee_bps: 0,
+        min_fee_cents: 0,
+        slippage_base_bps: 0,
+        slippage_impact_bps: 0,
+        slippage_depth_cents: 1,
+        max_slippage_bps: 0,
+        updated_at: time(0),
+    }
+}
+
+/// Base fee tier without a discount.
+pub fn base_tier() -> FeeTier {
+    FeeTier {
+        min_level: 1,
+        name: "Bronze".to_string(),
+        discount_percent: 0,
+        updated_at: time(0),
+    }
+}
+
+/// Hourly candle starting `hour` hours after the fixture time.
+pub fn candle(hour: i64, open: i64, high: i64, low: i64, close: i64) -> Candle {
+    Candle {
+        symbol: "ETH".to_string(),
+        timeframe: "1h".to_string(),
+        open_time: time(hour),
+        open,
+        high,
+        low,
+        close,
+        volume: 0,
+    }
+}
+
+/// Held position in micro units at an average price in cents.
+pub fn position(symbol: &str, quantity: i64, average_price: i64) -> Position {
+    Position {
+        id: Uuid::new_v4(),
+        user_id: Uuid::nil(),
+        symbol: symbol.to_string(),
+        quantity,
+        average_price,
+        current_value: 0,
+        updated_at: time(0),
+        opened_at: time(0),
+    }
+}
+
+/// Executed trade `hour` hours after the fixture time, without slippage.
+pub fn trade(hour: i64, symbol: &str, side: &str, quantity: i64, total_value: i64, fee_cents: i64) -> Trade {
+    Trade {
+        id: Uuid::new_v4(),
+        user_id: Uuid::nil(),
+        symbol: symbol.to_string(),
+        trade_type: side.to_string(),
+        quantity,
+        price: if quantity > 0 { total_value * 1_000_000 / quantity } else { 0 },
+        total_value,
+        executed_at: time(hour),
+        reference_price: 0,
+        fee_cents,
+        slippage_cents: 0,
+        bot_id: None,
+    }
+}
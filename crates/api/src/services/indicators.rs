@@ -0,0 +1,73 @@
+//! Technical indicators computed over price series.
+//! Each function returns one value per input point, `None` until enough data is available.
+
+/// Simple moving average over `period` points.
+pub fn sma(values: &[f64], period: usize) -> Vec<Option<f64>> {
+    let mut result = vec![None; values.len()];
+    if period == 0 {
+        return result;
+    }
+
+    let mut sum = 0.0;
+    for (i, value) in values.iter().enumerate() {
+        sum += value;
+        if i >= period {
+            sum -= values[i - period];
+        }
+        if i + 1 >= period {
+            result[i] = Some(sum / period as f64);
+        }
+    }
+    result
+}
+
+/// Exponential moving average seeded with the simple average of the first `period` points.
+pub fn ema(values: &[f64], period: usize) -> Vec<Option<f64>> {
+    let mut result = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return result;
+    }
+
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let mut current = values[..period].iter().sum::<f64>() / period as f64;
+    result[period - 1] = Some(current);
+    for i in period..values.len() {
+        current = alpha * values[i] + (1.0 - alpha) * current;
+        result[i] = Some(current);
+    }
+    result
+}
+
+/// Relative strength index (0 to 100) using Wilder's smoothing.
+pub fn rsi(values: &[f64], period: usize) -> Vec<Option<f64>> {
+    let mut result = vec![None; values.len()];
+    if period == 0 || values.len() <= period {
+        return result;
+    }
+
+    let (mut avg_gain, mut avg_loss) = (0.0, 0.0);
+    for i in 1..=period {
+        let change = values[i] - values[i - 1];
+        avg_gain += change.max(0.0);
+        avg_loss += (-change).max(0.0);
+    }
+    avg_gain /= period as f64;
+    avg_loss /= period as f64;
+    result[period] = Some(rsi_value(avg_gain, avg_loss));
+
+    for i in (period + 1)..values.len() {
+        let change = values[i] - values[i - 1];
+        avg_gain = (avg_gain * (period as f64 - 1.0) + change.max(0.0)) / period as f64;
+        avg_loss = (avg_loss * (period as f64 - 1.0) + (-change).max(0.0)) / period as f64;
+        result[i] = Some(rsi_value(avg_gain, avg_loss));
+    }
+    result
+}
+
+/// Converts average gain and loss into an RSI value.
+fn rsi_value(avg_gain: f64, avg_loss: f64) -> f64 {
+    if avg_loss == 0.0 {
+        return 100.0;
+    }
+    100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+}
@@ -0,0 +1,166 @@
+//! Isolated account service.
+//! Fills market orders against and values the paper accounts of tournaments and duels, kept apart from main portfolios.
+
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use db::queries::{assets, duels, fees, tournaments};
+
+use crate::services::execution::{self, Fill, Liquidity, OrderSide};
+use crate::services::oracle::PriceOracle;
+use crate::services::trading::{check_order_rules, MarketOrder, TradingError};
+use crate::state::SharedState;
+
+/// Paper account a player trades apart from their main portfolio.
+#[derive(Debug, Clone, Copy)]
+pub enum IsolatedAccount {
+    /// An entrant's account in a tournament.
+    Tournament { tournament_id: Uuid, user_id: Uuid },
+    /// A player's account in a duel.
+    Duel { duel_id: Uuid, user_id: Uuid },
+}
+
+impl IsolatedAccount {
+    /// Locks the account and returns its cash balance, or `None` when it does not exist.
+    async fn lock_cash(self, conn: &mut PgConnection) -> Result<Option<i64>, sqlx::Error> {
+        Ok(match self {
+            Self::Tournament { tournament_id, user_id } => tournaments::lock_entry(conn, tournament_id, user_id)
+                .await?
+                .map(|entry| entry.cash_balance_cents),
+            Self::Duel { duel_id, user_id } => duels::lock_account(conn, duel_id, user_id)
+                .await?
+                .map(|account| account.cash_balance_cents),
+        })
+    }
+
+    /// Locks the position in a symbol and returns its quantity and average price.
+    async fn lock_position(self, conn: &mut PgConnection, symbol: &str) -> Result<Option<(i64, i64)>, sqlx::Error> {
+        Ok(match self {
+            Self::Tournament { tournament_id, user_id } => {
+                tournaments::lock_position(conn, tournament_id, user_id, symbol)
+                    .await?
+                    .map(|p| (p.quantity, p.average_price))
+            }
+            Self::Duel { duel_id, user_id } => duels::lock_position(conn, duel_id, user_id, symbol)
+                .await?
+                .map(|p| (p.quantity, p.average_price)),
+        })
+    }
+
+    /// Stores the position in a symbol, deleting it once nothing is held.
+    async fn set_position(
+        self,
+        conn: &mut PgConnection,
+        symbol: &str,
+        quantity: i64,
+        average_price: i64,
+    ) -> Result<(), sqlx::Error> {
+        match (self, quantity) {
+            (Self::Tournament { tournament_id, user_id }, 0) => {
+                tournaments::delete_position(conn, tournament_id, user_id, symbol).await
+            }
+            (Self::Tournament { tournament_id, user_id }, _) => {
+                tournaments::upsert_position(conn, tournament_id, user_id, symbol, quantity, average_price).await
+            }
+            (Self::Duel { duel_id, user_id }, 0) => duels::delete_position(conn, duel_id, user_id, symbol).await,
+            (Self::Duel { duel_id, user_id }, _) => {
+                duels::upsert_position(conn, duel_id, user_id, symbol, quantity, average_price).await
+            }
+        }
+    }
+
+    /// Stores the account's cash balance.
+    async fn set_cash(self, conn: &mut PgConnection, cash_balance_cents: i64) -> Result<(), sqlx::Error> {
+        match self {
+            Self::Tournament { tournament_id, user_id } => {
+                tournaments::update_entry_cash(conn, tournament_id, user_id, cash_balance_cents).await
+            }
+            Self::Duel { duel_id, user_id } => {
+                duels::update_account_cash(conn, duel_id, user_id, cash_balance_cents).await
+            }
+        }
+    }
+}
+
+/// Prices a market order for an isolated account at the current oracle price.
+/// Fees and slippage follow the regular schedule at the base fee tier, so levels give no edge.
+pub async fn simulate_order(state: &SharedState, order: &MarketOrder) -> Result<Fill, TradingError> {
+    let asset = assets::find_asset(&state.db_pool, &order.symbol)
+        .await?
+        .ok_or_else(|| TradingError::UnknownSymbol(order.symbol.clone()))?;
+    let reference_price = state
+        .oracle
+        .price(&asset.symbol)
+        .ok_or_else(|| TradingError::NoPrice(asset.symbol.clone()))?;
+    check_order_rules(&asset, order.quantity, reference_price)?;
+    let schedule = fees::find_fee_schedule(&state.db_pool, &order.symbol).await?;
+    let tier = fees::find_fee_tier(&state.db_pool, 1).await?;
+
+    Ok(execution::simulate_fill(
+        order.side,
+        order.quantity,
+        reference_price,
+        asset.tick_size_cents,
+        &schedule,
+        Liquidity::Taker,
+        &tier,
+    ))
+}
+
+/// Applies a fill to the account's cash and position inside the caller's transaction.
+/// Returns `false` when the account does not exist; recording the trade is left to the caller.
+pub async fn apply_fill(
+    conn: &mut PgConnection,
+    account: IsolatedAccount,
+    order: &MarketOrder,
+    fill: &Fill,
+) -> Result<bool, TradingError> {
+    let Some(cash_balance_cents) = account.lock_cash(conn).await? else {
+        return Ok(false);
+    };
+
+    let new_cash = cash_balance_cents + fill.cash_delta_cents(order.side);
+    if new_cash < 0 {
+        return Err(TradingError::InsufficientFunds {
+            required_cents: fill.notional_cents + fill.fee_cents,
+            available_cents: cash_balance_cents,
+        });
+    }
+
+    let symbol = order.symbol.as_str();
+    let (held, average_price) = account.lock_position(conn, symbol).await?.unwrap_or((0, fill.price));
+    let (quantity, average_price) = match order.side {
+        OrderSide::Buy => {
+            let quantity = held + fill.quantity;
+            let cost = held as i128 * average_price as i128 + fill.quantity as i128 * fill.price as i128;
+            (quantity, (cost / quantity as i128) as i64)
+        }
+        OrderSide::Sell => {
+            if held < fill.quantity {
+                return Err(TradingError::InsufficientPosition {
+                    requested: fill.quantity,
+                    held,
+                });
+            }
+            (held - fill.quantity, average_price)
+        }
+    };
+
+    account.set_position(conn, symbol, quantity, average_price).await?;
+    account.set_cash(conn, new_cash).await?;
+    Ok(true)
+}
+
+/// Values isolated positions, given as symbol, quantity and average price, at current oracle prices in cents.
+/// Falls back to the average purchase price when the oracle has no price.
+pub fn positions_value_cents<'a>(
+    positions: impl IntoIterator<Item = (&'a str, i64, i64)>,
+    oracle: &PriceOracle,
+) -> i64 {
+    positions
+        .into_iter()
+        .map(|(symbol, quantity, average_price)| {
+            execution::notional_cents(quantity, oracle.price(symbol).unwrap_or(average_price))
+        })
+        .sum()
+}
@@ -0,0 +1,105 @@
+//! Leaderboard service.
+//! Recomputes the materialized player and clan leaderboards of every metric and window from XP, portfolio
+//! snapshots and ratings.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use db::queries::{leaderboards, ratings, seasons};
+use game::leaderboards::RETURN_PERIOD_SECONDS;
+use game::seasons::SEASON_RESET_XP_SOURCE;
+use game::{LeaderboardMetric, LeaderboardWindow, TraderActivity};
+
+use crate::services::clans::refresh_clan_leaderboards;
+use crate::services::ratings::current_rating;
+use crate::state::SharedState;
+
+/// Returns when a window starts, `None` for all time.
+/// Without a current season the season window is empty.
+pub async fn window_start(
+    state: &SharedState,
+    window: LeaderboardWindow,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    Ok(match window {
+        LeaderboardWindow::Week => Some(now - Duration::days(7)),
+        LeaderboardWindow::Season => Some(
+            seasons::find_current_season(&state.db_pool)
+                .await?
+                .map(|season| season.starts_at)
+                .unwrap_or(now),
+        ),
+        LeaderboardWindow::All => None,
+    })
+}
+
+/// Recomputes every player and clan leaderboard.
+/// Returns the number of entries written.
+pub async fn refresh_leaderboards(state: &SharedState, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let mut written = 0;
+
+    for window in LeaderboardWindow::ALL {
+        let since = window_start(state, window, now).await?;
+        let activity = load_activity(state, since, now).await?;
+
+        for metric in LeaderboardMetric::ALL {
+            let scored: Vec<(Uuid, f64)> = activity
+                .iter()
+                .filter_map(|(user_id, activity)| Some((*user_id, metric.score(activity)?)))
+                .collect();
+            let ranked = game::rank_scores(scored);
+
+            let user_ids: Vec<Uuid> = ranked.iter().map(|(user_id, _, _)| *user_id).collect();
+            let ranks: Vec<i32> = ranked.iter().map(|(_, rank, _)| *rank).collect();
+            let scores: Vec<f64> = ranked.iter().map(|(_, _, score)| *score).collect();
+            written += leaderboards::replace_leaderboard(
+                &state.db_pool,
+                metric.as_str(),
+                window.as_str(),
+                &user_ids,
+                &ranks,
+                &scores,
+                now,
+            )
+            .await?;
+        }
+
+        written += refresh_clan_leaderboards(state, window, since, &activity, now).await?;
+    }
+
+    Ok(written)
+}
+
+/// Loads every user's XP and portfolio performance between `since` and `now`, and the current
+/// rating of players rated since then.
+pub async fn load_activity(
+    state: &SharedState,
+    since: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<HashMap<Uuid, TraderActivity>, sqlx::Error> {
+    let mut activity: HashMap<Uuid, TraderActivity> = HashMap::new();
+
+    for (user_id, xp) in leaderboards::xp_earned_since(&state.db_pool, since, now, SEASON_RESET_XP_SOURCE).await? {
+        activity.entry(user_id).or_default().xp = xp;
+    }
+
+    for performance in leaderboards::portfolio_performance_since(&state.db_pool, since, now, RETURN_PERIOD_SECONDS).await? {
+        let entry = activity.entry(performance.user_id).or_default();
+        entry.trades = performance.trades;
+        entry.periods = performance.periods;
+        entry.pnl_cents = performance.pnl_cents;
+        entry.log_return = performance.log_return;
+        entry.mean_return = performance.mean_return;
+        entry.stddev_return = performance.stddev_return;
+    }
+
+    for rating in ratings::rated_players_since(&state.db_pool, since).await? {
+        let entry = activity.entry(rating.user_id).or_default();
+        entry.rating = Some(current_rating(&rating, now).conservative());
+        entry.rated_games = i64::from(rating.games);
+    }
+
+    Ok(activity)
+}
@@ -0,0 +1,238 @@
+//! League service.
+//! Places active players into weekly division groups, keeps their scores current and applies
+//! promotions and relegations once a week ends.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use db::models::{LeagueGroup, LeagueMember, Notification};
+use db::queries::leagues::{self, LeagueResult, LeagueStanding};
+use db::queries::notifications;
+use game::leagues::{GROUP_SIZE, WEEK_SECONDS};
+use game::{LeagueMetric, LeagueOutcome, TraderActivity};
+
+use crate::services::leaderboards::load_activity;
+use crate::services::notifications::deliver_to_webhooks;
+use crate::state::SharedState;
+
+/// Notification kind used for promotions and relegations.
+pub const LEAGUE_RESULT_KIND: &str = "league_result";
+
+/// Event-specific data included in league webhook payloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct LeagueEventData {
+    /// Group the week was played in.
+    pub group_id: Uuid,
+    /// Final rank in the group.
+    pub rank: i32,
+    /// "promoted" or "relegated".
+    pub outcome: &'static str,
+    /// Division the week was played in.
+    pub division: &'static str,
+    /// Division the player starts the next week in.
+    pub next_division: &'static str,
+}
+
+/// League group with its current table.
+#[derive(Debug, Clone)]
+pub struct LeagueTable {
+    /// Group being shown.
+    pub group: LeagueGroup,
+    /// Players best first.
+    pub standings: Vec<LeagueStanding>,
+}
+
+/// Returns the start of the league week containing `now`.
+pub fn week_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.timestamp_opt(game::calendar::week_start(now.timestamp()), 0)
+        .single()
+        .unwrap_or(now)
+}
+
+/// Returns when a league week ends.
+pub fn week_end(week_start: DateTime<Utc>) -> DateTime<Utc> {
+    week_start + Duration::seconds(WEEK_SECONDS)
+}
+
+/// Returns the user's group for the current week, placing them first if needed.
+/// New players start in the lowest division; returning players start in the division their
+/// last week sent them to.
+pub async fn enroll(state: &SharedState, user_id: Uuid, now: DateTime<Utc>) -> Result<LeagueMember, sqlx::Error> {
+    let week = week_start(now);
+    if let Some(member) = leagues::find_membership(&state.db_pool, user_id, week).await? {
+        return Ok(member);
+    }
+
+    let mut tx = state.db_pool.begin().await?;
+    let division = leagues::find_previous_membership(&mut tx, user_id, week)
+        .await?
+        .map(|(member, division)| member.next_division.unwrap_or(division))
+        .unwrap_or(0);
+
+    let metric = state.league_metric.as_str();
+    let group = match leagues::lock_open_group(&mut tx, week, division, metric, GROUP_SIZE).await? {
+        Some(group) => group,
+        None => leagues::create_group(&mut tx, week, division, metric).await?,
+    };
+    let inserted = leagues::insert_member(&mut tx, &group, user_id).await?;
+    tx.commit().await?;
+
+    match inserted {
+        Some(member) => Ok(member),
+        // Placed concurrently by another request
+        None => leagues::find_membership(&state.db_pool, user_id, week)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound),
+    }
+}
+
+/// Returns the table of the user's group this week, placing them first if needed.
+pub async fn current_table(state: &SharedState, user_id: Uuid, now: DateTime<Utc>) -> Result<LeagueTable, sqlx::Error> {
+    let member = enroll(state, user_id, now).await?;
+    let group = leagues::find_group(&state.db_pool, member.group_id)
+        .await?
+        .ok_or(sqlx::Error::RowNotFound)?;
+    let standings = leagues::group_standings(&state.db_pool, group.id).await?;
+
+    Ok(LeagueTable { group, standings })
+}
+
+/// Finalizes weeks that have ended, places players active this week and refreshes their scores.
+pub async fn update_leagues(state: &SharedState, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    let week = week_start(now);
+    finalize_ended_weeks(state, week, now).await?;
+
+    let activity = load_activity(state, Some(week), now).await?;
+    let enrolled: BTreeSet<Uuid> = leagues::list_week_members(&state.db_pool, week).await?.into_iter().collect();
+    let mut placed = 0;
+    for (user_id, _) in activity.iter().filter(|(_, activity)| activity.xp > 0) {
+        if enrolled.contains(user_id) {
+            continue;
+        }
+        match enroll(state, *user_id, now).await {
+            Ok(_) => placed += 1,
+            Err(e) => warn!("⚠️ Failed to place user {} in a league: {}", user_id, e),
+        }
+    }
+    if placed > 0 {
+        info!("🏅 Placed {} players in this week's leagues", placed);
+    }
+
+    update_scores(state, week, &activity).await?;
+    Ok(())
+}
+
+/// Scores a week's players in every metric from their activity.
+async fn update_scores(
+    state: &SharedState,
+    week: DateTime<Utc>,
+    activity: &HashMap<Uuid, TraderActivity>,
+) -> Result<(), sqlx::Error> {
+    for metric in LeagueMetric::ALL {
+        let (user_ids, scores): (Vec<Uuid>, Vec<f64>) = activity
+            .iter()
+            .filter_map(|(user_id, activity)| Some((*user_id, metric.score(activity)?)))
+            .unzip();
+        leagues::update_scores(&state.db_pool, week, metric.as_str(), &user_ids, &scores).await?;
+    }
+    Ok(())
+}
+
+/// Finalizes every group of weeks before `current_week`.
+/// Scores are recomputed from the activity within each week before ranking.
+async fn finalize_ended_weeks(
+    state: &SharedState,
+    current_week: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let groups = leagues::list_unfinalized_groups(&state.db_pool, current_week).await?;
+    let weeks: BTreeSet<DateTime<Utc>> = groups.iter().map(|group| group.week_start).collect();
+
+    for week in weeks {
+        let activity = load_activity(state, Some(week), week_end(week)).await?;
+        update_scores(state, week, &activity).await?;
+
+        let mut finalized = 0;
+        for group in groups.iter().filter(|group| group.week_start == week) {
+            match finalize_group(state, group.id, now).await {
+                Ok(true) => finalized += 1,
+                Ok(false) => {}
+                Err(e) => warn!("⚠️ Failed to finalize league group {}: {}", group.id, e),
+            }
+        }
+        info!("🏅 Finalized {} league groups for the week of {}", finalized, week.date_naive());
+    }
+    Ok(())
+}
+
+/// Records final ranks and outcomes of a group and notifies promoted and relegated players.
+/// Returns `false` if the group was already finalized.
+async fn finalize_group(state: &SharedState, group_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+    let mut tx = state.db_pool.begin().await?;
+    let Some(group) = leagues::lock_unfinalized_group(&mut tx, group_id).await? else {
+        return Ok(false);
+    };
+
+    let standings = leagues::group_standings(&mut *tx, group.id).await?;
+    let members = standings.len() as i64;
+    let division = game::division(group.division);
+    let mut results = Vec::with_capacity(standings.len());
+    let mut moves = Vec::new();
+    for standing in &standings {
+        let outcome = game::league_outcome(group.division, standing.rank, members, standing.score);
+        let next_division = outcome.next_tier(group.division);
+        results.push(LeagueResult {
+            user_id: standing.user_id,
+            rank: standing.rank as i32,
+            outcome: outcome.as_str(),
+            next_division,
+        });
+        if outcome != LeagueOutcome::Stayed {
+            moves.push((standing.user_id, standing.rank as i32, outcome, next_division));
+        }
+    }
+    leagues::finalize_group(&mut tx, group.id, &results, now).await?;
+
+    let mut announcements = Vec::with_capacity(moves.len());
+    for (user_id, rank, outcome, next_division) in moves {
+        let next = game::division(next_division);
+        let (title, message) = match outcome {
+            LeagueOutcome::Promoted => (
+                "League promotion",
+                format!("You finished #{} in {} and were promoted to {}", rank, division.name, next.name),
+            ),
+            _ => (
+                "League relegation",
+                format!("You finished #{} in {} and dropped to {}", rank, division.name, next.name),
+            ),
+        };
+        let notification =
+            notifications::create_notification(&mut *tx, user_id, LEAGUE_RESULT_KIND, title, &message, None).await?;
+        let data = LeagueEventData {
+            group_id: group.id,
+            rank,
+            outcome: outcome.as_str(),
+            division: division.name,
+            next_division: next.name,
+        };
+        announcements.push((notification, data));
+    }
+
+    tx.commit().await?;
+    announce(state, announcements);
+    Ok(true)
+}
+
+/// Delivers league notifications to webhooks in the background.
+fn announce(state: &SharedState, announcements: Vec<(Notification, LeagueEventData)>) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        for (notification, data) in announcements {
+            deliver_to_webhooks(&state, &notification, data).await;
+        }
+    });
+}
@@ -0,0 +1,156 @@
+//! Market data service.
+//! Defines candle timeframes, rolls oracle ticks up into OHLCV candles and computes 24h statistics.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use db::queries::market;
+
+use crate::services::oracle::PriceOracle;
+
+/// Supported candle timeframes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CandleInterval {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+    #[serde(rename = "1d")]
+    OneDay,
+}
+
+impl CandleInterval {
+    /// All timeframes, ordered so each one is built after its source.
+    pub const ALL: [CandleInterval; 4] = [
+        CandleInterval::OneMinute,
+        CandleInterval::FiveMinutes,
+        CandleInterval::OneHour,
+        CandleInterval::OneDay,
+    ];
+
+    /// Returns the timeframe stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CandleInterval::OneMinute => "1m",
+            CandleInterval::FiveMinutes => "5m",
+            CandleInterval::OneHour => "1h",
+            CandleInterval::OneDay => "1d",
+        }
+    }
+
+    /// Parses a timeframe as stored in the database.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|interval| interval.as_str() == value)
+    }
+
+    /// Length of one candle in seconds.
+    pub fn seconds(&self) -> i64 {
+        match self {
+            CandleInterval::OneMinute => 60,
+            CandleInterval::FiveMinutes => 5 * 60,
+            CandleInterval::OneHour => 60 * 60,
+            CandleInterval::OneDay => 24 * 60 * 60,
+        }
+    }
+
+    /// Finer timeframe this one is aggregated from, or `None` for raw ticks.
+    pub fn source(&self) -> Option<CandleInterval> {
+        match self {
+            CandleInterval::OneMinute => None,
+            CandleInterval::FiveMinutes => Some(CandleInterval::OneMinute),
+            CandleInterval::OneHour => Some(CandleInterval::FiveMinutes),
+            CandleInterval::OneDay => Some(CandleInterval::OneHour),
+        }
+    }
+
+    /// Rounds a timestamp down to the start of its candle.
+    pub fn align(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = timestamp.timestamp().div_euclid(self.seconds()) * self.seconds();
+        Utc.timestamp_opt(seconds, 0).single().unwrap_or(timestamp)
+    }
+}
+
+/// Rebuilds every timeframe coarser than `base` from `since` onwards.
+/// Used after importing history so higher timeframes reflect the new candles.
+/// Each timeframe is aligned to its own boundary so buckets are rebuilt whole.
+pub async fn rollup_coarser_candles(
+    pool: &PgPool,
+    base: CandleInterval,
+    since: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    for interval in CandleInterval::ALL {
+        if interval.seconds() > base.seconds() {
+            rollup_interval(pool, interval, interval.align(since)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Refreshes the current and previous candle of every timeframe.
+pub async fn rollup_recent_candles(pool: &PgPool, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    for interval in CandleInterval::ALL {
+        let since = interval.align(now - Duration::seconds(interval.seconds()));
+        rollup_interval(pool, interval, since).await?;
+    }
+
+    Ok(())
+}
+
+/// Loads 24h candle statistics of one or all symbols, keyed by symbol.
+pub async fn symbol_stats_24h(
+    pool: &PgPool,
+    symbol: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<HashMap<String, market::SymbolStats>, sqlx::Error> {
+    let stats = market::symbol_stats_since(pool, now - Duration::hours(24), symbol).await?;
+    Ok(stats.into_iter().map(|s| (s.symbol.clone(), s)).collect())
+}
+
+/// Rebuilds one timeframe from its source starting at an aligned time.
+async fn rollup_interval(pool: &PgPool, interval: CandleInterval, since: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    match interval.source() {
+        None => {
+            market::rollup_candles_from_ticks(pool, interval.as_str(), interval.seconds(), since).await?;
+        }
+        Some(source) => {
+            market::rollup_candles(pool, source.as_str(), interval.as_str(), interval.seconds(), since).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Publishes the one-minute candle closes at `cursor` to the oracle.
+/// Skips over gaps in the history and returns the cursor for the next step,
+/// or `None` once the history is exhausted.
+pub async fn replay_step(
+    pool: &PgPool,
+    oracle: &PriceOracle,
+    cursor: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let timeframe = CandleInterval::OneMinute;
+    let mut cursor = timeframe.align(cursor);
+
+    let mut closes = market::candle_closes_at(pool, timeframe.as_str(), cursor).await?;
+    if closes.is_empty() {
+        match market::next_candle_time(pool, timeframe.as_str(), cursor).await? {
+            Some(next) => {
+                cursor = next;
+                closes = market::candle_closes_at(pool, timeframe.as_str(), cursor).await?;
+            }
+            None => return Ok(None),
+        }
+    }
+
+    for (symbol, close) in closes {
+        oracle.publish(&symbol, close);
+    }
+
+    Ok(Some(cursor + Duration::seconds(timeframe.seconds())))
+}
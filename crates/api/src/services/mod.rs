@@ -0,0 +1,31 @@
+//! Domain services used by the API handlers and background jobs.
+//! Keeps trading and market logic out of the HTTP layer.
+
+pub mod achievements;
+pub mod alerts;
+pub mod analytics;
+pub mod backtest;
+pub mod bots;
+pub mod clans;
+pub mod duels;
+pub mod execution;
+pub mod events;
+pub mod export;
+#[cfg(test)]
+mod fixtures;
+pub mod indicators;
+pub mod isolated;
+pub mod leaderboards;
+pub mod leagues;
+pub mod market;
+pub mod notifications;
+pub mod oracle;
+pub mod portfolio;
+pub mod quests;
+pub mod ratings;
+pub mod rebalance;
+pub mod seasons;
+pub mod streaks;
+pub mod tournaments;
+pub mod trading;
+pub mod xp;
@@ -0,0 +1,260 @@
+//! Notification delivery service.
+//! Posts notifications to a user's webhooks as signed JSON payloads, only ever to public addresses.
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use serde::Serialize;
+use sha2::Sha256;
+use thiserror::Error;
+use tracing::warn;
+use uuid::Uuid;
+
+use db::models::{Notification, Webhook};
+use db::queries::notifications;
+
+use crate::state::{AppState, SharedState};
+
+/// Maximum number of webhooks per user.
+pub const MAX_WEBHOOKS_PER_USER: i64 = 5;
+
+/// Consecutive failed deliveries after which a webhook is disabled.
+pub const MAX_WEBHOOK_FAILURES: i32 = 10;
+
+/// How long a webhook endpoint may take to respond.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Header carrying the payload signature.
+pub const SIGNATURE_HEADER: &str = "x-vectra-signature";
+
+/// Header carrying the notification kind.
+pub const EVENT_HEADER: &str = "x-vectra-event";
+
+/// Reasons a webhook URL is refused.
+#[derive(Error, Debug)]
+pub enum WebhookUrlError {
+    #[error("URL must be a valid https URL")]
+    Invalid,
+    #[error("URL host could not be resolved")]
+    Unresolvable,
+    #[error("URL must not point to a loopback, private or link-local address")]
+    PrivateAddress,
+}
+
+/// Webhook URL whose host resolved to public addresses only.
+#[derive(Debug, Clone)]
+pub struct WebhookTarget {
+    /// Parsed URL.
+    pub url: Url,
+    /// Host name or address as written in the URL.
+    host: String,
+    /// Addresses the host resolved to; deliveries connect to these only.
+    addrs: Vec<SocketAddr>,
+}
+
+/// Body posted to webhooks.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload<T: Serialize> {
+    /// Notification identifier, usable to deduplicate retried deliveries.
+    pub id: Uuid,
+    /// What produced the notification, e.g. "price_alert".
+    pub event: String,
+    /// Short headline.
+    pub title: String,
+    /// Full message.
+    pub message: String,
+    /// When the notification was created.
+    pub created_at: DateTime<Utc>,
+    /// Event-specific details.
+    pub data: T,
+}
+
+/// Generates a random webhook signing secret, hex encoded.
+pub fn generate_webhook_secret() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Signs a payload with a webhook secret.
+/// Returns the header value `sha256=<hex HMAC-SHA256 of the body>`.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// Returns whether an address is publicly routable: not loopback, private, link-local, unspecified,
+/// shared, broadcast, multicast or documentation space. IPv4-mapped IPv6 addresses are checked as IPv4.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || first == 0
+                // 100.64.0.0/10 carrier-grade NAT
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_address(IpAddr::V4(ip)),
+            None => !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()),
+        },
+    }
+}
+
+/// Parses a webhook URL and resolves its host.
+/// Only https URLs whose host resolves exclusively to public addresses are accepted.
+pub async fn resolve_webhook_url(url: &str) -> Result<WebhookTarget, WebhookUrlError> {
+    let url = Url::parse(url).map_err(|_| WebhookUrlError::Invalid)?;
+    if url.scheme() != "https" {
+        return Err(WebhookUrlError::Invalid);
+    }
+    let host = url
+        .host_str()
+        .ok_or(WebhookUrlError::Invalid)?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = url.port_or_known_default().ok_or(WebhookUrlError::Invalid)?;
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|_| WebhookUrlError::Unresolvable)?
+        .collect();
+    if addrs.is_empty() {
+        return Err(WebhookUrlError::Unresolvable);
+    }
+    if !addrs.iter().all(|addr| is_public_address(addr.ip())) {
+        return Err(WebhookUrlError::PrivateAddress);
+    }
+
+    Ok(WebhookTarget { url, host, addrs })
+}
+
+/// Builds the client for one delivery: pinned to the checked addresses so the host cannot be
+/// re-resolved elsewhere, without redirects or proxies, and with a timeout.
+fn webhook_client(target: &WebhookTarget) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .redirect(Policy::none())
+        .no_proxy()
+        .timeout(WEBHOOK_TIMEOUT)
+        .resolve_to_addrs(&target.host, &target.addrs)
+        .build()
+}
+
+/// Webhook deliveries held back until the transaction that stored their notifications commits.
+#[derive(Debug, Default)]
+pub struct PendingDeliveries {
+    deliveries: Vec<(Notification, serde_json::Value)>,
+}
+
+impl PendingDeliveries {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a notification with its event-specific data.
+    pub fn push<T: Serialize>(&mut self, notification: Notification, data: T) {
+        match serde_json::to_value(data) {
+            Ok(data) => self.deliveries.push((notification, data)),
+            Err(e) => warn!("⚠️ Failed to serialize webhook payload: {}", e),
+        }
+    }
+
+    /// Delivers the queued notifications in the background, in the order they were queued.
+    /// Call only after the transaction has committed.
+    pub fn deliver(self, state: &SharedState) {
+        if self.deliveries.is_empty() {
+            return;
+        }
+        let state = state.clone();
+        tokio::spawn(async move {
+            for (notification, data) in self.deliveries {
+                deliver_to_webhooks(&state, &notification, data).await;
+            }
+        });
+    }
+}
+
+/// Delivers a notification to all of the user's active webhooks.
+/// Failures are recorded on the webhook and never returned to the caller.
+pub async fn deliver_to_webhooks<T: Serialize>(state: &AppState, notification: &Notification, data: T) {
+    let webhooks = match notifications::list_active_webhooks(&state.db_pool, notification.user_id).await {
+        Ok(webhooks) => webhooks,
+        Err(e) => {
+            warn!("⚠️ Failed to load webhooks for user {}: {}", notification.user_id, e);
+            return;
+        }
+    };
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let payload = WebhookPayload {
+        id: notification.id,
+        event: notification.kind.clone(),
+        title: notification.title.clone(),
+        message: notification.message.clone(),
+        created_at: notification.created_at,
+        data,
+    };
+    let body = match serde_json::to_vec(&payload) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("⚠️ Failed to serialize webhook payload: {}", e);
+            return;
+        }
+    };
+
+    for webhook in &webhooks {
+        deliver(state, webhook, &notification.kind, &body).await;
+    }
+}
+
+/// Posts a payload to one webhook and records the outcome.
+/// The URL is checked again before every delivery since its host may have been re-pointed.
+async fn deliver(state: &AppState, webhook: &Webhook, event: &str, body: &[u8]) {
+    let (status, succeeded) = match post(webhook, event, body).await {
+        Ok(response) => (Some(response.status().as_u16() as i16), response.status().is_success()),
+        Err(e) => {
+            warn!("⚠️ Webhook {} delivery failed: {}", webhook.id, e);
+            (None, false)
+        }
+    };
+
+    if let Err(e) =
+        notifications::record_webhook_delivery(&state.db_pool, webhook.id, status, succeeded, MAX_WEBHOOK_FAILURES).await
+    {
+        warn!("⚠️ Failed to record delivery of webhook {}: {}", webhook.id, e);
+    }
+}
+
+/// Checks a webhook's URL and posts a signed payload to it.
+async fn post(webhook: &Webhook, event: &str, body: &[u8]) -> Result<reqwest::Response, String> {
+    let target = resolve_webhook_url(&webhook.url).await.map_err(|e| e.to_string())?;
+    let client = webhook_client(&target).map_err(|e| e.to_string())?;
+    client
+        .post(target.url)
+        .header("content-type", "application/json")
+        .header(EVENT_HEADER, event)
+        .header(SIGNATURE_HEADER, sign_payload(&webhook.secret, body))
+        .body(body.to_vec())
+        .send()
+        .await
+        .map_err(|e| e.to_string())
+}
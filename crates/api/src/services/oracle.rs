@@ -0,0 +1,115 @@
+//! Simulated price oracle for paper trading.
+//! Keeps the latest price per symbol in memory and broadcasts every tick to subscribers.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use tokio::sync::broadcast;
+
+/// Markets seeded into the simulated oracle with their starting price in cents.
+pub const DEFAULT_MARKETS: [(&str, i64); 5] = [
+    ("BTC", 6_500_000),
+    ("ETH", 320_000),
+    ("SOL", 15_000),
+    ("AVAX", 3_000),
+    ("LINK", 1_500),
+];
+
+/// Maximum price move per simulated tick, in basis points.
+pub const DEFAULT_VOLATILITY_BPS: i64 = 20;
+
+/// Number of ticks buffered for slow subscribers before they start lagging.
+const TICK_CHANNEL_CAPACITY: usize = 1024;
+
+/// Single price update published by the oracle.
+#[derive(Debug, Clone)]
+pub struct PriceTick {
+    /// Trading symbol.
+    pub symbol: String,
+    /// New price in cents.
+    pub price_cents: i64,
+    /// When the price was observed.
+    pub timestamp: DateTime<Utc>,
+}
+
+/// In-memory price oracle.
+/// Holds the latest price of every known symbol and fans out ticks over a broadcast channel.
+pub struct PriceOracle {
+    prices: RwLock<HashMap<String, i64>>,
+    ticks: broadcast::Sender<PriceTick>,
+}
+
+impl PriceOracle {
+    /// Creates an empty oracle with no known symbols.
+    pub fn new() -> Self {
+        let (ticks, _) = broadcast::channel(TICK_CHANNEL_CAPACITY);
+        Self {
+            prices: RwLock::new(HashMap::new()),
+            ticks,
+        }
+    }
+
+    /// Creates an oracle seeded with the default simulated markets.
+    pub fn simulated() -> Self {
+        let oracle = Self::new();
+        for (symbol, price_cents) in DEFAULT_MARKETS {
+            oracle.publish(symbol, price_cents);
+        }
+        oracle
+    }
+
+    /// Returns the latest price of a symbol in cents.
+    pub fn price(&self, symbol: &str) -> Option<i64> {
+        self.prices.read().unwrap().get(symbol).copied()
+    }
+
+    /// Returns a copy of all latest prices keyed by symbol.
+    pub fn snapshot(&self) -> HashMap<String, i64> {
+        self.prices.read().unwrap().clone()
+    }
+
+    /// Subscribes to all future price ticks.
+    pub fn subscribe(&self) -> broadcast::Receiver<PriceTick> {
+        self.ticks.subscribe()
+    }
+
+    /// Records a new price for a symbol and broadcasts the tick.
+    pub fn publish(&self, symbol: &str, price_cents: i64) {
+        let price_cents = price_cents.max(1);
+        self.prices.write().unwrap().insert(symbol.to_string(), price_cents);
+
+        // Sending only fails when nobody is subscribed, which is fine
+        let _ = self.ticks.send(PriceTick {
+            symbol: symbol.to_string(),
+            price_cents,
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// Moves every price by a random amount of up to `volatility_bps` basis points.
+    pub fn simulate_step(&self, volatility_bps: i64) {
+        let updates: Vec<(String, i64)> = {
+            let mut rng = rand::rng();
+            self.snapshot()
+                .into_iter()
+                .map(|(symbol, price)| {
+                    let change_bps = rng.random_range(-volatility_bps..=volatility_bps);
+                    let delta = (price as i128 * change_bps as i128 / 10_000) as i64;
+                    (symbol, price + delta)
+                })
+                .collect()
+        };
+
+        for (symbol, price_cents) in updates {
+            self.publish(&symbol, price_cents);
+        }
+    }
+}
+
+impl Default for PriceOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
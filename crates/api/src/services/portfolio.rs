@@ -0,0 +1,153 @@
+//! Portfolio history service.
+//! Captures periodic portfolio snapshots and turns them into equity curves with returns and drawdowns.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use db::models::PortfolioSnapshot;
+use db::queries::{portfolio, trading, users};
+
+use crate::services::analytics;
+use crate::services::trading::positions_value_cents;
+use crate::state::AppState;
+
+/// Time range of an equity curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum HistoryRange {
+    #[serde(rename = "1d")]
+    Day,
+    #[serde(rename = "1w")]
+    Week,
+    #[default]
+    #[serde(rename = "1m")]
+    Month,
+    #[serde(rename = "3m")]
+    Quarter,
+    #[serde(rename = "1y")]
+    Year,
+    #[serde(rename = "all")]
+    All,
+}
+
+impl HistoryRange {
+    /// How far back the range reaches, or `None` for all history.
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            HistoryRange::Day => Some(Duration::days(1)),
+            HistoryRange::Week => Some(Duration::weeks(1)),
+            HistoryRange::Month => Some(Duration::days(30)),
+            HistoryRange::Quarter => Some(Duration::days(90)),
+            HistoryRange::Year => Some(Duration::days(365)),
+            HistoryRange::All => None,
+        }
+    }
+
+    /// Spacing of the returned points in seconds, keeping curves at a few hundred points.
+    pub fn bucket_seconds(&self) -> i64 {
+        match self {
+            HistoryRange::Day => 5 * 60,
+            HistoryRange::Week => 60 * 60,
+            HistoryRange::Month => 4 * 60 * 60,
+            HistoryRange::Quarter => 12 * 60 * 60,
+            HistoryRange::Year | HistoryRange::All => 24 * 60 * 60,
+        }
+    }
+
+    /// Start of the range ending at `now`.
+    pub fn start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self.duration() {
+            Some(duration) => now - duration,
+            None => Utc.timestamp_opt(0, 0).single().unwrap_or(now),
+        }
+    }
+}
+
+/// Portfolio value at a point in time.
+#[derive(Debug, Clone, Copy)]
+pub struct EquityPoint {
+    /// When the value was captured.
+    pub time: DateTime<Utc>,
+    /// Cash plus positions, in cents.
+    pub value_cents: i64,
+    /// Cash, in cents.
+    pub cash_cents: i64,
+    /// Return since the first point of the curve, as a fraction.
+    pub cumulative_return: f64,
+    /// Decline from the running peak, as a fraction.
+    pub drawdown: f64,
+}
+
+/// Equity curve with summary statistics.
+#[derive(Debug, Clone, Default)]
+pub struct EquityCurve {
+    /// Points oldest first.
+    pub points: Vec<EquityPoint>,
+    /// Return from the first to the last point, as a fraction.
+    pub total_return: f64,
+    /// Largest peak-to-trough decline, as a fraction.
+    pub max_drawdown: f64,
+}
+
+/// Builds an equity curve from snapshots ordered oldest first.
+pub fn equity_curve(snapshots: &[PortfolioSnapshot]) -> EquityCurve {
+    let values: Vec<f64> = snapshots.iter().map(|s| s.portfolio_value_cents as f64).collect();
+    let drawdowns = analytics::drawdowns(&values);
+    let first = values.first().copied().unwrap_or(0.0);
+    let return_since_start = |value: f64| if first > 0.0 { value / first - 1.0 } else { 0.0 };
+
+    let points: Vec<EquityPoint> = snapshots
+        .iter()
+        .zip(drawdowns)
+        .map(|(snapshot, drawdown)| EquityPoint {
+            time: snapshot.captured_at,
+            value_cents: snapshot.portfolio_value_cents,
+            cash_cents: snapshot.cash_balance_cents,
+            cumulative_return: return_since_start(snapshot.portfolio_value_cents as f64),
+            drawdown,
+        })
+        .collect();
+
+    EquityCurve {
+        total_return: points.last().map(|p| p.cumulative_return).unwrap_or(0.0),
+        max_drawdown: analytics::max_drawdown(&values),
+        points,
+    }
+}
+
+/// Snapshots every user's portfolio at current oracle prices.
+/// Returns the number of snapshots written.
+pub async fn capture_snapshots(state: &AppState, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let (symbols, prices): (Vec<String>, Vec<i64>) = state.oracle.snapshot().into_iter().unzip();
+    portfolio::capture_portfolio_snapshots(&state.db_pool, &symbols, &prices, now).await
+}
+
+/// Loads a user's equity curve over a range.
+/// The current live value is appended so the curve always ends at `now`.
+pub async fn portfolio_history(
+    state: &AppState,
+    user_id: Uuid,
+    range: HistoryRange,
+    now: DateTime<Utc>,
+) -> Result<EquityCurve, sqlx::Error> {
+    let mut snapshots = portfolio::list_portfolio_snapshots(
+        &state.db_pool,
+        user_id,
+        range.start(now),
+        now,
+        range.bucket_seconds(),
+    )
+    .await?;
+
+    if let Some(user) = users::find_user_by_id(&state.db_pool, user_id).await? {
+        let positions = trading::list_positions(&state.db_pool, user_id).await?;
+        snapshots.push(PortfolioSnapshot {
+            user_id,
+            captured_at: now,
+            portfolio_value_cents: user.cash_balance_cents + positions_value_cents(&positions, &state.oracle),
+            cash_balance_cents: user.cash_balance_cents,
+        });
+    }
+
+    Ok(equity_curve(&snapshots))
+}
@@ -0,0 +1,252 @@
+//! Quest service.
+//! Tracks progress on the current daily and weekly quests and grants XP when they are claimed.
+
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::PgConnection;
+use thiserror::Error;
+use uuid::Uuid;
+
+use db::models::QuestProgress;
+use db::queries::{market, portfolio, quests, trading, xp};
+use game::{QuestGoal, QuestPeriod, QuestTemplate};
+
+use crate::services::events::UserEvent;
+use crate::services::xp::grant_xp_in;
+use crate::state::SharedState;
+
+/// Rotation periods, in display order.
+const QUEST_PERIODS: [QuestPeriod; 2] = [QuestPeriod::Daily, QuestPeriod::Weekly];
+
+/// Errors that can occur while claiming a quest.
+#[derive(Error, Debug)]
+pub enum QuestError {
+    #[error("Quest {0} is not active")]
+    NotActive(String),
+    #[error("Quest is not complete yet")]
+    NotComplete,
+    #[error("Quest reward was already claimed")]
+    AlreadyClaimed,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Active quest with a user's progress.
+#[derive(Debug, Clone)]
+pub struct QuestStatus {
+    /// Quest template.
+    pub template: &'static QuestTemplate,
+    /// Start of the quest's period.
+    pub period_start: DateTime<Utc>,
+    /// End of the quest's period, exclusive.
+    pub period_end: DateTime<Utc>,
+    /// Current progress, in the goal's unit.
+    pub progress: i64,
+    /// When the reward was claimed.
+    pub claimed_at: Option<DateTime<Utc>>,
+    /// XP granted on claim.
+    pub xp_awarded: Option<i32>,
+}
+
+impl QuestStatus {
+    /// Returns whether the goal has been reached.
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.template.goal.target()
+    }
+}
+
+/// Returns the start and end of the period containing `now`.
+pub fn current_period(period: QuestPeriod, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let (start, end) = period.bounds(period.index_at(now.timestamp()));
+    (unix_time(start), unix_time(end))
+}
+
+/// Returns the quests active at a time with the start and end of their period.
+fn quests_at(now: DateTime<Utc>) -> Vec<(&'static QuestTemplate, DateTime<Utc>, DateTime<Utc>)> {
+    QUEST_PERIODS
+        .iter()
+        .flat_map(|period| {
+            let (start, end) = current_period(*period, now);
+            game::active_quests(*period, period.index_at(now.timestamp()))
+                .into_iter()
+                .map(move |template| (template, start, end))
+        })
+        .collect()
+}
+
+/// Advances the user's event-tracked quests that were active when the event happened, in the caller's transaction.
+pub async fn record_event(conn: &mut PgConnection, event: &UserEvent) -> Result<(), sqlx::Error> {
+    for (template, period_start, _) in quests_at(event.occurred_at) {
+        let amount = template.goal.event_progress(&event.event);
+        if amount > 0 {
+            quests::add_quest_progress(
+                &mut *conn,
+                event.user_id,
+                template.id,
+                template.period.as_str(),
+                period_start,
+                amount,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the user's status on every active quest.
+/// Hold quests are checked against the current portfolio and market quests against the period's snapshots.
+pub async fn active_quest_statuses(
+    state: &SharedState,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Vec<QuestStatus>, sqlx::Error> {
+    let active = quests_at(now);
+    let period_starts: Vec<DateTime<Utc>> = active.iter().map(|(_, start, _)| *start).collect();
+    let stored = quests::list_quest_progress(&state.db_pool, user_id, &period_starts).await?;
+
+    let mut statuses = Vec::with_capacity(active.len());
+    for (template, period_start, period_end) in active {
+        let row = stored
+            .iter()
+            .find(|row| row.quest_id == template.id && row.period_start == period_start);
+        let progress = match (row, template.goal) {
+            // Claimed quests keep the progress they were completed with
+            (Some(row), _) if row.claimed_at.is_some() => row.progress,
+            (row, goal) if goal.is_event_tracked() => row.map(|row| row.progress).unwrap_or(0),
+            (_, goal) => live_progress(state, user_id, &goal, period_start, now).await?,
+        };
+        statuses.push(QuestStatus {
+            template,
+            period_start,
+            period_end,
+            progress,
+            claimed_at: row.and_then(|row| row.claimed_at),
+            xp_awarded: row.and_then(|row| row.xp_awarded),
+        });
+    }
+
+    Ok(statuses)
+}
+
+/// Claims a completed quest and grants its XP reward in one transaction.
+/// Quest rewards are fixed and exempt from daily XP caps, so a claim always pays the full reward.
+pub async fn claim_quest(
+    state: &SharedState,
+    user_id: Uuid,
+    quest_id: &str,
+    now: DateTime<Utc>,
+) -> Result<QuestStatus, QuestError> {
+    let mut status = active_quest_statuses(state, user_id, now)
+        .await?
+        .into_iter()
+        .find(|status| status.template.id == quest_id)
+        .ok_or_else(|| QuestError::NotActive(quest_id.to_string()))?;
+    if status.claimed_at.is_some() {
+        return Err(QuestError::AlreadyClaimed);
+    }
+    if !status.is_complete() {
+        return Err(QuestError::NotComplete);
+    }
+
+    let mut tx = state.db_pool.begin().await?;
+
+    // The XP key makes a concurrent second claim grant nothing before the claim itself is rejected
+    let source = game::quests::QUEST_XP_SOURCE;
+    let reference_id = format!("{}:{}", quest_id, status.period_start.date_naive());
+    let key = format!("{}:{}", source, reference_id);
+    let granted = if status.template.xp_reward > 0 {
+        let grant = xp::XpGrant {
+            source,
+            amount: status.template.xp_reward as i32,
+            reference_id: Some(&reference_id),
+            idempotency_key: Some(&key),
+        };
+        grant_xp_in(&mut tx, user_id, &grant).await?
+    } else {
+        None
+    };
+
+    let claim = QuestProgress {
+        user_id,
+        quest_id: quest_id.to_string(),
+        period: status.template.period.as_str().to_string(),
+        period_start: status.period_start,
+        progress: status.progress,
+        claimed_at: Some(now),
+        xp_awarded: Some(granted.as_ref().map(|granted| granted.event.amount).unwrap_or(0)),
+        updated_at: now,
+    };
+    let claimed = quests::claim_quest(&mut *tx, &claim)
+        .await?
+        .ok_or(QuestError::AlreadyClaimed)?;
+    tx.commit().await?;
+
+    if let Some(granted) = granted {
+        granted.announce(state);
+    }
+
+    status.claimed_at = claimed.claimed_at;
+    status.xp_awarded = claimed.xp_awarded;
+    Ok(status)
+}
+
+/// Computes progress of goals that are not tracked from events.
+async fn live_progress(
+    state: &SharedState,
+    user_id: Uuid,
+    goal: &QuestGoal,
+    period_start: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<i64, sqlx::Error> {
+    match goal {
+        QuestGoal::HoldSymbol { symbol, hours } => {
+            let positions = trading::list_positions(&state.db_pool, user_id).await?;
+            Ok(positions
+                .iter()
+                .find(|position| position.symbol == *symbol)
+                .map(|position| (now - position.opened_at.max(period_start)).num_hours().clamp(0, *hours))
+                .unwrap_or(0))
+        }
+        QuestGoal::BeatMarket => Ok(beats_market(state, user_id, period_start, now).await? as i64),
+        _ => Ok(0),
+    }
+}
+
+/// Returns whether the user's portfolio has gained more than the average market within the quest period.
+/// Both returns are measured over the same window, from the user's first portfolio snapshot in the period
+/// to their last one before `until`; the market return averages every symbol's candle move in that window.
+async fn beats_market(
+    state: &SharedState,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let Some(start) = portfolio::first_portfolio_snapshot_since(&state.db_pool, user_id, since).await? else {
+        return Ok(false);
+    };
+    let Some(end) = portfolio::last_portfolio_snapshot_before(&state.db_pool, user_id, until).await? else {
+        return Ok(false);
+    };
+    if end.captured_at <= start.captured_at || start.portfolio_value_cents <= 0 {
+        return Ok(false);
+    }
+    let portfolio_return = end.portfolio_value_cents as f64 / start.portfolio_value_cents as f64 - 1.0;
+
+    let market_returns: Vec<f64> = market::symbol_moves_between(&state.db_pool, start.captured_at, end.captured_at)
+        .await?
+        .into_iter()
+        .filter(|(_, open, _)| *open > 0)
+        .map(|(_, open, close)| close as f64 / open as f64 - 1.0)
+        .collect();
+    if market_returns.is_empty() {
+        return Ok(false);
+    }
+    let market_return = market_returns.iter().sum::<f64>() / market_returns.len() as f64;
+
+    Ok(portfolio_return > market_return)
+}
+
+/// Converts a Unix timestamp to a UTC time.
+fn unix_time(seconds: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(seconds, 0).single().unwrap_or_default()
+}
@@ -0,0 +1,148 @@
+//! Skill rating service.
+//! Applies Glicko-2 updates from settled duels and finalized tournaments and finds balanced opponents.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use db::models::PlayerRating;
+use db::queries::ratings::{self, MatchCandidate, NewRatingEvent};
+use game::ratings::{DUEL_RATING_SOURCE, RATING_PERIOD_DAYS, TOURNAMENT_RATING_SOURCE};
+use game::{GameResult, Rating};
+
+use crate::state::SharedState;
+
+/// Returns a stored rating as of `now`, with the deviation grown for idle rating periods.
+pub fn current_rating(stored: &PlayerRating, now: DateTime<Utc>) -> Rating {
+    let rating = Rating {
+        rating: stored.rating,
+        deviation: stored.deviation,
+        volatility: stored.volatility,
+    };
+    if stored.games == 0 {
+        return rating;
+    }
+    let idle_seconds = (now - stored.updated_at).num_seconds().max(0) as f64;
+    rating.decayed(idle_seconds / (RATING_PERIOD_DAYS * 86_400.0))
+}
+
+/// Rates both players of a settled duel.
+/// `challenger_score` is 1.0 for a challenger win, 0.5 for a draw and 0.0 for a loss.
+pub async fn rate_duel(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    challenger_id: Uuid,
+    opponent_id: Uuid,
+    challenger_score: f64,
+    now: DateTime<Utc>,
+) -> Result<usize, sqlx::Error> {
+    let results = [vec![(1, challenger_score)], vec![(0, 1.0 - challenger_score)]];
+    apply_results(conn, DUEL_RATING_SOURCE, duel_id, &[challenger_id, opponent_id], &results, now).await
+}
+
+/// Rates a finalized tournament's finishers from their placements.
+/// `finishers` must be sorted best first; fewer than two finishers rate nobody.
+pub async fn rate_placements(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    finishers: &[(Uuid, i64)],
+    now: DateTime<Utc>,
+) -> Result<usize, sqlx::Error> {
+    if finishers.len() < 2 {
+        return Ok(0);
+    }
+    let players: Vec<Uuid> = finishers.iter().map(|(user_id, _)| *user_id).collect();
+    let ranks: Vec<i64> = finishers.iter().map(|(_, rank)| *rank).collect();
+    let results = game::placement_results(&ranks);
+    apply_results(conn, TOURNAMENT_RATING_SOURCE, tournament_id, &players, &results, now).await
+}
+
+/// Returns the user's current rating and the players closest to it, as of `now`.
+pub async fn match_candidates(
+    state: &SharedState,
+    user_id: Uuid,
+    limit: i64,
+    now: DateTime<Utc>,
+) -> Result<(Rating, Vec<(MatchCandidate, Rating)>), sqlx::Error> {
+    let rating = ratings::find_rating(&state.db_pool, user_id)
+        .await?
+        .map(|stored| current_rating(&stored, now))
+        .unwrap_or_default();
+
+    let candidates = ratings::find_match_candidates(&state.db_pool, user_id, rating.rating, limit)
+        .await?
+        .into_iter()
+        .map(|candidate| {
+            let mut candidate_rating = Rating {
+                rating: candidate.rating,
+                deviation: candidate.deviation,
+                volatility: candidate.volatility,
+            };
+            if let Some(updated_at) = candidate.updated_at {
+                let idle_seconds = (now - updated_at).num_seconds().max(0) as f64;
+                candidate_rating = candidate_rating.decayed(idle_seconds / (RATING_PERIOD_DAYS * 86_400.0));
+            }
+            (candidate, candidate_rating)
+        })
+        .collect();
+
+    Ok((rating, candidates))
+}
+
+/// Applies one rating period to every player, scoring each against their opponents' ratings from
+/// before the update. `results[i]` lists the opponents of `players[i]` by index with their scores.
+/// Returns the number of players rated.
+async fn apply_results(
+    conn: &mut PgConnection,
+    source: &str,
+    reference_id: Uuid,
+    players: &[Uuid],
+    results: &[Vec<(usize, f64)>],
+    now: DateTime<Utc>,
+) -> Result<usize, sqlx::Error> {
+    let stored = ratings::lock_ratings(conn, players).await?;
+    let before: Vec<Rating> = players
+        .iter()
+        .map(|user_id| {
+            stored
+                .iter()
+                .find(|rating| rating.user_id == *user_id)
+                .map(|rating| current_rating(rating, now))
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut rated = 0;
+    for (index, user_id) in players.iter().enumerate() {
+        let games: Vec<GameResult> = results[index]
+            .iter()
+            .map(|(opponent, score)| GameResult {
+                opponent: before[*opponent],
+                score: *score,
+            })
+            .collect();
+        if games.is_empty() {
+            continue;
+        }
+
+        let player = before[index];
+        let after = player.update(&games);
+        let event = NewRatingEvent {
+            user_id: *user_id,
+            source,
+            reference_id,
+            games: games.len() as i32,
+            score: games.iter().map(|game| game.score).sum(),
+            expected_score: games.iter().map(|game| game::expected_score(&player, &game.opponent)).sum(),
+            rating_before: player.rating,
+            rating_after: after.rating,
+            deviation_after: after.deviation,
+            volatility_after: after.volatility,
+        };
+        if ratings::record_rating(conn, &event).await? {
+            rated += 1;
+        }
+    }
+
+    Ok(rated)
+}
@@ -0,0 +1,450 @@
+//! Portfolio rebalancing service.
+//! Plans the trades that move a portfolio to target weights and executes them as one batch.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+use uuid::Uuid;
+
+use db::models::{Asset, FeeSchedule, FeeTier, Position, RebalanceSchedule, Trade};
+use db::queries::{assets, fees, rebalance, trading, users};
+
+use crate::services::bots::next_scheduled_run;
+use crate::services::execution::{self, Fill, Liquidity, OrderSide};
+use crate::services::trading::{
+    apply_fill, check_order_rules, check_order_size, MarketOrder, TradingError,
+};
+use crate::state::AppState;
+
+/// Basis points in 100%.
+pub const WEIGHT_DENOMINATOR_BPS: i64 = 10_000;
+
+/// Target share of the portfolio for one symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetWeight {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Share of total portfolio value, in basis points.
+    pub weight_bps: i64,
+}
+
+/// Trading conditions of a symbol at planning time.
+#[derive(Debug, Clone)]
+pub struct RebalanceMarket {
+    /// Asset and its trading rules.
+    pub asset: Asset,
+    /// Fee and slippage schedule.
+    pub schedule: FeeSchedule,
+    /// Current oracle price in cents.
+    pub price_cents: i64,
+}
+
+/// Order needed to reach a target weight.
+#[derive(Debug, Clone)]
+pub struct PlannedOrder {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Buy or sell.
+    pub side: OrderSide,
+    /// Simulated fill with fees and slippage.
+    pub fill: Fill,
+}
+
+/// Current, target and post-rebalance value of one holding.
+#[derive(Debug, Clone)]
+pub struct Allocation {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Value before rebalancing, in cents.
+    pub current_value_cents: i64,
+    /// Value at the target weight, in cents.
+    pub target_value_cents: i64,
+    /// Value after the planned orders, in cents.
+    pub projected_value_cents: i64,
+}
+
+/// Orders and resulting allocations of a rebalance.
+#[derive(Debug, Clone, Default)]
+pub struct RebalancePlan {
+    /// Cash plus positions before rebalancing, in cents.
+    pub total_value_cents: i64,
+    /// Cash before rebalancing, in cents.
+    pub current_cash_cents: i64,
+    /// Cash at the target weight, in cents.
+    pub target_cash_cents: i64,
+    /// Cash after the planned orders, in cents.
+    pub projected_cash_cents: i64,
+    /// Allocation of every held or targeted symbol.
+    pub allocations: Vec<Allocation>,
+    /// Orders to execute, sells first.
+    pub orders: Vec<PlannedOrder>,
+    /// Fees of all orders, in cents.
+    pub total_fees_cents: i64,
+    /// Slippage of all orders, in cents.
+    pub total_slippage_cents: i64,
+    /// Largest difference between a current and target weight, in basis points.
+    pub max_drift_bps: i64,
+}
+
+/// Plans the orders that move a portfolio to target weights.
+/// Cash receives whatever weight the targets leave over. Held symbols without a target are sold.
+/// Sells are planned first so their proceeds fund the buys; orders below an asset's
+/// minimum size are skipped.
+pub fn plan_rebalance(
+    cash_cents: i64,
+    positions: &[Position],
+    targets: &[TargetWeight],
+    markets: &HashMap<String, RebalanceMarket>,
+    tier: &FeeTier,
+) -> Result<RebalancePlan, TradingError> {
+    let held: HashMap<&str, i64> = positions.iter().map(|p| (p.symbol.as_str(), p.quantity)).collect();
+    let weights: HashMap<&str, i64> = targets.iter().map(|t| (t.symbol.as_str(), t.weight_bps)).collect();
+    let symbols: BTreeSet<&str> = held.keys().chain(weights.keys()).copied().collect();
+
+    let market = |symbol: &str| {
+        markets
+            .get(symbol)
+            .ok_or_else(|| TradingError::UnknownSymbol(symbol.to_string()))
+    };
+    let value_of = |symbol: &str, quantity: i64| -> Result<i64, TradingError> {
+        Ok(execution::notional_cents(quantity, market(symbol)?.price_cents))
+    };
+
+    let mut total_value = cash_cents;
+    for symbol in &symbols {
+        total_value += value_of(symbol, held.get(symbol).copied().unwrap_or(0))?;
+    }
+    let target_value = |symbol: &str| {
+        (total_value as i128 * weights.get(symbol).copied().unwrap_or(0) as i128 / WEIGHT_DENOMINATOR_BPS as i128) as i64
+    };
+
+    let mut plan = RebalancePlan {
+        total_value_cents: total_value,
+        current_cash_cents: cash_cents,
+        target_cash_cents: total_value
+            - symbols.iter().map(|symbol| target_value(symbol)).sum::<i64>(),
+        ..RebalancePlan::default()
+    };
+
+    if total_value > 0 {
+        let cash_drift = (cash_cents - plan.target_cash_cents).abs() as i128;
+        plan.max_drift_bps = (cash_drift * WEIGHT_DENOMINATOR_BPS as i128 / total_value as i128) as i64;
+    }
+
+    let mut cash = cash_cents;
+    let mut quantities: HashMap<&str, i64> = held.clone();
+    let mut buys = Vec::new();
+
+    for symbol in &symbols {
+        let market = market(symbol)?;
+        let quantity = held.get(symbol).copied().unwrap_or(0);
+        let current = value_of(symbol, quantity)?;
+        let target = target_value(symbol);
+        if total_value > 0 {
+            let drift = ((current - target).abs() as i128 * WEIGHT_DENOMINATOR_BPS as i128 / total_value as i128) as i64;
+            plan.max_drift_bps = plan.max_drift_bps.max(drift);
+        }
+
+        if target >= current {
+            buys.push((*symbol, target - current));
+            continue;
+        }
+
+        // Dropped symbols are sold completely, others down to the target
+        let lot = market.asset.lot_size;
+        let sell_quantity = if target == 0 {
+            quantity
+        } else {
+            let excess = ((current - target) as i128 * execution::MICRO_UNITS as i128 / market.price_cents as i128) as i64;
+            (excess / lot * lot).min(quantity)
+        };
+        if let Some(fill) = plan_fill(market, OrderSide::Sell, sell_quantity, tier)? {
+            cash += fill.cash_delta_cents(OrderSide::Sell);
+            *quantities.entry(symbol).or_default() -= fill.quantity;
+            plan.orders.push(PlannedOrder {
+                symbol: symbol.to_string(),
+                side: OrderSide::Sell,
+                fill,
+            });
+        }
+    }
+
+    // Largest shortfalls are funded first in case cash runs out
+    buys.sort_by_key(|b| std::cmp::Reverse(b.1));
+    for (symbol, shortfall) in buys {
+        let market = market(symbol)?;
+        let budget = shortfall.min(cash);
+        let quantity = execution::affordable_quantity(budget, market.price_cents, market.asset.lot_size, &market.schedule);
+        if let Some(fill) = plan_fill(market, OrderSide::Buy, quantity, tier)? {
+            if cash + fill.cash_delta_cents(OrderSide::Buy) < 0 {
+                continue;
+            }
+            cash += fill.cash_delta_cents(OrderSide::Buy);
+            *quantities.entry(symbol).or_default() += fill.quantity;
+            plan.orders.push(PlannedOrder {
+                symbol: symbol.to_string(),
+                side: OrderSide::Buy,
+                fill,
+            });
+        }
+    }
+
+    for symbol in &symbols {
+        plan.allocations.push(Allocation {
+            symbol: symbol.to_string(),
+            current_value_cents: value_of(symbol, held.get(symbol).copied().unwrap_or(0))?,
+            target_value_cents: target_value(symbol),
+            projected_value_cents: value_of(symbol, quantities.get(symbol).copied().unwrap_or(0))?,
+        });
+    }
+    plan.projected_cash_cents = cash;
+    plan.total_fees_cents = plan.orders.iter().map(|o| o.fill.fee_cents).sum();
+    plan.total_slippage_cents = plan.orders.iter().map(|o| o.fill.slippage_cents).sum();
+
+    Ok(plan)
+}
+
+/// Simulates a taker fill, or returns `None` when the order is too small to place.
+/// Fails when the market does not accept orders.
+fn plan_fill(
+    market: &RebalanceMarket,
+    side: OrderSide,
+    quantity: i64,
+    tier: &FeeTier,
+) -> Result<Option<Fill>, TradingError> {
+    if check_order_size(&market.asset, quantity, market.price_cents).is_err() {
+        return Ok(None);
+    }
+    check_order_rules(&market.asset, quantity, market.price_cents)?;
+
+    Ok(Some(execution::simulate_fill(
+        side,
+        quantity,
+        market.price_cents,
+        market.asset.tick_size_cents,
+        &market.schedule,
+        Liquidity::Taker,
+        tier,
+    )))
+}
+
+/// Loads the asset, fee schedule and oracle price of every symbol.
+pub async fn load_markets(
+    state: &AppState,
+    symbols: impl IntoIterator<Item = &str>,
+) -> Result<HashMap<String, RebalanceMarket>, TradingError> {
+    let mut markets = HashMap::new();
+    for symbol in symbols {
+        if markets.contains_key(symbol) {
+            continue;
+        }
+        let asset = assets::find_asset(&state.db_pool, symbol)
+            .await?
+            .ok_or_else(|| TradingError::UnknownSymbol(symbol.to_string()))?;
+        let price_cents = state
+            .oracle
+            .price(symbol)
+            .ok_or_else(|| TradingError::NoPrice(symbol.to_string()))?;
+        let schedule = fees::find_fee_schedule(&state.db_pool, symbol).await?;
+        markets.insert(symbol.to_string(), RebalanceMarket { asset, schedule, price_cents });
+    }
+
+    Ok(markets)
+}
+
+/// Plans a rebalance of a user's portfolio at current prices without trading.
+pub async fn preview_rebalance(
+    state: &AppState,
+    user_id: Uuid,
+    targets: &[TargetWeight],
+) -> Result<RebalancePlan, TradingError> {
+    let user = users::find_user_by_id(&state.db_pool, user_id)
+        .await?
+        .ok_or(TradingError::UserNotFound)?;
+    let positions = trading::list_positions(&state.db_pool, user_id).await?;
+    let markets = load_markets(state, market_symbols(&positions, targets)).await?;
+    let tier = fees::find_fee_tier(&state.db_pool, user.level).await?;
+
+    plan_rebalance(user.cash_balance_cents, &positions, targets, &markets, &tier)
+}
+
+/// Rebalances a user's portfolio to target weights.
+/// All orders execute in one transaction, so either every order fills or none does.
+pub async fn execute_rebalance(
+    state: &AppState,
+    user_id: Uuid,
+    targets: &[TargetWeight],
+) -> Result<(RebalancePlan, Vec<Trade>), TradingError> {
+    let mut tx = state.db_pool.begin().await?;
+
+    let user = trading::lock_user(&mut tx, user_id)
+        .await?
+        .ok_or(TradingError::UserNotFound)?;
+    let positions = trading::list_positions(&mut *tx, user_id).await?;
+    let markets = load_markets(state, market_symbols(&positions, targets)).await?;
+    let tier = fees::find_fee_tier(&mut *tx, user.level).await?;
+    let plan = plan_rebalance(user.cash_balance_cents, &positions, targets, &markets, &tier)?;
+
+    let mut cash = user.cash_balance_cents;
+    let mut applied_fills = Vec::with_capacity(plan.orders.len());
+    for planned in &plan.orders {
+        let order = MarketOrder {
+            symbol: planned.symbol.clone(),
+            side: planned.side,
+            quantity: planned.fill.quantity,
+            bot_id: None,
+        };
+        applied_fills.push(apply_fill(&mut tx, &state.oracle, user_id, cash, &order, &planned.fill).await?);
+        cash += planned.fill.cash_delta_cents(planned.side);
+    }
+
+    tx.commit().await?;
+    state.events.notify();
+
+    let trades = applied_fills.into_iter().map(|applied| applied.trade).collect();
+
+    Ok((plan, trades))
+}
+
+/// Rebalances every user whose scheduled rebalance is due.
+/// Runs are skipped while the portfolio is within the schedule's drift threshold.
+pub async fn run_due_rebalances(state: &AppState, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    for schedule in rebalance::list_due_rebalance_schedules(&state.db_pool, now).await? {
+        let targets = schedule_targets(&schedule);
+        let result = match preview_rebalance(state, schedule.user_id, &targets).await {
+            Ok(plan) if plan.max_drift_bps < i64::from(schedule.drift_threshold_bps) => Ok(()),
+            Ok(_) => execute_rebalance(state, schedule.user_id, &targets).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+
+        let error = match result {
+            Ok(()) => None,
+            Err(TradingError::Database(e)) => return Err(e),
+            Err(e) => {
+                warn!("⚠️ Scheduled rebalance for {} failed: {}", schedule.user_id, e);
+                Some(e.to_string())
+            }
+        };
+
+        let next_run_at = next_scheduled_run(schedule.next_run_at, schedule.interval_seconds, now);
+        rebalance::record_rebalance_run(&state.db_pool, schedule.user_id, next_run_at, error.as_deref()).await?;
+    }
+
+    Ok(())
+}
+
+/// Converts a stored schedule into target weights.
+pub fn schedule_targets(schedule: &RebalanceSchedule) -> Vec<TargetWeight> {
+    schedule
+        .symbols
+        .iter()
+        .zip(&schedule.weights_bps)
+        .map(|(symbol, weight)| TargetWeight {
+            symbol: symbol.clone(),
+            weight_bps: i64::from(*weight),
+        })
+        .collect()
+}
+
+/// Symbols that are held or targeted.
+fn market_symbols<'a>(positions: &'a [Position], targets: &'a [TargetWeight]) -> impl Iterator<Item = &'a str> {
+    positions
+        .iter()
+        .map(|p| p.symbol.as_str())
+        .chain(targets.iter().map(|t| t.symbol.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::fixtures::{asset, base_tier, free_schedule, position};
+
+    /// One token in micro units.
+    const TOKEN: i64 = 1_000_000;
+
+    /// ETH at $100, BTC at $500 and SOL at $10, each traded in 0.001 lots with a $10 minimum order.
+    fn markets() -> HashMap<String, RebalanceMarket> {
+        [("ETH", 10_000), ("BTC", 50_000), ("SOL", 1_000)]
+            .into_iter()
+            .map(|(symbol, price_cents)| {
+                let market = RebalanceMarket {
+                    asset: asset(symbol, 1_000, 1_000),
+                    schedule: free_schedule(),
+                    price_cents,
+                };
+                (symbol.to_string(), market)
+            })
+            .collect()
+    }
+
+    fn target(symbol: &str, weight_bps: i64) -> TargetWeight {
+        TargetWeight {
+            symbol: symbol.to_string(),
+            weight_bps,
+        }
+    }
+
+    fn plan(cash_cents: i64, positions: &[Position], targets: &[TargetWeight]) -> RebalancePlan {
+        plan_rebalance(cash_cents, positions, targets, &markets(), &base_tier()).unwrap()
+    }
+
+    /// Symbol, side and quantity of every planned order.
+    fn orders(plan: &RebalancePlan) -> Vec<(&str, OrderSide, i64)> {
+        plan.orders
+            .iter()
+            .map(|order| (order.symbol.as_str(), order.side, order.fill.quantity))
+            .collect()
+    }
+
+    #[test]
+    fn sells_fund_the_buys() {
+        let plan = plan(0, &[position("ETH", 100 * TOKEN, 10_000)], &[target("ETH", 5_000), target("BTC", 5_000)]);
+
+        assert_eq!(
+            orders(&plan),
+            vec![("ETH", OrderSide::Sell, 50 * TOKEN), ("BTC", OrderSide::Buy, 10 * TOKEN)]
+        );
+        assert_eq!(plan.total_value_cents, 1_000_000);
+        assert_eq!(plan.projected_cash_cents, 0);
+        assert_eq!(plan.max_drift_bps, 5_000);
+    }
+
+    #[test]
+    fn dropped_symbols_are_sold_in_full() {
+        let plan = plan(996_667, &[position("SOL", 3_333_000, 1_000)], &[target("ETH", 10_000)]);
+
+        assert_eq!(plan.orders[0].symbol, "SOL");
+        assert_eq!(plan.orders[0].side, OrderSide::Sell);
+        assert_eq!(plan.orders[0].fill.quantity, 3_333_000);
+        let sol = plan.allocations.iter().find(|a| a.symbol == "SOL").unwrap();
+        assert_eq!(sol.target_value_cents, 0);
+        assert_eq!(sol.projected_value_cents, 0);
+        assert_eq!(plan.orders[1].side, OrderSide::Buy);
+        assert_eq!(plan.orders[1].fill.quantity, 100 * TOKEN);
+    }
+
+    #[test]
+    fn orders_below_the_minimum_size_are_skipped() {
+        // A $5 buy and a $5 sell are both under the $10 minimum
+        let buy = plan(500, &[position("ETH", 100 * TOKEN, 10_000)], &[target("ETH", 10_000)]);
+        assert!(buy.orders.is_empty());
+        assert_eq!(buy.projected_cash_cents, 500);
+
+        let sell = plan(0, &[position("ETH", 100 * TOKEN, 10_000)], &[target("ETH", 9_995)]);
+        assert!(sell.orders.is_empty());
+        assert_eq!(sell.allocations[0].projected_value_cents, 1_000_000);
+    }
+
+    #[test]
+    fn cash_takes_the_weight_left_over() {
+        let plan = plan(1_000_000, &[], &[target("ETH", 3_000), target("BTC", 2_000)]);
+
+        assert_eq!(plan.target_cash_cents, 500_000);
+        assert_eq!(
+            orders(&plan),
+            vec![("ETH", OrderSide::Buy, 30 * TOKEN), ("BTC", OrderSide::Buy, 4 * TOKEN)]
+        );
+        assert_eq!(plan.projected_cash_cents, 500_000);
+    }
+}
@@ -0,0 +1,193 @@
+//! Season service.
+//! Rolls seasons over by archiving final standings, resetting progress and granting finish rewards.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::info;
+use uuid::Uuid;
+
+use db::models::{Notification, Season};
+use db::queries::{duels, notifications, seasons, xp};
+use game::seasons::{SEASON_RESET_XP_SOURCE, SEASON_REWARD_XP_SOURCE};
+use game::SEASON_REWARDS;
+
+use crate::services::duels::{announce as announce_duels, settle_locked_duels};
+use crate::services::notifications::deliver_to_webhooks;
+use crate::state::SharedState;
+
+/// Notification kind used for season finish rewards.
+pub const SEASON_REWARD_KIND: &str = "season_reward";
+
+/// Longest season that can be scheduled, in days.
+pub const MAX_SEASON_DAYS: i64 = 365;
+
+/// Errors that can occur while rolling a season over.
+#[derive(Error, Debug)]
+pub enum SeasonError {
+    #[error("No season is in progress")]
+    NoCurrentSeason,
+    #[error("Season was already rolled over")]
+    AlreadyRolledOver,
+    #[error("Season length must be between 1 and {MAX_SEASON_DAYS} days")]
+    InvalidLength,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Event-specific data included in season reward webhook payloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonRewardData {
+    /// Number of the season the reward was earned in.
+    pub season: i32,
+    /// Final XP rank.
+    pub rank: i32,
+    /// Reward title, e.g. "Champion".
+    pub title: &'static str,
+    /// XP granted in the new season.
+    pub xp_bonus: u32,
+}
+
+/// Outcome of a season rollover.
+#[derive(Debug, Clone)]
+pub struct SeasonRollover {
+    /// Season that was closed.
+    pub closed: Season,
+    /// Season that started.
+    pub started: Season,
+    /// Users who earned a finish reward.
+    pub rewarded: usize,
+}
+
+/// Rolls the current season over if it is past its scheduled end.
+pub async fn roll_over_due_season(
+    state: &SharedState,
+    length_days: i64,
+    now: DateTime<Utc>,
+) -> Result<Option<SeasonRollover>, SeasonError> {
+    let Some(current) = seasons::find_current_season(&state.db_pool).await? else {
+        return Ok(None);
+    };
+    if current.ends_at > now {
+        return Ok(None);
+    }
+
+    match rollover_season(state, Some(current.id), None, length_days, now).await {
+        Ok(rollover) => Ok(Some(rollover)),
+        // Another instance or an admin rolled it over first
+        Err(SeasonError::AlreadyRolledOver) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Closes the current season and starts the next one.
+/// Open duels are settled and final standings archived at current oracle prices, open positions are
+/// sold at the same prices, every user's XP, level and balances are reset, and top finishers are
+/// granted their reward XP in the new season.
+/// When `expected_season_id` is given the rollover only happens if that season is still current.
+pub async fn rollover_season(
+    state: &SharedState,
+    expected_season_id: Option<Uuid>,
+    name: Option<&str>,
+    length_days: i64,
+    now: DateTime<Utc>,
+) -> Result<SeasonRollover, SeasonError> {
+    if !(1..=MAX_SEASON_DAYS).contains(&length_days) {
+        return Err(SeasonError::InvalidLength);
+    }
+
+    let mut tx = state.db_pool.begin().await?;
+    // Open duels are settled before the reset so their escrowed stakes cannot be paid into the next
+    // season. They are locked first, as duel settlement locks the duel before its players
+    let open_duels = duels::lock_active_duels(&mut tx).await?;
+    // Standings and the reset must see the same balances, positions and XP. Users are locked before
+    // the season, in the order XP grants take them, so the two cannot deadlock
+    seasons::lock_all_users(&mut tx).await?;
+    let current = seasons::lock_current_season(&mut tx)
+        .await?
+        .ok_or(SeasonError::NoCurrentSeason)?;
+    if expected_season_id.is_some_and(|id| id != current.id) {
+        return Err(SeasonError::AlreadyRolledOver);
+    }
+
+    let (symbols, prices): (Vec<String>, Vec<i64>) = state.oracle.snapshot().into_iter().unzip();
+    let duel_results = settle_locked_duels(&mut tx, &open_duels, &symbols, &prices, now).await?;
+    let participants =
+        seasons::archive_season_standings(&mut tx, current.id, current.starts_at, &symbols, &prices).await?;
+
+    let max_rank = SEASON_REWARDS.iter().map(|reward| reward.max_rank).max().unwrap_or(0);
+    let finishers = seasons::list_standings_up_to_rank(&mut tx, current.id, max_rank as i32).await?;
+    let mut rewards = Vec::new();
+    for standing in finishers {
+        let Some(reward) = game::season_reward(i64::from(standing.xp_rank)) else {
+            continue;
+        };
+        seasons::set_standing_reward(&mut tx, current.id, standing.user_id, reward.title, reward.xp_bonus as i32)
+            .await?;
+        rewards.push((standing, reward));
+    }
+
+    let reset_key = format!("{}:{}", SEASON_RESET_XP_SOURCE, current.number);
+    seasons::reset_season_progress(&mut tx, current.id, SEASON_RESET_XP_SOURCE, &reset_key, &symbols, &prices)
+        .await?;
+
+    let closed = seasons::close_season(&mut tx, current.id, participants as i32, now).await?;
+    let number = current.number + 1;
+    let default_name = format!("Season {}", number);
+    let started = seasons::create_season(
+        &mut tx,
+        number,
+        name.unwrap_or(&default_name),
+        now,
+        now + Duration::days(length_days),
+    )
+    .await?;
+
+    // Rewards are granted after the reset so they count towards the new season
+    let reward_key = format!("{}:{}", SEASON_REWARD_XP_SOURCE, closed.number);
+    let reference_id = closed.id.to_string();
+    let mut announcements: Vec<(Notification, SeasonRewardData)> = Vec::with_capacity(rewards.len());
+    for (standing, reward) in rewards {
+        let grant = xp::XpGrant {
+            source: SEASON_REWARD_XP_SOURCE,
+            amount: reward.xp_bonus as i32,
+            reference_id: Some(&reference_id),
+            idempotency_key: Some(&reward_key),
+        };
+        xp::grant_xp(&mut tx, standing.user_id, &grant).await?;
+
+        let data = SeasonRewardData {
+            season: closed.number,
+            rank: standing.xp_rank,
+            title: reward.title,
+            xp_bonus: reward.xp_bonus,
+        };
+        let notification = notifications::create_notification(
+            &mut *tx,
+            standing.user_id,
+            SEASON_REWARD_KIND,
+            &format!("{} finish!", data.title),
+            &format!(
+                "You finished {} at rank {} and start {} with {} XP",
+                closed.name, data.rank, started.name, data.xp_bonus
+            ),
+            None,
+        )
+        .await?;
+        announcements.push((notification, data));
+    }
+
+    tx.commit().await?;
+    info!("🏁 {} closed with {} players, {} started", closed.name, closed.participant_count, started.name);
+
+    let rewarded = announcements.len();
+    announce_duels(state, duel_results);
+    let delivery_state = state.clone();
+    tokio::spawn(async move {
+        for (notification, data) in announcements {
+            deliver_to_webhooks(&delivery_state, &notification, data).await;
+        }
+    });
+
+    Ok(SeasonRollover { closed, started, rewarded })
+}
@@ -0,0 +1,143 @@
+//! Activity streak service.
+//! Records daily sign-in and trading activity in each user's time zone, spends freezes on missed
+//! days and supplies the streak XP multiplier.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use sqlx::PgConnection;
+use tracing::info;
+use uuid::Uuid;
+
+use db::models::ActivityStreak;
+use db::queries::streaks::{self, StreakChange};
+use db::queries::notifications;
+use game::Streak;
+
+use crate::services::events::UserEvent;
+use crate::services::notifications::PendingDeliveries;
+
+/// Notification kind used when freezes are earned or spent.
+pub const STREAK_FREEZE_KIND: &str = "streak_freeze";
+
+/// Event-specific data included in streak freeze webhook payloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreakFreezeData {
+    /// Streak length after the activity.
+    pub current_days: i32,
+    /// Freezes spent covering missed days.
+    pub freezes_used: i32,
+    /// Freezes earned by reaching a milestone.
+    pub freezes_earned: i32,
+    /// Freezes held afterwards.
+    pub freezes: i32,
+}
+
+/// Parses an IANA time zone name such as "Europe/Berlin".
+pub fn parse_time_zone(name: &str) -> Option<Tz> {
+    name.parse::<Tz>().ok()
+}
+
+/// Returns the calendar day of a time in a time zone, falling back to UTC for unknown zones.
+pub fn local_day(time_zone: &str, at: DateTime<Utc>) -> NaiveDate {
+    match parse_time_zone(time_zone) {
+        Some(tz) => at.with_timezone(&tz).date_naive(),
+        None => at.date_naive(),
+    }
+}
+
+/// Returns when a calendar day starts in a time zone, falling back to UTC for unknown zones.
+/// Where clocks skip midnight the day starts an hour later, at the first local time that exists.
+pub fn day_start(time_zone: &str, day: NaiveDate) -> DateTime<Utc> {
+    let midnight = day.and_time(NaiveTime::MIN);
+    parse_time_zone(time_zone)
+        .and_then(|tz| {
+            tz.from_local_datetime(&midnight)
+                .earliest()
+                .or_else(|| tz.from_local_datetime(&(midnight + Duration::hours(1))).earliest())
+        })
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+/// Returns a day as the consecutive day number streaks are counted in.
+pub fn day_number(day: NaiveDate) -> i64 {
+    i64::from(day.num_days_from_ce())
+}
+
+/// Converts a stored streak to the game representation.
+pub fn game_streak(stored: &ActivityStreak) -> Streak {
+    Streak {
+        current_days: stored.current_days,
+        longest_days: stored.longest_days,
+        last_active_day: stored.last_active_date.map(day_number),
+        freezes: stored.freezes,
+    }
+}
+
+/// Records a user event as activity in the caller's transaction and returns the XP multiplier it earns,
+/// in percent.
+pub async fn record_event(
+    conn: &mut PgConnection,
+    event: &UserEvent,
+    deliveries: &mut PendingDeliveries,
+) -> Result<u32, sqlx::Error> {
+    let streak = record_activity(conn, event.user_id, event.occurred_at, deliveries).await?;
+    Ok(game::streak_multiplier_percent(streak.current_days))
+}
+
+/// Records activity at a time in the caller's transaction, extending the user's streak on their first
+/// activity of the day. Queues a notification when freezes were spent on missed days or earned at a milestone.
+pub async fn record_activity(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    at: DateTime<Utc>,
+    deliveries: &mut PendingDeliveries,
+) -> Result<Streak, sqlx::Error> {
+    let stored = streaks::lock_streak(&mut *conn, user_id).await?;
+    let today = local_day(&stored.time_zone, at);
+    let update = game_streak(&stored).record_activity(day_number(today));
+    if !update.new_day {
+        return Ok(update.streak);
+    }
+
+    let change = StreakChange {
+        current_days: update.streak.current_days,
+        longest_days: update.streak.longest_days,
+        last_active_date: today,
+        freezes: update.streak.freezes,
+        freezes_used: update.freezes_used,
+    };
+    streaks::update_streak(&mut *conn, user_id, &change).await?;
+
+    if update.freezes_used == 0 && update.freezes_earned == 0 {
+        return Ok(update.streak);
+    }
+
+    let message = if update.freezes_used == 1 {
+        format!("A streak freeze covered the day you missed. Your streak is now {} days", update.streak.current_days)
+    } else if update.freezes_used > 1 {
+        format!(
+            "{} streak freezes covered the days you missed. Your streak is now {} days",
+            update.freezes_used, update.streak.current_days
+        )
+    } else {
+        format!("You earned a streak freeze for reaching a {}-day streak", update.streak.current_days)
+    };
+    let notification =
+        notifications::create_notification(&mut *conn, user_id, STREAK_FREEZE_KIND, "Streak freeze", &message, None)
+            .await?;
+    if update.freezes_used > 0 {
+        info!("🧊 User {} spent {} streak freezes", user_id, update.freezes_used);
+    }
+
+    let data = StreakFreezeData {
+        current_days: update.streak.current_days,
+        freezes_used: update.freezes_used,
+        freezes_earned: update.freezes_earned,
+        freezes: update.streak.freezes,
+    };
+    deliveries.push(notification, data);
+
+    Ok(update.streak)
+}
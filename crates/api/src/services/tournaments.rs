@@ -0,0 +1,330 @@
+//! Tournament service.
+//! Registers entrants, executes orders against their isolated accounts and finalizes ended tournaments.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use db::models::{Notification, Tournament, TournamentEntry, TournamentPosition, TournamentTrade};
+use db::queries::{assets, clans, notifications, tournaments, xp};
+use game::tournaments::{ScheduleError, TOURNAMENT_XP_SOURCE};
+use game::{TournamentPhase, TournamentSchedule, TOURNAMENT_PRIZES};
+
+use crate::services::isolated::{self, IsolatedAccount};
+use crate::services::notifications::deliver_to_webhooks;
+use crate::services::ratings;
+use crate::services::trading::{MarketOrder, TradingError};
+use crate::state::SharedState;
+
+/// Notification kind used for tournament prizes.
+pub const TOURNAMENT_PRIZE_KIND: &str = "tournament_prize";
+
+/// Errors that can occur while managing or trading in a tournament.
+#[derive(Error, Debug)]
+pub enum TournamentError {
+    #[error("Tournament not found")]
+    NotFound,
+    #[error("Invalid schedule: {0}")]
+    InvalidSchedule(ScheduleError),
+    #[error("Unknown symbol {0}")]
+    UnknownSymbol(String),
+    #[error("Registration is not open")]
+    RegistrationClosed,
+    #[error("Tournament is full")]
+    Full,
+    #[error("Already registered for this tournament")]
+    AlreadyJoined,
+    #[error("Not registered for this tournament")]
+    NotJoined,
+    #[error("Tournament is not live")]
+    NotLive,
+    #[error("{0} cannot be traded in this tournament")]
+    SymbolNotAllowed(String),
+    #[error(transparent)]
+    Trading(#[from] TradingError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Event-specific data included in tournament prize webhook payloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct TournamentPrizeData {
+    /// Tournament the prize was earned in.
+    pub tournament_id: Uuid,
+    /// Final rank.
+    pub rank: i32,
+    /// Prize badge.
+    pub badge: &'static str,
+    /// XP granted.
+    pub xp_bonus: u32,
+}
+
+/// Entrant's account in a tournament.
+#[derive(Debug, Clone)]
+pub struct TournamentAccount {
+    /// Entry with the cash balance and final results.
+    pub entry: TournamentEntry,
+    /// Open positions.
+    pub positions: Vec<TournamentPosition>,
+    /// Cash plus positions at current oracle prices, or the final value once finalized, in cents.
+    pub value_cents: i64,
+}
+
+/// Returns the tournament's schedule as Unix timestamps.
+pub fn schedule(tournament: &Tournament) -> TournamentSchedule {
+    TournamentSchedule {
+        registration_opens_at: tournament.registration_opens_at.timestamp(),
+        registration_closes_at: tournament.registration_closes_at.timestamp(),
+        starts_at: tournament.starts_at.timestamp(),
+        ends_at: tournament.ends_at.timestamp(),
+    }
+}
+
+/// Returns the tournament's phase at a time.
+pub fn phase(tournament: &Tournament, now: DateTime<Utc>) -> TournamentPhase {
+    schedule(tournament).phase_at(now.timestamp(), tournament.finalized_at.is_some())
+}
+
+/// Creates a tournament after checking its schedule and symbols.
+pub async fn create_tournament(
+    state: &SharedState,
+    tournament: &tournaments::NewTournament<'_>,
+) -> Result<Tournament, TournamentError> {
+    let schedule = TournamentSchedule {
+        registration_opens_at: tournament.registration_opens_at.timestamp(),
+        registration_closes_at: tournament.registration_closes_at.timestamp(),
+        starts_at: tournament.starts_at.timestamp(),
+        ends_at: tournament.ends_at.timestamp(),
+    };
+    schedule.validate().map_err(TournamentError::InvalidSchedule)?;
+
+    for symbol in tournament.allowed_symbols {
+        if assets::find_asset(&state.db_pool, symbol).await?.is_none() {
+            return Err(TournamentError::UnknownSymbol(symbol.clone()));
+        }
+    }
+
+    Ok(tournaments::create_tournament(&state.db_pool, tournament).await?)
+}
+
+/// Registers a user with a fresh account funded with the starting balance.
+pub async fn join_tournament(
+    state: &SharedState,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<TournamentEntry, TournamentError> {
+    let mut tx = state.db_pool.begin().await?;
+    let tournament = tournaments::lock_tournament(&mut tx, tournament_id)
+        .await?
+        .ok_or(TournamentError::NotFound)?;
+    if tournament.finalized_at.is_some() || !schedule(&tournament).is_registration_open(now.timestamp()) {
+        return Err(TournamentError::RegistrationClosed);
+    }
+    if let Some(max_participants) = tournament.max_participants
+        && tournaments::count_entries(&mut *tx, tournament_id).await? >= i64::from(max_participants)
+    {
+        return Err(TournamentError::Full);
+    }
+
+    let entry = tournaments::create_entry(&mut tx, tournament_id, user_id, tournament.starting_balance_cents)
+        .await?
+        .ok_or(TournamentError::AlreadyJoined)?;
+    tx.commit().await?;
+
+    Ok(entry)
+}
+
+/// Executes a market order against the user's tournament account at the current oracle price.
+/// Fees and slippage follow the regular schedule at the base fee tier, so levels give no edge.
+pub async fn execute_tournament_order(
+    state: &SharedState,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    order: &MarketOrder,
+    now: DateTime<Utc>,
+) -> Result<TournamentTrade, TournamentError> {
+    let tournament = tournaments::find_tournament(&state.db_pool, tournament_id)
+        .await?
+        .ok_or(TournamentError::NotFound)?;
+    if phase(&tournament, now) != TournamentPhase::Live {
+        return Err(TournamentError::NotLive);
+    }
+    if !tournament.allowed_symbols.contains(&order.symbol) {
+        return Err(TournamentError::SymbolNotAllowed(order.symbol.clone()));
+    }
+
+    let fill = isolated::simulate_order(state, order).await?;
+
+    let mut tx = state.db_pool.begin().await?;
+    let account = IsolatedAccount::Tournament { tournament_id, user_id };
+    if !isolated::apply_fill(&mut tx, account, order, &fill).await? {
+        return Err(TournamentError::NotJoined);
+    }
+
+    let trade = tournaments::insert_trade(
+        &mut tx,
+        &tournaments::NewTournamentTrade {
+            tournament_id,
+            user_id,
+            symbol: &order.symbol,
+            trade_type: order.side.as_str(),
+            quantity: fill.quantity,
+            price: fill.price,
+            total_value: fill.notional_cents,
+            fee_cents: fill.fee_cents,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(trade)
+}
+
+/// Loads the user's tournament account valued at current oracle prices, or at its final value once finalized.
+pub async fn tournament_account(
+    state: &SharedState,
+    tournament_id: Uuid,
+    user_id: Uuid,
+) -> Result<TournamentAccount, TournamentError> {
+    let entry = tournaments::find_entry(&state.db_pool, tournament_id, user_id)
+        .await?
+        .ok_or(TournamentError::NotJoined)?;
+    let positions = tournaments::list_positions(&state.db_pool, tournament_id, user_id).await?;
+    let open_value_cents = isolated::positions_value_cents(
+        positions.iter().map(|p| (p.symbol.as_str(), p.quantity, p.average_price)),
+        &state.oracle,
+    );
+    let value_cents = entry.final_value_cents.unwrap_or(entry.cash_balance_cents + open_value_cents);
+
+    Ok(TournamentAccount {
+        entry,
+        positions,
+        value_cents,
+    })
+}
+
+/// Returns the clans entered in a tournament ranked by mean entrant value: the recorded ranking once
+/// finalized, otherwise live values.
+pub async fn clan_standings(state: &SharedState, tournament: &Tournament) -> Result<Vec<clans::ClanStanding>, sqlx::Error> {
+    if tournament.finalized_at.is_some() {
+        return clans::final_clan_standings(&state.db_pool, tournament.id).await;
+    }
+
+    let (symbols, prices): (Vec<String>, Vec<i64>) = state.oracle.snapshot().into_iter().unzip();
+    clans::live_clan_standings(&state.db_pool, tournament.id, &symbols, &prices).await
+}
+
+/// Returns a page of standings: the recorded ranking once finalized, otherwise live values.
+pub async fn tournament_standings(
+    state: &SharedState,
+    tournament: &Tournament,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<tournaments::TournamentStanding>, sqlx::Error> {
+    if tournament.finalized_at.is_some() {
+        return tournaments::final_standings(&state.db_pool, tournament.id, limit, offset).await;
+    }
+
+    let (symbols, prices): (Vec<String>, Vec<i64>) = state.oracle.snapshot().into_iter().unzip();
+    tournaments::live_standings(&state.db_pool, tournament.id, &symbols, &prices, limit, offset).await
+}
+
+/// Finalizes every tournament whose trading has ended.
+/// Returns the number of tournaments finalized.
+pub async fn finalize_due_tournaments(state: &SharedState, now: DateTime<Utc>) -> Result<usize, sqlx::Error> {
+    let due = tournaments::list_due_tournaments(&state.db_pool, now).await?;
+    let mut finalized = 0;
+
+    for tournament in due {
+        match finalize_tournament(state, tournament.id, now).await {
+            Ok(true) => finalized += 1,
+            Ok(false) => {}
+            Err(e) => warn!("⚠️ Failed to finalize tournament {}: {}", tournament.id, e),
+        }
+    }
+
+    Ok(finalized)
+}
+
+/// Records the final ranking of entrants and entered clans at current oracle prices, rates entrants
+/// who traded by placement and grants prizes to top finishers who traded.
+/// Returns `false` when the tournament was already finalized or has not ended.
+async fn finalize_tournament(state: &SharedState, tournament_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+    let mut tx = state.db_pool.begin().await?;
+    let Some(tournament) = tournaments::lock_tournament(&mut tx, tournament_id).await? else {
+        return Ok(false);
+    };
+    if phase(&tournament, now) != TournamentPhase::Ended {
+        return Ok(false);
+    }
+
+    let (symbols, prices): (Vec<String>, Vec<i64>) = state.oracle.snapshot().into_iter().unzip();
+    let ranked = tournaments::record_final_ranking(&mut tx, tournament_id, &symbols, &prices).await?;
+    clans::record_clan_ranking(&mut tx, tournament_id).await?;
+    let finishers = tournaments::list_rated_finishers(&mut tx, tournament_id).await?;
+    ratings::rate_placements(&mut tx, tournament_id, &finishers, now).await?;
+
+    let max_rank = TOURNAMENT_PRIZES.iter().map(|prize| prize.max_rank).max().unwrap_or(0);
+    let finishers = tournaments::list_prize_candidates(&mut tx, tournament_id, max_rank as i32).await?;
+    let reference_id = tournament_id.to_string();
+    let idempotency_key = format!("{}:{}", TOURNAMENT_XP_SOURCE, tournament_id);
+    let mut announcements: Vec<(Notification, TournamentPrizeData)> = Vec::new();
+
+    for entry in finishers {
+        let rank = entry.final_rank.unwrap_or_default();
+        let Some(prize) = game::tournament_prize(i64::from(rank)) else {
+            continue;
+        };
+        tournaments::set_entry_prize(&mut tx, tournament_id, entry.user_id, prize.badge, prize.xp_bonus as i32).await?;
+
+        let grant = xp::XpGrant {
+            source: TOURNAMENT_XP_SOURCE,
+            amount: prize.xp_bonus as i32,
+            reference_id: Some(&reference_id),
+            idempotency_key: Some(&idempotency_key),
+        };
+        xp::grant_xp(&mut tx, entry.user_id, &grant).await?;
+
+        let data = TournamentPrizeData {
+            tournament_id,
+            rank,
+            badge: prize.badge,
+            xp_bonus: prize.xp_bonus,
+        };
+        let notification = notifications::create_notification(
+            &mut *tx,
+            entry.user_id,
+            TOURNAMENT_PRIZE_KIND,
+            &format!("{} finish in {}", capitalize(prize.badge), tournament.name),
+            &format!("You finished {} at rank {} and earned {} XP", tournament.name, rank, prize.xp_bonus),
+            None,
+        )
+        .await?;
+        announcements.push((notification, data));
+    }
+
+    tournaments::mark_finalized(&mut tx, tournament_id, now).await?;
+    tx.commit().await?;
+    info!("🏆 Finalized tournament {} with {} entrants", tournament.name, ranked);
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        for (notification, data) in announcements {
+            deliver_to_webhooks(&state, &notification, data).await;
+        }
+    });
+
+    Ok(true)
+}
+
+/// Uppercases the first letter of a badge name for display.
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
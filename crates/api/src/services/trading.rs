@@ -0,0 +1,244 @@
+//! Paper trading service.
+//! Executes market orders against oracle prices and keeps cash, positions and trades in sync.
+
+use chrono::Utc;
+use game::GameEvent;
+use sqlx::PgConnection;
+use thiserror::Error;
+use uuid::Uuid;
+
+use db::models::{Asset, Position, Trade};
+use db::queries::{assets, fees, trading};
+
+use crate::services::events::store_event;
+use crate::services::execution::{self, Liquidity, OrderSide};
+use crate::services::oracle::PriceOracle;
+use crate::state::AppState;
+
+/// Errors that can occur while executing an order.
+#[derive(Error, Debug)]
+pub enum TradingError {
+    #[error("Unknown symbol {0}")]
+    UnknownSymbol(String),
+    #[error("No price available for symbol {0}")]
+    NoPrice(String),
+    #[error("Trading is halted for {0}")]
+    MarketHalted(String),
+    #[error("Quantity must be greater than zero")]
+    InvalidQuantity,
+    #[error("Quantity must be a multiple of the lot size ({lot_size} micro units)")]
+    InvalidLotSize { lot_size: i64 },
+    #[error("Order value must be at least {min_notional_cents} cents")]
+    BelowMinNotional { min_notional_cents: i64 },
+    #[error("Insufficient cash: required {required_cents} cents, available {available_cents} cents")]
+    InsufficientFunds { required_cents: i64, available_cents: i64 },
+    #[error("Insufficient position: requested {requested} micro units, held {held} micro units")]
+    InsufficientPosition { requested: i64, held: i64 },
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Market order submitted by a user.
+#[derive(Debug, Clone)]
+pub struct MarketOrder {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Buy or sell.
+    pub side: OrderSide,
+    /// Quantity in micro units.
+    pub quantity: i64,
+    /// Bot placing the order, `None` for manual orders.
+    pub bot_id: Option<Uuid>,
+}
+
+/// Trade recorded for a fill and the gamification events it raises.
+#[derive(Debug, Clone)]
+pub struct AppliedFill {
+    /// Recorded trade.
+    pub trade: Trade,
+    /// Events stored with the trade.
+    pub events: Vec<GameEvent>,
+}
+
+/// Checks an order against the asset's trading rules.
+/// Delisted assets are treated as unknown; halted assets reject all orders.
+pub fn check_order_rules(asset: &Asset, quantity: i64, reference_price: i64) -> Result<(), TradingError> {
+    match asset.status.as_str() {
+        "active" => {}
+        "halted" => return Err(TradingError::MarketHalted(asset.symbol.clone())),
+        _ => return Err(TradingError::UnknownSymbol(asset.symbol.clone())),
+    }
+
+    check_order_size(asset, quantity, reference_price)
+}
+
+/// Checks an order's quantity against the asset's lot size and minimum notional.
+pub fn check_order_size(asset: &Asset, quantity: i64, reference_price: i64) -> Result<(), TradingError> {
+    if quantity <= 0 {
+        return Err(TradingError::InvalidQuantity);
+    }
+    if quantity % asset.lot_size != 0 {
+        return Err(TradingError::InvalidLotSize { lot_size: asset.lot_size });
+    }
+    if execution::notional_cents(quantity, reference_price) < asset.min_notional_cents {
+        return Err(TradingError::BelowMinNotional {
+            min_notional_cents: asset.min_notional_cents,
+        });
+    }
+
+    Ok(())
+}
+
+/// Executes a market order at the current oracle price.
+/// Validates the order against the asset catalog, charges taker fees and slippage
+/// and applies the fill atomically.
+pub async fn execute_market_order(
+    state: &AppState,
+    user_id: Uuid,
+    order: &MarketOrder,
+) -> Result<Trade, TradingError> {
+    let asset = assets::find_asset(&state.db_pool, &order.symbol)
+        .await?
+        .ok_or_else(|| TradingError::UnknownSymbol(order.symbol.clone()))?;
+    let reference_price = state
+        .oracle
+        .price(&asset.symbol)
+        .ok_or_else(|| TradingError::NoPrice(asset.symbol.clone()))?;
+    check_order_rules(&asset, order.quantity, reference_price)?;
+
+    let schedule = fees::find_fee_schedule(&state.db_pool, &order.symbol).await?;
+
+    let mut tx = state.db_pool.begin().await?;
+
+    let user = trading::lock_user(&mut tx, user_id)
+        .await?
+        .ok_or(TradingError::UserNotFound)?;
+    let tier = fees::find_fee_tier(&mut *tx, user.level).await?;
+
+    let fill = execution::simulate_fill(
+        order.side,
+        order.quantity,
+        reference_price,
+        asset.tick_size_cents,
+        &schedule,
+        Liquidity::Taker,
+        &tier,
+    );
+
+    let applied = apply_fill(&mut tx, &state.oracle, user_id, user.cash_balance_cents, order, &fill).await?;
+
+    tx.commit().await?;
+    state.events.notify();
+
+    Ok(applied.trade)
+}
+
+/// Applies a simulated fill to the user's cash and position and records the trade.
+/// Must run inside a transaction in which the user row is already locked.
+/// The fill's events are stored with the trade; wake their consumer once the transaction commits.
+pub async fn apply_fill(
+    conn: &mut PgConnection,
+    oracle: &PriceOracle,
+    user_id: Uuid,
+    cash_balance_cents: i64,
+    order: &MarketOrder,
+    fill: &execution::Fill,
+) -> Result<AppliedFill, TradingError> {
+    let (symbol, side) = (order.symbol.as_str(), order.side);
+    let position = trading::lock_position(conn, user_id, symbol).await?;
+    let held = position.as_ref().map(|p| p.quantity).unwrap_or(0);
+    let mut events = vec![GameEvent::TradeExecuted {
+        notional_cents: fill.notional_cents,
+    }];
+
+    let new_cash = cash_balance_cents + fill.cash_delta_cents(side);
+    if new_cash < 0 {
+        return Err(TradingError::InsufficientFunds {
+            required_cents: fill.notional_cents + fill.fee_cents,
+            available_cents: cash_balance_cents,
+        });
+    }
+
+    match side {
+        OrderSide::Buy => {
+            let (quantity, average_price) = match &position {
+                Some(p) => {
+                    let quantity = p.quantity + fill.quantity;
+                    let cost = p.quantity as i128 * p.average_price as i128
+                        + fill.quantity as i128 * fill.price as i128;
+                    (quantity, (cost / quantity as i128) as i64)
+                }
+                None => (fill.quantity, fill.price),
+            };
+            let current_value = execution::notional_cents(quantity, fill.reference_price);
+            trading::upsert_position(conn, user_id, symbol, quantity, average_price, current_value).await?;
+        }
+        OrderSide::Sell => {
+            if held < fill.quantity {
+                return Err(TradingError::InsufficientPosition {
+                    requested: fill.quantity,
+                    held,
+                });
+            }
+            if let Some(p) = &position {
+                let cost_cents = execution::notional_cents(fill.quantity, p.average_price);
+                events.push(GameEvent::PositionClosed {
+                    realized_pnl_cents: fill.notional_cents - fill.fee_cents - cost_cents,
+                    cost_cents,
+                    held_seconds: (Utc::now() - p.opened_at).num_seconds(),
+                });
+            }
+
+            let quantity = held - fill.quantity;
+            if quantity == 0 {
+                trading::delete_position(conn, user_id, symbol).await?;
+            } else {
+                let average_price = position.as_ref().map(|p| p.average_price).unwrap_or(fill.price);
+                let current_value = execution::notional_cents(quantity, fill.reference_price);
+                trading::upsert_position(conn, user_id, symbol, quantity, average_price, current_value).await?;
+            }
+        }
+    }
+
+    let trade = trading::insert_trade(
+        conn,
+        &trading::NewTrade {
+            user_id,
+            symbol,
+            trade_type: side.as_str(),
+            quantity: fill.quantity,
+            price: fill.price,
+            total_value: fill.notional_cents,
+            reference_price: fill.reference_price,
+            fee_cents: fill.fee_cents,
+            slippage_cents: fill.slippage_cents,
+            bot_id: order.bot_id,
+        },
+    )
+    .await?;
+
+    let trade_id = trade.id.to_string();
+    for event in &events {
+        store_event(&mut *conn, user_id, event, Some(&trade_id)).await?;
+    }
+
+    let positions = trading::list_positions(&mut *conn, user_id).await?;
+    let portfolio_value = new_cash + positions_value_cents(&positions, oracle);
+    trading::update_user_balances(conn, user_id, new_cash, portfolio_value).await?;
+
+    Ok(AppliedFill { trade, events })
+}
+
+/// Values positions at current oracle prices, in cents.
+/// Falls back to the average purchase price when the oracle has no price.
+pub fn positions_value_cents(positions: &[Position], oracle: &PriceOracle) -> i64 {
+    positions
+        .iter()
+        .map(|p| {
+            let price = oracle.price(&p.symbol).unwrap_or(p.average_price);
+            execution::notional_cents(p.quantity, price)
+        })
+        .sum()
+}
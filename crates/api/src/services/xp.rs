@@ -0,0 +1,171 @@
+//! XP award service.
+//! Turns user events into XP grants under the game rules and announces level-ups.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::PgConnection;
+use tracing::warn;
+use uuid::Uuid;
+
+use db::models::{Notification, XpEvent};
+use db::queries::{notifications, streaks, xp};
+use game::{AwardScope, XpAward};
+
+use crate::services::events::UserEvent;
+use crate::services::notifications::{deliver_to_webhooks, PendingDeliveries};
+use crate::services::streaks::{day_start, local_day};
+use crate::state::SharedState;
+
+/// Notification kind used for level-ups.
+pub const LEVEL_UP_KIND: &str = "level_up";
+
+/// Event-specific data included in level-up webhook payloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelUpData {
+    /// Level before the XP was granted.
+    pub previous_level: i16,
+    /// Level reached.
+    pub level: i16,
+    /// XP total after the grant.
+    pub xp: i32,
+}
+
+/// Grants the XP a user event earns in the caller's transaction, boosted by the user's streak
+/// multiplier in percent. Awards are reduced by diminishing returns and daily caps and each is applied
+/// at most once. Days for caps and daily awards are counted in the user's streak time zone.
+pub async fn award_event(
+    conn: &mut PgConnection,
+    event: &UserEvent,
+    multiplier_percent: u32,
+    deliveries: &mut PendingDeliveries,
+) -> Result<Vec<XpEvent>, sqlx::Error> {
+    let time_zone = streaks::find_streak(&mut *conn, event.user_id)
+        .await?
+        .map(|streak| streak.time_zone)
+        .unwrap_or_else(|| "UTC".to_string());
+    let today = local_day(&time_zone, event.occurred_at);
+    let since = day_start(&time_zone, today);
+    let mut granted = Vec::new();
+
+    for award in game::awards_for_event(&event.event) {
+        let Some(key) = idempotency_key(&award, event, today) else {
+            continue;
+        };
+
+        let (awards_today, xp_today) = xp::xp_activity_since(&mut *conn, event.user_id, award.source, since).await?;
+        let amount = game::limit_boosted_award(&award, awards_today as u32, xp_today as u32, multiplier_percent);
+        if amount == 0 {
+            continue;
+        }
+
+        let grant = xp::XpGrant {
+            source: award.source,
+            amount: amount as i32,
+            reference_id: event.reference_id.as_deref(),
+            idempotency_key: Some(&key),
+        };
+        let Some(xp_event) = grant_xp_in(&mut *conn, event.user_id, &grant).await? else {
+            continue;
+        };
+        granted.push(xp_event.queue(deliveries));
+    }
+
+    Ok(granted)
+}
+
+/// XP granted in a caller's transaction, with the level-up to announce once it commits.
+#[derive(Debug)]
+pub struct GrantedXp {
+    /// Recorded XP event.
+    pub event: XpEvent,
+    /// Level-up notification stored with the grant, if the grant reached a new level.
+    level_up: Option<(Notification, LevelUpData)>,
+}
+
+impl GrantedXp {
+    /// Queues the level-up, if any, for delivery once the caller's transaction commits.
+    pub fn queue(self, deliveries: &mut PendingDeliveries) -> XpEvent {
+        if let Some((notification, data)) = self.level_up {
+            deliveries.push(notification, data);
+        }
+        self.event
+    }
+
+    /// Delivers the level-up, if any, to the user's webhooks. Call only after the grant has been committed.
+    pub fn announce(self, state: &SharedState) -> XpEvent {
+        if let Some((notification, data)) = self.level_up {
+            let state = state.clone();
+            tokio::spawn(async move {
+                deliver_to_webhooks(&state, &notification, data).await;
+            });
+        }
+        self.event
+    }
+}
+
+/// Grants XP and notifies the user when it takes them to a new level.
+/// Returns `None` when a grant with the same idempotency key was already applied.
+pub async fn grant_xp(state: &SharedState, user_id: Uuid, grant: &xp::XpGrant<'_>) -> Result<Option<XpEvent>, sqlx::Error> {
+    let mut tx = state.db_pool.begin().await?;
+    let granted = grant_xp_in(&mut tx, user_id, grant).await?;
+    tx.commit().await?;
+
+    Ok(granted.map(|granted| granted.announce(state)))
+}
+
+/// Grants XP in the caller's transaction and stores a level-up notification when it reaches a new level.
+/// Announce the result once the transaction commits.
+/// Returns `None` when a grant with the same idempotency key was already applied.
+pub async fn grant_xp_in(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    grant: &xp::XpGrant<'_>,
+) -> Result<Option<GrantedXp>, sqlx::Error> {
+    let Some(xp_event) = xp::grant_xp(conn, user_id, grant).await? else {
+        return Ok(None);
+    };
+
+    let previous_level = game::calculate_level_from_xp((xp_event.xp_after - xp_event.amount).max(0) as u32) as i16;
+    if xp_event.level_after <= previous_level {
+        return Ok(Some(GrantedXp {
+            event: xp_event,
+            level_up: None,
+        }));
+    }
+
+    let data = LevelUpData {
+        previous_level,
+        level: xp_event.level_after,
+        xp: xp_event.xp_after,
+    };
+    let notification = notifications::create_notification(
+        conn,
+        user_id,
+        LEVEL_UP_KIND,
+        "Level up!",
+        &format!("You reached level {} with {} XP", data.level, data.xp),
+        None,
+    )
+    .await?;
+
+    Ok(Some(GrantedXp {
+        event: xp_event,
+        level_up: Some((notification, data)),
+    }))
+}
+
+/// Builds the key that limits how often an award is granted; daily awards are keyed by the user's local day.
+/// Per-event awards without a reference cannot be deduplicated and are skipped.
+fn idempotency_key(award: &XpAward, event: &UserEvent, today: NaiveDate) -> Option<String> {
+    match award.scope {
+        AwardScope::Once => Some(award.source.to_string()),
+        AwardScope::Daily => Some(format!("{}:{}", award.source, today)),
+        AwardScope::PerEvent => match &event.reference_id {
+            Some(reference_id) => Some(format!("{}:{}", award.source, reference_id)),
+            None => {
+                warn!("⚠️ Skipping {} XP for user {} without a reference", award.source, event.user_id);
+                None
+            }
+        },
+    }
+}
@@ -1,8 +1,30 @@
 //! Application state management for API handlers.
-//! Provides shared database connection pool and configuration.
+//! Provides shared database connection pool, price oracle, event bus and configuration.
 
+use game::{LeagueMetric, LevelCurveError};
 use sqlx::PgPool;
 use std::sync::Arc;
+use thiserror::Error;
+use tracing::warn;
+
+use crate::services::events::EventBus;
+use crate::services::oracle::PriceOracle;
+use crate::services::seasons::MAX_SEASON_DAYS;
+
+/// Secret used to sign session tokens when JWT_SECRET is not set.
+/// Only suitable for local development.
+const DEV_JWT_SECRET: &str = "vectra-dev-secret";
+
+/// Configuration the server refuses to start with.
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Invalid LEVEL_CURVE '{spec}': {source}")]
+    LevelCurve {
+        spec: String,
+        #[source]
+        source: LevelCurveError,
+    },
+}
 
 /// Shared application state containing database connection pool.
 /// Used by all API handlers to access the database.
@@ -10,12 +32,75 @@ use std::sync::Arc;
 pub struct AppState {
     /// PostgreSQL connection pool for database operations.
     pub db_pool: PgPool,
+    /// Price oracle used for order execution and valuations.
+    pub oracle: Arc<PriceOracle>,
+    /// Secret used to sign and verify session tokens.
+    pub jwt_secret: String,
+    /// Lowercased wallet addresses allowed to use admin endpoints.
+    pub admin_wallets: Vec<String>,
+    /// Gamification events raised by user actions.
+    pub events: EventBus,
+    /// Length of new seasons, in days.
+    pub season_days: i64,
+    /// What new league groups rank their players by.
+    pub league_metric: LeagueMetric,
 }
 
 impl AppState {
     /// Creates a new application state with database pool.
-    pub fn new(db_pool: PgPool) -> Self {
-        Self { db_pool }
+    /// Reads JWT_SECRET, ADMIN_WALLETS, LEVEL_CURVE, SEASON_LENGTH_DAYS and LEAGUE_METRIC from the environment and starts a simulated oracle.
+    /// Fails on an invalid LEVEL_CURVE rather than silently levelling players on another curve.
+    pub fn new(db_pool: PgPool) -> Result<Self, ConfigError> {
+        let jwt_secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| {
+            warn!("⚠️ JWT_SECRET not set - using insecure development secret");
+            DEV_JWT_SECRET.to_string()
+        });
+
+        // Comma-separated list of wallets with admin access
+        let admin_wallets = std::env::var("ADMIN_WALLETS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|wallet| wallet.trim().to_lowercase())
+            .filter(|wallet| !wallet.is_empty())
+            .collect();
+
+        // Level curve used for all XP to level conversions, e.g. "exponential:500:1.1"
+        if let Ok(spec) = std::env::var("LEVEL_CURVE") {
+            let curve = game::parse_level_curve(&spec).map_err(|source| ConfigError::LevelCurve {
+                spec: spec.clone(),
+                source,
+            })?;
+            if !game::set_level_curve(curve) {
+                warn!("⚠️ Level curve already in use - ignoring LEVEL_CURVE");
+            }
+        }
+
+        let season_days = std::env::var("SEASON_LENGTH_DAYS")
+            .ok()
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|value| (1..=MAX_SEASON_DAYS).contains(value))
+            .unwrap_or(game::seasons::DEFAULT_SEASON_DAYS);
+
+        // Weekly leagues rank by "xp" or "return"
+        let league_metric = std::env::var("LEAGUE_METRIC")
+            .ok()
+            .and_then(|value| LeagueMetric::parse(&value))
+            .unwrap_or(LeagueMetric::Xp);
+
+        Ok(Self {
+            db_pool,
+            oracle: Arc::new(PriceOracle::simulated()),
+            jwt_secret,
+            admin_wallets,
+            events: EventBus::new(),
+            season_days,
+            league_metric,
+        })
+    }
+
+    /// Returns whether a wallet address has admin access.
+    pub fn is_admin(&self, wallet_address: &str) -> bool {
+        self.admin_wallets.contains(&wallet_address.to_lowercase())
     }
 }
 
@@ -7,6 +7,23 @@ use validator::Validate;
 use std::sync::LazyLock;
 use regex::Regex;
 
+use crate::services::backtest::{BacktestResult, StrategySpec};
+use crate::services::bots::BotPnl;
+use crate::services::duels::{DuelAccountView, DuelScore};
+use crate::services::execution::{notional_cents, OrderSide, MICRO_UNITS};
+use crate::services::leagues::{self, LeagueTable};
+use crate::services::export::ExportFormat;
+use crate::services::market::CandleInterval;
+use crate::services::portfolio::{EquityCurve, HistoryRange};
+use crate::services::oracle::PriceOracle;
+use crate::services::quests::QuestStatus;
+use crate::services::tournaments::{self, TournamentAccount};
+use crate::services::rebalance::RebalancePlan;
+use crate::services::streaks;
+use chrono::{DateTime, NaiveDate, Utc};
+use db::queries::duels::{DuelPlayer, DuelRecord};
+use db::queries::leagues::LeagueHistoryRow;
+
 // Validation regex patterns
 
 /// Regex pattern for validating Ethereum wallet addresses.
@@ -15,6 +32,12 @@ static WALLET_ADDRESS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^0x[a-fA-F0-9]{40}$").unwrap()
 });
 
+/// Regex pattern for validating trading symbols.
+/// Matches 1-10 uppercase letters or digits.
+static SYMBOL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Z0-9]{1,10}$").unwrap()
+});
+
 // Wallet authentication related types
 
 /// Request to initiate wallet connection process.
@@ -42,7 +65,7 @@ pub struct WalletConnectResponse {
     /// Current XP points.
     pub xp_points: u32,
     /// Current level.
-    pub level: u8,
+    pub level: u16,
     /// Portfolio value in cents.
     pub portfolio_value_cents: u64,
     /// Available cash balance in cents.
@@ -133,12 +156,2704 @@ pub struct Trade {
     pub trade_type: String,
     /// Number of tokens traded.
     pub quantity: f64,
-    /// Price per token.
+    /// Price per token, including slippage.
     pub price: f64,
+    /// Oracle price per token before slippage.
+    pub reference_price: f64,
+    /// Quantity times price, excluding fees.
+    pub total_value: f64,
+    /// Trading fee charged.
+    pub fee: f64,
+    /// Cost of slippage versus the reference price.
+    pub slippage: f64,
+    /// Bot that placed the trade, if any.
+    pub bot_id: Option<String>,
     /// When the trade was executed.
     pub timestamp: String,
 }
 
+impl From<db::models::Trade> for Trade {
+    fn from(trade: db::models::Trade) -> Self {
+        Self {
+            id: trade.id.to_string(),
+            symbol: trade.symbol,
+            trade_type: trade.trade_type,
+            quantity: micros_to_units(trade.quantity),
+            price: cents_to_usd(trade.price),
+            reference_price: cents_to_usd(trade.reference_price),
+            total_value: cents_to_usd(trade.total_value),
+            fee: cents_to_usd(trade.fee_cents),
+            slippage: cents_to_usd(trade.slippage_cents),
+            bot_id: trade.bot_id.map(|id| id.to_string()),
+            timestamp: trade.executed_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Query parameters for the portfolio history endpoint.
+#[derive(Deserialize)]
+pub struct PortfolioHistoryQuery {
+    /// Time range: "1d", "1w", "1m", "3m", "1y" or "all". Defaults to "1m".
+    pub range: Option<HistoryRange>,
+}
+
+/// Query parameters for the trade export endpoint.
+#[derive(Deserialize)]
+pub struct TradeExportQuery {
+    /// File format: "csv", "json" or "koinly". Defaults to "csv".
+    pub format: Option<ExportFormat>,
+}
+
+/// Portfolio value at a point in time.
+#[derive(Serialize)]
+pub struct PortfolioHistoryPoint {
+    /// When the value was captured.
+    pub timestamp: DateTime<Utc>,
+    /// Cash plus positions in USD.
+    pub total_value: f64,
+    /// Cash in USD.
+    pub cash_balance: f64,
+    /// Return since the start of the range in percent.
+    pub return_percent: f64,
+    /// Decline from the running peak in percent.
+    pub drawdown_percent: f64,
+}
+
+/// Equity curve of a user's portfolio.
+#[derive(Serialize)]
+pub struct PortfolioHistoryResponse {
+    /// Requested time range.
+    pub range: HistoryRange,
+    /// Return over the range in percent.
+    pub total_return_percent: f64,
+    /// Largest peak-to-trough decline over the range in percent.
+    pub max_drawdown_percent: f64,
+    /// Points oldest first, ending with the current value.
+    pub points: Vec<PortfolioHistoryPoint>,
+}
+
+impl PortfolioHistoryResponse {
+    /// Converts an equity curve into the API representation.
+    pub fn new(range: HistoryRange, curve: EquityCurve) -> Self {
+        Self {
+            range,
+            total_return_percent: curve.total_return * 100.0,
+            max_drawdown_percent: curve.max_drawdown * 100.0,
+            points: curve
+                .points
+                .into_iter()
+                .map(|p| PortfolioHistoryPoint {
+                    timestamp: p.time,
+                    total_value: cents_to_usd(p.value_cents),
+                    cash_balance: cents_to_usd(p.cash_cents),
+                    return_percent: p.cumulative_return * 100.0,
+                    drawdown_percent: p.drawdown * 100.0,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Request to place a market order.
+/// Orders fill immediately at the oracle price plus fees and slippage.
+#[derive(Deserialize, Validate)]
+pub struct PlaceOrderRequest {
+    /// Trading symbol (e.g., "ETH").
+    #[validate(length(min = 1, max = 10, message = "Symbol must be 1-10 characters"))]
+    pub symbol: String,
+    /// Order side: "buy" or "sell".
+    pub side: OrderSide,
+    /// Number of tokens to trade.
+    #[validate(range(exclusive_min = 0.0, message = "Quantity must be greater than zero"))]
+    pub quantity: f64,
+}
+
+// Rebalancing related types
+
+/// Target share of the portfolio for one symbol.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct TargetWeightRequest {
+    /// Trading symbol.
+    #[validate(length(min = 1, max = 10, message = "Symbol must be 1-10 characters"))]
+    pub symbol: String,
+    /// Share of total portfolio value in percent.
+    #[validate(range(exclusive_min = 0.0, max = 100.0, message = "Weight must be greater than 0 and at most 100"))]
+    pub weight_percent: f64,
+}
+
+/// Request to preview or execute a rebalance.
+/// Cash receives whatever weight the targets leave over.
+#[derive(Deserialize, Validate)]
+pub struct RebalanceRequest {
+    /// Target weights; held symbols that are not listed are sold.
+    #[validate(length(max = 20, message = "At most 20 targets are allowed"), nested)]
+    pub targets: Vec<TargetWeightRequest>,
+}
+
+/// Request to rebalance automatically on a schedule.
+#[derive(Deserialize, Validate)]
+pub struct RebalanceScheduleRequest {
+    /// Target weights; held symbols that are not listed are sold.
+    #[validate(length(max = 20, message = "At most 20 targets are allowed"), nested)]
+    pub targets: Vec<TargetWeightRequest>,
+    /// Hours between rebalances.
+    #[validate(range(min = 1, max = 8760, message = "Interval must be between 1 and 8760 hours"))]
+    pub interval_hours: u32,
+    /// Skip runs while no weight is off by more than this many percentage points.
+    #[validate(range(min = 0.0, max = 100.0, message = "Drift threshold must be between 0 and 100"))]
+    pub drift_threshold_percent: Option<f64>,
+}
+
+/// Order needed to reach the target weights.
+#[derive(Serialize)]
+pub struct RebalanceOrderResponse {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Order side.
+    pub side: OrderSide,
+    /// Number of tokens.
+    pub quantity: f64,
+    /// Oracle price per token in USD.
+    pub reference_price: f64,
+    /// Expected fill price per token including slippage in USD.
+    pub price: f64,
+    /// Quantity times fill price in USD.
+    pub total_value: f64,
+    /// Expected fee in USD.
+    pub fee: f64,
+    /// Expected slippage in USD.
+    pub slippage: f64,
+}
+
+/// Current, target and projected share of one holding.
+/// Cash is reported with the symbol "USD".
+#[derive(Serialize)]
+pub struct AllocationResponse {
+    /// Trading symbol, or "USD" for cash.
+    pub symbol: String,
+    /// Value before rebalancing in USD.
+    pub current_value: f64,
+    /// Weight before rebalancing in percent.
+    pub current_weight_percent: f64,
+    /// Target weight in percent.
+    pub target_weight_percent: f64,
+    /// Value after rebalancing in USD.
+    pub projected_value: f64,
+    /// Weight after rebalancing in percent.
+    pub projected_weight_percent: f64,
+}
+
+/// Planned rebalance with fees and resulting allocations.
+#[derive(Serialize)]
+pub struct RebalancePreviewResponse {
+    /// Portfolio value before rebalancing in USD.
+    pub total_value: f64,
+    /// Largest difference between a current and target weight in percentage points.
+    pub max_drift_percent: f64,
+    /// Allocation of every held or targeted symbol and cash.
+    pub allocations: Vec<AllocationResponse>,
+    /// Orders to execute, sells first.
+    pub orders: Vec<RebalanceOrderResponse>,
+    /// Fees of all orders in USD.
+    pub total_fees: f64,
+    /// Slippage of all orders in USD.
+    pub total_slippage: f64,
+}
+
+impl From<RebalancePlan> for RebalancePreviewResponse {
+    fn from(plan: RebalancePlan) -> Self {
+        let total = plan.total_value_cents;
+        let weight = |value: i64| if total > 0 { value as f64 / total as f64 * 100.0 } else { 0.0 };
+        let projected_total = plan.projected_cash_cents
+            + plan.allocations.iter().map(|a| a.projected_value_cents).sum::<i64>();
+        let projected_weight = |value: i64| {
+            if projected_total > 0 { value as f64 / projected_total as f64 * 100.0 } else { 0.0 }
+        };
+
+        let mut allocations: Vec<AllocationResponse> = plan
+            .allocations
+            .into_iter()
+            .map(|a| AllocationResponse {
+                current_value: cents_to_usd(a.current_value_cents),
+                current_weight_percent: weight(a.current_value_cents),
+                target_weight_percent: weight(a.target_value_cents),
+                projected_value: cents_to_usd(a.projected_value_cents),
+                projected_weight_percent: projected_weight(a.projected_value_cents),
+                symbol: a.symbol,
+            })
+            .collect();
+        allocations.push(AllocationResponse {
+            symbol: "USD".to_string(),
+            current_value: cents_to_usd(plan.current_cash_cents),
+            current_weight_percent: weight(plan.current_cash_cents),
+            target_weight_percent: weight(plan.target_cash_cents),
+            projected_value: cents_to_usd(plan.projected_cash_cents),
+            projected_weight_percent: projected_weight(plan.projected_cash_cents),
+        });
+
+        Self {
+            total_value: cents_to_usd(total),
+            max_drift_percent: plan.max_drift_bps as f64 / 100.0,
+            allocations,
+            orders: plan
+                .orders
+                .into_iter()
+                .map(|o| RebalanceOrderResponse {
+                    symbol: o.symbol,
+                    side: o.side,
+                    quantity: micros_to_units(o.fill.quantity),
+                    reference_price: cents_to_usd(o.fill.reference_price),
+                    price: cents_to_usd(o.fill.price),
+                    total_value: cents_to_usd(o.fill.notional_cents),
+                    fee: cents_to_usd(o.fill.fee_cents),
+                    slippage: cents_to_usd(o.fill.slippage_cents),
+                })
+                .collect(),
+            total_fees: cents_to_usd(plan.total_fees_cents),
+            total_slippage: cents_to_usd(plan.total_slippage_cents),
+        }
+    }
+}
+
+/// Executed rebalance with the plan it followed and the resulting trades.
+#[derive(Serialize)]
+pub struct RebalanceExecutionResponse {
+    /// Plan that was executed.
+    pub plan: RebalancePreviewResponse,
+    /// Trades made, in execution order.
+    pub trades: Vec<Trade>,
+}
+
+/// Scheduled rebalance of the user's portfolio.
+#[derive(Serialize)]
+pub struct RebalanceScheduleResponse {
+    /// Target weights.
+    pub targets: Vec<TargetWeightRequest>,
+    /// Hours between rebalances.
+    pub interval_hours: u32,
+    /// Runs are skipped while no weight is off by more than this many percentage points.
+    pub drift_threshold_percent: f64,
+    /// When the next rebalance is due.
+    pub next_run_at: DateTime<Utc>,
+    /// When the schedule last ran.
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Why the last run failed, if it did.
+    pub last_error: Option<String>,
+}
+
+impl From<db::models::RebalanceSchedule> for RebalanceScheduleResponse {
+    fn from(schedule: db::models::RebalanceSchedule) -> Self {
+        Self {
+            targets: schedule
+                .symbols
+                .into_iter()
+                .zip(schedule.weights_bps)
+                .map(|(symbol, weight_bps)| TargetWeightRequest {
+                    symbol,
+                    weight_percent: weight_bps as f64 / 100.0,
+                })
+                .collect(),
+            interval_hours: (schedule.interval_seconds / 3600) as u32,
+            drift_threshold_percent: schedule.drift_threshold_bps as f64 / 100.0,
+            next_run_at: schedule.next_run_at,
+            last_run_at: schedule.last_run_at,
+            last_error: schedule.last_error,
+        }
+    }
+}
+
+// Asset catalog related types
+
+/// Trading status of an asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetStatus {
+    /// Orders are accepted.
+    Active,
+    /// Orders are temporarily rejected.
+    Halted,
+    /// Asset is no longer tradable.
+    Delisted,
+}
+
+impl AssetStatus {
+    /// Returns the status stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetStatus::Active => "active",
+            AssetStatus::Halted => "halted",
+            AssetStatus::Delisted => "delisted",
+        }
+    }
+}
+
+/// Asset catalog entry with its trading rules.
+#[derive(Serialize)]
+pub struct AssetResponse {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Human-readable asset name.
+    pub name: String,
+    /// On-chain token decimals.
+    pub decimals: i16,
+    /// Price increment in USD.
+    pub tick_size: f64,
+    /// Quantity increment in tokens.
+    pub lot_size: f64,
+    /// Minimum order value in USD.
+    pub min_notional: f64,
+    /// Trading status: "active", "halted" or "delisted".
+    pub status: String,
+    /// Chain the asset lives on.
+    pub chain: Option<String>,
+    /// Token contract address, absent for native assets.
+    pub contract_address: Option<String>,
+}
+
+impl From<db::models::Asset> for AssetResponse {
+    fn from(asset: db::models::Asset) -> Self {
+        Self {
+            symbol: asset.symbol,
+            name: asset.name,
+            decimals: asset.decimals,
+            tick_size: cents_to_usd(asset.tick_size_cents),
+            lot_size: micros_to_units(asset.lot_size),
+            min_notional: cents_to_usd(asset.min_notional_cents),
+            status: asset.status,
+            chain: asset.chain,
+            contract_address: asset.contract_address,
+        }
+    }
+}
+
+/// Query parameters for listing assets.
+#[derive(Deserialize)]
+pub struct ListAssetsQuery {
+    /// Only return assets with this status.
+    pub status: Option<AssetStatus>,
+}
+
+/// Request to add an asset to the catalog (admin only).
+#[derive(Deserialize, Validate)]
+pub struct CreateAssetRequest {
+    /// Trading symbol (uppercase letters and digits).
+    #[validate(regex(path = "SYMBOL_REGEX", message = "Symbol must be 1-10 uppercase letters or digits"))]
+    pub symbol: String,
+    /// Human-readable asset name.
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+    /// On-chain token decimals.
+    #[validate(range(min = 0, max = 36, message = "Decimals must be between 0 and 36"))]
+    pub decimals: i16,
+    /// Price increment in USD.
+    #[validate(range(min = 0.01, message = "Tick size must be at least 0.01"))]
+    pub tick_size: f64,
+    /// Quantity increment in tokens.
+    #[validate(range(min = 0.000001, message = "Lot size must be at least 0.000001"))]
+    pub lot_size: f64,
+    /// Minimum order value in USD.
+    #[validate(range(min = 0.0, message = "Minimum notional cannot be negative"))]
+    pub min_notional: f64,
+    /// Initial trading status (defaults to active).
+    pub status: Option<AssetStatus>,
+    /// Chain the asset lives on.
+    #[validate(length(min = 1, max = 32, message = "Chain must be 1-32 characters"))]
+    pub chain: Option<String>,
+    /// Token contract address.
+    #[validate(regex(path = "WALLET_ADDRESS_REGEX", message = "Invalid contract address format"))]
+    pub contract_address: Option<String>,
+    /// Starting price in USD for the simulated oracle.
+    #[validate(range(exclusive_min = 0.0, message = "Initial price must be greater than zero"))]
+    pub initial_price: Option<f64>,
+}
+
+/// Request to update an asset's trading rules or status (admin only).
+/// Omitted fields keep their current value.
+#[derive(Deserialize, Validate)]
+pub struct UpdateAssetRequest {
+    /// Human-readable asset name.
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: Option<String>,
+    /// On-chain token decimals.
+    #[validate(range(min = 0, max = 36, message = "Decimals must be between 0 and 36"))]
+    pub decimals: Option<i16>,
+    /// Price increment in USD.
+    #[validate(range(min = 0.01, message = "Tick size must be at least 0.01"))]
+    pub tick_size: Option<f64>,
+    /// Quantity increment in tokens.
+    #[validate(range(min = 0.000001, message = "Lot size must be at least 0.000001"))]
+    pub lot_size: Option<f64>,
+    /// Minimum order value in USD.
+    #[validate(range(min = 0.0, message = "Minimum notional cannot be negative"))]
+    pub min_notional: Option<f64>,
+    /// Trading status.
+    pub status: Option<AssetStatus>,
+    /// Chain the asset lives on.
+    #[validate(length(min = 1, max = 32, message = "Chain must be 1-32 characters"))]
+    pub chain: Option<String>,
+    /// Token contract address.
+    #[validate(regex(path = "WALLET_ADDRESS_REGEX", message = "Invalid contract address format"))]
+    pub contract_address: Option<String>,
+}
+
+// Market data related types
+
+/// Query parameters for fetching candles.
+#[derive(Deserialize, Validate)]
+pub struct CandlesQuery {
+    /// Trading symbol.
+    #[validate(length(min = 1, max = 10, message = "Symbol must be 1-10 characters"))]
+    pub symbol: String,
+    /// Candle timeframe: "1m", "5m", "1h" or "1d".
+    pub interval: CandleInterval,
+    /// Start of the range (inclusive). Defaults to `limit` candles before `to`.
+    pub from: Option<DateTime<Utc>>,
+    /// End of the range (exclusive). Defaults to now.
+    pub to: Option<DateTime<Utc>>,
+    /// Maximum number of candles to return.
+    #[validate(range(min = 1, max = 1000, message = "Limit must be between 1 and 1000"))]
+    pub limit: Option<i64>,
+}
+
+/// Single OHLCV candle.
+#[derive(Serialize)]
+pub struct CandleResponse {
+    /// Start of the candle.
+    pub open_time: DateTime<Utc>,
+    /// First price in USD.
+    pub open: f64,
+    /// Highest price in USD.
+    pub high: f64,
+    /// Lowest price in USD.
+    pub low: f64,
+    /// Last price in USD.
+    pub close: f64,
+    /// Traded quantity in tokens.
+    pub volume: f64,
+}
+
+impl From<db::models::Candle> for CandleResponse {
+    fn from(candle: db::models::Candle) -> Self {
+        Self {
+            open_time: candle.open_time,
+            open: cents_to_usd(candle.open),
+            high: cents_to_usd(candle.high),
+            low: cents_to_usd(candle.low),
+            close: cents_to_usd(candle.close),
+            volume: micros_to_units(candle.volume),
+        }
+    }
+}
+
+/// Query parameters for the ticker endpoint.
+#[derive(Deserialize)]
+pub struct TickerQuery {
+    /// Only return the ticker of this symbol.
+    pub symbol: Option<String>,
+}
+
+/// Current price and 24h statistics of a symbol.
+#[derive(Serialize)]
+pub struct TickerResponse {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Current oracle price in USD.
+    pub price: f64,
+    /// Price 24 hours ago in USD.
+    pub open_24h: f64,
+    /// Highest price in the last 24 hours in USD.
+    pub high_24h: f64,
+    /// Lowest price in the last 24 hours in USD.
+    pub low_24h: f64,
+    /// Absolute price change over 24 hours in USD.
+    pub change_24h: f64,
+    /// Relative price change over 24 hours in percent.
+    pub change_percent_24h: f64,
+    /// Traded quantity in the last 24 hours in tokens.
+    pub volume_24h: f64,
+}
+
+impl TickerResponse {
+    /// Builds a ticker from the current price and the symbol's 24h candle statistics.
+    /// Symbols without candles in the window report the current price and no volume.
+    pub fn new(symbol: String, price_cents: i64, stats: Option<&db::queries::market::SymbolStats>) -> Self {
+        let (open, high, low, volume) = match stats {
+            Some(s) => (s.open, s.high.max(price_cents), s.low.min(price_cents), s.volume),
+            None => (price_cents, price_cents, price_cents, 0),
+        };
+        let change = price_cents - open;
+
+        Self {
+            symbol,
+            price: cents_to_usd(price_cents),
+            open_24h: cents_to_usd(open),
+            high_24h: cents_to_usd(high),
+            low_24h: cents_to_usd(low),
+            change_24h: cents_to_usd(change),
+            change_percent_24h: if open > 0 { change as f64 / open as f64 * 100.0 } else { 0.0 },
+            volume_24h: micros_to_units(volume),
+        }
+    }
+}
+
+/// Tradable symbol with its current price.
+#[derive(Serialize)]
+pub struct MarketSymbol {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Human-readable asset name.
+    pub name: String,
+    /// Trading status: "active" or "halted".
+    pub status: String,
+    /// Current oracle price in USD, if known.
+    pub price: Option<f64>,
+}
+
+// Backtesting related types
+
+/// Request to backtest a strategy on stored candles.
+#[derive(Deserialize, Validate)]
+pub struct BacktestRequest {
+    /// Trading symbol.
+    #[validate(length(min = 1, max = 10, message = "Symbol must be 1-10 characters"))]
+    pub symbol: String,
+    /// Candle timeframe the strategy runs on.
+    pub interval: CandleInterval,
+    /// Start of the test range (inclusive).
+    pub from: DateTime<Utc>,
+    /// End of the test range (exclusive).
+    pub to: DateTime<Utc>,
+    /// Starting cash in USD. Defaults to the $10,000 paper trading balance.
+    #[validate(range(min = 1.0, max = 1_000_000_000.0, message = "Initial cash must be between 1 and 1,000,000,000"))]
+    pub initial_cash: Option<f64>,
+    /// Entry and exit rules of the strategy.
+    pub strategy: StrategySpec,
+}
+
+/// Simulated trade made during a backtest.
+#[derive(Serialize)]
+pub struct BacktestTradeResponse {
+    /// Buy or sell.
+    pub side: OrderSide,
+    /// Open time of the candle the trade happened on.
+    pub time: DateTime<Utc>,
+    /// Why the trade was made.
+    pub reason: String,
+    /// Quantity in tokens.
+    pub quantity: f64,
+    /// Price before slippage in USD.
+    pub reference_price: f64,
+    /// Fill price in USD.
+    pub price: f64,
+    /// Fee charged in USD.
+    pub fee: f64,
+    /// Cost of slippage in USD.
+    pub slippage: f64,
+    /// Profit or loss of the round trip in USD, set on closing trades.
+    pub pnl: Option<f64>,
+}
+
+/// Portfolio value at a point of the backtest.
+#[derive(Serialize)]
+pub struct EquityPointResponse {
+    /// Open time of the candle.
+    pub time: DateTime<Utc>,
+    /// Cash plus position value in USD.
+    pub equity: f64,
+}
+
+/// Performance summary, trades and equity curve of a backtest.
+#[derive(Serialize)]
+pub struct BacktestResponse {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Candle timeframe.
+    pub interval: CandleInterval,
+    /// Number of candles replayed.
+    pub candles: usize,
+    /// Starting cash in USD.
+    pub initial_cash: f64,
+    /// Equity at the end in USD.
+    pub final_equity: f64,
+    /// Total return in percent.
+    pub total_return_percent: f64,
+    /// Largest peak-to-trough decline in percent.
+    pub max_drawdown_percent: f64,
+    /// Annualized Sharpe ratio, if returns varied.
+    pub sharpe_ratio: Option<f64>,
+    /// Share of profitable round trips in percent, if any were closed.
+    pub win_rate_percent: Option<f64>,
+    /// Fees paid in USD.
+    pub total_fees: f64,
+    /// Slippage paid in USD.
+    pub total_slippage: f64,
+    /// All simulated trades.
+    pub trades: Vec<BacktestTradeResponse>,
+    /// Equity after every candle.
+    pub equity_curve: Vec<EquityPointResponse>,
+}
+
+impl BacktestResponse {
+    /// Converts an engine result into the API representation.
+    pub fn new(symbol: String, interval: CandleInterval, candles: usize, result: BacktestResult) -> Self {
+        Self {
+            symbol,
+            interval,
+            candles,
+            initial_cash: cents_to_usd(result.initial_cash_cents),
+            final_equity: cents_to_usd(result.final_equity_cents),
+            total_return_percent: result.total_return * 100.0,
+            max_drawdown_percent: result.max_drawdown * 100.0,
+            sharpe_ratio: result.sharpe_ratio,
+            win_rate_percent: result.win_rate.map(|rate| rate * 100.0),
+            total_fees: cents_to_usd(result.total_fees_cents),
+            total_slippage: cents_to_usd(result.total_slippage_cents),
+            trades: result
+                .trades
+                .into_iter()
+                .map(|t| BacktestTradeResponse {
+                    side: t.side,
+                    time: t.time,
+                    reason: t.reason.to_string(),
+                    quantity: micros_to_units(t.fill.quantity),
+                    reference_price: cents_to_usd(t.fill.reference_price),
+                    price: cents_to_usd(t.fill.price),
+                    fee: cents_to_usd(t.fill.fee_cents),
+                    slippage: cents_to_usd(t.fill.slippage_cents),
+                    pnl: t.pnl_cents.map(cents_to_usd),
+                })
+                .collect(),
+            equity_curve: result
+                .equity_curve
+                .into_iter()
+                .map(|p| EquityPointResponse {
+                    time: p.time,
+                    equity: cents_to_usd(p.equity_cents),
+                })
+                .collect(),
+        }
+    }
+}
+
+// Trading bot related types
+
+/// Strategy settings of a bot, tagged by `kind`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum BotConfig {
+    /// Buys a fixed USD amount on a fixed interval.
+    Dca {
+        /// Amount spent per buy in USD, excluding fees.
+        amount: f64,
+        /// Minutes between buys.
+        interval_minutes: u32,
+    },
+    /// Buys as the price falls through grid lines and sells as it rises through them.
+    Grid {
+        /// Lowest grid line in USD.
+        lower_price: f64,
+        /// Highest grid line in USD.
+        upper_price: f64,
+        /// Number of evenly spaced grid lines.
+        levels: u16,
+        /// Tokens traded per crossed grid line.
+        quantity_per_level: f64,
+    },
+}
+
+/// Request to create a trading bot.
+#[derive(Deserialize, Validate)]
+pub struct CreateBotRequest {
+    /// Display name.
+    #[validate(length(min = 1, max = 50, message = "Name must be 1-50 characters"))]
+    pub name: String,
+    /// Trading symbol.
+    #[validate(length(min = 1, max = 10, message = "Symbol must be 1-10 characters"))]
+    pub symbol: String,
+    /// Strategy settings.
+    #[serde(flatten)]
+    pub config: BotConfig,
+}
+
+/// P&L of the trades a bot placed.
+#[derive(Serialize)]
+pub struct BotPnlResponse {
+    /// Number of trades placed.
+    pub trade_count: i64,
+    /// Tokens still held from the bot's buys.
+    pub held_quantity: f64,
+    /// Cash spent on buys including fees, in USD.
+    pub invested: f64,
+    /// P&L of sold tokens against the average buy cost, in USD.
+    pub realized_pnl: f64,
+    /// P&L of held tokens at the current price, in USD.
+    pub unrealized_pnl: f64,
+    /// Realized plus unrealized P&L, in USD.
+    pub total_pnl: f64,
+    /// Fees paid in USD.
+    pub fees: f64,
+}
+
+impl From<BotPnl> for BotPnlResponse {
+    fn from(pnl: BotPnl) -> Self {
+        Self {
+            trade_count: pnl.trade_count,
+            held_quantity: micros_to_units(pnl.held_quantity),
+            invested: cents_to_usd(pnl.invested_cents),
+            realized_pnl: cents_to_usd(pnl.realized_pnl_cents),
+            unrealized_pnl: cents_to_usd(pnl.unrealized_pnl_cents),
+            total_pnl: cents_to_usd(pnl.realized_pnl_cents + pnl.unrealized_pnl_cents),
+            fees: cents_to_usd(pnl.fees_cents),
+        }
+    }
+}
+
+/// Trading bot with its settings, schedule and P&L.
+#[derive(Serialize)]
+pub struct BotResponse {
+    /// Unique bot identifier.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Trading symbol.
+    pub symbol: String,
+    /// Bot status: "active" or "paused".
+    pub status: String,
+    /// Strategy settings.
+    #[serde(flatten)]
+    pub config: BotConfig,
+    /// When the next DCA buy is due.
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// When the bot last placed an order.
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Why the last order failed, if it did.
+    pub last_error: Option<String>,
+    /// When the bot was created.
+    pub created_at: DateTime<Utc>,
+    /// P&L of the bot's trades.
+    pub pnl: BotPnlResponse,
+}
+
+impl BotResponse {
+    /// Builds the API representation of a bot and its P&L.
+    pub fn new(bot: db::models::Bot, pnl: BotPnl) -> Self {
+        let config = match bot.kind.as_str() {
+            "grid" => BotConfig::Grid {
+                lower_price: cents_to_usd(bot.grid_lower_cents.unwrap_or_default()),
+                upper_price: cents_to_usd(bot.grid_upper_cents.unwrap_or_default()),
+                levels: bot.grid_levels.unwrap_or_default() as u16,
+                quantity_per_level: micros_to_units(bot.grid_quantity.unwrap_or_default()),
+            },
+            _ => BotConfig::Dca {
+                amount: cents_to_usd(bot.amount_cents.unwrap_or_default()),
+                interval_minutes: (bot.interval_seconds.unwrap_or_default() / 60) as u32,
+            },
+        };
+
+        Self {
+            id: bot.id.to_string(),
+            name: bot.name,
+            symbol: bot.symbol,
+            status: bot.status,
+            config,
+            next_run_at: bot.next_run_at,
+            last_run_at: bot.last_run_at,
+            last_error: bot.last_error,
+            created_at: bot.created_at,
+            pnl: BotPnlResponse::from(pnl),
+        }
+    }
+}
+
+// Price alert and notification related types
+
+/// Condition that fires an alert, tagged by `condition`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "condition", rename_all = "lowercase")]
+pub enum AlertCondition {
+    /// Fires when the price rises to or through a level.
+    Above {
+        /// Level in USD.
+        price: f64,
+    },
+    /// Fires when the price falls to or through a level.
+    Below {
+        /// Level in USD.
+        price: f64,
+    },
+    /// Fires when the price moves by a percentage in either direction.
+    Percent {
+        /// Move in percent.
+        percent: f64,
+    },
+}
+
+/// Request to create or replace a price alert.
+#[derive(Deserialize, Validate)]
+pub struct AlertRequest {
+    /// Watched symbol.
+    #[validate(length(min = 1, max = 10, message = "Symbol must be 1-10 characters"))]
+    pub symbol: String,
+    /// Condition that fires the alert.
+    #[serde(flatten)]
+    pub condition: AlertCondition,
+    /// Whether the alert re-arms after firing instead of deactivating.
+    #[serde(default)]
+    pub recurring: bool,
+    /// Optional text included in notifications.
+    #[validate(length(max = 140, message = "Note must be at most 140 characters"))]
+    pub note: Option<String>,
+}
+
+/// Price alert with its state.
+#[derive(Serialize)]
+pub struct AlertResponse {
+    /// Unique alert identifier.
+    pub id: String,
+    /// Watched symbol.
+    pub symbol: String,
+    /// Condition that fires the alert.
+    #[serde(flatten)]
+    pub condition: AlertCondition,
+    /// Price percent moves are measured from, in USD.
+    pub reference_price: Option<f64>,
+    /// Whether the alert re-arms after firing.
+    pub recurring: bool,
+    /// Whether the price is on the near side of the level, so the alert can fire.
+    pub armed: bool,
+    /// Whether the alert is evaluated. One-shot alerts deactivate after firing.
+    pub is_active: bool,
+    /// Optional text included in notifications.
+    pub note: Option<String>,
+    /// Number of times the alert fired.
+    pub trigger_count: i32,
+    /// When the alert last fired.
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    /// Price the alert last fired at, in USD.
+    pub last_triggered_price: Option<f64>,
+    /// When the alert was created.
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<db::models::Alert> for AlertResponse {
+    fn from(alert: db::models::Alert) -> Self {
+        let condition = match alert.condition.as_str() {
+            "percent" => AlertCondition::Percent {
+                percent: f64::from(alert.percent_bps.unwrap_or_default()) / 100.0,
+            },
+            "below" => AlertCondition::Below {
+                price: cents_to_usd(alert.target_price_cents.unwrap_or_default()),
+            },
+            _ => AlertCondition::Above {
+                price: cents_to_usd(alert.target_price_cents.unwrap_or_default()),
+            },
+        };
+
+        Self {
+            id: alert.id.to_string(),
+            symbol: alert.symbol,
+            condition,
+            reference_price: alert.reference_price_cents.map(cents_to_usd),
+            recurring: alert.recurring,
+            armed: alert.armed,
+            is_active: alert.is_active,
+            note: alert.note,
+            trigger_count: alert.trigger_count,
+            last_triggered_at: alert.last_triggered_at,
+            last_triggered_price: alert.last_triggered_price_cents.map(cents_to_usd),
+            created_at: alert.created_at,
+        }
+    }
+}
+
+/// Query parameters for the notification inbox.
+#[derive(Deserialize, Validate)]
+pub struct NotificationListQuery {
+    /// Only return unread notifications.
+    #[serde(default)]
+    pub unread_only: bool,
+    /// Maximum number of notifications, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+}
+
+/// Notification in a user's inbox.
+#[derive(Serialize)]
+pub struct NotificationResponse {
+    /// Unique notification identifier.
+    pub id: String,
+    /// What produced the notification, e.g. "price_alert".
+    pub kind: String,
+    /// Short headline.
+    pub title: String,
+    /// Full message.
+    pub message: String,
+    /// Alert that fired, for price alert notifications.
+    pub alert_id: Option<String>,
+    /// When the user read the notification.
+    pub read_at: Option<DateTime<Utc>>,
+    /// When the notification was created.
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<db::models::Notification> for NotificationResponse {
+    fn from(notification: db::models::Notification) -> Self {
+        Self {
+            id: notification.id.to_string(),
+            kind: notification.kind,
+            title: notification.title,
+            message: notification.message,
+            alert_id: notification.alert_id.map(|id| id.to_string()),
+            read_at: notification.read_at,
+            created_at: notification.created_at,
+        }
+    }
+}
+
+/// Page of the notification inbox.
+#[derive(Serialize)]
+pub struct NotificationListResponse {
+    /// Total unread notifications.
+    pub unread_count: i64,
+    /// Notifications, newest first.
+    pub notifications: Vec<NotificationResponse>,
+}
+
+/// Request to register a webhook.
+#[derive(Deserialize, Validate)]
+pub struct CreateWebhookRequest {
+    /// URL notifications are posted to.
+    #[validate(url(message = "URL must be a valid https URL"), length(max = 2048, message = "URL must be at most 2048 characters"))]
+    pub url: String,
+}
+
+/// Registered webhook.
+#[derive(Serialize)]
+pub struct WebhookResponse {
+    /// Unique webhook identifier.
+    pub id: String,
+    /// URL notifications are posted to.
+    pub url: String,
+    /// Signing secret for verifying payloads. Only returned when the webhook is registered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Whether notifications are delivered. Cleared after repeated failures.
+    pub is_active: bool,
+    /// Consecutive failed deliveries.
+    pub failure_count: i32,
+    /// When a notification was last delivered.
+    pub last_delivery_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last delivery, null if the endpoint was unreachable.
+    pub last_status: Option<i16>,
+    /// When the webhook was registered.
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<db::models::Webhook> for WebhookResponse {
+    fn from(webhook: db::models::Webhook) -> Self {
+        Self {
+            id: webhook.id.to_string(),
+            url: webhook.url,
+            secret: None,
+            is_active: webhook.is_active,
+            failure_count: webhook.failure_count,
+            last_delivery_at: webhook.last_delivery_at,
+            last_status: webhook.last_status,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+// Watchlist related types
+
+/// Request to create a watchlist.
+#[derive(Deserialize, Validate)]
+pub struct CreateWatchlistRequest {
+    /// Display name, unique per user.
+    #[validate(length(min = 1, max = 50, message = "Name must be 1-50 characters"))]
+    pub name: String,
+    /// Initial symbols in display order.
+    #[serde(default)]
+    #[validate(length(max = 50, message = "A watchlist can hold at most 50 symbols"))]
+    pub symbols: Vec<String>,
+}
+
+/// Request to rename a watchlist and/or replace its symbols.
+/// Sending the existing symbols in a new order reorders the list.
+#[derive(Deserialize, Validate)]
+pub struct UpdateWatchlistRequest {
+    /// New display name.
+    #[validate(length(min = 1, max = 50, message = "Name must be 1-50 characters"))]
+    pub name: Option<String>,
+    /// Symbols in display order.
+    #[validate(length(max = 50, message = "A watchlist can hold at most 50 symbols"))]
+    pub symbols: Option<Vec<String>>,
+}
+
+/// Request to add a symbol to a watchlist.
+#[derive(Deserialize, Validate)]
+pub struct AddWatchlistSymbolRequest {
+    /// Symbol to append.
+    #[validate(length(min = 1, max = 10, message = "Symbol must be 1-10 characters"))]
+    pub symbol: String,
+}
+
+/// Watched symbol with its live quote.
+/// Quote fields are null while the oracle has no price for the symbol.
+#[derive(Serialize)]
+pub struct WatchlistQuote {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Current oracle price in USD.
+    pub price: Option<f64>,
+    /// Absolute price change over 24 hours in USD.
+    pub change_24h: Option<f64>,
+    /// Relative price change over 24 hours in percent.
+    pub change_percent_24h: Option<f64>,
+    /// Traded quantity in the last 24 hours in tokens.
+    pub volume_24h: Option<f64>,
+}
+
+impl WatchlistQuote {
+    /// Builds a quote from a symbol's ticker, if it has one.
+    pub fn new(symbol: String, ticker: Option<TickerResponse>) -> Self {
+        Self {
+            symbol,
+            price: ticker.as_ref().map(|t| t.price),
+            change_24h: ticker.as_ref().map(|t| t.change_24h),
+            change_percent_24h: ticker.as_ref().map(|t| t.change_percent_24h),
+            volume_24h: ticker.as_ref().map(|t| t.volume_24h),
+        }
+    }
+}
+
+/// Watchlist with a live quote for every symbol.
+#[derive(Serialize)]
+pub struct WatchlistResponse {
+    /// Unique watchlist identifier.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Watched symbols with quotes, in display order.
+    pub symbols: Vec<WatchlistQuote>,
+    /// When the watchlist was created.
+    pub created_at: DateTime<Utc>,
+    /// When the watchlist was last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Converts an amount in cents to USD.
+pub fn cents_to_usd(cents: i64) -> f64 {
+    cents as f64 / 100.0
+}
+
+/// Converts an amount in USD to cents, rounding to the nearest cent.
+pub fn usd_to_cents(usd: f64) -> i64 {
+    (usd * 100.0).round() as i64
+}
+
+/// Converts a quantity in micro units to whole tokens.
+pub fn micros_to_units(micros: i64) -> f64 {
+    micros as f64 / MICRO_UNITS as f64
+}
+
+/// Converts a quantity in whole tokens to micro units, rounding to the nearest unit.
+pub fn units_to_micros(units: f64) -> i64 {
+    (units * MICRO_UNITS as f64).round() as i64
+}
+
+// XP and level related types
+
+/// Query parameters for the XP history endpoint.
+#[derive(Deserialize, Validate)]
+pub struct XpHistoryQuery {
+    /// Only return events recorded before this time, for paging.
+    pub before: Option<DateTime<Utc>>,
+    /// Id of the last event seen at `before`; events at that time with smaller ids are returned too.
+    pub before_id: Option<Uuid>,
+    /// Maximum number of events, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+}
+
+/// User's XP total and progress towards the next level.
+#[derive(Serialize)]
+pub struct XpSummaryResponse {
+    /// Total XP earned.
+    pub xp: u32,
+    /// Current level.
+    pub level: u16,
+    /// Total XP at which the current level was reached.
+    pub current_level_xp: u64,
+    /// Total XP required for the next level, 0 at the maximum level.
+    pub next_level_xp: u64,
+    /// Progress towards the next level, between 0.0 and 1.0.
+    pub progress: f32,
+}
+
+impl XpSummaryResponse {
+    /// Builds the summary for an XP total using the active level curve.
+    pub fn new(xp_points: i32) -> Self {
+        let xp = game::safe_xp_conversion(xp_points);
+        let level = game::calculate_level_from_xp(xp);
+        Self {
+            xp,
+            level,
+            current_level_xp: game::xp_for_level(level),
+            next_level_xp: game::xp_required_for_next_level(level),
+            progress: game::level_progress(xp),
+        }
+    }
+}
+
+/// Entry in a user's XP history.
+#[derive(Serialize)]
+pub struct XpEventResponse {
+    /// Unique event identifier.
+    pub id: String,
+    /// What changed the XP, e.g. "trade" or "adjustment".
+    pub source: String,
+    /// XP applied, negative for reversals and deductions.
+    pub amount: i32,
+    /// Entity behind the change, e.g. a trade id.
+    pub reference_id: Option<String>,
+    /// XP total after the event.
+    pub xp_after: i32,
+    /// Level after the event.
+    pub level_after: i16,
+    /// When the event was recorded.
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<db::models::XpEvent> for XpEventResponse {
+    fn from(event: db::models::XpEvent) -> Self {
+        Self {
+            id: event.id.to_string(),
+            source: event.source,
+            amount: event.amount,
+            reference_id: event.reference_id,
+            xp_after: event.xp_after,
+            level_after: event.level_after,
+            created_at: event.created_at,
+        }
+    }
+}
+
+/// Page of a user's XP history.
+#[derive(Serialize)]
+pub struct XpHistoryResponse {
+    /// Current XP total and level progress.
+    pub summary: XpSummaryResponse,
+    /// Events, newest first.
+    pub events: Vec<XpEventResponse>,
+    /// Value to pass as `before` for the next page, absent on the last page.
+    pub next_before: Option<DateTime<Utc>>,
+    /// Value to pass as `before_id` for the next page, absent on the last page.
+    pub next_before_id: Option<String>,
+}
+
+// Achievement related types
+
+/// Unit achievement progress is reported in, with values converted for display.
+fn criterion_display(criterion: &game::Criterion, current: i64, target: i64) -> (&'static str, f64, f64) {
+    use game::Criterion;
+    match criterion {
+        Criterion::TradeCount(_) => ("trades", current as f64, target as f64),
+        Criterion::VolumeCents(_) | Criterion::RealizedPnlCents(_) => ("usd", cents_to_usd(current), cents_to_usd(target)),
+        Criterion::ProfitableCloses(_) => ("sells", current as f64, target as f64),
+        Criterion::SingleReturnBps(_) => ("percent", current as f64 / 100.0, target as f64 / 100.0),
+        Criterion::HoldDays(_) | Criterion::LoginStreakDays(_) => ("days", current as f64, target as f64),
+        Criterion::Xp(_) => ("xp", current as f64, target as f64),
+        Criterion::Level(_) => ("level", current as f64, target as f64),
+    }
+}
+
+/// Achievement in the catalog.
+#[derive(Serialize)]
+pub struct AchievementResponse {
+    /// Stable achievement key.
+    pub id: &'static str,
+    /// Display name.
+    pub name: &'static str,
+    /// What the player has to do.
+    pub description: &'static str,
+    /// Badge rarity: "bronze", "silver", "gold" or "platinum".
+    pub badge: &'static str,
+    /// XP granted on unlock.
+    pub xp_reward: u32,
+    /// Unit of `target`: "trades", "usd", "sells", "percent", "days", "xp" or "level".
+    pub unit: &'static str,
+    /// Value required to unlock.
+    pub target: f64,
+}
+
+impl From<&game::Achievement> for AchievementResponse {
+    fn from(achievement: &game::Achievement) -> Self {
+        let (_, target) = achievement.criterion.progress(&game::PlayerProgress::default());
+        let (unit, _, target) = criterion_display(&achievement.criterion, 0, target);
+        Self {
+            id: achievement.id,
+            name: achievement.name,
+            description: achievement.description,
+            badge: achievement.badge,
+            xp_reward: achievement.xp_reward,
+            unit,
+            target,
+        }
+    }
+}
+
+/// User's progress towards an achievement.
+#[derive(Serialize)]
+pub struct AchievementProgressResponse {
+    /// Catalog entry.
+    #[serde(flatten)]
+    pub achievement: AchievementResponse,
+    /// User's current value, in the same unit as `target`.
+    pub current: f64,
+    /// Progress towards the target, between 0.0 and 1.0.
+    pub progress: f64,
+    /// When the achievement was unlocked, absent while locked.
+    pub unlocked_at: Option<DateTime<Utc>>,
+}
+
+impl AchievementProgressResponse {
+    /// Builds the progress of a player towards an achievement.
+    pub fn new(
+        achievement: &game::Achievement,
+        player: &game::PlayerProgress,
+        unlocked_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let (current, target) = achievement.criterion.progress(player);
+        let progress = if unlocked_at.is_some() || target <= 0 {
+            1.0
+        } else {
+            (current as f64 / target as f64).clamp(0.0, 1.0)
+        };
+        let (_, current, _) = criterion_display(&achievement.criterion, current, target);
+        Self {
+            achievement: AchievementResponse::from(achievement),
+            current,
+            progress,
+            unlocked_at,
+        }
+    }
+}
+
+/// User's achievements with progress.
+#[derive(Serialize)]
+pub struct AchievementListResponse {
+    /// Number of achievements unlocked.
+    pub unlocked_count: usize,
+    /// Number of achievements in the catalog.
+    pub total_count: usize,
+    /// Every achievement in catalog order.
+    pub achievements: Vec<AchievementProgressResponse>,
+}
+
+// Quest related types
+
+/// Quest in the current rotation with the user's progress.
+#[derive(Serialize)]
+pub struct QuestResponse {
+    /// Quest key, used to claim it.
+    pub id: &'static str,
+    /// Display title.
+    pub title: &'static str,
+    /// What the player has to do.
+    pub description: &'static str,
+    /// Rotation period: "daily" or "weekly".
+    pub period: &'static str,
+    /// XP granted on claim before daily limits.
+    pub xp_reward: u32,
+    /// Current progress, in the same unit as `target`.
+    pub progress: f64,
+    /// Value at which the quest is complete.
+    pub target: f64,
+    /// Unit of `progress` and `target`: "trades", "usd", "sells", "hours" or "outperformed".
+    pub unit: &'static str,
+    /// Whether the goal has been reached.
+    pub completed: bool,
+    /// When the reward was claimed, absent while unclaimed.
+    pub claimed_at: Option<DateTime<Utc>>,
+    /// XP granted on claim.
+    pub xp_awarded: Option<i32>,
+    /// When the quest rotates out.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<QuestStatus> for QuestResponse {
+    fn from(status: QuestStatus) -> Self {
+        use game::QuestGoal;
+        let completed = status.is_complete();
+        let target = status.template.goal.target();
+        let (unit, progress, target) = match status.template.goal {
+            QuestGoal::Trades(_) => ("trades", status.progress as f64, target as f64),
+            QuestGoal::VolumeCents(_) => ("usd", cents_to_usd(status.progress), cents_to_usd(target)),
+            QuestGoal::ProfitableCloses(_) => ("sells", status.progress as f64, target as f64),
+            QuestGoal::HoldSymbol { .. } => ("hours", status.progress as f64, target as f64),
+            QuestGoal::BeatMarket => ("outperformed", status.progress as f64, target as f64),
+        };
+        Self {
+            id: status.template.id,
+            title: status.template.title,
+            description: status.template.description,
+            period: status.template.period.as_str(),
+            xp_reward: status.template.xp_reward,
+            progress,
+            target,
+            unit,
+            completed,
+            claimed_at: status.claimed_at,
+            xp_awarded: status.xp_awarded,
+            expires_at: status.period_end,
+        }
+    }
+}
+
+// Leaderboard related types
+
+/// Query parameters for a leaderboard page.
+#[derive(Deserialize, Validate)]
+pub struct LeaderboardQuery {
+    /// Time window: "week", "season" or "all". Defaults to "season".
+    pub window: Option<String>,
+    /// Maximum number of entries, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+    /// Number of entries to skip, for paging.
+    #[validate(range(min = 0, message = "Offset cannot be negative"))]
+    pub offset: Option<i64>,
+}
+
+/// Query parameters for a user's leaderboard position.
+#[derive(Deserialize)]
+pub struct LeaderboardRankQuery {
+    /// Time window: "week", "season" or "all". Defaults to "season".
+    pub window: Option<String>,
+}
+
+/// Converts a stored score to the unit shown for its metric.
+/// Returns the display value and unit: "xp", "percent", "usd", "ratio" or "rating".
+pub fn leaderboard_score_display(metric: game::LeaderboardMetric, score: f64) -> (f64, &'static str) {
+    use game::LeaderboardMetric;
+    match metric {
+        LeaderboardMetric::Xp => (score, "xp"),
+        LeaderboardMetric::Roi => (score * 100.0, "percent"),
+        LeaderboardMetric::Pnl => (cents_to_usd(score.round() as i64), "usd"),
+        LeaderboardMetric::Sharpe => (score, "ratio"),
+        LeaderboardMetric::Skill => (score, "rating"),
+    }
+}
+
+/// Ranked player on a leaderboard.
+#[derive(Serialize)]
+pub struct LeaderboardEntryResponse {
+    /// Position on the leaderboard. Ties share a rank.
+    pub rank: i32,
+    /// Player's display name.
+    pub username: Option<String>,
+    /// Player's wallet address.
+    pub wallet_address: String,
+    /// Score in `unit`.
+    pub score: f64,
+}
+
+/// Page of a leaderboard.
+#[derive(Serialize)]
+pub struct LeaderboardResponse {
+    /// What the leaderboard ranks by: "xp", "roi", "pnl", "sharpe" or "skill".
+    pub metric: &'static str,
+    /// Time window: "week", "season" or "all".
+    pub window: &'static str,
+    /// Unit of the scores: "xp", "percent", "usd", "ratio" or "rating".
+    pub unit: &'static str,
+    /// Players ranked on the leaderboard.
+    pub total: i64,
+    /// When the leaderboard was last computed, absent before the first run.
+    pub computed_at: Option<DateTime<Utc>>,
+    /// Entries, best first.
+    pub entries: Vec<LeaderboardEntryResponse>,
+}
+
+/// User's position on a leaderboard.
+#[derive(Serialize)]
+pub struct LeaderboardRankResponse {
+    /// What the leaderboard ranks by.
+    pub metric: &'static str,
+    /// Time window.
+    pub window: &'static str,
+    /// Unit of the score.
+    pub unit: &'static str,
+    /// Position on the leaderboard, absent when the user is not eligible.
+    pub rank: Option<i32>,
+    /// Score in `unit`, absent when the user is not eligible.
+    pub score: Option<f64>,
+    /// Players ranked on the leaderboard.
+    pub total: i64,
+    /// Share of ranked players the user is ahead of or tied with, in percent.
+    pub percentile: Option<f64>,
+    /// When the leaderboard was last computed.
+    pub computed_at: Option<DateTime<Utc>>,
+}
+
+// Tournament related types
+
+/// Request to create a tournament (admin only).
+#[derive(Deserialize, Validate)]
+pub struct CreateTournamentRequest {
+    /// Display name.
+    #[validate(length(min = 1, max = 100, message = "Name must be 1-100 characters"))]
+    pub name: String,
+    /// Rules or prize description.
+    #[validate(length(max = 2000, message = "Description must be at most 2000 characters"))]
+    pub description: Option<String>,
+    /// Paper cash every entrant starts with, in USD.
+    #[validate(range(min = 1.0, max = 100000000.0, message = "Starting balance must be between $1 and $100,000,000"))]
+    pub starting_balance: f64,
+    /// Symbols entrants may trade.
+    #[validate(length(min = 1, max = 50, message = "Between 1 and 50 symbols must be allowed"))]
+    pub allowed_symbols: Vec<String>,
+    /// Maximum number of entrants, unlimited when absent.
+    #[validate(range(min = 2, message = "A tournament needs room for at least 2 entrants"))]
+    pub max_participants: Option<i32>,
+    /// When registration opens.
+    pub registration_opens_at: DateTime<Utc>,
+    /// When registration closes. May be after the start to allow late entries.
+    pub registration_closes_at: DateTime<Utc>,
+    /// When trading starts.
+    pub starts_at: DateTime<Utc>,
+    /// When trading ends.
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Query parameters for listing tournaments.
+#[derive(Deserialize)]
+pub struct ListTournamentsQuery {
+    /// Include finalized tournaments. Defaults to false.
+    pub include_finalized: Option<bool>,
+}
+
+/// Tournament with its current phase.
+#[derive(Serialize)]
+pub struct TournamentResponse {
+    /// Unique tournament identifier.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Rules or prize description.
+    pub description: Option<String>,
+    /// Phase: "upcoming", "registration", "live", "ended" or "finalized".
+    pub phase: &'static str,
+    /// Paper cash every entrant starts with, in USD.
+    pub starting_balance: f64,
+    /// Symbols entrants may trade.
+    pub allowed_symbols: Vec<String>,
+    /// Entrants registered so far.
+    pub participants: i64,
+    /// Maximum number of entrants, absent for unlimited.
+    pub max_participants: Option<i32>,
+    /// When registration opens.
+    pub registration_opens_at: DateTime<Utc>,
+    /// When registration closes.
+    pub registration_closes_at: DateTime<Utc>,
+    /// When trading starts.
+    pub starts_at: DateTime<Utc>,
+    /// When trading ends.
+    pub ends_at: DateTime<Utc>,
+    /// When the final ranking was recorded.
+    pub finalized_at: Option<DateTime<Utc>>,
+}
+
+impl TournamentResponse {
+    /// Builds the response for a tournament's phase at `now`.
+    pub fn new(tournament: db::models::Tournament, participants: i64, now: DateTime<Utc>) -> Self {
+        Self {
+            id: tournament.id.to_string(),
+            phase: tournaments::phase(&tournament, now).as_str(),
+            name: tournament.name,
+            description: tournament.description,
+            starting_balance: cents_to_usd(tournament.starting_balance_cents),
+            allowed_symbols: tournament.allowed_symbols,
+            participants,
+            max_participants: tournament.max_participants,
+            registration_opens_at: tournament.registration_opens_at,
+            registration_closes_at: tournament.registration_closes_at,
+            starts_at: tournament.starts_at,
+            ends_at: tournament.ends_at,
+            finalized_at: tournament.finalized_at,
+        }
+    }
+}
+
+/// Query parameters for tournament standings.
+#[derive(Deserialize, Validate)]
+pub struct TournamentStandingsQuery {
+    /// Maximum number of standings, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+    /// Number of standings to skip, for paging.
+    #[validate(range(min = 0, message = "Offset cannot be negative"))]
+    pub offset: Option<i64>,
+}
+
+/// Entrant's place in a tournament.
+#[derive(Serialize)]
+pub struct TournamentStandingResponse {
+    /// Rank by account value. Ties share a rank.
+    pub rank: i64,
+    /// Entrant's display name.
+    pub username: Option<String>,
+    /// Entrant's wallet address.
+    pub wallet_address: String,
+    /// Account value in USD.
+    pub total_value: f64,
+    /// Return on the starting balance, in percent.
+    pub return_percent: f64,
+    /// Prize badge, once finalized.
+    pub badge: Option<String>,
+    /// XP granted for the finish, once finalized.
+    pub xp_awarded: i32,
+}
+
+impl TournamentStandingResponse {
+    /// Builds the response for a standing in a tournament with the given starting balance.
+    pub fn new(standing: db::queries::tournaments::TournamentStanding, starting_balance_cents: i64) -> Self {
+        Self {
+            rank: standing.rank,
+            username: standing.username,
+            wallet_address: standing.wallet_address,
+            total_value: cents_to_usd(standing.value_cents),
+            return_percent: return_percent(standing.value_cents, starting_balance_cents),
+            badge: standing.badge,
+            xp_awarded: standing.xp_awarded,
+        }
+    }
+}
+
+/// Returns the change from a starting value, in percent.
+fn return_percent(value_cents: i64, starting_cents: i64) -> f64 {
+    if starting_cents <= 0 {
+        return 0.0;
+    }
+    (value_cents - starting_cents) as f64 / starting_cents as f64 * 100.0
+}
+
+/// Fill within a tournament account.
+#[derive(Serialize)]
+pub struct TournamentTradeResponse {
+    /// Unique trade identifier.
+    pub id: String,
+    /// Trading symbol.
+    pub symbol: String,
+    /// Trade type: "buy" or "sell".
+    pub trade_type: String,
+    /// Number of tokens traded.
+    pub quantity: f64,
+    /// Fill price per token in USD, including slippage.
+    pub price: f64,
+    /// Quantity times price in USD, excluding fees.
+    pub total_value: f64,
+    /// Fee charged in USD.
+    pub fee: f64,
+    /// When the trade was executed.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<db::models::TournamentTrade> for TournamentTradeResponse {
+    fn from(trade: db::models::TournamentTrade) -> Self {
+        Self {
+            id: trade.id.to_string(),
+            symbol: trade.symbol,
+            trade_type: trade.trade_type,
+            quantity: micros_to_units(trade.quantity),
+            price: cents_to_usd(trade.price),
+            total_value: cents_to_usd(trade.total_value),
+            fee: cents_to_usd(trade.fee_cents),
+            timestamp: trade.executed_at,
+        }
+    }
+}
+
+/// User's account in a tournament.
+#[derive(Serialize)]
+pub struct TournamentAccountResponse {
+    /// Cash and positions valued at current prices.
+    pub portfolio: Portfolio,
+    /// Return on the starting balance, in percent.
+    pub return_percent: f64,
+    /// When the user registered.
+    pub joined_at: DateTime<Utc>,
+    /// Final rank, once finalized.
+    pub final_rank: Option<i32>,
+    /// Prize badge, once finalized.
+    pub badge: Option<String>,
+    /// XP granted for the finish.
+    pub xp_awarded: i32,
+    /// Most recent trades, newest first.
+    pub trades: Vec<TournamentTradeResponse>,
+}
+
+impl TournamentAccountResponse {
+    /// Builds the response for an account in a tournament with the given starting balance.
+    pub fn new(
+        account: TournamentAccount,
+        trades: Vec<db::models::TournamentTrade>,
+        starting_balance_cents: i64,
+        oracle: &PriceOracle,
+    ) -> Self {
+        let positions = account
+            .positions
+            .into_iter()
+            .map(|p| {
+                let price = oracle.price(&p.symbol).unwrap_or(p.average_price);
+                Position {
+                    current_value: cents_to_usd(notional_cents(p.quantity, price)),
+                    symbol: p.symbol,
+                    quantity: micros_to_units(p.quantity),
+                    avg_price: cents_to_usd(p.average_price),
+                }
+            })
+            .collect();
+
+        Self {
+            portfolio: Portfolio {
+                total_value: cents_to_usd(account.value_cents),
+                cash_balance: cents_to_usd(account.entry.cash_balance_cents),
+                positions,
+            },
+            return_percent: return_percent(account.value_cents, starting_balance_cents),
+            joined_at: account.entry.joined_at,
+            final_rank: account.entry.final_rank,
+            badge: account.entry.badge,
+            xp_awarded: account.entry.xp_awarded,
+            trades: trades.into_iter().map(TournamentTradeResponse::from).collect(),
+        }
+    }
+}
+
+// Duel related types
+
+/// Request to challenge another user to a duel.
+#[derive(Deserialize, Validate)]
+pub struct CreateDuelRequest {
+    /// Wallet address of the user to challenge.
+    #[validate(regex(path = "WALLET_ADDRESS_REGEX", message = "Invalid wallet address format"))]
+    pub opponent_wallet: String,
+    /// Paper cash both players start with in USD. Defaults to $10,000.
+    #[validate(range(min = 1.0, max = 100000000.0, message = "Starting balance must be between $1 and $100,000,000"))]
+    pub starting_balance: Option<f64>,
+    /// Symbols both players may trade. Defaults to every active asset.
+    #[validate(length(max = 50, message = "At most 50 symbols can be allowed"))]
+    pub allowed_symbols: Option<Vec<String>>,
+    /// Trading time once accepted, in hours. Defaults to 24.
+    #[validate(range(min = 1, max = 168, message = "Duel length must be between 1 and 168 hours"))]
+    pub duration_hours: Option<i32>,
+    /// XP each player puts up. The winner takes both stakes. Defaults to 0.
+    #[validate(range(min = 0, max = 1000, message = "Stake must be between 0 and 1000 XP"))]
+    pub stake_xp: Option<i32>,
+}
+
+/// Query parameters for listing the user's duels.
+#[derive(Deserialize, Validate)]
+pub struct ListDuelsQuery {
+    /// Only duels with this status: "pending", "declined", "expired", "active" or "settled".
+    pub status: Option<String>,
+    /// Maximum number of duels, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+    /// Number of duels to skip, for paging.
+    #[validate(range(min = 0, message = "Offset cannot be negative"))]
+    pub offset: Option<i64>,
+}
+
+/// Player in a duel with their score once trading has started.
+#[derive(Serialize)]
+pub struct DuelPlayerResponse {
+    /// Player's user id.
+    pub user_id: String,
+    /// Player's display name.
+    pub username: Option<String>,
+    /// Player's wallet address.
+    pub wallet_address: String,
+    /// Account value in USD: live while active, final once settled.
+    pub total_value: Option<f64>,
+    /// Return on the starting balance, in percent.
+    pub return_percent: Option<f64>,
+}
+
+/// Duel with both players and the score comparison.
+#[derive(Serialize)]
+pub struct DuelResponse {
+    /// Unique duel identifier.
+    pub id: String,
+    /// Status: "pending", "declined", "expired", "active" or "settled".
+    pub status: String,
+    /// Player who issued the challenge.
+    pub challenger: DuelPlayerResponse,
+    /// Player who was challenged.
+    pub opponent: DuelPlayerResponse,
+    /// Paper cash both players start with, in USD.
+    pub starting_balance: f64,
+    /// Symbols both players may trade.
+    pub allowed_symbols: Vec<String>,
+    /// Trading time once accepted, in hours.
+    pub duration_hours: i32,
+    /// XP each player puts up.
+    pub stake_xp: i32,
+    /// Who is ahead: "challenger", "opponent" or "tied". Absent before trading starts.
+    pub leader: Option<&'static str>,
+    /// Winner's user id once settled, absent for a draw.
+    pub winner_id: Option<String>,
+    /// When the challenge was issued.
+    pub created_at: DateTime<Utc>,
+    /// When the challenge expires if unanswered.
+    pub expires_at: DateTime<Utc>,
+    /// When trading started.
+    pub starts_at: Option<DateTime<Utc>>,
+    /// When trading ends.
+    pub ends_at: Option<DateTime<Utc>>,
+    /// When the stakes were paid out.
+    pub settled_at: Option<DateTime<Utc>>,
+}
+
+impl DuelResponse {
+    /// Builds the response from a duel, its players' profiles and the current or final score.
+    pub fn new(duel: db::models::Duel, players: &[DuelPlayer], score: Option<DuelScore>) -> Self {
+        let starting = duel.starting_balance_cents;
+        let player = |user_id: Uuid, value_cents: Option<i64>| {
+            let profile = players.iter().find(|p| p.user_id == user_id);
+            DuelPlayerResponse {
+                user_id: user_id.to_string(),
+                username: profile.and_then(|p| p.username.clone()),
+                wallet_address: profile.map(|p| p.wallet_address.clone()).unwrap_or_default(),
+                total_value: value_cents.map(cents_to_usd),
+                return_percent: value_cents.map(|value| return_percent(value, starting)),
+            }
+        };
+        let leader = score.map(|s| match game::DuelResult::decide(s.challenger_value_cents, s.opponent_value_cents) {
+            game::DuelResult::ChallengerWon => "challenger",
+            game::DuelResult::OpponentWon => "opponent",
+            game::DuelResult::Draw => "tied",
+        });
+
+        Self {
+            id: duel.id.to_string(),
+            challenger: player(duel.challenger_id, score.map(|s| s.challenger_value_cents)),
+            opponent: player(duel.opponent_id, score.map(|s| s.opponent_value_cents)),
+            status: duel.status,
+            starting_balance: cents_to_usd(starting),
+            allowed_symbols: duel.allowed_symbols,
+            duration_hours: duel.duration_hours,
+            stake_xp: duel.stake_xp,
+            leader,
+            winner_id: duel.winner_id.map(|id| id.to_string()),
+            created_at: duel.created_at,
+            expires_at: duel.expires_at,
+            starts_at: duel.starts_at,
+            ends_at: duel.ends_at,
+            settled_at: duel.settled_at,
+        }
+    }
+}
+
+/// Fill within a duel account.
+#[derive(Serialize)]
+pub struct DuelTradeResponse {
+    /// Unique trade identifier.
+    pub id: String,
+    /// Trading symbol.
+    pub symbol: String,
+    /// Trade type: "buy" or "sell".
+    pub trade_type: String,
+    /// Number of tokens traded.
+    pub quantity: f64,
+    /// Fill price per token in USD, including slippage.
+    pub price: f64,
+    /// Quantity times price in USD, excluding fees.
+    pub total_value: f64,
+    /// Fee charged in USD.
+    pub fee: f64,
+    /// When the trade was executed.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<db::models::DuelTrade> for DuelTradeResponse {
+    fn from(trade: db::models::DuelTrade) -> Self {
+        Self {
+            id: trade.id.to_string(),
+            symbol: trade.symbol,
+            trade_type: trade.trade_type,
+            quantity: micros_to_units(trade.quantity),
+            price: cents_to_usd(trade.price),
+            total_value: cents_to_usd(trade.total_value),
+            fee: cents_to_usd(trade.fee_cents),
+            timestamp: trade.executed_at,
+        }
+    }
+}
+
+/// User's account in a duel.
+#[derive(Serialize)]
+pub struct DuelAccountResponse {
+    /// Cash and positions valued at current prices.
+    pub portfolio: Portfolio,
+    /// Return on the starting balance, in percent.
+    pub return_percent: f64,
+    /// Most recent trades, newest first.
+    pub trades: Vec<DuelTradeResponse>,
+}
+
+impl DuelAccountResponse {
+    /// Builds the response for an account in a duel with the given starting balance.
+    pub fn new(
+        view: DuelAccountView,
+        trades: Vec<db::models::DuelTrade>,
+        starting_balance_cents: i64,
+        oracle: &PriceOracle,
+    ) -> Self {
+        let positions = view
+            .positions
+            .into_iter()
+            .map(|p| {
+                let price = oracle.price(&p.symbol).unwrap_or(p.average_price);
+                Position {
+                    current_value: cents_to_usd(notional_cents(p.quantity, price)),
+                    symbol: p.symbol,
+                    quantity: micros_to_units(p.quantity),
+                    avg_price: cents_to_usd(p.average_price),
+                }
+            })
+            .collect();
+
+        Self {
+            portfolio: Portfolio {
+                total_value: cents_to_usd(view.value_cents),
+                cash_balance: cents_to_usd(view.account.cash_balance_cents),
+                positions,
+            },
+            return_percent: return_percent(view.value_cents, starting_balance_cents),
+            trades: trades.into_iter().map(DuelTradeResponse::from).collect(),
+        }
+    }
+}
+
+/// User's settled duel history.
+#[derive(Serialize)]
+pub struct DuelRecordResponse {
+    /// Duels won.
+    pub wins: i64,
+    /// Duels lost.
+    pub losses: i64,
+    /// Duels drawn.
+    pub draws: i64,
+    /// Share of settled duels won, from 0.0 to 1.0.
+    pub win_rate: f64,
+    /// XP won minus XP lost through stakes.
+    pub net_xp: i64,
+}
+
+impl From<DuelRecord> for DuelRecordResponse {
+    fn from(record: DuelRecord) -> Self {
+        let settled = record.wins + record.losses + record.draws;
+        Self {
+            wins: record.wins,
+            losses: record.losses,
+            draws: record.draws,
+            win_rate: if settled > 0 { record.wins as f64 / settled as f64 } else { 0.0 },
+            net_xp: record.net_xp,
+        }
+    }
+}
+
+// Skill rating related types
+
+/// Player's skill rating.
+#[derive(Serialize)]
+pub struct RatingResponse {
+    /// Estimated skill on the Glicko scale.
+    pub rating: f64,
+    /// Uncertainty of the rating, grown for idle periods.
+    pub deviation: f64,
+    /// Expected fluctuation in performance.
+    pub volatility: f64,
+    /// Lower bound of the 95% confidence interval, used for the skill leaderboard.
+    pub conservative_rating: f64,
+    /// Rated games played.
+    pub games: i32,
+    /// Whether too few games have been rated to appear on the skill leaderboard.
+    pub provisional: bool,
+    /// When the rating last changed, absent for unrated players.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl RatingResponse {
+    /// Builds the response from a current rating and the stored row it came from, if any.
+    pub fn new(rating: game::Rating, stored: Option<&db::models::PlayerRating>) -> Self {
+        let games = stored.map(|stored| stored.games).unwrap_or(0);
+        Self {
+            rating: rating.rating,
+            deviation: rating.deviation,
+            volatility: rating.volatility,
+            conservative_rating: rating.conservative(),
+            games,
+            provisional: i64::from(games) < game::ratings::MIN_RATED_GAMES,
+            updated_at: stored.map(|stored| stored.updated_at),
+        }
+    }
+}
+
+/// Query parameters for a player's rating history.
+#[derive(Deserialize, Validate)]
+pub struct RatingHistoryQuery {
+    /// Maximum number of updates, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+}
+
+/// Rating update from a duel or tournament.
+#[derive(Serialize)]
+pub struct RatingEventResponse {
+    /// What was rated: "duel" or "tournament".
+    pub source: String,
+    /// Duel or tournament that was rated.
+    pub reference_id: String,
+    /// Head-to-head results in the update.
+    pub games: i32,
+    /// Points scored: 1 per win, 0.5 per draw.
+    pub score: f64,
+    /// Points expected from the ratings beforehand.
+    pub expected_score: f64,
+    /// Rating before the update.
+    pub rating_before: f64,
+    /// Rating after the update.
+    pub rating_after: f64,
+    /// Change in rating.
+    pub change: f64,
+    /// Deviation after the update.
+    pub deviation: f64,
+    /// When the update was applied.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<db::models::RatingEvent> for RatingEventResponse {
+    fn from(event: db::models::RatingEvent) -> Self {
+        Self {
+            source: event.source,
+            reference_id: event.reference_id.to_string(),
+            games: event.games,
+            score: event.score,
+            expected_score: event.expected_score,
+            rating_before: event.rating_before,
+            rating_after: event.rating_after,
+            change: event.rating_after - event.rating_before,
+            deviation: event.deviation_after,
+            timestamp: event.created_at,
+        }
+    }
+}
+
+/// Query parameters for matchmaking.
+#[derive(Deserialize, Validate)]
+pub struct MatchmakingQuery {
+    /// Maximum number of candidates, defaults to 10.
+    #[validate(range(min = 1, max = 50, message = "Limit must be between 1 and 50"))]
+    pub limit: Option<i64>,
+}
+
+/// Suggested duel opponent.
+#[derive(Serialize)]
+pub struct MatchCandidateResponse {
+    /// Candidate's user id.
+    pub user_id: String,
+    /// Candidate's display name.
+    pub username: Option<String>,
+    /// Candidate's wallet address, used to challenge them.
+    pub wallet_address: String,
+    /// Candidate's rating.
+    pub rating: f64,
+    /// Candidate's rating deviation.
+    pub deviation: f64,
+    /// Probability of beating the candidate, from 0.0 to 1.0.
+    pub win_probability: f64,
+    /// How balanced the pairing is, from 0.0 (one-sided) to 1.0 (a coin flip).
+    pub match_quality: f64,
+}
+
+/// User's rating with suggested opponents, closest rating first.
+#[derive(Serialize)]
+pub struct MatchmakingResponse {
+    /// User's current rating.
+    pub rating: f64,
+    /// Suggested opponents.
+    pub candidates: Vec<MatchCandidateResponse>,
+}
+
+// League related types
+
+/// League division.
+#[derive(Serialize)]
+pub struct LeagueDivisionResponse {
+    /// Position in the ladder, 0 being the lowest.
+    pub tier: i16,
+    /// Display name.
+    pub name: String,
+}
+
+impl LeagueDivisionResponse {
+    /// Builds the response for the division at a tier.
+    pub fn new(tier: i16) -> Self {
+        let division = game::division(tier);
+        Self {
+            tier: division.tier,
+            name: division.name.to_string(),
+        }
+    }
+}
+
+/// Player's row in a league table.
+#[derive(Serialize)]
+pub struct LeagueStandingResponse {
+    /// Rank by score. Ties share a rank.
+    pub rank: i64,
+    /// Player's user id.
+    pub user_id: String,
+    /// Player's display name.
+    pub username: Option<String>,
+    /// Player's wallet address.
+    pub wallet_address: String,
+    /// XP earned or return this week, absent until the player is active.
+    pub score: Option<f64>,
+    /// Where the player would go if the week ended now, or went once finalized:
+    /// "promoted", "stayed" or "relegated".
+    pub outcome: String,
+    /// Whether this row is the requesting user.
+    pub is_you: bool,
+}
+
+/// Table of the user's league group this week.
+#[derive(Serialize)]
+pub struct LeagueTableResponse {
+    /// Group identifier.
+    pub group_id: String,
+    /// Division the group plays in.
+    pub division: LeagueDivisionResponse,
+    /// What players are ranked by: "xp" or "return".
+    pub metric: String,
+    /// When the league week started.
+    pub week_start: DateTime<Utc>,
+    /// When the league week ends and promotions and relegations are applied.
+    pub ends_at: DateTime<Utc>,
+    /// Top finishers promoted.
+    pub promotion_slots: i64,
+    /// Bottom finishers relegated.
+    pub relegation_slots: i64,
+    /// Players best first.
+    pub standings: Vec<LeagueStandingResponse>,
+}
+
+impl LeagueTableResponse {
+    /// Builds the table for a user, projecting outcomes until the group is finalized.
+    pub fn new(table: LeagueTable, user_id: Uuid) -> Self {
+        let group = table.group;
+        let members = table.standings.len() as i64;
+        let standings = table
+            .standings
+            .into_iter()
+            .map(|standing| {
+                let outcome = standing.outcome.unwrap_or_else(|| {
+                    game::league_outcome(group.division, standing.rank, members, standing.score)
+                        .as_str()
+                        .to_string()
+                });
+                LeagueStandingResponse {
+                    rank: standing.rank,
+                    user_id: standing.user_id.to_string(),
+                    username: standing.username,
+                    wallet_address: standing.wallet_address,
+                    score: standing.score,
+                    outcome,
+                    is_you: standing.user_id == user_id,
+                }
+            })
+            .collect();
+
+        Self {
+            group_id: group.id.to_string(),
+            division: LeagueDivisionResponse::new(group.division),
+            metric: group.metric,
+            week_start: group.week_start,
+            ends_at: leagues::week_end(group.week_start),
+            promotion_slots: game::leagues::PROMOTION_SLOTS,
+            relegation_slots: game::leagues::RELEGATION_SLOTS,
+            standings,
+        }
+    }
+}
+
+/// Query parameters for a player's league history.
+#[derive(Deserialize, Validate)]
+pub struct LeagueHistoryQuery {
+    /// Maximum number of weeks, defaults to 20.
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<i64>,
+}
+
+/// Finished league week of the user.
+#[derive(Serialize)]
+pub struct LeagueHistoryResponse {
+    /// Group the week was played in.
+    pub group_id: String,
+    /// When the league week started.
+    pub week_start: DateTime<Utc>,
+    /// Division the week was played in.
+    pub division: LeagueDivisionResponse,
+    /// What players were ranked by: "xp" or "return".
+    pub metric: String,
+    /// Final XP or return, absent if the user was not active.
+    pub score: Option<f64>,
+    /// Final rank in the group.
+    pub rank: Option<i32>,
+    /// Players in the group.
+    pub members: i64,
+    /// "promoted", "stayed" or "relegated".
+    pub outcome: Option<String>,
+    /// Division the user moved on to.
+    pub next_division: Option<LeagueDivisionResponse>,
+}
+
+impl From<LeagueHistoryRow> for LeagueHistoryResponse {
+    fn from(row: LeagueHistoryRow) -> Self {
+        Self {
+            group_id: row.member.group_id.to_string(),
+            week_start: row.member.week_start,
+            division: LeagueDivisionResponse::new(row.division),
+            metric: row.metric,
+            score: row.member.score,
+            rank: row.member.final_rank,
+            members: row.members,
+            outcome: row.member.outcome,
+            next_division: row.member.next_division.map(LeagueDivisionResponse::new),
+        }
+    }
+}
+
+// User profile related types
+
+/// User's activity streak as of today in their time zone.
+#[derive(Serialize)]
+pub struct StreakResponse {
+    /// Consecutive active days, 0 once the streak can no longer be continued.
+    pub current_days: i32,
+    /// Longest streak reached.
+    pub longest_days: i32,
+    /// Whether the user has signed in or traded today.
+    pub active_today: bool,
+    /// Whether the streak ends unless the user is active today or holds a freeze.
+    pub at_risk: bool,
+    /// Freezes held, each covering one missed day.
+    pub freezes: i32,
+    /// Freezes the days missed so far will use up on the next activity.
+    pub freezes_pending: i32,
+    /// Freezes spent so far.
+    pub freezes_used: i32,
+    /// Most freezes that can be held at once.
+    pub max_freezes: i32,
+    /// XP multiplier earned by the streak, e.g. 1.25.
+    pub xp_multiplier: f64,
+    /// Streak length that raises the multiplier next, absent at the highest multiplier.
+    pub next_multiplier_days: Option<i32>,
+    /// Multiplier reached at `next_multiplier_days`.
+    pub next_xp_multiplier: Option<f64>,
+    /// Latest active day in the user's time zone.
+    pub last_active_date: Option<NaiveDate>,
+    /// IANA time zone days are counted in.
+    pub time_zone: String,
+}
+
+impl StreakResponse {
+    /// Builds the response from a stored streak as of `now`; users without one get an empty streak.
+    pub fn new(stored: Option<&db::models::ActivityStreak>, now: DateTime<Utc>) -> Self {
+        let time_zone = stored.map(|stored| stored.time_zone.clone()).unwrap_or_else(|| "UTC".to_string());
+        let streak = stored.map(streaks::game_streak).unwrap_or_default();
+        let today = streaks::day_number(streaks::local_day(&time_zone, now));
+        let current_days = streak.days_as_of(today);
+        // The last active day can be ahead of today right after moving to an earlier time zone
+        let active_today = streak.last_active_day.is_some_and(|last| last >= today);
+        let next = game::streaks::next_streak_multiplier(current_days);
+
+        Self {
+            current_days,
+            longest_days: streak.longest_days,
+            active_today,
+            at_risk: current_days > 0 && !active_today && streak.missed_days(today + 1) > i64::from(streak.freezes),
+            freezes: streak.freezes,
+            freezes_pending: if current_days > 0 { streak.missed_days(today) as i32 } else { 0 },
+            freezes_used: stored.map(|stored| stored.freezes_used).unwrap_or(0),
+            max_freezes: game::streaks::MAX_STREAK_FREEZES,
+            xp_multiplier: f64::from(game::streak_multiplier_percent(current_days)) / 100.0,
+            next_multiplier_days: next.map(|(days, _)| days),
+            next_xp_multiplier: next.map(|(_, percent)| f64::from(percent) / 100.0),
+            last_active_date: stored.and_then(|stored| stored.last_active_date),
+            time_zone,
+        }
+    }
+}
+
+/// Authenticated user's profile.
+#[derive(Serialize)]
+pub struct ProfileResponse {
+    /// User's unique identifier.
+    pub user_id: String,
+    /// User's wallet address.
+    pub wallet_address: String,
+    /// User's display name, if set.
+    pub username: Option<String>,
+    /// Current XP points.
+    pub xp_points: i32,
+    /// Current level.
+    pub level: i16,
+    /// Portfolio value in USD.
+    pub portfolio_value: f64,
+    /// Available cash balance in USD.
+    pub cash_balance: f64,
+    /// Daily activity streak.
+    pub streak: StreakResponse,
+    /// When the account was created.
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProfileResponse {
+    /// Builds the profile of a user with their streak as of `now`.
+    pub fn new(user: db::models::User, streak: Option<&db::models::ActivityStreak>, now: DateTime<Utc>) -> Self {
+        Self {
+            user_id: user.id.to_string(),
+            wallet_address: user.wallet_address,
+            username: user.username,
+            xp_points: user.xp_points,
+            level: user.level,
+            portfolio_value: cents_to_usd(user.portfolio_value_cents),
+            cash_balance: cents_to_usd(user.cash_balance_cents),
+            streak: StreakResponse::new(streak, now),
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// Request to update the authenticated user's profile settings.
+#[derive(Deserialize, Validate)]
+pub struct UpdateProfileRequest {
+    /// IANA time zone streak days are counted in, e.g. "Europe/Berlin".
+    #[validate(length(min = 1, max = 64, message = "Time zone must be between 1 and 64 characters"))]
+    pub time_zone: Option<String>,
+}
+
+// Clan related types
+
+/// Request to found a clan.
+#[derive(Deserialize, Validate)]
+pub struct CreateClanRequest {
+    /// Display name, unique ignoring case.
+    #[validate(length(min = 3, max = 50, message = "Name must be 3-50 characters"))]
+    pub name: String,
+    /// Short tag of 2-5 letters or digits, stored uppercase.
+    #[validate(length(min = 2, max = 5, message = "Tag must be 2-5 characters"))]
+    pub tag: String,
+    /// Description shown on the clan page.
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+}
+
+/// Query parameters for listing clans.
+#[derive(Deserialize, Validate)]
+pub struct ListClansQuery {
+    /// Matches the name or tag, ignoring case.
+    #[validate(length(min = 1, max = 50, message = "Search must be 1-50 characters"))]
+    pub search: Option<String>,
+    /// Maximum number of clans, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+    /// Number of clans to skip, for paging.
+    #[validate(range(min = 0, message = "Offset cannot be negative"))]
+    pub offset: Option<i64>,
+}
+
+/// Request to join a clan.
+#[derive(Deserialize, Validate)]
+pub struct JoinClanRequest {
+    /// Invite code shared by the clan's owner or an officer.
+    #[validate(length(min = 1, max = 16, message = "Invite code must be 1-16 characters"))]
+    pub invite_code: String,
+}
+
+/// Request to change a member's role.
+#[derive(Deserialize)]
+pub struct SetClanRoleRequest {
+    /// "owner", "officer" or "member". Making someone the owner hands over ownership.
+    pub role: String,
+}
+
+/// Clan summary.
+#[derive(Serialize)]
+pub struct ClanResponse {
+    /// Unique clan identifier.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Short uppercase tag.
+    pub tag: String,
+    /// Description shown on the clan page.
+    pub description: Option<String>,
+    /// Current members.
+    pub members: i64,
+    /// Most members the clan can have.
+    pub max_members: i64,
+    /// When the clan was founded.
+    pub created_at: DateTime<Utc>,
+}
+
+impl ClanResponse {
+    /// Builds the summary of a clan with its member count.
+    pub fn new(clan: db::models::Clan, members: i64) -> Self {
+        Self {
+            id: clan.id.to_string(),
+            name: clan.name,
+            tag: clan.tag,
+            description: clan.description,
+            members,
+            max_members: game::clans::MAX_CLAN_MEMBERS,
+            created_at: clan.created_at,
+        }
+    }
+}
+
+/// Clan member.
+#[derive(Serialize)]
+pub struct ClanMemberResponse {
+    /// Member's unique identifier.
+    pub user_id: String,
+    /// Member's display name.
+    pub username: Option<String>,
+    /// Member's wallet address.
+    pub wallet_address: String,
+    /// "owner", "officer" or "member".
+    pub role: String,
+    /// Member's lifetime XP.
+    pub xp_points: i32,
+    /// When the member joined.
+    pub joined_at: DateTime<Utc>,
+}
+
+impl From<db::queries::clans::ClanMemberRow> for ClanMemberResponse {
+    fn from(row: db::queries::clans::ClanMemberRow) -> Self {
+        Self {
+            user_id: row.member.user_id.to_string(),
+            username: row.username,
+            wallet_address: row.wallet_address,
+            role: row.member.role,
+            xp_points: row.xp_points,
+            joined_at: row.member.joined_at,
+        }
+    }
+}
+
+/// Converts a stored clan score to the unit shown for its metric.
+/// Returns the display value and unit: "xp" or "percent".
+pub fn clan_score_display(metric: game::ClanMetric, score: f64) -> (f64, &'static str) {
+    match metric {
+        game::ClanMetric::Xp => (score, "xp"),
+        game::ClanMetric::Return => (score * 100.0, "percent"),
+    }
+}
+
+/// Clan's position on one leaderboard.
+#[derive(Serialize)]
+pub struct ClanRankResponse {
+    /// What the leaderboard ranks by: "xp" or "return".
+    pub metric: String,
+    /// Time window: "week", "season" or "all".
+    pub window: String,
+    /// Unit of the score.
+    pub unit: &'static str,
+    /// Position on the leaderboard. Ties share a rank.
+    pub rank: i32,
+    /// Score in `unit`.
+    pub score: f64,
+}
+
+impl From<db::models::ClanLeaderboardEntry> for ClanRankResponse {
+    fn from(entry: db::models::ClanLeaderboardEntry) -> Self {
+        let metric = game::ClanMetric::parse(&entry.metric).unwrap_or(game::ClanMetric::Xp);
+        let (score, unit) = clan_score_display(metric, entry.score);
+        Self {
+            metric: entry.metric,
+            window: entry.period,
+            unit,
+            rank: entry.rank,
+            score,
+        }
+    }
+}
+
+/// Clan page with its members and leaderboard positions.
+#[derive(Serialize)]
+pub struct ClanDetailsResponse {
+    /// Clan summary.
+    pub clan: ClanResponse,
+    /// Members, owner and officers first.
+    pub members: Vec<ClanMemberResponse>,
+    /// Positions on the clan leaderboards the clan is ranked on.
+    pub ranks: Vec<ClanRankResponse>,
+    /// Authenticated user's role, present on their own clan.
+    pub my_role: Option<String>,
+    /// Invite code, shown to the owner and officers only.
+    pub invite_code: Option<String>,
+}
+
+/// Result of leaving a clan.
+#[derive(Serialize)]
+pub struct LeaveClanResponse {
+    /// Clan left.
+    pub clan_id: String,
+    /// Whether the clan was deleted because its last member left.
+    pub disbanded: bool,
+    /// Member who became the owner when the owner left.
+    pub new_owner_id: Option<String>,
+}
+
+/// Query parameters for a clan leaderboard page.
+#[derive(Deserialize, Validate)]
+pub struct ClanLeaderboardQuery {
+    /// Time window: "week", "season" or "all". Defaults to "season".
+    pub window: Option<String>,
+    /// Maximum number of entries, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+    /// Number of entries to skip, for paging.
+    #[validate(range(min = 0, message = "Offset cannot be negative"))]
+    pub offset: Option<i64>,
+}
+
+/// Ranked clan on a clan leaderboard.
+#[derive(Serialize)]
+pub struct ClanLeaderboardEntryResponse {
+    /// Position on the leaderboard. Ties share a rank.
+    pub rank: i32,
+    /// Clan's unique identifier.
+    pub clan_id: String,
+    /// Clan's display name.
+    pub name: String,
+    /// Clan's tag.
+    pub tag: String,
+    /// Members when the leaderboard was computed.
+    pub members: i32,
+    /// Score in `unit`.
+    pub score: f64,
+}
+
+/// Page of a clan leaderboard.
+#[derive(Serialize)]
+pub struct ClanLeaderboardResponse {
+    /// What the leaderboard ranks by: "xp" or "return".
+    pub metric: &'static str,
+    /// Time window: "week", "season" or "all".
+    pub window: &'static str,
+    /// Unit of the scores: "xp" or "percent".
+    pub unit: &'static str,
+    /// Clans ranked on the leaderboard.
+    pub total: i64,
+    /// When the leaderboard was last computed, absent before the first run.
+    pub computed_at: Option<DateTime<Utc>>,
+    /// Entries, best first.
+    pub entries: Vec<ClanLeaderboardEntryResponse>,
+}
+
+/// Clan's entry into a tournament.
+#[derive(Serialize)]
+pub struct TournamentClanResponse {
+    /// Tournament entered.
+    pub tournament_id: String,
+    /// Clan entered.
+    pub clan_id: String,
+    /// When the clan was entered.
+    pub entered_at: DateTime<Utc>,
+}
+
+impl From<db::models::TournamentClan> for TournamentClanResponse {
+    fn from(entry: db::models::TournamentClan) -> Self {
+        Self {
+            tournament_id: entry.tournament_id.to_string(),
+            clan_id: entry.clan_id.to_string(),
+            entered_at: entry.entered_at,
+        }
+    }
+}
+
+/// Clan's place in a tournament's clan standings.
+#[derive(Serialize)]
+pub struct TournamentClanStandingResponse {
+    /// Rank by mean entrant account value. Ties share a rank; clans without entrants come last.
+    pub rank: i64,
+    /// Clan's unique identifier.
+    pub clan_id: String,
+    /// Clan's display name.
+    pub name: String,
+    /// Clan's tag.
+    pub tag: String,
+    /// Members playing for the clan.
+    pub entrants: i64,
+    /// Mean account value of the entrants in USD, absent without entrants.
+    pub average_value: Option<f64>,
+    /// Mean return on the starting balance in percent, absent without entrants.
+    pub return_percent: Option<f64>,
+}
+
+impl TournamentClanStandingResponse {
+    /// Builds the response for a clan standing in a tournament with the given starting balance.
+    pub fn new(standing: db::queries::clans::ClanStanding, starting_balance_cents: i64) -> Self {
+        Self {
+            rank: standing.rank,
+            clan_id: standing.clan_id.to_string(),
+            name: standing.name,
+            tag: standing.tag,
+            entrants: standing.entrants,
+            average_value: standing.value_cents.map(cents_to_usd),
+            return_percent: standing
+                .value_cents
+                .map(|value_cents| return_percent(value_cents, starting_balance_cents)),
+        }
+    }
+}
+
+// Season related types
+
+/// Season summary.
+#[derive(Serialize)]
+pub struct SeasonResponse {
+    /// Sequential season number, used to look the season up.
+    pub number: i32,
+    /// Display name.
+    pub name: String,
+    /// When the season started.
+    pub starts_at: DateTime<Utc>,
+    /// When the season ends or is scheduled to end.
+    pub ends_at: DateTime<Utc>,
+    /// When the season rolled over, absent for the current season.
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Whether this is the current season.
+    pub current: bool,
+    /// Players in the final standings, 0 for the current season.
+    pub participant_count: i32,
+}
+
+impl From<db::models::Season> for SeasonResponse {
+    fn from(season: db::models::Season) -> Self {
+        Self {
+            number: season.number,
+            name: season.name,
+            starts_at: season.starts_at,
+            ends_at: season.ends_at,
+            closed_at: season.closed_at,
+            current: season.closed_at.is_none(),
+            participant_count: season.participant_count,
+        }
+    }
+}
+
+/// Query parameters for a season's final standings.
+#[derive(Deserialize, Validate)]
+pub struct SeasonStandingsQuery {
+    /// Maximum number of standings, defaults to 50.
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1 and 200"))]
+    pub limit: Option<i64>,
+    /// Number of standings to skip, for paging.
+    #[validate(range(min = 0, message = "Offset cannot be negative"))]
+    pub offset: Option<i64>,
+}
+
+/// Player's final standing in a closed season.
+#[derive(Serialize)]
+pub struct SeasonStandingResponse {
+    /// Rank by seasonal XP. Ties share a rank.
+    pub rank: i32,
+    /// Player's display name.
+    pub username: Option<String>,
+    /// Player's wallet address.
+    pub wallet_address: Option<String>,
+    /// Seasonal XP at the rollover.
+    pub xp: i32,
+    /// Seasonal level at the rollover.
+    pub level: i16,
+    /// Rank by final portfolio value.
+    pub portfolio_rank: i32,
+    /// Final portfolio value in USD.
+    pub portfolio_value: f64,
+    /// Return on the starting balance, in percent.
+    pub return_percent: f64,
+    /// Season reward earned, e.g. "Champion".
+    pub reward_title: Option<String>,
+    /// XP granted in the next season for the finish.
+    pub reward_xp: i32,
+}
+
+impl From<db::models::SeasonStanding> for SeasonStandingResponse {
+    fn from(standing: db::models::SeasonStanding) -> Self {
+        Self {
+            rank: standing.xp_rank,
+            username: None,
+            wallet_address: None,
+            xp: standing.xp_points,
+            level: standing.level,
+            portfolio_rank: standing.portfolio_rank,
+            portfolio_value: cents_to_usd(standing.portfolio_value_cents),
+            return_percent: standing.return_bps as f64 / 100.0,
+            reward_title: standing.reward_title,
+            reward_xp: standing.reward_xp,
+        }
+    }
+}
+
+impl From<db::queries::seasons::SeasonStandingEntry> for SeasonStandingResponse {
+    fn from(entry: db::queries::seasons::SeasonStandingEntry) -> Self {
+        Self {
+            username: entry.username,
+            wallet_address: Some(entry.wallet_address),
+            ..Self::from(entry.standing)
+        }
+    }
+}
+
+/// Season with a page of its final standings.
+#[derive(Serialize)]
+pub struct SeasonDetailResponse {
+    /// Season summary.
+    pub season: SeasonResponse,
+    /// Final standings, best first. Empty for the current season.
+    pub standings: Vec<SeasonStandingResponse>,
+}
+
+/// Request to roll the current season over (admin only).
+#[derive(Deserialize, Validate)]
+pub struct SeasonRolloverRequest {
+    /// Name of the new season, defaults to "Season N".
+    #[validate(length(min = 1, max = 50, message = "Name must be 1-50 characters"))]
+    pub name: Option<String>,
+    /// Length of the new season in days, defaults to the configured season length.
+    #[validate(range(min = 1, max = 365, message = "Length must be between 1 and 365 days"))]
+    pub length_days: Option<i64>,
+}
+
+/// Result of a season rollover.
+#[derive(Serialize)]
+pub struct SeasonRolloverResponse {
+    /// Season that was closed.
+    pub closed: SeasonResponse,
+    /// Season that started.
+    pub started: SeasonResponse,
+    /// Players who earned a finish reward.
+    pub rewarded: usize,
+}
+
 // Generic API response wrapper
 
 /// Standardized API response wrapper.
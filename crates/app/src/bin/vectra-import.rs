@@ -0,0 +1,403 @@
+//! Historical price importer for Vectra DEX.
+//! Loads OHLCV candles for a symbol from CSV or Parquet files into the candles table,
+//! so the oracle can replay real market history (see ORACLE_REPLAY_FROM).
+//!
+//! Usage:
+//!   vectra-import --symbol BTC --file btc-1m.csv [--interval 1m] [--format csv|parquet] [--strict] [--dry-run]
+//!
+//! Files need a timestamp column (`timestamp`, `time`, `open_time`, `date` or `datetime`;
+//! RFC 3339, `YYYY-MM-DD HH:MM:SS`, unix seconds or unix milliseconds) and `open`, `high`,
+//! `low`, `close` columns in USD. A `volume` column in tokens is optional.
+//! Re-running an import overwrites the same candles, so imports are idempotent.
+
+use std::error::Error;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use api::services::market::{rollup_coarser_candles, CandleInterval};
+use api::types::{units_to_micros, usd_to_cents};
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use db::queries::{assets, market::{self, CandleBatch}};
+use db::{create_pool, DatabaseConfig};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+
+/// Number of candles written per database round trip.
+const BATCH_SIZE: usize = 1000;
+
+/// Maximum number of individual problems printed per category.
+const MAX_REPORTED: usize = 10;
+
+/// Accepted names of the timestamp column.
+const TIMESTAMP_COLUMNS: [&str; 5] = ["timestamp", "time", "open_time", "date", "datetime"];
+
+/// Input file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileFormat {
+    Csv,
+    Parquet,
+}
+
+/// Parsed command line arguments.
+#[derive(Debug)]
+struct ImportArgs {
+    /// Symbol the candles belong to.
+    symbol: String,
+    /// File to import.
+    file: PathBuf,
+    /// Timeframe of the candles in the file.
+    interval: CandleInterval,
+    /// Format of the file.
+    format: FileFormat,
+    /// Treat gaps in the history as errors.
+    strict: bool,
+    /// Validate only, without writing to the database.
+    dry_run: bool,
+}
+
+/// Candle read from a file, converted to cents and micro units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RawCandle {
+    open_time: DateTime<Utc>,
+    open: i64,
+    high: i64,
+    low: i64,
+    close: i64,
+    volume: i64,
+}
+
+/// Problems found while validating the candles of a file.
+#[derive(Debug, Default)]
+struct ValidationReport {
+    /// Rows that repeat an earlier row exactly; they are skipped.
+    duplicates: usize,
+    /// Rows that share a timestamp with a different earlier row.
+    conflicts: Vec<DateTime<Utc>>,
+    /// Rows whose timestamp is not aligned to the timeframe.
+    misaligned: Vec<DateTime<Utc>>,
+    /// Rows with impossible prices or volume.
+    invalid: Vec<DateTime<Utc>>,
+    /// Missing ranges between consecutive candles, as (last present, next present).
+    gaps: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl ValidationReport {
+    /// Returns whether the file can be imported.
+    fn is_importable(&self, strict: bool) -> bool {
+        self.conflicts.is_empty()
+            && self.misaligned.is_empty()
+            && self.invalid.is_empty()
+            && (!strict || self.gaps.is_empty())
+    }
+
+    /// Prints a summary of every problem category.
+    fn print(&self) {
+        if self.duplicates > 0 {
+            println!("ℹ️  Skipped {} exact duplicate rows", self.duplicates);
+        }
+        print_times("❌ Conflicting duplicates at", &self.conflicts);
+        print_times("❌ Timestamps not aligned to the interval at", &self.misaligned);
+        print_times("❌ Invalid OHLCV values at", &self.invalid);
+        if !self.gaps.is_empty() {
+            println!("⚠️  {} gaps in history:", self.gaps.len());
+            for (from, to) in self.gaps.iter().take(MAX_REPORTED) {
+                println!("     {} -> {}", from.to_rfc3339(), to.to_rfc3339());
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run().await {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Parses arguments, validates the file and imports it.
+async fn run() -> Result<(), Box<dyn Error>> {
+    let args = parse_args(std::env::args().skip(1).collect())?;
+
+    println!("📂 Reading {} ({:?})", args.file.display(), args.format);
+    let rows = match args.format {
+        FileFormat::Csv => read_csv(&args.file)?,
+        FileFormat::Parquet => read_parquet(&args.file)?,
+    };
+    println!("📊 Read {} rows", rows.len());
+
+    let (candles, report) = validate(rows, args.interval);
+    report.print();
+    if !report.is_importable(args.strict) {
+        return Err("Validation failed, nothing was imported".into());
+    }
+    let (Some(first), Some(last)) = (candles.first(), candles.last()) else {
+        return Err("File contains no candles".into());
+    };
+    println!(
+        "✅ {} {} candles from {} to {}",
+        candles.len(),
+        args.interval.as_str(),
+        first.open_time.to_rfc3339(),
+        last.open_time.to_rfc3339()
+    );
+
+    if args.dry_run {
+        println!("🔍 Dry run, nothing was imported");
+        return Ok(());
+    }
+
+    let config = DatabaseConfig::from_env()?;
+    let pool = create_pool(&config).await?;
+
+    if assets::find_asset(&pool, &args.symbol).await?.is_none() {
+        return Err(format!("Unknown symbol {}; add it to the asset catalog first", args.symbol).into());
+    }
+
+    let mut inserted = 0;
+    for (index, chunk) in candles.chunks(BATCH_SIZE).enumerate() {
+        let batch = CandleBatch {
+            open_time: chunk.iter().map(|c| c.open_time).collect(),
+            open: chunk.iter().map(|c| c.open).collect(),
+            high: chunk.iter().map(|c| c.high).collect(),
+            low: chunk.iter().map(|c| c.low).collect(),
+            close: chunk.iter().map(|c| c.close).collect(),
+            volume: chunk.iter().map(|c| c.volume).collect(),
+        };
+        inserted += market::upsert_candles(&pool, &args.symbol, args.interval.as_str(), &batch).await?;
+
+        let done = (index * BATCH_SIZE + chunk.len()).min(candles.len());
+        println!(
+            "⏳ {}/{} candles ({:.0}%)",
+            done,
+            candles.len(),
+            done as f64 / candles.len() as f64 * 100.0
+        );
+    }
+
+    println!("🔄 Rolling up coarser timeframes");
+    rollup_coarser_candles(&pool, args.interval, first.open_time).await?;
+
+    println!(
+        "🎉 Imported {} candles ({} new, {} updated)",
+        candles.len(),
+        inserted,
+        candles.len() as u64 - inserted
+    );
+
+    Ok(())
+}
+
+/// Parses command line arguments.
+fn parse_args(args: Vec<String>) -> Result<ImportArgs, String> {
+    let mut symbol = None;
+    let mut file = None;
+    let mut interval = CandleInterval::OneMinute;
+    let mut format = None;
+    let mut strict = false;
+    let mut dry_run = false;
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--symbol" => symbol = iter.next().map(|s| s.to_uppercase()),
+            "--file" => file = iter.next().map(PathBuf::from),
+            "--interval" => {
+                let value = iter.next().unwrap_or_default();
+                interval = CandleInterval::parse(&value)
+                    .ok_or_else(|| format!("Unsupported interval '{}' (use 1m, 5m, 1h or 1d)", value))?;
+            }
+            "--format" => {
+                format = match iter.next().as_deref() {
+                    Some("csv") => Some(FileFormat::Csv),
+                    Some("parquet") => Some(FileFormat::Parquet),
+                    other => return Err(format!("Unsupported format '{}' (use csv or parquet)", other.unwrap_or_default())),
+                }
+            }
+            "--strict" => strict = true,
+            "--dry-run" => dry_run = true,
+            other => return Err(format!("Unknown argument '{}'", other)),
+        }
+    }
+
+    let symbol = symbol.ok_or("Missing --symbol")?;
+    let file = file.ok_or("Missing --file")?;
+    // Infer the format from the extension when not given
+    let format = match format {
+        Some(format) => format,
+        None => match file.extension().and_then(|e| e.to_str()) {
+            Some("parquet") => FileFormat::Parquet,
+            _ => FileFormat::Csv,
+        },
+    };
+
+    Ok(ImportArgs { symbol, file, interval, format, strict, dry_run })
+}
+
+/// Reads candles from a CSV file with a header row.
+fn read_csv(path: &Path) -> Result<Vec<RawCandle>, Box<dyn Error>> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_lowercase()).collect();
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+
+    let time_idx = column(&TIMESTAMP_COLUMNS).ok_or("Missing timestamp column")?;
+    let open_idx = column(&["open"]).ok_or("Missing open column")?;
+    let high_idx = column(&["high"]).ok_or("Missing high column")?;
+    let low_idx = column(&["low"]).ok_or("Missing low column")?;
+    let close_idx = column(&["close"]).ok_or("Missing close column")?;
+    let volume_idx = column(&["volume"]);
+
+    let mut candles = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        let record = record?;
+        let field = |idx: usize| record.get(idx).unwrap_or_default().trim();
+        let number = |idx: usize| -> Result<f64, Box<dyn Error>> {
+            field(idx)
+                .parse::<f64>()
+                .map_err(|_| format!("Row {}: invalid number '{}'", line + 2, field(idx)).into())
+        };
+
+        candles.push(RawCandle {
+            open_time: parse_timestamp(field(time_idx))
+                .ok_or_else(|| format!("Row {}: invalid timestamp '{}'", line + 2, field(time_idx)))?,
+            open: usd_to_cents(number(open_idx)?),
+            high: usd_to_cents(number(high_idx)?),
+            low: usd_to_cents(number(low_idx)?),
+            close: usd_to_cents(number(close_idx)?),
+            volume: match volume_idx {
+                Some(idx) if !field(idx).is_empty() => units_to_micros(number(idx)?),
+                _ => 0,
+            },
+        });
+    }
+
+    Ok(candles)
+}
+
+/// Reads candles from a Parquet file.
+fn read_parquet(path: &Path) -> Result<Vec<RawCandle>, Box<dyn Error>> {
+    let reader = SerializedFileReader::new(File::open(path)?)?;
+
+    let mut candles = Vec::new();
+    for (index, row) in reader.get_row_iter(None)?.enumerate() {
+        let row = row?;
+        let mut open_time = None;
+        let (mut open, mut high, mut low, mut close, mut volume) = (None, None, None, None, None);
+
+        for (name, field) in row.get_column_iter() {
+            let name = name.to_lowercase();
+            match name.as_str() {
+                n if TIMESTAMP_COLUMNS.contains(&n) => open_time = field_as_timestamp(field),
+                "open" => open = field_as_f64(field),
+                "high" => high = field_as_f64(field),
+                "low" => low = field_as_f64(field),
+                "close" => close = field_as_f64(field),
+                "volume" => volume = field_as_f64(field),
+                _ => {}
+            }
+        }
+
+        let missing = |column: &str| format!("Row {}: missing or invalid {}", index + 1, column);
+        candles.push(RawCandle {
+            open_time: open_time.ok_or_else(|| missing("timestamp"))?,
+            open: usd_to_cents(open.ok_or_else(|| missing("open"))?),
+            high: usd_to_cents(high.ok_or_else(|| missing("high"))?),
+            low: usd_to_cents(low.ok_or_else(|| missing("low"))?),
+            close: usd_to_cents(close.ok_or_else(|| missing("close"))?),
+            volume: volume.map(units_to_micros).unwrap_or(0),
+        });
+    }
+
+    Ok(candles)
+}
+
+/// Converts a numeric Parquet field to a float.
+fn field_as_f64(field: &Field) -> Option<f64> {
+    match field {
+        Field::Double(v) => Some(*v),
+        Field::Float(v) => Some(*v as f64),
+        Field::Long(v) => Some(*v as f64),
+        Field::Int(v) => Some(*v as f64),
+        Field::Str(v) => v.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Converts a Parquet timestamp, integer or string field to a UTC time.
+fn field_as_timestamp(field: &Field) -> Option<DateTime<Utc>> {
+    match field {
+        Field::TimestampMillis(v) => Utc.timestamp_millis_opt(*v).single(),
+        Field::TimestampMicros(v) => Utc.timestamp_micros(*v).single(),
+        Field::Long(v) => parse_timestamp(&v.to_string()),
+        Field::Int(v) => parse_timestamp(&v.to_string()),
+        Field::Str(v) => parse_timestamp(v),
+        _ => None,
+    }
+}
+
+/// Parses RFC 3339, `YYYY-MM-DD HH:MM:SS` (UTC), unix seconds or unix milliseconds.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(number) = value.parse::<i64>() {
+        // Values this large are milliseconds (seconds would be past the year 5000)
+        return if number.abs() >= 100_000_000_000 {
+            Utc.timestamp_millis_opt(number).single()
+        } else {
+            Utc.timestamp_opt(number, 0).single()
+        };
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+/// Sorts candles, drops exact duplicates and reports conflicts, bad rows and gaps.
+fn validate(mut rows: Vec<RawCandle>, interval: CandleInterval) -> (Vec<RawCandle>, ValidationReport) {
+    let mut report = ValidationReport::default();
+    rows.sort_by_key(|c| c.open_time);
+
+    let mut candles: Vec<RawCandle> = Vec::with_capacity(rows.len());
+    for candle in rows {
+        if let Some(previous) = candles.last() {
+            if previous.open_time == candle.open_time {
+                if *previous == candle {
+                    report.duplicates += 1;
+                } else {
+                    report.conflicts.push(candle.open_time);
+                }
+                continue;
+            }
+            let expected = previous.open_time + Duration::seconds(interval.seconds());
+            if candle.open_time > expected {
+                report.gaps.push((previous.open_time, candle.open_time));
+            }
+        }
+
+        if interval.align(candle.open_time) != candle.open_time {
+            report.misaligned.push(candle.open_time);
+        }
+        let prices_valid = candle.low > 0
+            && candle.low <= candle.open.min(candle.close)
+            && candle.high >= candle.open.max(candle.close);
+        if !prices_valid || candle.volume < 0 {
+            report.invalid.push(candle.open_time);
+        }
+
+        candles.push(candle);
+    }
+
+    (candles, report)
+}
+
+/// Prints a headline and the first few timestamps of a problem category.
+fn print_times(headline: &str, times: &[DateTime<Utc>]) {
+    if times.is_empty() {
+        return;
+    }
+    println!("{} {} rows:", headline, times.len());
+    for time in times.iter().take(MAX_REPORTED) {
+        println!("     {}", time.to_rfc3339());
+    }
+}
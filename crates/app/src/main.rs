@@ -5,7 +5,6 @@ use axum::Router;
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tracing::info;
-use sqlx;
 use db::{create_pool, test_connection, DatabaseConfig};
 
 #[tokio::main]
@@ -72,7 +71,7 @@ async fn create_app(db_pool: sqlx::PgPool) -> Result<Router, Box<dyn std::error:
         .route("/", axum::routing::get(|| async { "Vectra DEX - More Than a DEX. It's an Arena." }))
         // Health check endpoint required by Elastic Beanstalk load balancer
         .route("/health", axum::routing::get(health_check))
-        .nest("/api/v1", api::create_router(db_pool).await);
+        .nest("/api/v1", api::create_router(db_pool).await?);
 
     Ok(app)
 }
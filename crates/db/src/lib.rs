@@ -11,5 +11,29 @@ pub use models::*;
 /// Database query modules.
 /// Contains organized query functions for different data domains.
 pub mod queries {
+    pub mod achievements;
+    pub mod alerts;
+    pub mod assets;
+    pub mod bots;
+    pub mod clans;
+    pub mod duels;
+    pub mod events;
+    pub mod fees;
+    pub mod idempotency;
+    pub mod leaderboards;
+    pub mod leagues;
+    pub mod market;
+    pub mod notifications;
+    pub mod portfolio;
+    pub mod quests;
+    pub mod ratings;
+    pub mod rebalance;
+    pub mod seasons;
+    pub mod sessions;
+    pub mod streaks;
+    pub mod tournaments;
+    pub mod trading;
     pub mod users;
+    pub mod watchlists;
+    pub mod xp;
 }
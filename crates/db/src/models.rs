@@ -4,7 +4,7 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 
 /// User account information.
 /// Represents a user in the Vectra DEX platform with wallet-based authentication.
@@ -42,14 +42,22 @@ pub struct Trade {
     pub symbol: String,
     /// Trade type: "buy" or "sell".
     pub trade_type: String,
-    /// Number of tokens traded.
-    pub quantity: f64,
-    /// Price per token at execution.
-    pub price: f64,
-    /// Total trade value (quantity * price).
-    pub total_value: f64,
+    /// Number of tokens traded. Represented in micro units.
+    pub quantity: i64,
+    /// Fill price per token, including slippage. Represented in cents.
+    pub price: i64,
+    /// Total trade value (quantity * price), excluding fees. Represented in cents.
+    pub total_value: i64,
     /// When the trade was executed.
     pub executed_at: DateTime<Utc>,
+    /// Oracle price per token before slippage. Represented in cents.
+    pub reference_price: i64,
+    /// Trading fee charged for the trade. Represented in cents.
+    pub fee_cents: i64,
+    /// Cost of slippage versus the reference price. Represented in cents.
+    pub slippage_cents: i64,
+    /// Bot that placed the trade, if any.
+    pub bot_id: Option<Uuid>,
 }
 
 /// User's current portfolio positions.
@@ -62,14 +70,16 @@ pub struct Position {
     pub user_id: Uuid,
     /// Trading symbol.
     pub symbol: String,
-    /// Total quantity held.
-    pub quantity: f64,
-    /// Average purchase price.
-    pub average_price: f64,
-    /// Current market value of the position.
-    pub current_value: f64,
+    /// Total quantity held. Represented in micro units.
+    pub quantity: i64,
+    /// Average purchase price. Represented in cents.
+    pub average_price: i64,
+    /// Market value of the position at the last update. Represented in cents.
+    pub current_value: i64,
     /// When the position was last updated.
     pub updated_at: DateTime<Utc>,
+    /// When the position was opened.
+    pub opened_at: DateTime<Utc>,
 }
 
 /// User session information.
@@ -87,3 +97,829 @@ pub struct UserSession {
     /// When the session was created.
     pub created_at: DateTime<Utc>,
 }
+
+/// Fee and slippage configuration for a trading symbol.
+/// The schedule with symbol "*" applies to symbols without their own entry.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeeSchedule {
+    /// Trading symbol, or "*" for the default schedule.
+    pub symbol: String,
+    /// Fee for liquidity-adding fills, in basis points.
+    pub maker_fee_bps: i32,
+    /// Fee for liquidity-taking fills, in basis points.
+    pub taker_fee_bps: i32,
+    /// Minimum fee charged per trade. Represented in cents.
+    pub min_fee_cents: i64,
+    /// Slippage applied to every fill, in basis points.
+    pub slippage_base_bps: i32,
+    /// Extra slippage per `slippage_depth_cents` of notional, in basis points.
+    pub slippage_impact_bps: i32,
+    /// Notional at which the full impact applies. Represented in cents.
+    pub slippage_depth_cents: i64,
+    /// Upper bound for total slippage, in basis points.
+    pub max_slippage_bps: i32,
+    /// When the schedule was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Trading fee tier unlocked by reaching a level.
+/// Users are in the tier with the highest level requirement they meet; the level 1 tier applies to everyone else.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeeTier {
+    /// Lowest level in the tier.
+    pub min_level: i16,
+    /// Display name of the tier.
+    pub name: String,
+    /// Discount applied to maker and taker fees, in percent (0-100).
+    pub discount_percent: i32,
+    /// When the tier was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Tradable asset with its trading rules.
+/// Orders are validated against the asset's status, lot size and minimum notional.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Asset {
+    /// Trading symbol (e.g., "ETH").
+    pub symbol: String,
+    /// Human-readable asset name.
+    pub name: String,
+    /// On-chain token decimals.
+    pub decimals: i16,
+    /// Price increment. Represented in cents.
+    pub tick_size_cents: i64,
+    /// Quantity increment. Represented in micro units.
+    pub lot_size: i64,
+    /// Minimum order value. Represented in cents.
+    pub min_notional_cents: i64,
+    /// Trading status: "active", "halted" or "delisted".
+    pub status: String,
+    /// Chain the asset lives on (optional).
+    pub chain: Option<String>,
+    /// Token contract address, absent for native assets.
+    pub contract_address: Option<String>,
+    /// When the asset was added.
+    pub created_at: DateTime<Utc>,
+    /// When the asset was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// OHLCV candle for a symbol and timeframe.
+/// Prices are rolled up from oracle ticks or imported from historical data.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Candle {
+    /// Trading symbol.
+    pub symbol: String,
+    /// Candle timeframe: "1m", "5m", "1h" or "1d".
+    pub timeframe: String,
+    /// Start of the candle, aligned to the timeframe.
+    pub open_time: DateTime<Utc>,
+    /// First price in the candle. Represented in cents.
+    pub open: i64,
+    /// Highest price in the candle. Represented in cents.
+    pub high: i64,
+    /// Lowest price in the candle. Represented in cents.
+    pub low: i64,
+    /// Last price in the candle. Represented in cents.
+    pub close: i64,
+    /// Traded quantity. Represented in micro units.
+    pub volume: i64,
+}
+
+/// Server-side trading bot owned by a user.
+/// DCA bots use the `amount_cents`/`interval_seconds` settings, grid bots the `grid_*` settings.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Bot {
+    /// Unique bot identifier.
+    pub id: Uuid,
+    /// User who owns the bot.
+    pub user_id: Uuid,
+    /// Display name chosen by the user.
+    pub name: String,
+    /// Bot type: "dca" or "grid".
+    pub kind: String,
+    /// Trading symbol.
+    pub symbol: String,
+    /// Bot status: "active" or "paused".
+    pub status: String,
+    /// Amount spent per DCA buy. Represented in cents.
+    pub amount_cents: Option<i64>,
+    /// Seconds between DCA buys.
+    pub interval_seconds: Option<i32>,
+    /// When the next DCA buy is due.
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// Lowest grid line. Represented in cents.
+    pub grid_lower_cents: Option<i64>,
+    /// Highest grid line. Represented in cents.
+    pub grid_upper_cents: Option<i64>,
+    /// Number of evenly spaced grid lines.
+    pub grid_levels: Option<i16>,
+    /// Quantity traded per crossed grid line. Represented in micro units.
+    pub grid_quantity: Option<i64>,
+    /// Number of grid lines at or below the last seen price.
+    pub grid_level: Option<i16>,
+    /// When the bot last placed an order.
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Why the last order failed, if it did.
+    pub last_error: Option<String>,
+    /// When the bot was created.
+    pub created_at: DateTime<Utc>,
+    /// When the bot was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Stored outcome of a request made with an `Idempotency-Key` header.
+/// The response is empty while the original request is still being processed.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IdempotencyKey {
+    /// User who made the request.
+    pub user_id: Uuid,
+    /// Client-supplied idempotency key.
+    pub key: String,
+    /// SHA-256 of the request method, path and body, hex encoded.
+    pub request_hash: String,
+    /// HTTP status of the stored response.
+    pub response_status: Option<i16>,
+    /// Body of the stored response.
+    pub response_body: Option<Vec<u8>>,
+    /// Content type of the stored response.
+    pub content_type: Option<String>,
+    /// When the key was first used.
+    pub created_at: DateTime<Utc>,
+    /// When the key may be reused for a different request.
+    pub expires_at: DateTime<Utc>,
+    /// Until when the request holding the key is considered in flight, absent once completed.
+    pub locked_until: Option<DateTime<Utc>>,
+    /// Token of the claim holding the key, checked by every later write.
+    pub claim_token: Option<Uuid>,
+}
+
+/// Point-in-time value of a user's portfolio.
+/// Captured periodically to build equity curves.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PortfolioSnapshot {
+    /// User the snapshot belongs to.
+    pub user_id: Uuid,
+    /// When the snapshot was taken.
+    pub captured_at: DateTime<Utc>,
+    /// Cash plus positions at oracle prices. Represented in cents.
+    pub portfolio_value_cents: i64,
+    /// Available cash. Represented in cents.
+    pub cash_balance_cents: i64,
+}
+
+/// Recurring rebalance of a user's portfolio to target weights.
+/// Cash receives whatever weight the targets leave over.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RebalanceSchedule {
+    /// User whose portfolio is rebalanced.
+    pub user_id: Uuid,
+    /// Target symbols, parallel to `weights_bps`.
+    pub symbols: Vec<String>,
+    /// Target weights in basis points.
+    pub weights_bps: Vec<i32>,
+    /// Seconds between rebalances.
+    pub interval_seconds: i32,
+    /// Runs are skipped while no weight drifts further than this, in basis points.
+    pub drift_threshold_bps: i32,
+    /// When the next rebalance is due.
+    pub next_run_at: DateTime<Utc>,
+    /// When the schedule last ran.
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Why the last run failed, if it did.
+    pub last_error: Option<String>,
+    /// When the schedule was created.
+    pub created_at: DateTime<Utc>,
+    /// When the schedule was last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Price alert on a symbol.
+/// Level alerts fire when the price crosses a target, percent alerts when it moves far enough from a reference.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Alert {
+    /// Unique alert identifier.
+    pub id: Uuid,
+    /// User who owns the alert.
+    pub user_id: Uuid,
+    /// Watched symbol.
+    pub symbol: String,
+    /// Alert condition: "above", "below" or "percent".
+    pub condition: String,
+    /// Level crossed by "above" and "below" alerts. Represented in cents.
+    pub target_price_cents: Option<i64>,
+    /// Move that triggers "percent" alerts, in basis points.
+    pub percent_bps: Option<i32>,
+    /// Price "percent" moves are measured from. Represented in cents.
+    pub reference_price_cents: Option<i64>,
+    /// Whether the alert re-arms after triggering instead of deactivating.
+    pub recurring: bool,
+    /// Whether a level alert can fire, i.e. the price has been on the near side of the level.
+    pub armed: bool,
+    /// Whether the alert is evaluated.
+    pub is_active: bool,
+    /// Optional text included in notifications.
+    pub note: Option<String>,
+    /// Number of times the alert fired.
+    pub trigger_count: i32,
+    /// When the alert last fired.
+    pub last_triggered_at: Option<DateTime<Utc>>,
+    /// Price the alert last fired at. Represented in cents.
+    pub last_triggered_price_cents: Option<i64>,
+    /// When the alert was created.
+    pub created_at: DateTime<Utc>,
+    /// When the alert was last updated.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Message in a user's in-app inbox.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Notification {
+    /// Unique notification identifier.
+    pub id: Uuid,
+    /// User the notification is for.
+    pub user_id: Uuid,
+    /// What produced the notification, e.g. "price_alert".
+    pub kind: String,
+    /// Short headline.
+    pub title: String,
+    /// Full message.
+    pub message: String,
+    /// Alert that fired, for price alert notifications.
+    pub alert_id: Option<Uuid>,
+    /// When the user read the notification.
+    pub read_at: Option<DateTime<Utc>>,
+    /// When the notification was created.
+    pub created_at: DateTime<Utc>,
+}
+
+/// User-registered endpoint that receives signed notification payloads.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    /// Unique webhook identifier.
+    pub id: Uuid,
+    /// User who registered the webhook.
+    pub user_id: Uuid,
+    /// URL payloads are posted to.
+    pub url: String,
+    /// HMAC-SHA256 signing key, hex encoded.
+    pub secret: String,
+    /// Whether payloads are delivered. Cleared after repeated failures.
+    pub is_active: bool,
+    /// Consecutive failed deliveries.
+    pub failure_count: i32,
+    /// When a payload was last delivered.
+    pub last_delivery_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last delivery, `None` if the endpoint was unreachable.
+    pub last_status: Option<i16>,
+    /// When the webhook was registered.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Named list of symbols a user follows.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Watchlist {
+    /// Unique watchlist identifier.
+    pub id: Uuid,
+    /// User who owns the watchlist.
+    pub user_id: Uuid,
+    /// Display name, unique per user.
+    pub name: String,
+    /// Watched symbols in display order.
+    pub symbols: Vec<String>,
+    /// When the watchlist was created.
+    pub created_at: DateTime<Utc>,
+    /// When the watchlist was last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Entry in the append-only XP ledger.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct XpEvent {
+    /// Unique event identifier.
+    pub id: Uuid,
+    /// User whose XP changed.
+    pub user_id: Uuid,
+    /// What changed the XP, e.g. "trade" or "adjustment".
+    pub source: String,
+    /// XP applied. Negative for reversals and deductions.
+    pub amount: i32,
+    /// Entity behind the change, e.g. a trade id.
+    pub reference_id: Option<String>,
+    /// Key that makes the grant apply at most once per user.
+    pub idempotency_key: Option<String>,
+    /// User's XP total after the event.
+    pub xp_after: i32,
+    /// User's level after the event.
+    pub level_after: i16,
+    /// When the event was recorded.
+    pub created_at: DateTime<Utc>,
+    /// Season the XP counts towards.
+    pub season_id: Option<Uuid>,
+}
+
+/// Running statistics of a player, used for achievement criteria.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct PlayerStats {
+    /// User the statistics belong to.
+    pub user_id: Uuid,
+    /// Trades executed.
+    pub trade_count: i64,
+    /// Notional traded. Represented in cents.
+    pub volume_cents: i64,
+    /// Sells that realized a profit.
+    pub profitable_closes: i64,
+    /// Profit realized across all sells. Represented in cents.
+    pub realized_pnl_cents: i64,
+    /// Best return of a single sell on its cost, in basis points.
+    pub best_return_bps: i64,
+    /// Longest a position was held before selling, in seconds.
+    pub longest_hold_seconds: i64,
+    /// When the statistics last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Achievement unlocked by a user.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserAchievement {
+    /// User who unlocked the achievement.
+    pub user_id: Uuid,
+    /// Catalog key of the achievement.
+    pub achievement_id: String,
+    /// When the achievement was unlocked.
+    pub unlocked_at: DateTime<Utc>,
+}
+
+/// User's progress on a quest in one rotation period.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QuestProgress {
+    /// User working on the quest.
+    pub user_id: Uuid,
+    /// Template key of the quest.
+    pub quest_id: String,
+    /// Rotation period: "daily" or "weekly".
+    pub period: String,
+    /// Start of the rotation period.
+    pub period_start: DateTime<Utc>,
+    /// Accumulated progress, in the goal's unit.
+    pub progress: i64,
+    /// When the reward was claimed.
+    pub claimed_at: Option<DateTime<Utc>>,
+    /// XP granted on claim.
+    pub xp_awarded: Option<i32>,
+    /// When the progress last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Competitive season scoping XP, levels and paper balances.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Season {
+    /// Unique season identifier.
+    pub id: Uuid,
+    /// Sequential season number, starting at 1.
+    pub number: i32,
+    /// Display name.
+    pub name: String,
+    /// When the season started.
+    pub starts_at: DateTime<Utc>,
+    /// When the season is scheduled to roll over.
+    pub ends_at: DateTime<Utc>,
+    /// When the season rolled over. `None` for the current season.
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Users in the archived standings.
+    pub participant_count: i32,
+    /// When the season was created.
+    pub created_at: DateTime<Utc>,
+}
+
+/// User's final standing in a closed season.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SeasonStanding {
+    /// Season the standing belongs to.
+    pub season_id: Uuid,
+    /// User the standing belongs to.
+    pub user_id: Uuid,
+    /// Rank by seasonal XP. Ties share a rank.
+    pub xp_rank: i32,
+    /// Seasonal XP at the rollover.
+    pub xp_points: i32,
+    /// Seasonal level at the rollover.
+    pub level: i16,
+    /// Rank by final portfolio value.
+    pub portfolio_rank: i32,
+    /// Final portfolio value at oracle prices. Represented in cents.
+    pub portfolio_value_cents: i64,
+    /// Return on the starting balance, in basis points.
+    pub return_bps: i64,
+    /// Season reward earned, e.g. "Champion".
+    pub reward_title: Option<String>,
+    /// XP granted in the next season for the finish.
+    pub reward_xp: i32,
+}
+
+/// Materialized leaderboard position.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LeaderboardEntry {
+    /// What the leaderboard ranks by, e.g. "roi".
+    pub metric: String,
+    /// Time window, e.g. "week".
+    pub period: String,
+    /// Ranked user.
+    pub user_id: Uuid,
+    /// Position on the leaderboard. Ties share a rank.
+    pub rank: i32,
+    /// Score in the metric's unit: XP points, ROI fraction, P&L in cents or Sharpe ratio.
+    pub score: f64,
+    /// When the leaderboard was computed.
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Trading tournament with isolated paper accounts.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Tournament {
+    /// Unique tournament identifier.
+    pub id: Uuid,
+    /// Display name.
+    pub name: String,
+    /// Rules or prize description shown to players.
+    pub description: Option<String>,
+    /// Paper cash every entrant starts with. Represented in cents.
+    pub starting_balance_cents: i64,
+    /// Symbols entrants may trade.
+    pub allowed_symbols: Vec<String>,
+    /// Maximum number of entrants. `None` for unlimited.
+    pub max_participants: Option<i32>,
+    /// When registration opens.
+    pub registration_opens_at: DateTime<Utc>,
+    /// When registration closes.
+    pub registration_closes_at: DateTime<Utc>,
+    /// When trading starts.
+    pub starts_at: DateTime<Utc>,
+    /// When trading ends.
+    pub ends_at: DateTime<Utc>,
+    /// When the final ranking was recorded.
+    pub finalized_at: Option<DateTime<Utc>>,
+    /// When the tournament was created.
+    pub created_at: DateTime<Utc>,
+}
+
+/// User's entry and isolated cash balance in a tournament.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TournamentEntry {
+    /// Tournament entered.
+    pub tournament_id: Uuid,
+    /// Entrant.
+    pub user_id: Uuid,
+    /// Tournament cash balance. Represented in cents.
+    pub cash_balance_cents: i64,
+    /// When the user registered.
+    pub joined_at: DateTime<Utc>,
+    /// Final rank by account value. Ties share a rank.
+    pub final_rank: Option<i32>,
+    /// Final account value at oracle prices. Represented in cents.
+    pub final_value_cents: Option<i64>,
+    /// Prize badge, e.g. "gold".
+    pub badge: Option<String>,
+    /// XP granted for the finish.
+    pub xp_awarded: i32,
+    /// Clan the entrant played for, when their clan was entered.
+    pub clan_id: Option<Uuid>,
+}
+
+/// Holding within a tournament account.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TournamentPosition {
+    /// Tournament the position belongs to.
+    pub tournament_id: Uuid,
+    /// Entrant holding the position.
+    pub user_id: Uuid,
+    /// Trading symbol.
+    pub symbol: String,
+    /// Quantity held. Represented in micro units.
+    pub quantity: i64,
+    /// Average purchase price. Represented in cents.
+    pub average_price: i64,
+    /// Last time the position changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fill within a tournament account.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TournamentTrade {
+    /// Unique trade identifier.
+    pub id: Uuid,
+    /// Tournament the trade belongs to.
+    pub tournament_id: Uuid,
+    /// Entrant who traded.
+    pub user_id: Uuid,
+    /// Trading symbol.
+    pub symbol: String,
+    /// Trade type: "buy" or "sell".
+    pub trade_type: String,
+    /// Quantity traded. Represented in micro units.
+    pub quantity: i64,
+    /// Fill price including slippage. Represented in cents.
+    pub price: i64,
+    /// Quantity times price, excluding fees. Represented in cents.
+    pub total_value: i64,
+    /// Trading fee charged. Represented in cents.
+    pub fee_cents: i64,
+    /// When the trade was executed.
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Head-to-head trading duel between two users.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Duel {
+    /// Unique duel identifier.
+    pub id: Uuid,
+    /// User who issued the challenge.
+    pub challenger_id: Uuid,
+    /// User who was challenged.
+    pub opponent_id: Uuid,
+    /// Status: "pending", "declined", "expired", "active" or "settled".
+    pub status: String,
+    /// Paper cash both players start with. Represented in cents.
+    pub starting_balance_cents: i64,
+    /// Symbols both players may trade.
+    pub allowed_symbols: Vec<String>,
+    /// Trading time once accepted, in hours.
+    pub duration_hours: i32,
+    /// XP each player puts up. The winner takes both stakes.
+    pub stake_xp: i32,
+    /// When the challenge was issued.
+    pub created_at: DateTime<Utc>,
+    /// When the challenge expires if unanswered.
+    pub expires_at: DateTime<Utc>,
+    /// When the opponent accepted or declined.
+    pub responded_at: Option<DateTime<Utc>>,
+    /// When trading started.
+    pub starts_at: Option<DateTime<Utc>>,
+    /// When trading ends.
+    pub ends_at: Option<DateTime<Utc>>,
+    /// When the stakes were paid out.
+    pub settled_at: Option<DateTime<Utc>>,
+    /// Winner once settled. `None` for a draw.
+    pub winner_id: Option<Uuid>,
+    /// Challenger's final account value. Represented in cents.
+    pub challenger_final_value_cents: Option<i64>,
+    /// Opponent's final account value. Represented in cents.
+    pub opponent_final_value_cents: Option<i64>,
+}
+
+/// Player's isolated cash balance in a duel.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DuelAccount {
+    /// Duel the account belongs to.
+    pub duel_id: Uuid,
+    /// Player owning the account.
+    pub user_id: Uuid,
+    /// Duel cash balance. Represented in cents.
+    pub cash_balance_cents: i64,
+}
+
+/// Holding within a duel account.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DuelPosition {
+    /// Duel the position belongs to.
+    pub duel_id: Uuid,
+    /// Player holding the position.
+    pub user_id: Uuid,
+    /// Trading symbol.
+    pub symbol: String,
+    /// Quantity held. Represented in micro units.
+    pub quantity: i64,
+    /// Average purchase price. Represented in cents.
+    pub average_price: i64,
+    /// Last time the position changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fill within a duel account.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DuelTrade {
+    /// Unique trade identifier.
+    pub id: Uuid,
+    /// Duel the trade belongs to.
+    pub duel_id: Uuid,
+    /// Player who traded.
+    pub user_id: Uuid,
+    /// Trading symbol.
+    pub symbol: String,
+    /// Trade type: "buy" or "sell".
+    pub trade_type: String,
+    /// Quantity traded. Represented in micro units.
+    pub quantity: i64,
+    /// Fill price including slippage. Represented in cents.
+    pub price: i64,
+    /// Quantity times price, excluding fees. Represented in cents.
+    pub total_value: i64,
+    /// Trading fee charged. Represented in cents.
+    pub fee_cents: i64,
+    /// When the trade was executed.
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Player's Glicko-2 skill rating.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PlayerRating {
+    /// Rated player.
+    pub user_id: Uuid,
+    /// Estimated skill.
+    pub rating: f64,
+    /// Uncertainty of the rating.
+    pub deviation: f64,
+    /// Expected fluctuation in performance.
+    pub volatility: f64,
+    /// Rated games played.
+    pub games: i32,
+    /// When the rating last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Rating update from a duel or tournament.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RatingEvent {
+    /// Unique event identifier.
+    pub id: Uuid,
+    /// Rated player.
+    pub user_id: Uuid,
+    /// What was rated: "duel" or "tournament".
+    pub source: String,
+    /// Duel or tournament that was rated.
+    pub reference_id: Uuid,
+    /// Head-to-head results in the update.
+    pub games: i32,
+    /// Points scored: 1 per win, 0.5 per draw.
+    pub score: f64,
+    /// Points expected from the ratings beforehand.
+    pub expected_score: f64,
+    /// Rating before the update.
+    pub rating_before: f64,
+    /// Rating after the update.
+    pub rating_after: f64,
+    /// Deviation after the update.
+    pub deviation_after: f64,
+    /// Volatility after the update.
+    pub volatility_after: f64,
+    /// When the update was applied.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Group of players competing in a division for one league week.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LeagueGroup {
+    /// Unique group identifier.
+    pub id: Uuid,
+    /// Monday 00:00 UTC the league week starts.
+    pub week_start: DateTime<Utc>,
+    /// Division tier, 0 being Bronze.
+    pub division: i16,
+    /// What members are ranked by: "xp" or "return".
+    pub metric: String,
+    /// When the group was opened.
+    pub created_at: DateTime<Utc>,
+    /// When promotions and relegations were applied.
+    pub finalized_at: Option<DateTime<Utc>>,
+}
+
+/// Player's place in a league group.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LeagueMember {
+    /// Group the player competes in.
+    pub group_id: Uuid,
+    /// Competing player.
+    pub user_id: Uuid,
+    /// Monday 00:00 UTC the league week starts.
+    pub week_start: DateTime<Utc>,
+    /// XP or return this week. `None` until the player is active.
+    pub score: Option<f64>,
+    /// Final rank in the group, set when the week is finalized.
+    pub final_rank: Option<i32>,
+    /// Outcome: "promoted", "stayed" or "relegated".
+    pub outcome: Option<String>,
+    /// Division tier the player starts the next week in.
+    pub next_division: Option<i16>,
+    /// When the player joined the group.
+    pub joined_at: DateTime<Utc>,
+}
+
+/// User's daily activity streak.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ActivityStreak {
+    /// User the streak belongs to.
+    pub user_id: Uuid,
+    /// Consecutive active days up to `last_active_date`, including days covered by freezes.
+    pub current_days: i32,
+    /// Longest streak reached.
+    pub longest_days: i32,
+    /// Latest day with a sign-in or trade, in the user's time zone.
+    pub last_active_date: Option<NaiveDate>,
+    /// Freezes held, each covering one missed day.
+    pub freezes: i32,
+    /// Freezes spent so far.
+    pub freezes_used: i32,
+    /// IANA time zone days are counted in.
+    pub time_zone: String,
+    /// When the streak last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Team of players with shared standings.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Clan {
+    /// Unique identifier.
+    pub id: Uuid,
+    /// Display name, unique ignoring case.
+    pub name: String,
+    /// Short uppercase tag, e.g. "VECT".
+    pub tag: String,
+    /// Description shown on the clan page.
+    pub description: Option<String>,
+    /// Code players join with.
+    pub invite_code: String,
+    /// Founder.
+    pub created_by: Option<Uuid>,
+    /// When the clan was founded.
+    pub created_at: DateTime<Utc>,
+    /// When the clan was last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Player's membership in a clan.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ClanMember {
+    /// Clan joined.
+    pub clan_id: Uuid,
+    /// Member.
+    pub user_id: Uuid,
+    /// "owner", "officer" or "member".
+    pub role: String,
+    /// When the player joined.
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Materialized clan leaderboard position.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ClanLeaderboardEntry {
+    /// What the leaderboard ranks by, e.g. "xp".
+    pub metric: String,
+    /// Time window, e.g. "week".
+    pub period: String,
+    /// Ranked clan.
+    pub clan_id: Uuid,
+    /// Position on the leaderboard. Ties share a rank.
+    pub rank: i32,
+    /// Score in the metric's unit: total XP points or mean ROI fraction.
+    pub score: f64,
+    /// Members when the leaderboard was computed.
+    pub members: i32,
+    /// When the leaderboard was computed.
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Clan entered into a tournament.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TournamentClan {
+    /// Tournament entered.
+    pub tournament_id: Uuid,
+    /// Clan entered.
+    pub clan_id: Uuid,
+    /// Officer or owner who entered the clan.
+    pub entered_by: Option<Uuid>,
+    /// When the clan was entered.
+    pub entered_at: DateTime<Utc>,
+    /// Final rank by mean entrant account value. Ties share a rank.
+    pub final_rank: Option<i32>,
+    /// Mean final account value of the clan's entrants, in cents.
+    pub final_value_cents: Option<i64>,
+}
+
+/// Stored gamification event awaiting or past processing.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserEventRecord {
+    /// Consumption order.
+    pub id: i64,
+    /// User who acted.
+    pub user_id: Uuid,
+    /// Event kind, e.g. "trade_executed".
+    pub kind: String,
+    /// Trade notional in cents, for trade events.
+    pub notional_cents: Option<i64>,
+    /// Realized P&L after fees in cents, for closed positions.
+    pub realized_pnl_cents: Option<i64>,
+    /// Cost of the sold quantity in cents, for closed positions.
+    pub cost_cents: Option<i64>,
+    /// How long the position was open in seconds, for closed positions.
+    pub held_seconds: Option<i64>,
+    /// Entity behind the event, e.g. a trade id.
+    pub reference_id: Option<String>,
+    /// When the event happened.
+    pub occurred_at: DateTime<Utc>,
+    /// When the progression job applied the event.
+    pub processed_at: Option<DateTime<Utc>>,
+    /// Failed processing attempts.
+    pub attempts: i32,
+    /// Earliest time a failed event is retried.
+    pub retry_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,122 @@
+//! Achievement database queries.
+//! Keeps per-player statistics up to date and records unlocked achievements.
+
+use sqlx::PgExecutor;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::{PlayerStats, UserAchievement};
+
+/// Finds a player's statistics. Players without recorded activity have none.
+pub async fn find_player_stats(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+) -> Result<Option<PlayerStats>, sqlx::Error> {
+    let stats = sqlx::query_as!(
+        PlayerStats,
+        "SELECT * FROM player_stats WHERE user_id = $1",
+        user_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(stats)
+}
+
+/// Adds an executed trade to a player's statistics.
+pub async fn record_trade_stats(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+    notional_cents: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO player_stats (user_id, trade_count, volume_cents, updated_at)
+        VALUES ($1, 1, $2, $3)
+        ON CONFLICT (user_id) DO UPDATE
+        SET trade_count = player_stats.trade_count + 1,
+            volume_cents = player_stats.volume_cents + EXCLUDED.volume_cents,
+            updated_at = EXCLUDED.updated_at
+        "#,
+        user_id,
+        notional_cents,
+        Utc::now()
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Adds a sell to a player's realized P&L, best return and longest holding period.
+pub async fn record_close_stats(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+    realized_pnl_cents: i64,
+    return_bps: i64,
+    held_seconds: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO player_stats (
+            user_id, profitable_closes, realized_pnl_cents, best_return_bps, longest_hold_seconds, updated_at
+        )
+        VALUES (
+            $1, CASE WHEN $2::BIGINT > 0 THEN 1 ELSE 0 END, $2, GREATEST($3::BIGINT, 0), GREATEST($4::BIGINT, 0), $5
+        )
+        ON CONFLICT (user_id) DO UPDATE
+        SET profitable_closes = player_stats.profitable_closes + EXCLUDED.profitable_closes,
+            realized_pnl_cents = player_stats.realized_pnl_cents + EXCLUDED.realized_pnl_cents,
+            best_return_bps = GREATEST(player_stats.best_return_bps, EXCLUDED.best_return_bps),
+            longest_hold_seconds = GREATEST(player_stats.longest_hold_seconds, EXCLUDED.longest_hold_seconds),
+            updated_at = EXCLUDED.updated_at
+        "#,
+        user_id,
+        realized_pnl_cents,
+        return_bps,
+        held_seconds,
+        Utc::now()
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Lists a user's unlocked achievements, oldest first.
+pub async fn list_user_achievements(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+) -> Result<Vec<UserAchievement>, sqlx::Error> {
+    let achievements = sqlx::query_as!(
+        UserAchievement,
+        "SELECT * FROM user_achievements WHERE user_id = $1 ORDER BY unlocked_at, achievement_id",
+        user_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(achievements)
+}
+
+/// Records an unlocked achievement. Returns `false` when it was already unlocked.
+pub async fn unlock_achievement(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+    achievement_id: &str,
+    unlocked_at: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO user_achievements (user_id, achievement_id, unlocked_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, achievement_id) DO NOTHING
+        "#,
+        user_id,
+        achievement_id,
+        unlocked_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,227 @@
+//! Price alert database queries.
+//! Handles alert definitions and the state changes made as ticks arm and trigger them.
+
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::Alert;
+
+/// Alert settings supplied when a user creates or replaces an alert.
+/// Only the settings of the alert's condition are set.
+#[derive(Debug, Clone, Default)]
+pub struct NewAlert<'a> {
+    /// User who owns the alert.
+    pub user_id: Uuid,
+    /// Watched symbol.
+    pub symbol: &'a str,
+    /// Alert condition: "above", "below" or "percent".
+    pub condition: &'a str,
+    /// Level crossed by "above" and "below" alerts, in cents.
+    pub target_price_cents: Option<i64>,
+    /// Move that triggers "percent" alerts, in basis points.
+    pub percent_bps: Option<i32>,
+    /// Price "percent" moves are measured from, in cents.
+    pub reference_price_cents: Option<i64>,
+    /// Whether the alert re-arms after triggering.
+    pub recurring: bool,
+    /// Whether a level alert can fire right away.
+    pub armed: bool,
+    /// Optional text included in notifications.
+    pub note: Option<&'a str>,
+}
+
+/// Creates an alert.
+pub async fn create_alert(
+    pool: &PgPool,
+    alert: &NewAlert<'_>,
+) -> Result<Alert, sqlx::Error> {
+    let now = Utc::now();
+    let alert = sqlx::query_as!(
+        Alert,
+        r#"
+        INSERT INTO alerts (id, user_id, symbol, condition, target_price_cents, percent_bps, reference_price_cents,
+                            recurring, armed, is_active, note, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, TRUE, $10, $11, $11)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        alert.user_id,
+        alert.symbol,
+        alert.condition,
+        alert.target_price_cents,
+        alert.percent_bps,
+        alert.reference_price_cents,
+        alert.recurring,
+        alert.armed,
+        alert.note,
+        now
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(alert)
+}
+
+/// Replaces the settings of a user's alert and reactivates it.
+/// Returns `None` when the alert does not exist or belongs to another user.
+pub async fn replace_alert(
+    pool: &PgPool,
+    id: Uuid,
+    alert: &NewAlert<'_>,
+) -> Result<Option<Alert>, sqlx::Error> {
+    let alert = sqlx::query_as!(
+        Alert,
+        r#"
+        UPDATE alerts
+        SET symbol = $3, condition = $4, target_price_cents = $5, percent_bps = $6, reference_price_cents = $7,
+            recurring = $8, armed = $9, is_active = TRUE, note = $10, updated_at = $11
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+        id,
+        alert.user_id,
+        alert.symbol,
+        alert.condition,
+        alert.target_price_cents,
+        alert.percent_bps,
+        alert.reference_price_cents,
+        alert.recurring,
+        alert.armed,
+        alert.note,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(alert)
+}
+
+/// Counts the alerts owned by a user.
+pub async fn count_alerts(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM alerts WHERE user_id = $1"#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Lists a user's alerts, newest first.
+pub async fn list_alerts(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<Alert>, sqlx::Error> {
+    let alerts = sqlx::query_as!(
+        Alert,
+        "SELECT * FROM alerts WHERE user_id = $1 ORDER BY created_at DESC",
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(alerts)
+}
+
+/// Finds a user's alert.
+pub async fn find_alert(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<Alert>, sqlx::Error> {
+    let alert = sqlx::query_as!(
+        Alert,
+        "SELECT * FROM alerts WHERE id = $1 AND user_id = $2",
+        id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(alert)
+}
+
+/// Deletes a user's alert. Returns `false` when there was nothing to delete.
+pub async fn delete_alert(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM alerts WHERE id = $1 AND user_id = $2",
+        id,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lists the active alerts on a symbol.
+pub async fn list_active_alerts_for_symbol(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<Vec<Alert>, sqlx::Error> {
+    let alerts = sqlx::query_as!(
+        Alert,
+        "SELECT * FROM alerts WHERE symbol = $1 AND is_active",
+        symbol
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(alerts)
+}
+
+/// Arms level alerts whose price moved back to the near side of their level.
+pub async fn arm_alerts(
+    pool: &PgPool,
+    ids: &[Uuid],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE alerts SET armed = TRUE, updated_at = NOW() WHERE id = ANY($1) AND is_active",
+        ids
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Records that an alert fired and stores its state for the next evaluation.
+/// Returns `None` when the alert was changed, disarmed or deactivated since it was loaded.
+pub async fn trigger_alert(
+    conn: &mut PgConnection,
+    id: Uuid,
+    price_cents: i64,
+    triggered_at: DateTime<Utc>,
+    is_active: bool,
+    armed: bool,
+    reference_price_cents: Option<i64>,
+) -> Result<Option<Alert>, sqlx::Error> {
+    let alert = sqlx::query_as!(
+        Alert,
+        r#"
+        UPDATE alerts
+        SET trigger_count = trigger_count + 1, last_triggered_at = $3, last_triggered_price_cents = $2,
+            is_active = $4, armed = $5, reference_price_cents = $6, updated_at = $3
+        WHERE id = $1 AND is_active AND armed
+        RETURNING *
+        "#,
+        id,
+        price_cents,
+        triggered_at,
+        is_active,
+        armed,
+        reference_price_cents
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(alert)
+}
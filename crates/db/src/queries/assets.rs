@@ -0,0 +1,167 @@
+//! Asset catalog database queries.
+//! Handles listing, lookup and admin management of tradable assets.
+
+use sqlx::PgPool;
+use chrono::Utc;
+use crate::models::Asset;
+
+/// Asset fields supplied when adding an asset to the catalog.
+#[derive(Debug, Clone)]
+pub struct NewAsset<'a> {
+    /// Trading symbol.
+    pub symbol: &'a str,
+    /// Human-readable asset name.
+    pub name: &'a str,
+    /// On-chain token decimals.
+    pub decimals: i16,
+    /// Price increment in cents.
+    pub tick_size_cents: i64,
+    /// Quantity increment in micro units.
+    pub lot_size: i64,
+    /// Minimum order value in cents.
+    pub min_notional_cents: i64,
+    /// Trading status.
+    pub status: &'a str,
+    /// Chain the asset lives on.
+    pub chain: Option<&'a str>,
+    /// Token contract address.
+    pub contract_address: Option<&'a str>,
+}
+
+/// Asset fields that can be changed. `None` keeps the current value.
+#[derive(Debug, Clone, Default)]
+pub struct AssetUpdate<'a> {
+    /// Human-readable asset name.
+    pub name: Option<&'a str>,
+    /// On-chain token decimals.
+    pub decimals: Option<i16>,
+    /// Price increment in cents.
+    pub tick_size_cents: Option<i64>,
+    /// Quantity increment in micro units.
+    pub lot_size: Option<i64>,
+    /// Minimum order value in cents.
+    pub min_notional_cents: Option<i64>,
+    /// Trading status.
+    pub status: Option<&'a str>,
+    /// Chain the asset lives on.
+    pub chain: Option<&'a str>,
+    /// Token contract address.
+    pub contract_address: Option<&'a str>,
+}
+
+/// Lists assets ordered by symbol, optionally filtered by status.
+pub async fn list_assets(
+    pool: &PgPool,
+    status: Option<&str>,
+) -> Result<Vec<Asset>, sqlx::Error> {
+    let assets = sqlx::query_as!(
+        Asset,
+        "SELECT * FROM assets WHERE ($1::VARCHAR IS NULL OR status = $1) ORDER BY symbol",
+        status
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(assets)
+}
+
+/// Finds an asset by its symbol.
+pub async fn find_asset(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<Option<Asset>, sqlx::Error> {
+    let asset = sqlx::query_as!(
+        Asset,
+        "SELECT * FROM assets WHERE symbol = $1",
+        symbol
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(asset)
+}
+
+/// Adds a new asset to the catalog.
+pub async fn create_asset(
+    pool: &PgPool,
+    asset: &NewAsset<'_>,
+) -> Result<Asset, sqlx::Error> {
+    let now = Utc::now();
+
+    let asset = sqlx::query_as!(
+        Asset,
+        r#"
+        INSERT INTO assets (symbol, name, decimals, tick_size_cents, lot_size, min_notional_cents, status, chain, contract_address, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+        asset.symbol,
+        asset.name,
+        asset.decimals,
+        asset.tick_size_cents,
+        asset.lot_size,
+        asset.min_notional_cents,
+        asset.status,
+        asset.chain,
+        asset.contract_address,
+        now,
+        now
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(asset)
+}
+
+/// Updates an asset's trading rules or status.
+/// Returns `None` when the asset does not exist.
+pub async fn update_asset(
+    pool: &PgPool,
+    symbol: &str,
+    update: &AssetUpdate<'_>,
+) -> Result<Option<Asset>, sqlx::Error> {
+    let asset = sqlx::query_as!(
+        Asset,
+        r#"
+        UPDATE assets
+        SET name = COALESCE($2, name),
+            decimals = COALESCE($3, decimals),
+            tick_size_cents = COALESCE($4, tick_size_cents),
+            lot_size = COALESCE($5, lot_size),
+            min_notional_cents = COALESCE($6, min_notional_cents),
+            status = COALESCE($7, status),
+            chain = COALESCE($8, chain),
+            contract_address = COALESCE($9, contract_address),
+            updated_at = $10
+        WHERE symbol = $1
+        RETURNING *
+        "#,
+        symbol,
+        update.name,
+        update.decimals,
+        update.tick_size_cents,
+        update.lot_size,
+        update.min_notional_cents,
+        update.status,
+        update.chain,
+        update.contract_address,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(asset)
+}
+
+/// Removes an asset from the catalog.
+/// Fails with a foreign key violation if the asset has trades or positions.
+pub async fn delete_asset(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM assets WHERE symbol = $1", symbol)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,329 @@
+//! Trading bot database queries.
+//! Handles bot definitions, scheduler bookkeeping and per-bot trade statistics.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::Bot;
+
+/// Bot fields supplied when a user creates a bot.
+/// Only the settings of the bot's kind are set.
+#[derive(Debug, Clone, Default)]
+pub struct NewBot<'a> {
+    /// User who owns the bot.
+    pub user_id: Uuid,
+    /// Display name.
+    pub name: &'a str,
+    /// Bot type: "dca" or "grid".
+    pub kind: &'a str,
+    /// Trading symbol.
+    pub symbol: &'a str,
+    /// Amount spent per DCA buy in cents.
+    pub amount_cents: Option<i64>,
+    /// Seconds between DCA buys.
+    pub interval_seconds: Option<i32>,
+    /// When the first DCA buy is due.
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// Lowest grid line in cents.
+    pub grid_lower_cents: Option<i64>,
+    /// Highest grid line in cents.
+    pub grid_upper_cents: Option<i64>,
+    /// Number of grid lines.
+    pub grid_levels: Option<i16>,
+    /// Quantity per crossed grid line in micro units.
+    pub grid_quantity: Option<i64>,
+    /// Grid lines at or below the price at creation.
+    pub grid_level: Option<i16>,
+}
+
+/// Aggregated trades of a bot.
+#[derive(Debug, Clone, Default)]
+pub struct BotTradeStats {
+    /// Bot the trades belong to.
+    pub bot_id: Uuid,
+    /// Number of trades placed.
+    pub trade_count: i64,
+    /// Quantity bought in micro units.
+    pub bought_quantity: i64,
+    /// Quantity sold in micro units.
+    pub sold_quantity: i64,
+    /// Cash spent on buys including fees, in cents.
+    pub buy_cost_cents: i64,
+    /// Cash received from sells after fees, in cents.
+    pub sell_proceeds_cents: i64,
+    /// Fees paid in cents.
+    pub fees_cents: i64,
+}
+
+/// Creates a bot.
+pub async fn create_bot(
+    pool: &PgPool,
+    bot: &NewBot<'_>,
+) -> Result<Bot, sqlx::Error> {
+    let now = Utc::now();
+    let bot = sqlx::query_as!(
+        Bot,
+        r#"
+        INSERT INTO bots (id, user_id, name, kind, symbol, status, amount_cents, interval_seconds, next_run_at,
+                          grid_lower_cents, grid_upper_cents, grid_levels, grid_quantity, grid_level, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, 'active', $6, $7, $8, $9, $10, $11, $12, $13, $14, $14)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        bot.user_id,
+        bot.name,
+        bot.kind,
+        bot.symbol,
+        bot.amount_cents,
+        bot.interval_seconds,
+        bot.next_run_at,
+        bot.grid_lower_cents,
+        bot.grid_upper_cents,
+        bot.grid_levels,
+        bot.grid_quantity,
+        bot.grid_level,
+        now
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(bot)
+}
+
+/// Counts the bots owned by a user.
+pub async fn count_bots(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM bots WHERE user_id = $1"#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Lists a user's bots, oldest first.
+pub async fn list_bots(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<Bot>, sqlx::Error> {
+    let bots = sqlx::query_as!(
+        Bot,
+        "SELECT * FROM bots WHERE user_id = $1 ORDER BY created_at",
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(bots)
+}
+
+/// Finds a bot owned by a user.
+pub async fn find_bot(
+    pool: &PgPool,
+    user_id: Uuid,
+    bot_id: Uuid,
+) -> Result<Option<Bot>, sqlx::Error> {
+    let bot = sqlx::query_as!(
+        Bot,
+        "SELECT * FROM bots WHERE id = $1 AND user_id = $2",
+        bot_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(bot)
+}
+
+/// Pauses a bot owned by a user.
+/// Returns `None` when the bot does not exist.
+pub async fn pause_bot(
+    pool: &PgPool,
+    user_id: Uuid,
+    bot_id: Uuid,
+) -> Result<Option<Bot>, sqlx::Error> {
+    let bot = sqlx::query_as!(
+        Bot,
+        r#"
+        UPDATE bots
+        SET status = 'paused', updated_at = $3
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+        bot_id,
+        user_id,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(bot)
+}
+
+/// Resumes a bot owned by a user from a fresh schedule or grid position.
+/// `None` values keep the stored setting. Returns `None` when the bot does not exist.
+pub async fn resume_bot(
+    pool: &PgPool,
+    user_id: Uuid,
+    bot_id: Uuid,
+    next_run_at: Option<DateTime<Utc>>,
+    grid_level: Option<i16>,
+) -> Result<Option<Bot>, sqlx::Error> {
+    let bot = sqlx::query_as!(
+        Bot,
+        r#"
+        UPDATE bots
+        SET status = 'active',
+            next_run_at = COALESCE($3, next_run_at),
+            grid_level = COALESCE($4, grid_level),
+            last_error = NULL,
+            updated_at = $5
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+        bot_id,
+        user_id,
+        next_run_at,
+        grid_level,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(bot)
+}
+
+/// Deletes a bot owned by a user. Its trades are kept without the bot reference.
+pub async fn delete_bot(
+    pool: &PgPool,
+    user_id: Uuid,
+    bot_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM bots WHERE id = $1 AND user_id = $2",
+        bot_id,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lists active DCA bots whose next buy is due.
+pub async fn list_due_dca_bots(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<Bot>, sqlx::Error> {
+    let bots = sqlx::query_as!(
+        Bot,
+        r#"
+        SELECT * FROM bots
+        WHERE status = 'active' AND kind = 'dca' AND next_run_at <= $1
+        ORDER BY next_run_at
+        "#,
+        now
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(bots)
+}
+
+/// Lists all active grid bots.
+pub async fn list_active_grid_bots(
+    pool: &PgPool,
+) -> Result<Vec<Bot>, sqlx::Error> {
+    let bots = sqlx::query_as!(
+        Bot,
+        "SELECT * FROM bots WHERE status = 'active' AND kind = 'grid' ORDER BY created_at"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(bots)
+}
+
+/// Records the outcome of a scheduler run.
+/// `None` for `next_run_at` or `grid_level` keeps the stored value; `last_error` is always replaced.
+pub async fn record_bot_run(
+    pool: &PgPool,
+    bot_id: Uuid,
+    next_run_at: Option<DateTime<Utc>>,
+    grid_level: Option<i16>,
+    last_error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    sqlx::query!(
+        r#"
+        UPDATE bots
+        SET next_run_at = COALESCE($2, next_run_at),
+            grid_level = COALESCE($3, grid_level),
+            last_error = $4,
+            last_run_at = $5,
+            updated_at = $5
+        WHERE id = $1
+        "#,
+        bot_id,
+        next_run_at,
+        grid_level,
+        last_error,
+        now
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Aggregates the trades of every bot owned by a user.
+/// Bots without trades are omitted.
+pub async fn list_bot_trade_stats(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<BotTradeStats>, sqlx::Error> {
+    let stats = sqlx::query_as!(
+        BotTradeStats,
+        r#"
+        SELECT
+            bot_id AS "bot_id!",
+            COUNT(*) AS "trade_count!",
+            COALESCE(SUM(quantity) FILTER (WHERE trade_type = 'buy'), 0)::BIGINT AS "bought_quantity!",
+            COALESCE(SUM(quantity) FILTER (WHERE trade_type = 'sell'), 0)::BIGINT AS "sold_quantity!",
+            COALESCE(SUM(total_value + fee_cents) FILTER (WHERE trade_type = 'buy'), 0)::BIGINT AS "buy_cost_cents!",
+            COALESCE(SUM(total_value - fee_cents) FILTER (WHERE trade_type = 'sell'), 0)::BIGINT AS "sell_proceeds_cents!",
+            COALESCE(SUM(fee_cents), 0)::BIGINT AS "fees_cents!"
+        FROM trades
+        WHERE user_id = $1 AND bot_id IS NOT NULL
+        GROUP BY bot_id
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stats)
+}
+
+/// Returns the quantity a bot currently holds from its own trades, in micro units.
+pub async fn bot_held_quantity(
+    pool: &PgPool,
+    bot_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    let held = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(CASE WHEN trade_type = 'buy' THEN quantity ELSE -quantity END), 0)::BIGINT AS "held!"
+        FROM trades
+        WHERE bot_id = $1
+        "#,
+        bot_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(held)
+}
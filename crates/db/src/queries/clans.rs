@@ -0,0 +1,630 @@
+//! Clan database queries.
+//! Manages clans and their members, the materialized clan leaderboards and clan tournament entries.
+
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::{Clan, ClanLeaderboardEntry, ClanMember, TournamentClan};
+
+/// Clan to be created.
+#[derive(Debug, Clone)]
+pub struct NewClan<'a> {
+    /// Display name.
+    pub name: &'a str,
+    /// Short uppercase tag.
+    pub tag: &'a str,
+    /// Description shown on the clan page.
+    pub description: Option<&'a str>,
+    /// Code players join with.
+    pub invite_code: &'a str,
+    /// Founder, who becomes the owner.
+    pub created_by: Uuid,
+}
+
+/// Clan with its member count.
+#[derive(Debug, Clone)]
+pub struct ClanSummary {
+    /// Clan.
+    pub clan: Clan,
+    /// Current members.
+    pub members: i64,
+}
+
+/// Member with their public profile.
+#[derive(Debug, Clone)]
+pub struct ClanMemberRow {
+    /// Membership.
+    pub member: ClanMember,
+    /// Member's display name.
+    pub username: Option<String>,
+    /// Member's wallet address.
+    pub wallet_address: String,
+    /// Member's lifetime XP.
+    pub xp_points: i32,
+}
+
+/// Clan's computed position on a leaderboard.
+#[derive(Debug, Clone, Copy)]
+pub struct RankedClan {
+    /// Ranked clan.
+    pub clan_id: Uuid,
+    /// Position. Ties share a rank.
+    pub rank: i32,
+    /// Score in the metric's unit.
+    pub score: f64,
+    /// Members when computed.
+    pub members: i32,
+}
+
+/// Clan leaderboard entry with the clan's name and tag.
+#[derive(Debug, Clone)]
+pub struct ClanLeaderboardRow {
+    /// Materialized entry.
+    pub entry: ClanLeaderboardEntry,
+    /// Clan's display name.
+    pub name: String,
+    /// Clan's tag.
+    pub tag: String,
+}
+
+/// Clan's place in a tournament's clan standings.
+#[derive(Debug, Clone)]
+pub struct ClanStanding {
+    /// Clan.
+    pub clan_id: Uuid,
+    /// Clan's display name.
+    pub name: String,
+    /// Clan's tag.
+    pub tag: String,
+    /// Rank by mean entrant account value. Ties share a rank; clans without entrants come last.
+    pub rank: i64,
+    /// Members playing for the clan.
+    pub entrants: i64,
+    /// Mean account value of the entrants in cents, absent without entrants.
+    pub value_cents: Option<i64>,
+}
+
+/// Unique indexes that reject a clan whose name, ignoring case, or tag is taken.
+pub const CLAN_NAME_INDEXES: [&str; 2] = ["idx_clans_name", "idx_clans_tag"];
+
+/// Unique constraint that rejects an invite code held by another clan.
+pub const INVITE_CODE_CONSTRAINT: &str = "clans_invite_code_key";
+
+/// Creates a clan.
+/// Returns `None` when the invite code is already taken. A taken name or tag fails with a unique
+/// violation of one of `CLAN_NAME_INDEXES`.
+pub async fn create_clan(conn: &mut PgConnection, clan: &NewClan<'_>) -> Result<Option<Clan>, sqlx::Error> {
+    let now = Utc::now();
+    let clan = sqlx::query_as!(
+        Clan,
+        r#"
+        INSERT INTO clans (id, name, tag, description, invite_code, created_by, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+        ON CONFLICT (invite_code) DO NOTHING
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        clan.name,
+        clan.tag,
+        clan.description,
+        clan.invite_code,
+        clan.created_by,
+        now
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(clan)
+}
+
+/// Finds a clan by ID.
+pub async fn find_clan(executor: impl PgExecutor<'_>, clan_id: Uuid) -> Result<Option<Clan>, sqlx::Error> {
+    let clan = sqlx::query_as!(Clan, "SELECT * FROM clans WHERE id = $1", clan_id)
+        .fetch_optional(executor)
+        .await?;
+
+    Ok(clan)
+}
+
+/// Loads a clan and locks it for the rest of the transaction, serializing membership changes.
+pub async fn lock_clan(conn: &mut PgConnection, clan_id: Uuid) -> Result<Option<Clan>, sqlx::Error> {
+    let clan = sqlx::query_as!(Clan, "SELECT * FROM clans WHERE id = $1 FOR UPDATE", clan_id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(clan)
+}
+
+/// Finds the clan an invite code belongs to and locks it for the rest of the transaction.
+pub async fn lock_clan_by_invite_code(conn: &mut PgConnection, invite_code: &str) -> Result<Option<Clan>, sqlx::Error> {
+    let clan = sqlx::query_as!(Clan, "SELECT * FROM clans WHERE invite_code = $1 FOR UPDATE", invite_code)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(clan)
+}
+
+/// Lists a page of clans with their member counts, largest first.
+/// `search` matches the name or tag, ignoring case.
+pub async fn list_clans(
+    pool: &PgPool,
+    search: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ClanSummary>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT c.id, c.name, c.tag, c.description, c.invite_code, c.created_by, c.created_at, c.updated_at,
+               (SELECT COUNT(*) FROM clan_members m WHERE m.clan_id = c.id) AS "members!"
+        FROM clans c
+        WHERE $1::TEXT IS NULL OR c.name ILIKE '%' || $1 || '%' OR c.tag ILIKE '%' || $1 || '%'
+        ORDER BY 9 DESC, c.name
+        LIMIT $2 OFFSET $3
+        "#,
+        search,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ClanSummary {
+            clan: Clan {
+                id: row.id,
+                name: row.name,
+                tag: row.tag,
+                description: row.description,
+                invite_code: row.invite_code,
+                created_by: row.created_by,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            },
+            members: row.members,
+        })
+        .collect())
+}
+
+/// Replaces a clan's invite code, invalidating the old one.
+/// Fails with a unique violation of `INVITE_CODE_CONSTRAINT` when another clan holds the code.
+pub async fn set_invite_code(conn: &mut PgConnection, clan_id: Uuid, invite_code: &str) -> Result<Clan, sqlx::Error> {
+    let clan = sqlx::query_as!(
+        Clan,
+        "UPDATE clans SET invite_code = $2, updated_at = $3 WHERE id = $1 RETURNING *",
+        clan_id,
+        invite_code,
+        Utc::now()
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(clan)
+}
+
+/// Deletes a clan with its memberships and standings.
+pub async fn delete_clan(conn: &mut PgConnection, clan_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM clans WHERE id = $1", clan_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Adds a member to a clan.
+/// Returns `None` when the user already belongs to a clan.
+pub async fn add_member(
+    conn: &mut PgConnection,
+    clan_id: Uuid,
+    user_id: Uuid,
+    role: &str,
+) -> Result<Option<ClanMember>, sqlx::Error> {
+    let member = sqlx::query_as!(
+        ClanMember,
+        r#"
+        INSERT INTO clan_members (clan_id, user_id, role, joined_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT DO NOTHING
+        RETURNING *
+        "#,
+        clan_id,
+        user_id,
+        role,
+        Utc::now()
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(member)
+}
+
+/// Finds the clan membership of a user.
+pub async fn find_membership(executor: impl PgExecutor<'_>, user_id: Uuid) -> Result<Option<ClanMember>, sqlx::Error> {
+    let member = sqlx::query_as!(ClanMember, "SELECT * FROM clan_members WHERE user_id = $1", user_id)
+        .fetch_optional(executor)
+        .await?;
+
+    Ok(member)
+}
+
+/// Counts a clan's members.
+pub async fn count_members(executor: impl PgExecutor<'_>, clan_id: Uuid) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM clan_members WHERE clan_id = $1"#,
+        clan_id
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(count)
+}
+
+/// Lists a clan's members with their profiles, owner and officers first.
+pub async fn list_members(executor: impl PgExecutor<'_>, clan_id: Uuid) -> Result<Vec<ClanMemberRow>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT m.clan_id, m.user_id, m.role, m.joined_at, u.username, u.wallet_address, u.xp_points
+        FROM clan_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.clan_id = $1
+        ORDER BY CASE m.role WHEN 'owner' THEN 0 WHEN 'officer' THEN 1 ELSE 2 END, m.joined_at, m.user_id
+        "#,
+        clan_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ClanMemberRow {
+            member: ClanMember {
+                clan_id: row.clan_id,
+                user_id: row.user_id,
+                role: row.role,
+                joined_at: row.joined_at,
+            },
+            username: row.username,
+            wallet_address: row.wallet_address,
+            xp_points: row.xp_points,
+        })
+        .collect())
+}
+
+/// Finds the member with the highest role other than `user_id`, longest-standing first.
+/// Used to pick the next owner.
+pub async fn find_successor(
+    executor: impl PgExecutor<'_>,
+    clan_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<ClanMember>, sqlx::Error> {
+    let member = sqlx::query_as!(
+        ClanMember,
+        r#"
+        SELECT * FROM clan_members
+        WHERE clan_id = $1 AND user_id <> $2
+        ORDER BY CASE role WHEN 'officer' THEN 0 ELSE 1 END, joined_at, user_id
+        LIMIT 1
+        "#,
+        clan_id,
+        user_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(member)
+}
+
+/// Removes a member from a clan.
+/// Returns `false` if the user was not a member.
+pub async fn remove_member(conn: &mut PgConnection, clan_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM clan_members WHERE clan_id = $1 AND user_id = $2",
+        clan_id,
+        user_id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Changes a member's role.
+pub async fn set_role(
+    conn: &mut PgConnection,
+    clan_id: Uuid,
+    user_id: Uuid,
+    role: &str,
+) -> Result<Option<ClanMember>, sqlx::Error> {
+    let member = sqlx::query_as!(
+        ClanMember,
+        "UPDATE clan_members SET role = $3 WHERE clan_id = $1 AND user_id = $2 RETURNING *",
+        clan_id,
+        user_id,
+        role
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(member)
+}
+
+/// Lists every clan membership as (clan, user, XP) with the XP the member earned between `since`,
+/// or ever when `None`, and `until`, counting only XP earned since they joined.
+/// Ledger entries from `excluded_source`, e.g. season resets, are left out.
+pub async fn list_members_with_xp(
+    pool: &PgPool,
+    since: Option<DateTime<Utc>>,
+    until: DateTime<Utc>,
+    excluded_source: &str,
+) -> Result<Vec<(Uuid, Uuid, i64)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT m.clan_id, m.user_id, COALESCE(SUM(e.amount), 0)::BIGINT AS "xp!"
+        FROM clan_members m
+        LEFT JOIN xp_events e
+            ON e.user_id = m.user_id
+            AND e.created_at >= GREATEST(m.joined_at, $1)
+            AND e.created_at < $2
+            AND e.source <> $3
+        GROUP BY m.clan_id, m.user_id
+        "#,
+        since,
+        until,
+        excluded_source
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.clan_id, row.user_id, row.xp)).collect())
+}
+
+/// Replaces a clan leaderboard with freshly computed entries in one transaction.
+pub async fn replace_clan_leaderboard(
+    pool: &PgPool,
+    metric: &str,
+    period: &str,
+    ranked: &[RankedClan],
+    computed_at: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let clan_ids: Vec<Uuid> = ranked.iter().map(|clan| clan.clan_id).collect();
+    let ranks: Vec<i32> = ranked.iter().map(|clan| clan.rank).collect();
+    let scores: Vec<f64> = ranked.iter().map(|clan| clan.score).collect();
+    let members: Vec<i32> = ranked.iter().map(|clan| clan.members).collect();
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "DELETE FROM clan_leaderboard_entries WHERE metric = $1 AND period = $2",
+        metric,
+        period
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Clans deleted since the activity was loaded are skipped
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO clan_leaderboard_entries (metric, period, clan_id, rank, score, members, computed_at)
+        SELECT $1, $2, t.clan_id, t.rank, t.score, t.members, $7
+        FROM UNNEST($3::UUID[], $4::INTEGER[], $5::FLOAT8[], $6::INTEGER[]) AS t(clan_id, rank, score, members)
+        WHERE EXISTS (SELECT 1 FROM clans c WHERE c.id = t.clan_id)
+        "#,
+        metric,
+        period,
+        &clan_ids,
+        &ranks,
+        &scores,
+        &members,
+        computed_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Lists a page of a clan leaderboard, best first.
+pub async fn list_clan_leaderboard(
+    pool: &PgPool,
+    metric: &str,
+    period: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ClanLeaderboardRow>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT l.metric, l.period, l.clan_id, l.rank, l.score, l.members, l.computed_at, c.name, c.tag
+        FROM clan_leaderboard_entries l
+        JOIN clans c ON c.id = l.clan_id
+        WHERE l.metric = $1 AND l.period = $2
+        ORDER BY l.rank, l.clan_id
+        LIMIT $3 OFFSET $4
+        "#,
+        metric,
+        period,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ClanLeaderboardRow {
+            entry: ClanLeaderboardEntry {
+                metric: row.metric,
+                period: row.period,
+                clan_id: row.clan_id,
+                rank: row.rank,
+                score: row.score,
+                members: row.members,
+                computed_at: row.computed_at,
+            },
+            name: row.name,
+            tag: row.tag,
+        })
+        .collect())
+}
+
+/// Counts the clans ranked on a leaderboard.
+pub async fn count_clan_leaderboard_entries(pool: &PgPool, metric: &str, period: &str) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM clan_leaderboard_entries WHERE metric = $1 AND period = $2"#,
+        metric,
+        period
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Lists a clan's entries on every leaderboard.
+pub async fn list_clan_ranks(pool: &PgPool, clan_id: Uuid) -> Result<Vec<ClanLeaderboardEntry>, sqlx::Error> {
+    let entries = sqlx::query_as!(
+        ClanLeaderboardEntry,
+        "SELECT * FROM clan_leaderboard_entries WHERE clan_id = $1 ORDER BY metric, period",
+        clan_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Enters a clan into a tournament and marks its members' existing entries as playing for it.
+/// Returns `None` when the clan was already entered.
+pub async fn enter_tournament(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    clan_id: Uuid,
+    entered_by: Uuid,
+) -> Result<Option<TournamentClan>, sqlx::Error> {
+    let entry = sqlx::query_as!(
+        TournamentClan,
+        r#"
+        INSERT INTO tournament_clans (tournament_id, clan_id, entered_by, entered_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (tournament_id, clan_id) DO NOTHING
+        RETURNING *
+        "#,
+        tournament_id,
+        clan_id,
+        entered_by,
+        Utc::now()
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if entry.is_some() {
+        sqlx::query!(
+            r#"
+            UPDATE tournament_entries e SET clan_id = $2
+            FROM clan_members m
+            WHERE e.tournament_id = $1 AND e.clan_id IS NULL AND m.clan_id = $2 AND m.user_id = e.user_id
+            "#,
+            tournament_id,
+            clan_id
+        )
+        .execute(conn)
+        .await?;
+    }
+
+    Ok(entry)
+}
+
+/// Ranks the clans entered in a tournament by their entrants' mean account value at the given prices.
+pub async fn live_clan_standings(
+    pool: &PgPool,
+    tournament_id: Uuid,
+    symbols: &[String],
+    prices_cents: &[i64],
+) -> Result<Vec<ClanStanding>, sqlx::Error> {
+    let standings = sqlx::query_as!(
+        ClanStanding,
+        r#"
+        WITH entrant_values AS (
+            SELECT e.clan_id,
+                   (e.cash_balance_cents + COALESCE(SUM(
+                       ROUND(p.quantity::NUMERIC * COALESCE(px.price_cents, p.average_price) / 1000000)
+                   ), 0))::BIGINT AS value_cents
+            FROM tournament_entries e
+            LEFT JOIN tournament_positions p ON p.tournament_id = e.tournament_id AND p.user_id = e.user_id
+            LEFT JOIN UNNEST($2::VARCHAR[], $3::BIGINT[]) AS px(symbol, price_cents) ON px.symbol = p.symbol
+            WHERE e.tournament_id = $1 AND e.clan_id IS NOT NULL
+            GROUP BY e.user_id, e.clan_id, e.cash_balance_cents
+        ),
+        clan_values AS (
+            SELECT tc.clan_id, COUNT(v.value_cents) AS entrants, ROUND(AVG(v.value_cents))::BIGINT AS value_cents
+            FROM tournament_clans tc
+            LEFT JOIN entrant_values v ON v.clan_id = tc.clan_id
+            WHERE tc.tournament_id = $1
+            GROUP BY tc.clan_id
+        )
+        SELECT s.clan_id AS "clan_id!", c.name, c.tag,
+               RANK() OVER (ORDER BY s.value_cents DESC NULLS LAST) AS "rank!",
+               s.entrants AS "entrants!", s.value_cents
+        FROM clan_values s
+        JOIN clans c ON c.id = s.clan_id
+        ORDER BY 4, c.name
+        "#,
+        tournament_id,
+        symbols,
+        prices_cents
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(standings)
+}
+
+/// Lists the recorded clan ranking of a finalized tournament, best first.
+pub async fn final_clan_standings(pool: &PgPool, tournament_id: Uuid) -> Result<Vec<ClanStanding>, sqlx::Error> {
+    let standings = sqlx::query_as!(
+        ClanStanding,
+        r#"
+        SELECT tc.clan_id, c.name, c.tag, tc.final_rank::BIGINT AS "rank!",
+               (SELECT COUNT(*) FROM tournament_entries e
+                WHERE e.tournament_id = tc.tournament_id AND e.clan_id = tc.clan_id) AS "entrants!",
+               tc.final_value_cents AS value_cents
+        FROM tournament_clans tc
+        JOIN clans c ON c.id = tc.clan_id
+        WHERE tc.tournament_id = $1 AND tc.final_rank IS NOT NULL
+        ORDER BY tc.final_rank, c.name
+        "#,
+        tournament_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(standings)
+}
+
+/// Records every entered clan's final mean account value and rank from its entrants' final values.
+/// Must run after the entrants' final ranking has been recorded.
+pub async fn record_clan_ranking(conn: &mut PgConnection, tournament_id: Uuid) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE tournament_clans tc
+        SET final_value_cents = r.value_cents, final_rank = r.rank
+        FROM (
+            SELECT v.clan_id, v.value_cents,
+                   RANK() OVER (ORDER BY v.value_cents DESC NULLS LAST)::INTEGER AS rank
+            FROM (
+                SELECT tc.clan_id, ROUND(AVG(e.final_value_cents))::BIGINT AS value_cents
+                FROM tournament_clans tc
+                LEFT JOIN tournament_entries e ON e.tournament_id = tc.tournament_id AND e.clan_id = tc.clan_id
+                WHERE tc.tournament_id = $1
+                GROUP BY tc.clan_id
+            ) v
+        ) r
+        WHERE tc.tournament_id = $1 AND tc.clan_id = r.clan_id
+        "#,
+        tournament_id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,547 @@
+//! Duel database queries.
+//! Manages challenges, the players' isolated duel accounts and settlement results.
+
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::{Duel, DuelAccount, DuelPosition, DuelTrade};
+
+/// Challenge to be recorded.
+#[derive(Debug, Clone)]
+pub struct NewDuel<'a> {
+    /// User issuing the challenge.
+    pub challenger_id: Uuid,
+    /// User being challenged.
+    pub opponent_id: Uuid,
+    /// Paper cash both players start with, in cents.
+    pub starting_balance_cents: i64,
+    /// Symbols both players may trade.
+    pub allowed_symbols: &'a [String],
+    /// Trading time once accepted, in hours.
+    pub duration_hours: i32,
+    /// XP each player puts up.
+    pub stake_xp: i32,
+    /// When the challenge expires if unanswered.
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Duel trade row to be inserted after an order has been filled.
+#[derive(Debug, Clone)]
+pub struct NewDuelTrade<'a> {
+    /// Duel traded in.
+    pub duel_id: Uuid,
+    /// Player who traded.
+    pub user_id: Uuid,
+    /// Trading symbol.
+    pub symbol: &'a str,
+    /// Trade type: "buy" or "sell".
+    pub trade_type: &'a str,
+    /// Quantity in micro units.
+    pub quantity: i64,
+    /// Fill price in cents, including slippage.
+    pub price: i64,
+    /// Quantity times fill price in cents.
+    pub total_value: i64,
+    /// Fee charged in cents.
+    pub fee_cents: i64,
+}
+
+/// Public profile of a duel player.
+#[derive(Debug, Clone)]
+pub struct DuelPlayer {
+    /// Player.
+    pub user_id: Uuid,
+    /// Player's display name.
+    pub username: Option<String>,
+    /// Player's wallet address.
+    pub wallet_address: String,
+}
+
+/// User's settled duel history.
+#[derive(Debug, Clone, Default)]
+pub struct DuelRecord {
+    /// Duels won.
+    pub wins: i64,
+    /// Duels lost.
+    pub losses: i64,
+    /// Duels drawn.
+    pub draws: i64,
+    /// XP won minus XP lost through stakes.
+    pub net_xp: i64,
+}
+
+/// Records a pending challenge.
+/// Returns `None` when the two players already have a pending or active duel.
+pub async fn create_duel(pool: &PgPool, duel: &NewDuel<'_>) -> Result<Option<Duel>, sqlx::Error> {
+    let duel = sqlx::query_as!(
+        Duel,
+        r#"
+        INSERT INTO duels (
+            id, challenger_id, opponent_id, status, starting_balance_cents, allowed_symbols,
+            duration_hours, stake_xp, created_at, expires_at
+        )
+        VALUES ($1, $2, $3, 'pending', $4, $5, $6, $7, $8, $9)
+        ON CONFLICT DO NOTHING
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        duel.challenger_id,
+        duel.opponent_id,
+        duel.starting_balance_cents,
+        duel.allowed_symbols,
+        duel.duration_hours,
+        duel.stake_xp,
+        Utc::now(),
+        duel.expires_at
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(duel)
+}
+
+/// Finds a duel by id.
+pub async fn find_duel(pool: &PgPool, duel_id: Uuid) -> Result<Option<Duel>, sqlx::Error> {
+    let duel = sqlx::query_as!(Duel, "SELECT * FROM duels WHERE id = $1", duel_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(duel)
+}
+
+/// Loads a duel and locks it for the rest of the transaction.
+/// Serializes answers to the challenge and settlement.
+pub async fn lock_duel(conn: &mut PgConnection, duel_id: Uuid) -> Result<Option<Duel>, sqlx::Error> {
+    let duel = sqlx::query_as!(Duel, "SELECT * FROM duels WHERE id = $1 FOR UPDATE", duel_id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(duel)
+}
+
+/// Lists a page of the duels a user challenged or was challenged to, newest first.
+/// Only duels with the given status are returned when it is given.
+pub async fn list_user_duels(
+    pool: &PgPool,
+    user_id: Uuid,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Duel>, sqlx::Error> {
+    let duels = sqlx::query_as!(
+        Duel,
+        r#"
+        SELECT * FROM duels
+        WHERE (challenger_id = $1 OR opponent_id = $1) AND ($2::VARCHAR IS NULL OR status = $2)
+        ORDER BY created_at DESC, id
+        LIMIT $3 OFFSET $4
+        "#,
+        user_id,
+        status,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(duels)
+}
+
+/// Loads the public profiles of duel players.
+pub async fn find_players(pool: &PgPool, user_ids: &[Uuid]) -> Result<Vec<DuelPlayer>, sqlx::Error> {
+    let players = sqlx::query_as!(
+        DuelPlayer,
+        "SELECT id AS user_id, username, wallet_address FROM users WHERE id = ANY($1)",
+        user_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(players)
+}
+
+/// Locks a user and returns their current XP so stakes can be escrowed.
+pub async fn lock_user_xp(conn: &mut PgConnection, user_id: Uuid) -> Result<Option<i32>, sqlx::Error> {
+    let xp_points = sqlx::query_scalar!("SELECT xp_points FROM users WHERE id = $1 FOR UPDATE", user_id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(xp_points)
+}
+
+/// Records the opponent's answer to a pending challenge.
+pub async fn set_duel_status(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    status: &str,
+    responded_at: DateTime<Utc>,
+) -> Result<Duel, sqlx::Error> {
+    let duel = sqlx::query_as!(
+        Duel,
+        "UPDATE duels SET status = $2, responded_at = $3 WHERE id = $1 RETURNING *",
+        duel_id,
+        status,
+        responded_at
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(duel)
+}
+
+/// Starts an accepted duel's trading window.
+pub async fn activate_duel(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> Result<Duel, sqlx::Error> {
+    let duel = sqlx::query_as!(
+        Duel,
+        r#"
+        UPDATE duels
+        SET status = 'active', responded_at = $2, starts_at = $2, ends_at = $3
+        WHERE id = $1
+        RETURNING *
+        "#,
+        duel_id,
+        starts_at,
+        ends_at
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(duel)
+}
+
+/// Expires pending challenges that were not answered in time.
+/// Returns the number of challenges expired.
+pub async fn expire_pending_duels(pool: &PgPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        "UPDATE duels SET status = 'expired' WHERE status = 'pending' AND expires_at <= $1",
+        now
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Lists active duels whose trading has ended.
+pub async fn list_due_duels(pool: &PgPool, now: DateTime<Utc>) -> Result<Vec<Duel>, sqlx::Error> {
+    let duels = sqlx::query_as!(
+        Duel,
+        "SELECT * FROM duels WHERE status = 'active' AND ends_at <= $1 ORDER BY ends_at",
+        now
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(duels)
+}
+
+/// Locks every active duel, ended or not.
+/// Used to settle open duels before a season reset.
+pub async fn lock_active_duels(conn: &mut PgConnection) -> Result<Vec<Duel>, sqlx::Error> {
+    let duels = sqlx::query_as!(Duel, "SELECT * FROM duels WHERE status = 'active' ORDER BY id FOR UPDATE")
+        .fetch_all(conn)
+        .await?;
+
+    Ok(duels)
+}
+
+/// Records a duel's final values and winner.
+pub async fn settle_duel(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    winner_id: Option<Uuid>,
+    challenger_final_value_cents: i64,
+    opponent_final_value_cents: i64,
+    settled_at: DateTime<Utc>,
+) -> Result<Duel, sqlx::Error> {
+    let duel = sqlx::query_as!(
+        Duel,
+        r#"
+        UPDATE duels
+        SET status = 'settled', winner_id = $2, challenger_final_value_cents = $3,
+            opponent_final_value_cents = $4, settled_at = $5
+        WHERE id = $1
+        RETURNING *
+        "#,
+        duel_id,
+        winner_id,
+        challenger_final_value_cents,
+        opponent_final_value_cents,
+        settled_at
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(duel)
+}
+
+/// Summarizes a user's settled duels.
+pub async fn duel_record(pool: &PgPool, user_id: Uuid) -> Result<DuelRecord, sqlx::Error> {
+    let record = sqlx::query_as!(
+        DuelRecord,
+        r#"
+        SELECT COUNT(*) FILTER (WHERE winner_id = $1) AS "wins!",
+               COUNT(*) FILTER (WHERE winner_id IS NOT NULL AND winner_id <> $1) AS "losses!",
+               COUNT(*) FILTER (WHERE winner_id IS NULL) AS "draws!",
+               COALESCE(SUM(CASE
+                   WHEN winner_id = $1 THEN stake_xp
+                   WHEN winner_id IS NULL THEN 0
+                   ELSE -stake_xp
+               END), 0)::BIGINT AS "net_xp!"
+        FROM duels
+        WHERE (challenger_id = $1 OR opponent_id = $1) AND status = 'settled'
+        "#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(record)
+}
+
+/// Opens a player's duel account.
+pub async fn create_account(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    user_id: Uuid,
+    cash_balance_cents: i64,
+) -> Result<DuelAccount, sqlx::Error> {
+    let account = sqlx::query_as!(
+        DuelAccount,
+        "INSERT INTO duel_accounts (duel_id, user_id, cash_balance_cents) VALUES ($1, $2, $3) RETURNING *",
+        duel_id,
+        user_id,
+        cash_balance_cents
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(account)
+}
+
+/// Finds a player's duel account.
+pub async fn find_account(
+    executor: impl PgExecutor<'_>,
+    duel_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<DuelAccount>, sqlx::Error> {
+    let account = sqlx::query_as!(
+        DuelAccount,
+        "SELECT * FROM duel_accounts WHERE duel_id = $1 AND user_id = $2",
+        duel_id,
+        user_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(account)
+}
+
+/// Loads a player's duel account and locks it for the rest of the transaction.
+/// Prevents concurrent orders from spending the same duel cash twice.
+pub async fn lock_account(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<DuelAccount>, sqlx::Error> {
+    let account = sqlx::query_as!(
+        DuelAccount,
+        "SELECT * FROM duel_accounts WHERE duel_id = $1 AND user_id = $2 FOR UPDATE",
+        duel_id,
+        user_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(account)
+}
+
+/// Sets a player's duel cash balance.
+pub async fn update_account_cash(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    user_id: Uuid,
+    cash_balance_cents: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE duel_accounts SET cash_balance_cents = $3 WHERE duel_id = $1 AND user_id = $2",
+        duel_id,
+        user_id,
+        cash_balance_cents
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Values both players' duel accounts at the given prices.
+/// Positions in symbols without a price are valued at their average price.
+pub async fn account_values(
+    executor: impl PgExecutor<'_>,
+    duel_id: Uuid,
+    symbols: &[String],
+    prices_cents: &[i64],
+) -> Result<Vec<(Uuid, i64)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT a.user_id,
+               (a.cash_balance_cents + COALESCE(SUM(
+                   ROUND(p.quantity::NUMERIC * COALESCE(px.price_cents, p.average_price) / 1000000)
+               ), 0))::BIGINT AS "value_cents!"
+        FROM duel_accounts a
+        LEFT JOIN duel_positions p ON p.duel_id = a.duel_id AND p.user_id = a.user_id
+        LEFT JOIN UNNEST($2::VARCHAR[], $3::BIGINT[]) AS px(symbol, price_cents) ON px.symbol = p.symbol
+        WHERE a.duel_id = $1
+        GROUP BY a.user_id, a.cash_balance_cents
+        "#,
+        duel_id,
+        symbols,
+        prices_cents
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.user_id, row.value_cents)).collect())
+}
+
+/// Loads a player's position in a symbol and locks it for the rest of the transaction.
+pub async fn lock_position(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    user_id: Uuid,
+    symbol: &str,
+) -> Result<Option<DuelPosition>, sqlx::Error> {
+    let position = sqlx::query_as!(
+        DuelPosition,
+        "SELECT * FROM duel_positions WHERE duel_id = $1 AND user_id = $2 AND symbol = $3 FOR UPDATE",
+        duel_id,
+        user_id,
+        symbol
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(position)
+}
+
+/// Creates or replaces a player's position in a symbol.
+pub async fn upsert_position(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    user_id: Uuid,
+    symbol: &str,
+    quantity: i64,
+    average_price: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO duel_positions (duel_id, user_id, symbol, quantity, average_price, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (duel_id, user_id, symbol) DO UPDATE
+        SET quantity = EXCLUDED.quantity,
+            average_price = EXCLUDED.average_price,
+            updated_at = EXCLUDED.updated_at
+        "#,
+        duel_id,
+        user_id,
+        symbol,
+        quantity,
+        average_price,
+        Utc::now()
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes a player's position once it has been fully sold.
+pub async fn delete_position(
+    conn: &mut PgConnection,
+    duel_id: Uuid,
+    user_id: Uuid,
+    symbol: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM duel_positions WHERE duel_id = $1 AND user_id = $2 AND symbol = $3",
+        duel_id,
+        user_id,
+        symbol
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Lists a player's open duel positions.
+pub async fn list_positions(
+    executor: impl PgExecutor<'_>,
+    duel_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<DuelPosition>, sqlx::Error> {
+    let positions = sqlx::query_as!(
+        DuelPosition,
+        "SELECT * FROM duel_positions WHERE duel_id = $1 AND user_id = $2 ORDER BY symbol",
+        duel_id,
+        user_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(positions)
+}
+
+/// Records a duel fill.
+pub async fn insert_trade(conn: &mut PgConnection, trade: &NewDuelTrade<'_>) -> Result<DuelTrade, sqlx::Error> {
+    let trade = sqlx::query_as!(
+        DuelTrade,
+        r#"
+        INSERT INTO duel_trades (
+            id, duel_id, user_id, symbol, trade_type, quantity, price, total_value, fee_cents, executed_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        trade.duel_id,
+        trade.user_id,
+        trade.symbol,
+        trade.trade_type,
+        trade.quantity,
+        trade.price,
+        trade.total_value,
+        trade.fee_cents,
+        Utc::now()
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(trade)
+}
+
+/// Lists a player's most recent duel trades, newest first.
+pub async fn list_trades(pool: &PgPool, duel_id: Uuid, user_id: Uuid, limit: i64) -> Result<Vec<DuelTrade>, sqlx::Error> {
+    let trades = sqlx::query_as!(
+        DuelTrade,
+        r#"
+        SELECT * FROM duel_trades
+        WHERE duel_id = $1 AND user_id = $2
+        ORDER BY executed_at DESC
+        LIMIT $3
+        "#,
+        duel_id,
+        user_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(trades)
+}
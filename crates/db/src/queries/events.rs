@@ -0,0 +1,113 @@
+//! User event outbox database queries.
+//! Stores gamification events with the actions that raise them and hands them out in order until applied.
+
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::UserEventRecord;
+
+/// Event to store in the outbox.
+#[derive(Debug, Clone, Default)]
+pub struct NewUserEvent<'a> {
+    /// Event kind, e.g. "trade_executed".
+    pub kind: &'a str,
+    /// Trade notional in cents, for trade events.
+    pub notional_cents: Option<i64>,
+    /// Realized P&L after fees in cents, for closed positions.
+    pub realized_pnl_cents: Option<i64>,
+    /// Cost of the sold quantity in cents, for closed positions.
+    pub cost_cents: Option<i64>,
+    /// How long the position was open in seconds, for closed positions.
+    pub held_seconds: Option<i64>,
+    /// Entity behind the event, e.g. a trade id.
+    pub reference_id: Option<&'a str>,
+}
+
+/// Stores an event for a user. Run it in the transaction of the action that raised the event.
+pub async fn insert_user_event(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+    event: &NewUserEvent<'_>,
+    occurred_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_events (
+            user_id, kind, notional_cents, realized_pnl_cents, cost_cents, held_seconds, reference_id, occurred_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        user_id,
+        event.kind,
+        event.notional_cents,
+        event.realized_pnl_cents,
+        event.cost_cents,
+        event.held_seconds,
+        event.reference_id,
+        occurred_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Claims the oldest unprocessed event that is not waiting for a retry and locks it for the rest of
+/// the transaction. Events locked by a concurrent consumer are skipped.
+/// Apply the event and `mark_user_event_processed` in the same transaction, so a failure or restart
+/// leaves it unprocessed.
+pub async fn claim_next_user_event(conn: &mut PgConnection) -> Result<Option<UserEventRecord>, sqlx::Error> {
+    let event = sqlx::query_as!(
+        UserEventRecord,
+        r#"
+        SELECT * FROM user_events
+        WHERE processed_at IS NULL AND (retry_at IS NULL OR retry_at <= NOW())
+        ORDER BY id
+        LIMIT 1
+        FOR UPDATE SKIP LOCKED
+        "#
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(event)
+}
+
+/// Marks a claimed event processed.
+pub async fn mark_user_event_processed(conn: &mut PgConnection, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query!("UPDATE user_events SET processed_at = NOW() WHERE id = $1", id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Records a failed attempt at an event and holds it back until `retry_at`.
+/// Returns the number of failed attempts so far.
+pub async fn record_user_event_failure(
+    executor: impl PgExecutor<'_>,
+    id: i64,
+    retry_at: DateTime<Utc>,
+) -> Result<i32, sqlx::Error> {
+    let attempts = sqlx::query_scalar!(
+        "UPDATE user_events SET attempts = attempts + 1, retry_at = $2 WHERE id = $1 RETURNING attempts",
+        id,
+        retry_at
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(attempts)
+}
+
+/// Deletes events processed before a time.
+pub async fn delete_processed_user_events(pool: &PgPool, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM user_events WHERE processed_at < $1",
+        before
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,53 @@
+//! Fee schedule database queries.
+//! Resolves per-symbol trading fee and slippage configuration and the fee tiers unlocked by levels.
+
+use sqlx::{PgExecutor, PgPool};
+use crate::models::{FeeSchedule, FeeTier};
+
+/// Symbol of the fallback fee schedule.
+pub const DEFAULT_FEE_SCHEDULE: &str = "*";
+
+/// Finds the fee schedule for a symbol.
+/// Falls back to the default schedule when the symbol has no entry.
+pub async fn find_fee_schedule(
+    pool: &PgPool,
+    symbol: &str,
+) -> Result<FeeSchedule, sqlx::Error> {
+    let schedule = sqlx::query_as!(
+        FeeSchedule,
+        r#"
+        SELECT * FROM fee_schedules
+        WHERE symbol = $1 OR symbol = $2
+        ORDER BY (symbol = $1) DESC
+        LIMIT 1
+        "#,
+        symbol,
+        DEFAULT_FEE_SCHEDULE
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(schedule)
+}
+
+/// Finds the fee tier of a level.
+/// Levels below every tier's requirement fall back to the level 1 tier.
+pub async fn find_fee_tier(
+    executor: impl PgExecutor<'_>,
+    level: i16,
+) -> Result<FeeTier, sqlx::Error> {
+    let tier = sqlx::query_as!(
+        FeeTier,
+        r#"
+        SELECT * FROM fee_tiers
+        WHERE min_level <= $1
+        ORDER BY min_level DESC
+        LIMIT 1
+        "#,
+        level.max(1)
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(tier)
+}
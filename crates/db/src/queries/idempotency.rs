@@ -0,0 +1,141 @@
+//! Idempotency key database queries.
+//! Claims keys for in-flight requests and stores their responses for replay.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::IdempotencyKey;
+
+/// Claims a key for a new request, holding it as in flight until `locked_until`.
+/// Expired keys, and keys whose request never completed and whose lease has lapsed, are taken over.
+/// Returns the token fencing later writes for this claim, or `None` when the key is already in use.
+pub async fn claim_idempotency_key(
+    pool: &PgPool,
+    user_id: Uuid,
+    key: &str,
+    request_hash: &str,
+    locked_until: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    let now = Utc::now();
+    let claim_token = Uuid::new_v4();
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO idempotency_keys (user_id, key, request_hash, created_at, expires_at, locked_until, claim_token)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (user_id, key) DO UPDATE
+        SET request_hash = EXCLUDED.request_hash,
+            response_status = NULL,
+            response_body = NULL,
+            content_type = NULL,
+            created_at = EXCLUDED.created_at,
+            expires_at = EXCLUDED.expires_at,
+            locked_until = EXCLUDED.locked_until,
+            claim_token = EXCLUDED.claim_token
+        WHERE idempotency_keys.expires_at <= $4
+           OR (idempotency_keys.response_status IS NULL
+               AND (idempotency_keys.locked_until IS NULL OR idempotency_keys.locked_until <= $4))
+        "#,
+        user_id,
+        key,
+        request_hash,
+        now,
+        expires_at,
+        locked_until,
+        claim_token
+    )
+    .execute(pool)
+    .await?;
+
+    Ok((result.rows_affected() > 0).then_some(claim_token))
+}
+
+/// Finds a user's key.
+pub async fn find_idempotency_key(
+    pool: &PgPool,
+    user_id: Uuid,
+    key: &str,
+) -> Result<Option<IdempotencyKey>, sqlx::Error> {
+    let record = sqlx::query_as!(
+        IdempotencyKey,
+        "SELECT * FROM idempotency_keys WHERE user_id = $1 AND key = $2",
+        user_id,
+        key
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
+
+/// Extends the lease of a claimed key still waiting for its response.
+/// Returns `false` when the claim is no longer held, e.g. after a retry took the key over.
+pub async fn renew_idempotency_key(
+    pool: &PgPool,
+    user_id: Uuid,
+    key: &str,
+    claim_token: Uuid,
+    locked_until: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE idempotency_keys
+        SET locked_until = $4
+        WHERE user_id = $1 AND key = $2 AND claim_token = $3 AND response_status IS NULL
+        "#,
+        user_id,
+        key,
+        claim_token,
+        locked_until
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Stores the response of a claimed key and ends its lease.
+/// Returns `false` when the claim is no longer held, in which case nothing is stored.
+pub async fn complete_idempotency_key(
+    pool: &PgPool,
+    user_id: Uuid,
+    key: &str,
+    claim_token: Uuid,
+    response_status: i16,
+    response_body: &[u8],
+    content_type: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE idempotency_keys
+        SET response_status = $4, response_body = $5, content_type = $6, locked_until = NULL
+        WHERE user_id = $1 AND key = $2 AND claim_token = $3
+        "#,
+        user_id,
+        key,
+        claim_token,
+        response_status,
+        response_body,
+        content_type
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deletes keys that expired before the given time.
+/// Returns the number of deleted keys.
+pub async fn delete_expired_idempotency_keys(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM idempotency_keys WHERE expires_at < $1",
+        before
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
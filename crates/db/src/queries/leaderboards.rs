@@ -0,0 +1,236 @@
+//! Leaderboard database queries.
+//! Aggregates windowed XP and portfolio performance and stores the materialized rankings.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::LeaderboardEntry;
+
+/// User's portfolio performance over a window, measured between bucketed snapshots.
+#[derive(Debug, Clone)]
+pub struct PortfolioPerformance {
+    /// User the performance belongs to.
+    pub user_id: Uuid,
+    /// Trades executed in the window.
+    pub trades: i64,
+    /// Return periods with a snapshot at both ends.
+    pub periods: i64,
+    /// Change in portfolio value, in cents.
+    pub pnl_cents: i64,
+    /// Sum of the periods' log returns.
+    pub log_return: f64,
+    /// Mean log return per period.
+    pub mean_return: f64,
+    /// Sample standard deviation of the periods' log returns, absent with a single period.
+    pub stddev_return: Option<f64>,
+}
+
+/// Leaderboard entry with the user's public profile.
+#[derive(Debug, Clone)]
+pub struct LeaderboardRow {
+    /// Materialized entry.
+    pub entry: LeaderboardEntry,
+    /// User's display name.
+    pub username: Option<String>,
+    /// User's wallet address.
+    pub wallet_address: String,
+}
+
+/// Sums the XP every user earned between `since`, or ever when `None`, and `until`.
+/// Ledger entries from `excluded_source`, e.g. season resets, are left out.
+pub async fn xp_earned_since(
+    pool: &PgPool,
+    since: Option<DateTime<Utc>>,
+    until: DateTime<Utc>,
+    excluded_source: &str,
+) -> Result<Vec<(Uuid, i64)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT user_id, SUM(amount)::BIGINT AS "xp!"
+        FROM xp_events
+        WHERE ($1::TIMESTAMPTZ IS NULL OR created_at >= $1) AND created_at < $2 AND source <> $3
+        GROUP BY user_id
+        "#,
+        since,
+        until,
+        excluded_source
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.user_id, row.xp)).collect())
+}
+
+/// Measures every user's portfolio performance between `since`, or ever when `None`, and `until`.
+/// Snapshots are reduced to the last one of every `bucket_seconds` bucket and each pair of
+/// consecutive buckets is one return period. Periods spanning a season rollover are skipped
+/// so balance resets do not count as losses or gains.
+pub async fn portfolio_performance_since(
+    pool: &PgPool,
+    since: Option<DateTime<Utc>>,
+    until: DateTime<Utc>,
+    bucket_seconds: i64,
+) -> Result<Vec<PortfolioPerformance>, sqlx::Error> {
+    let performance = sqlx::query_as!(
+        PortfolioPerformance,
+        r#"
+        WITH buckets AS (
+            SELECT DISTINCT ON (user_id, date_bin($3::BIGINT * INTERVAL '1 second', captured_at, TIMESTAMPTZ 'epoch'))
+                user_id, captured_at, portfolio_value_cents
+            FROM portfolio_snapshots
+            WHERE ($1::TIMESTAMPTZ IS NULL OR captured_at >= $1) AND captured_at < $2
+            ORDER BY user_id, date_bin($3::BIGINT * INTERVAL '1 second', captured_at, TIMESTAMPTZ 'epoch'), captured_at DESC
+        ),
+        steps AS (
+            SELECT user_id, captured_at, portfolio_value_cents AS value,
+                   LAG(portfolio_value_cents) OVER w AS prev_value,
+                   LAG(captured_at) OVER w AS prev_at
+            FROM buckets
+            WINDOW w AS (PARTITION BY user_id ORDER BY captured_at)
+        ),
+        returns AS (
+            SELECT st.user_id, st.value - st.prev_value AS pnl,
+                   LN(GREATEST(st.value, 1)::FLOAT8 / st.prev_value) AS log_return
+            FROM steps st
+            WHERE st.prev_value > 0
+              AND NOT EXISTS (
+                  SELECT 1 FROM seasons se WHERE se.starts_at > st.prev_at AND se.starts_at <= st.captured_at
+              )
+        )
+        SELECT r.user_id AS "user_id!",
+               (SELECT COUNT(*) FROM trades t
+                WHERE t.user_id = r.user_id AND ($1::TIMESTAMPTZ IS NULL OR t.executed_at >= $1)
+                  AND t.executed_at < $2) AS "trades!",
+               COUNT(*) AS "periods!",
+               SUM(r.pnl)::BIGINT AS "pnl_cents!",
+               SUM(r.log_return) AS "log_return!",
+               AVG(r.log_return) AS "mean_return!",
+               STDDEV_SAMP(r.log_return) AS "stddev_return"
+        FROM returns r
+        GROUP BY r.user_id
+        "#,
+        since,
+        until,
+        bucket_seconds
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(performance)
+}
+
+/// Replaces a leaderboard with freshly computed entries in one transaction.
+/// The three slices must have the same length.
+pub async fn replace_leaderboard(
+    pool: &PgPool,
+    metric: &str,
+    period: &str,
+    user_ids: &[Uuid],
+    ranks: &[i32],
+    scores: &[f64],
+    computed_at: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "DELETE FROM leaderboard_entries WHERE metric = $1 AND period = $2",
+        metric,
+        period
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO leaderboard_entries (metric, period, user_id, rank, score, computed_at)
+        SELECT $1, $2, t.user_id, t.rank, t.score, $6
+        FROM UNNEST($3::UUID[], $4::INTEGER[], $5::FLOAT8[]) AS t(user_id, rank, score)
+        "#,
+        metric,
+        period,
+        user_ids,
+        ranks,
+        scores,
+        computed_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Lists a page of a leaderboard with user profiles, best first.
+pub async fn list_leaderboard(
+    pool: &PgPool,
+    metric: &str,
+    period: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<LeaderboardRow>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT l.metric, l.period, l.user_id, l.rank, l.score, l.computed_at, u.username, u.wallet_address
+        FROM leaderboard_entries l
+        JOIN users u ON u.id = l.user_id
+        WHERE l.metric = $1 AND l.period = $2
+        ORDER BY l.rank, l.user_id
+        LIMIT $3 OFFSET $4
+        "#,
+        metric,
+        period,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| LeaderboardRow {
+            entry: LeaderboardEntry {
+                metric: row.metric,
+                period: row.period,
+                user_id: row.user_id,
+                rank: row.rank,
+                score: row.score,
+                computed_at: row.computed_at,
+            },
+            username: row.username,
+            wallet_address: row.wallet_address,
+        })
+        .collect())
+}
+
+/// Counts the players ranked on a leaderboard.
+pub async fn count_leaderboard_entries(pool: &PgPool, metric: &str, period: &str) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM leaderboard_entries WHERE metric = $1 AND period = $2"#,
+        metric,
+        period
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Finds a user's entry on a leaderboard.
+pub async fn find_leaderboard_entry(
+    pool: &PgPool,
+    metric: &str,
+    period: &str,
+    user_id: Uuid,
+) -> Result<Option<LeaderboardEntry>, sqlx::Error> {
+    let entry = sqlx::query_as!(
+        LeaderboardEntry,
+        "SELECT * FROM leaderboard_entries WHERE metric = $1 AND period = $2 AND user_id = $3",
+        metric,
+        period,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(entry)
+}
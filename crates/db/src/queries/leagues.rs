@@ -0,0 +1,373 @@
+//! League database queries.
+//! Places players into weekly division groups, keeps their scores current and records week-end outcomes.
+
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::{LeagueGroup, LeagueMember};
+
+/// Player's place in a group's table.
+#[derive(Debug, Clone)]
+pub struct LeagueStanding {
+    /// Player.
+    pub user_id: Uuid,
+    /// Player's display name.
+    pub username: Option<String>,
+    /// Player's wallet address.
+    pub wallet_address: String,
+    /// Rank by score. Ties share a rank and players without a score share the last one.
+    pub rank: i64,
+    /// XP or return this week, absent until the player is active.
+    pub score: Option<f64>,
+    /// Outcome once the week is finalized.
+    pub outcome: Option<String>,
+}
+
+/// Finished league week of a player.
+#[derive(Debug, Clone)]
+pub struct LeagueHistoryRow {
+    /// Player's membership, with their final rank and outcome.
+    pub member: LeagueMember,
+    /// Division tier the week was played in.
+    pub division: i16,
+    /// What the group was ranked by.
+    pub metric: String,
+    /// Players in the group.
+    pub members: i64,
+}
+
+/// Outcome recorded for a player when a week is finalized.
+#[derive(Debug, Clone)]
+pub struct LeagueResult<'a> {
+    /// Player.
+    pub user_id: Uuid,
+    /// Final rank in the group.
+    pub rank: i32,
+    /// "promoted", "stayed" or "relegated".
+    pub outcome: &'a str,
+    /// Division tier the player starts the next week in.
+    pub next_division: i16,
+}
+
+/// Finds a player's membership for a week.
+pub async fn find_membership(
+    pool: &PgPool,
+    user_id: Uuid,
+    week_start: DateTime<Utc>,
+) -> Result<Option<LeagueMember>, sqlx::Error> {
+    let member = sqlx::query_as!(
+        LeagueMember,
+        "SELECT * FROM league_members WHERE user_id = $1 AND week_start = $2",
+        user_id,
+        week_start
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(member)
+}
+
+/// Finds a player's most recent membership before a week, to place them in the right division.
+pub async fn find_previous_membership(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    week_start: DateTime<Utc>,
+) -> Result<Option<(LeagueMember, i16)>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT m.group_id, m.user_id, m.week_start, m.score, m.final_rank, m.outcome, m.next_division,
+               m.joined_at, g.division
+        FROM league_members m
+        JOIN league_groups g ON g.id = m.group_id
+        WHERE m.user_id = $1 AND m.week_start < $2
+        ORDER BY m.week_start DESC
+        LIMIT 1
+        "#,
+        user_id,
+        week_start
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(row.map(|row| {
+        let member = LeagueMember {
+            group_id: row.group_id,
+            user_id: row.user_id,
+            week_start: row.week_start,
+            score: row.score,
+            final_rank: row.final_rank,
+            outcome: row.outcome,
+            next_division: row.next_division,
+            joined_at: row.joined_at,
+        };
+        (member, row.division)
+    }))
+}
+
+/// Finds a group with room in a division for a week and locks it, so concurrent placements
+/// do not overfill it. The oldest group with room is filled first.
+pub async fn lock_open_group(
+    conn: &mut PgConnection,
+    week_start: DateTime<Utc>,
+    division: i16,
+    metric: &str,
+    group_size: i64,
+) -> Result<Option<LeagueGroup>, sqlx::Error> {
+    let group = sqlx::query_as!(
+        LeagueGroup,
+        r#"
+        SELECT g.* FROM league_groups g
+        WHERE g.week_start = $1 AND g.division = $2 AND g.metric = $3 AND g.finalized_at IS NULL
+          AND (SELECT COUNT(*) FROM league_members m WHERE m.group_id = g.id) < $4
+        ORDER BY g.created_at, g.id
+        LIMIT 1
+        FOR UPDATE
+        "#,
+        week_start,
+        division,
+        metric,
+        group_size
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(group)
+}
+
+/// Opens a new group in a division for a week.
+pub async fn create_group(
+    conn: &mut PgConnection,
+    week_start: DateTime<Utc>,
+    division: i16,
+    metric: &str,
+) -> Result<LeagueGroup, sqlx::Error> {
+    let group = sqlx::query_as!(
+        LeagueGroup,
+        r#"
+        INSERT INTO league_groups (id, week_start, division, metric, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        week_start,
+        division,
+        metric,
+        Utc::now()
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(group)
+}
+
+/// Adds a player to a group.
+/// Returns `None` when the player already has a group that week.
+pub async fn insert_member(
+    conn: &mut PgConnection,
+    group: &LeagueGroup,
+    user_id: Uuid,
+) -> Result<Option<LeagueMember>, sqlx::Error> {
+    let member = sqlx::query_as!(
+        LeagueMember,
+        r#"
+        INSERT INTO league_members (group_id, user_id, week_start, joined_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, week_start) DO NOTHING
+        RETURNING *
+        "#,
+        group.id,
+        user_id,
+        group.week_start,
+        Utc::now()
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(member)
+}
+
+/// Finds a group by id.
+pub async fn find_group(pool: &PgPool, group_id: Uuid) -> Result<Option<LeagueGroup>, sqlx::Error> {
+    let group = sqlx::query_as!(LeagueGroup, "SELECT * FROM league_groups WHERE id = $1", group_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(group)
+}
+
+/// Lists the players placed in any group for a week.
+pub async fn list_week_members(pool: &PgPool, week_start: DateTime<Utc>) -> Result<Vec<Uuid>, sqlx::Error> {
+    let user_ids = sqlx::query_scalar!("SELECT user_id FROM league_members WHERE week_start = $1", week_start)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(user_ids)
+}
+
+/// Sets the scores of a week's players in groups ranked by `metric`.
+/// The two slices must have the same length; players left out keep their score.
+/// Returns the number of players updated.
+pub async fn update_scores(
+    pool: &PgPool,
+    week_start: DateTime<Utc>,
+    metric: &str,
+    user_ids: &[Uuid],
+    scores: &[f64],
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE league_members m
+        SET score = s.score
+        FROM UNNEST($3::UUID[], $4::FLOAT8[]) AS s(user_id, score), league_groups g
+        WHERE m.week_start = $1 AND m.user_id = s.user_id AND g.id = m.group_id AND g.metric = $2
+          AND g.finalized_at IS NULL
+        "#,
+        week_start,
+        metric,
+        user_ids,
+        scores
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Lists a group's table, best first.
+pub async fn group_standings(
+    executor: impl PgExecutor<'_>,
+    group_id: Uuid,
+) -> Result<Vec<LeagueStanding>, sqlx::Error> {
+    let standings = sqlx::query_as!(
+        LeagueStanding,
+        r#"
+        SELECT m.user_id, u.username, u.wallet_address,
+               RANK() OVER (ORDER BY m.score DESC NULLS LAST) AS "rank!",
+               m.score, m.outcome
+        FROM league_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.group_id = $1
+        ORDER BY 4, m.joined_at, m.user_id
+        "#,
+        group_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(standings)
+}
+
+/// Lists the unfinalized groups of weeks that started before a time.
+pub async fn list_unfinalized_groups(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> Result<Vec<LeagueGroup>, sqlx::Error> {
+    let groups = sqlx::query_as!(
+        LeagueGroup,
+        r#"
+        SELECT * FROM league_groups
+        WHERE finalized_at IS NULL AND week_start < $1
+        ORDER BY week_start, division, created_at
+        "#,
+        before
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(groups)
+}
+
+/// Locks a group that has not been finalized yet.
+pub async fn lock_unfinalized_group(
+    conn: &mut PgConnection,
+    group_id: Uuid,
+) -> Result<Option<LeagueGroup>, sqlx::Error> {
+    let group = sqlx::query_as!(
+        LeagueGroup,
+        "SELECT * FROM league_groups WHERE id = $1 AND finalized_at IS NULL FOR UPDATE",
+        group_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(group)
+}
+
+/// Records every player's final rank and outcome and marks the group finalized.
+pub async fn finalize_group(
+    conn: &mut PgConnection,
+    group_id: Uuid,
+    results: &[LeagueResult<'_>],
+    finalized_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let user_ids: Vec<Uuid> = results.iter().map(|result| result.user_id).collect();
+    let ranks: Vec<i32> = results.iter().map(|result| result.rank).collect();
+    let outcomes: Vec<String> = results.iter().map(|result| result.outcome.to_string()).collect();
+    let next_divisions: Vec<i16> = results.iter().map(|result| result.next_division).collect();
+
+    sqlx::query!(
+        r#"
+        UPDATE league_members m
+        SET final_rank = r.rank, outcome = r.outcome, next_division = r.next_division
+        FROM UNNEST($2::UUID[], $3::INTEGER[], $4::VARCHAR[], $5::SMALLINT[])
+            AS r(user_id, rank, outcome, next_division)
+        WHERE m.group_id = $1 AND m.user_id = r.user_id
+        "#,
+        group_id,
+        &user_ids,
+        &ranks,
+        &outcomes,
+        &next_divisions
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE league_groups SET finalized_at = $2 WHERE id = $1",
+        group_id,
+        finalized_at
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Lists a player's finalized league weeks, newest first.
+pub async fn list_history(pool: &PgPool, user_id: Uuid, limit: i64) -> Result<Vec<LeagueHistoryRow>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT m.group_id, m.user_id, m.week_start, m.score, m.final_rank, m.outcome, m.next_division,
+               m.joined_at, g.division, g.metric,
+               (SELECT COUNT(*) FROM league_members o WHERE o.group_id = m.group_id) AS "members!"
+        FROM league_members m
+        JOIN league_groups g ON g.id = m.group_id
+        WHERE m.user_id = $1 AND g.finalized_at IS NOT NULL
+        ORDER BY m.week_start DESC
+        LIMIT $2
+        "#,
+        user_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| LeagueHistoryRow {
+            member: LeagueMember {
+                group_id: row.group_id,
+                user_id: row.user_id,
+                week_start: row.week_start,
+                score: row.score,
+                final_rank: row.final_rank,
+                outcome: row.outcome,
+                next_division: row.next_division,
+                joined_at: row.joined_at,
+            },
+            division: row.division,
+            metric: row.metric,
+            members: row.members,
+        })
+        .collect())
+}
@@ -0,0 +1,336 @@
+//! Market data database queries.
+//! Handles oracle tick persistence, candle rollups and market statistics.
+
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use crate::models::Candle;
+
+/// Aggregated statistics for a symbol over a time window.
+#[derive(Debug, Clone)]
+pub struct SymbolStats {
+    /// Trading symbol.
+    pub symbol: String,
+    /// First price in the window. Represented in cents.
+    pub open: i64,
+    /// Highest price in the window. Represented in cents.
+    pub high: i64,
+    /// Lowest price in the window. Represented in cents.
+    pub low: i64,
+    /// Traded quantity in the window. Represented in micro units.
+    pub volume: i64,
+}
+
+/// Inserts a batch of oracle ticks in a single statement.
+/// The three slices must have the same length.
+pub async fn insert_ticks(
+    pool: &PgPool,
+    symbols: &[String],
+    prices_cents: &[i64],
+    observed_at: &[DateTime<Utc>],
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO price_ticks (symbol, price_cents, observed_at)
+        SELECT t.symbol, t.price_cents, t.observed_at
+        FROM UNNEST($1::VARCHAR[], $2::BIGINT[], $3::TIMESTAMPTZ[]) AS t(symbol, price_cents, observed_at)
+        WHERE EXISTS (SELECT 1 FROM assets a WHERE a.symbol = t.symbol)
+        "#,
+        symbols,
+        prices_cents,
+        observed_at
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Returns the most recently persisted price of every symbol.
+/// Used to restore oracle prices after a restart.
+pub async fn latest_tick_prices(pool: &PgPool) -> Result<Vec<(String, i64)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT DISTINCT ON (symbol) symbol, price_cents
+        FROM price_ticks
+        ORDER BY symbol, observed_at DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.symbol, row.price_cents)).collect())
+}
+
+/// Deletes ticks older than the given time.
+pub async fn delete_ticks_before(
+    pool: &PgPool,
+    before: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!("DELETE FROM price_ticks WHERE observed_at < $1", before)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Aggregates oracle ticks into candles of the given timeframe.
+/// `since` must be aligned to the timeframe so no bucket is rebuilt from partial data.
+/// Volume is taken from trades executed within each candle.
+pub async fn rollup_candles_from_ticks(
+    pool: &PgPool,
+    timeframe: &str,
+    timeframe_seconds: i64,
+    since: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO candles (symbol, timeframe, open_time, open, high, low, close, volume)
+        SELECT b.symbol, $2, b.open_time, b.open, b.high, b.low, b.close,
+               COALESCE((
+                   SELECT SUM(tr.quantity) FROM trades tr
+                   WHERE tr.symbol = b.symbol
+                     AND tr.executed_at >= b.open_time
+                     AND tr.executed_at < b.open_time + $3::BIGINT * INTERVAL '1 second'
+               ), 0)::BIGINT
+        FROM (
+            SELECT symbol,
+                   date_bin($3::BIGINT * INTERVAL '1 second', observed_at, TIMESTAMPTZ 'epoch') AS open_time,
+                   (ARRAY_AGG(price_cents ORDER BY observed_at))[1] AS open,
+                   MAX(price_cents) AS high,
+                   MIN(price_cents) AS low,
+                   (ARRAY_AGG(price_cents ORDER BY observed_at DESC))[1] AS close
+            FROM price_ticks
+            WHERE observed_at >= $1
+            GROUP BY symbol, 2
+        ) b
+        ON CONFLICT (symbol, timeframe, open_time) DO UPDATE
+        SET open = EXCLUDED.open,
+            high = EXCLUDED.high,
+            low = EXCLUDED.low,
+            close = EXCLUDED.close,
+            volume = EXCLUDED.volume
+        "#,
+        since,
+        timeframe,
+        timeframe_seconds
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Aggregates candles of a finer timeframe into a coarser one.
+/// `since` must be aligned to the target timeframe.
+pub async fn rollup_candles(
+    pool: &PgPool,
+    source_timeframe: &str,
+    timeframe: &str,
+    timeframe_seconds: i64,
+    since: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO candles (symbol, timeframe, open_time, open, high, low, close, volume)
+        SELECT symbol,
+               $3,
+               date_bin($4::BIGINT * INTERVAL '1 second', open_time, TIMESTAMPTZ 'epoch') AS bucket,
+               (ARRAY_AGG(open ORDER BY open_time))[1],
+               MAX(high),
+               MIN(low),
+               (ARRAY_AGG(close ORDER BY open_time DESC))[1],
+               SUM(volume)::BIGINT
+        FROM candles
+        WHERE timeframe = $2 AND open_time >= $1
+        GROUP BY symbol, bucket
+        ON CONFLICT (symbol, timeframe, open_time) DO UPDATE
+        SET open = EXCLUDED.open,
+            high = EXCLUDED.high,
+            low = EXCLUDED.low,
+            close = EXCLUDED.close,
+            volume = EXCLUDED.volume
+        "#,
+        since,
+        source_timeframe,
+        timeframe,
+        timeframe_seconds
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Lists candles of a symbol in `[from, to)`.
+/// Returns at most `limit` of the most recent candles, oldest first.
+pub async fn list_candles(
+    pool: &PgPool,
+    symbol: &str,
+    timeframe: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<Candle>, sqlx::Error> {
+    let mut candles = sqlx::query_as!(
+        Candle,
+        r#"
+        SELECT * FROM candles
+        WHERE symbol = $1 AND timeframe = $2 AND open_time >= $3 AND open_time < $4
+        ORDER BY open_time DESC
+        LIMIT $5
+        "#,
+        symbol,
+        timeframe,
+        from,
+        to,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    candles.reverse();
+    Ok(candles)
+}
+
+/// Aggregates one-minute candles since the given time per symbol.
+/// Optionally restricted to a single symbol.
+pub async fn symbol_stats_since(
+    pool: &PgPool,
+    since: DateTime<Utc>,
+    symbol: Option<&str>,
+) -> Result<Vec<SymbolStats>, sqlx::Error> {
+    let stats = sqlx::query_as!(
+        SymbolStats,
+        r#"
+        SELECT symbol AS "symbol!",
+               (ARRAY_AGG(open ORDER BY open_time))[1] AS "open!",
+               MAX(high) AS "high!",
+               MIN(low) AS "low!",
+               SUM(volume)::BIGINT AS "volume!"
+        FROM candles
+        WHERE timeframe = '1m' AND open_time >= $1 AND ($2::VARCHAR IS NULL OR symbol = $2)
+        GROUP BY symbol
+        ORDER BY symbol
+        "#,
+        since,
+        symbol
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stats)
+}
+
+/// Returns every symbol's first open and last close of one-minute candles starting in `[from, to)`.
+pub async fn symbol_moves_between(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<(String, i64, i64)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT symbol AS "symbol!",
+               (ARRAY_AGG(open ORDER BY open_time))[1] AS "open!",
+               (ARRAY_AGG(close ORDER BY open_time DESC))[1] AS "close!"
+        FROM candles
+        WHERE timeframe = '1m' AND open_time >= $1 AND open_time < $2
+        GROUP BY symbol
+        ORDER BY symbol
+        "#,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.symbol, row.open, row.close)).collect())
+}
+
+/// Column-oriented batch of candles for a single symbol and timeframe.
+/// All vectors must have the same length.
+#[derive(Debug, Clone, Default)]
+pub struct CandleBatch {
+    /// Start of each candle.
+    pub open_time: Vec<DateTime<Utc>>,
+    /// Open prices in cents.
+    pub open: Vec<i64>,
+    /// High prices in cents.
+    pub high: Vec<i64>,
+    /// Low prices in cents.
+    pub low: Vec<i64>,
+    /// Close prices in cents.
+    pub close: Vec<i64>,
+    /// Traded quantities in micro units.
+    pub volume: Vec<i64>,
+}
+
+/// Inserts or overwrites a batch of candles.
+/// Safe to re-run with the same data. Returns how many candles were newly inserted.
+pub async fn upsert_candles(
+    pool: &PgPool,
+    symbol: &str,
+    timeframe: &str,
+    batch: &CandleBatch,
+) -> Result<u64, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        INSERT INTO candles (symbol, timeframe, open_time, open, high, low, close, volume)
+        SELECT $1, $2, t.open_time, t.open, t.high, t.low, t.close, t.volume
+        FROM UNNEST($3::TIMESTAMPTZ[], $4::BIGINT[], $5::BIGINT[], $6::BIGINT[], $7::BIGINT[], $8::BIGINT[])
+            AS t(open_time, open, high, low, close, volume)
+        ON CONFLICT (symbol, timeframe, open_time) DO UPDATE
+        SET open = EXCLUDED.open,
+            high = EXCLUDED.high,
+            low = EXCLUDED.low,
+            close = EXCLUDED.close,
+            volume = EXCLUDED.volume
+        RETURNING (xmax = 0) AS "inserted!"
+        "#,
+        symbol,
+        timeframe,
+        &batch.open_time,
+        &batch.open,
+        &batch.high,
+        &batch.low,
+        &batch.close,
+        &batch.volume
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().filter(|row| row.inserted).count() as u64)
+}
+
+/// Returns the close price of every symbol's candle starting at the given time.
+pub async fn candle_closes_at(
+    pool: &PgPool,
+    timeframe: &str,
+    open_time: DateTime<Utc>,
+) -> Result<Vec<(String, i64)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        "SELECT symbol, close FROM candles WHERE timeframe = $1 AND open_time = $2",
+        timeframe,
+        open_time
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.symbol, row.close)).collect())
+}
+
+/// Finds the start of the first candle strictly after the given time.
+pub async fn next_candle_time(
+    pool: &PgPool,
+    timeframe: &str,
+    after: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"SELECT MIN(open_time) AS "open_time" FROM candles WHERE timeframe = $1 AND open_time > $2"#,
+        timeframe,
+        after
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.open_time)
+}
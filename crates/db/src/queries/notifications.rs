@@ -0,0 +1,239 @@
+//! Notification database queries.
+//! Handles the in-app inbox and the webhooks notifications are delivered to.
+
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::Utc;
+use crate::models::{Notification, Webhook};
+
+/// Adds a notification to a user's inbox.
+pub async fn create_notification(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+    kind: &str,
+    title: &str,
+    message: &str,
+    alert_id: Option<Uuid>,
+) -> Result<Notification, sqlx::Error> {
+    let notification = sqlx::query_as!(
+        Notification,
+        r#"
+        INSERT INTO notifications (id, user_id, kind, title, message, alert_id, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        kind,
+        title,
+        message,
+        alert_id,
+        Utc::now()
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(notification)
+}
+
+/// Lists a user's notifications, newest first.
+pub async fn list_notifications(
+    pool: &PgPool,
+    user_id: Uuid,
+    unread_only: bool,
+    limit: i64,
+) -> Result<Vec<Notification>, sqlx::Error> {
+    let notifications = sqlx::query_as!(
+        Notification,
+        r#"
+        SELECT * FROM notifications
+        WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+        ORDER BY created_at DESC
+        LIMIT $3
+        "#,
+        user_id,
+        unread_only,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(notifications)
+}
+
+/// Counts a user's unread notifications.
+pub async fn count_unread_notifications(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM notifications WHERE user_id = $1 AND read_at IS NULL"#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Marks a user's notification as read.
+/// Returns `None` when the notification does not exist or belongs to another user.
+pub async fn mark_notification_read(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<Notification>, sqlx::Error> {
+    let notification = sqlx::query_as!(
+        Notification,
+        r#"
+        UPDATE notifications
+        SET read_at = COALESCE(read_at, $3)
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+        id,
+        user_id,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(notification)
+}
+
+/// Marks all of a user's notifications as read.
+/// Returns the number of notifications that were unread.
+pub async fn mark_all_notifications_read(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        "UPDATE notifications SET read_at = $2 WHERE user_id = $1 AND read_at IS NULL",
+        user_id,
+        Utc::now()
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Registers a webhook.
+pub async fn create_webhook(
+    pool: &PgPool,
+    user_id: Uuid,
+    url: &str,
+    secret: &str,
+) -> Result<Webhook, sqlx::Error> {
+    let webhook = sqlx::query_as!(
+        Webhook,
+        r#"
+        INSERT INTO webhooks (id, user_id, url, secret, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        url,
+        secret,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(webhook)
+}
+
+/// Counts the webhooks registered by a user.
+pub async fn count_webhooks(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM webhooks WHERE user_id = $1"#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Lists a user's webhooks, oldest first.
+pub async fn list_webhooks(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<Webhook>, sqlx::Error> {
+    let webhooks = sqlx::query_as!(
+        Webhook,
+        "SELECT * FROM webhooks WHERE user_id = $1 ORDER BY created_at",
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(webhooks)
+}
+
+/// Lists the webhooks a user's notifications are delivered to.
+pub async fn list_active_webhooks(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<Webhook>, sqlx::Error> {
+    let webhooks = sqlx::query_as!(
+        Webhook,
+        "SELECT * FROM webhooks WHERE user_id = $1 AND is_active",
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(webhooks)
+}
+
+/// Deletes a user's webhook. Returns `false` when there was nothing to delete.
+pub async fn delete_webhook(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM webhooks WHERE id = $1 AND user_id = $2",
+        id,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Records the outcome of a delivery.
+/// Successes reset the failure count; the webhook is disabled once failures reach `max_failures`.
+pub async fn record_webhook_delivery(
+    pool: &PgPool,
+    id: Uuid,
+    status: Option<i16>,
+    succeeded: bool,
+    max_failures: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webhooks
+        SET last_delivery_at = $2,
+            last_status = $3,
+            failure_count = CASE WHEN $4 THEN 0 ELSE failure_count + 1 END,
+            is_active = $4 OR failure_count + 1 < $5
+        WHERE id = $1
+        "#,
+        id,
+        Utc::now(),
+        status,
+        succeeded,
+        max_failures
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
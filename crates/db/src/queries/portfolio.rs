@@ -0,0 +1,122 @@
+//! Portfolio history database queries.
+//! Captures periodic portfolio snapshots and reads them back as equity curves.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::PortfolioSnapshot;
+
+/// Snapshots the portfolio of every user at the given prices.
+/// Positions in symbols without a price are valued at their average price.
+/// Returns the number of snapshots written.
+pub async fn capture_portfolio_snapshots(
+    pool: &PgPool,
+    symbols: &[String],
+    prices_cents: &[i64],
+    captured_at: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO portfolio_snapshots (user_id, captured_at, portfolio_value_cents, cash_balance_cents)
+        SELECT
+            u.id,
+            $3,
+            u.cash_balance_cents + COALESCE(SUM(
+                ROUND(p.quantity::NUMERIC * COALESCE(px.price_cents, p.average_price) / 1000000)
+            ), 0)::BIGINT,
+            u.cash_balance_cents
+        FROM users u
+        LEFT JOIN positions p ON p.user_id = u.id
+        LEFT JOIN UNNEST($1::VARCHAR[], $2::BIGINT[]) AS px(symbol, price_cents) ON px.symbol = p.symbol
+        GROUP BY u.id, u.cash_balance_cents
+        ON CONFLICT (user_id, captured_at) DO NOTHING
+        "#,
+        symbols,
+        prices_cents,
+        captured_at
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Lists a user's snapshots in `[from, to)`, keeping the last snapshot of every bucket.
+/// Buckets are `bucket_seconds` long and aligned to the Unix epoch. Oldest first.
+pub async fn list_portfolio_snapshots(
+    pool: &PgPool,
+    user_id: Uuid,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    bucket_seconds: i64,
+) -> Result<Vec<PortfolioSnapshot>, sqlx::Error> {
+    let snapshots = sqlx::query_as!(
+        PortfolioSnapshot,
+        r#"
+        SELECT user_id AS "user_id!", captured_at AS "captured_at!",
+               portfolio_value_cents AS "portfolio_value_cents!", cash_balance_cents AS "cash_balance_cents!"
+        FROM (
+            SELECT DISTINCT ON (date_bin($4::BIGINT * INTERVAL '1 second', captured_at, TIMESTAMPTZ 'epoch'))
+                user_id, captured_at, portfolio_value_cents, cash_balance_cents
+            FROM portfolio_snapshots
+            WHERE user_id = $1 AND captured_at >= $2 AND captured_at < $3
+            ORDER BY date_bin($4::BIGINT * INTERVAL '1 second', captured_at, TIMESTAMPTZ 'epoch'), captured_at DESC
+        ) AS buckets
+        ORDER BY captured_at
+        "#,
+        user_id,
+        from,
+        to,
+        bucket_seconds
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(snapshots)
+}
+
+/// Finds a user's last snapshot taken before a time, e.g. the latest value within a period.
+pub async fn last_portfolio_snapshot_before(
+    pool: &PgPool,
+    user_id: Uuid,
+    before: DateTime<Utc>,
+) -> Result<Option<PortfolioSnapshot>, sqlx::Error> {
+    let snapshot = sqlx::query_as!(
+        PortfolioSnapshot,
+        r#"
+        SELECT * FROM portfolio_snapshots
+        WHERE user_id = $1 AND captured_at < $2
+        ORDER BY captured_at DESC
+        LIMIT 1
+        "#,
+        user_id,
+        before
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(snapshot)
+}
+
+/// Finds a user's first snapshot taken at or after a time, e.g. the value at the start of a period.
+pub async fn first_portfolio_snapshot_since(
+    pool: &PgPool,
+    user_id: Uuid,
+    since: DateTime<Utc>,
+) -> Result<Option<PortfolioSnapshot>, sqlx::Error> {
+    let snapshot = sqlx::query_as!(
+        PortfolioSnapshot,
+        r#"
+        SELECT * FROM portfolio_snapshots
+        WHERE user_id = $1 AND captured_at >= $2
+        ORDER BY captured_at
+        LIMIT 1
+        "#,
+        user_id,
+        since
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(snapshot)
+}
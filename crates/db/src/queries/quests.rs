@@ -0,0 +1,92 @@
+//! Quest database queries.
+//! Tracks per-user progress on rotating quests and records claimed rewards.
+
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::QuestProgress;
+
+/// Adds progress to a user's quest in a period, starting it if needed.
+/// Claimed quests are left unchanged.
+pub async fn add_quest_progress(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+    quest_id: &str,
+    period: &str,
+    period_start: DateTime<Utc>,
+    amount: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO quest_progress (user_id, quest_id, period, period_start, progress, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (user_id, quest_id, period_start) DO UPDATE
+        SET progress = quest_progress.progress + EXCLUDED.progress,
+            updated_at = EXCLUDED.updated_at
+        WHERE quest_progress.claimed_at IS NULL
+        "#,
+        user_id,
+        quest_id,
+        period,
+        period_start,
+        amount,
+        Utc::now()
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Lists a user's quest progress in the periods starting at the given times.
+pub async fn list_quest_progress(
+    pool: &PgPool,
+    user_id: Uuid,
+    period_starts: &[DateTime<Utc>],
+) -> Result<Vec<QuestProgress>, sqlx::Error> {
+    let progress = sqlx::query_as!(
+        QuestProgress,
+        "SELECT * FROM quest_progress WHERE user_id = $1 AND period_start = ANY($2)",
+        user_id,
+        period_starts
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(progress)
+}
+
+/// Marks a quest as claimed with the progress it was completed with.
+/// Returns `None` when the quest was already claimed in that period.
+pub async fn claim_quest(
+    executor: impl PgExecutor<'_>,
+    progress: &QuestProgress,
+) -> Result<Option<QuestProgress>, sqlx::Error> {
+    let claimed = sqlx::query_as!(
+        QuestProgress,
+        r#"
+        INSERT INTO quest_progress (
+            user_id, quest_id, period, period_start, progress, claimed_at, xp_awarded, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $6)
+        ON CONFLICT (user_id, quest_id, period_start) DO UPDATE
+        SET progress = EXCLUDED.progress,
+            claimed_at = EXCLUDED.claimed_at,
+            xp_awarded = EXCLUDED.xp_awarded,
+            updated_at = EXCLUDED.updated_at
+        WHERE quest_progress.claimed_at IS NULL
+        RETURNING *
+        "#,
+        progress.user_id,
+        progress.quest_id,
+        progress.period,
+        progress.period_start,
+        progress.progress,
+        progress.claimed_at,
+        progress.xp_awarded
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(claimed)
+}
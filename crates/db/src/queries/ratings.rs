@@ -0,0 +1,214 @@
+//! Skill rating database queries.
+//! Stores players' Glicko-2 ratings, their update history and matchmaking lookups.
+
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::{PlayerRating, RatingEvent};
+
+/// Rating update to record for a player.
+#[derive(Debug, Clone)]
+pub struct NewRatingEvent<'a> {
+    /// Rated player.
+    pub user_id: Uuid,
+    /// What was rated: "duel" or "tournament".
+    pub source: &'a str,
+    /// Duel or tournament that was rated.
+    pub reference_id: Uuid,
+    /// Head-to-head results in the update.
+    pub games: i32,
+    /// Points scored.
+    pub score: f64,
+    /// Points expected beforehand.
+    pub expected_score: f64,
+    /// Rating before the update.
+    pub rating_before: f64,
+    /// Rating after the update.
+    pub rating_after: f64,
+    /// Deviation after the update.
+    pub deviation_after: f64,
+    /// Volatility after the update.
+    pub volatility_after: f64,
+}
+
+/// Possible opponent for matchmaking.
+#[derive(Debug, Clone)]
+pub struct MatchCandidate {
+    /// Candidate.
+    pub user_id: Uuid,
+    /// Candidate's display name.
+    pub username: Option<String>,
+    /// Candidate's wallet address.
+    pub wallet_address: String,
+    /// Candidate's rating, the default for unrated players.
+    pub rating: f64,
+    /// Candidate's rating deviation.
+    pub deviation: f64,
+    /// Candidate's volatility.
+    pub volatility: f64,
+    /// When the candidate's rating last changed, absent for unrated players.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Loads players' ratings and locks them for the rest of the transaction.
+/// Players without a rating get the default one. Rows are locked in user id order so
+/// concurrent updates cannot deadlock.
+pub async fn lock_ratings(conn: &mut PgConnection, user_ids: &[Uuid]) -> Result<Vec<PlayerRating>, sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO player_ratings (user_id, updated_at)
+        SELECT id, $2 FROM UNNEST($1::UUID[]) AS t(id)
+        ON CONFLICT (user_id) DO NOTHING
+        "#,
+        user_ids,
+        Utc::now()
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let ratings = sqlx::query_as!(
+        PlayerRating,
+        "SELECT * FROM player_ratings WHERE user_id = ANY($1) ORDER BY user_id FOR UPDATE",
+        user_ids
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(ratings)
+}
+
+/// Records a rating update and applies it to the player's rating.
+/// Returns `false` when the same duel or tournament was already rated for the player.
+pub async fn record_rating(conn: &mut PgConnection, event: &NewRatingEvent<'_>) -> Result<bool, sqlx::Error> {
+    let now = Utc::now();
+    let inserted = sqlx::query!(
+        r#"
+        INSERT INTO rating_events (
+            id, user_id, source, reference_id, games, score, expected_score,
+            rating_before, rating_after, deviation_after, volatility_after, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT (user_id, source, reference_id) DO NOTHING
+        "#,
+        Uuid::new_v4(),
+        event.user_id,
+        event.source,
+        event.reference_id,
+        event.games,
+        event.score,
+        event.expected_score,
+        event.rating_before,
+        event.rating_after,
+        event.deviation_after,
+        event.volatility_after,
+        now
+    )
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+    if inserted == 0 {
+        return Ok(false);
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE player_ratings
+        SET rating = $2, deviation = $3, volatility = $4, games = games + $5, updated_at = $6
+        WHERE user_id = $1
+        "#,
+        event.user_id,
+        event.rating_after,
+        event.deviation_after,
+        event.volatility_after,
+        event.games,
+        now
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(true)
+}
+
+/// Finds a player's rating.
+pub async fn find_rating(pool: &PgPool, user_id: Uuid) -> Result<Option<PlayerRating>, sqlx::Error> {
+    let rating = sqlx::query_as!(PlayerRating, "SELECT * FROM player_ratings WHERE user_id = $1", user_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(rating)
+}
+
+/// Lists a player's most recent rating updates, newest first.
+pub async fn list_rating_events(pool: &PgPool, user_id: Uuid, limit: i64) -> Result<Vec<RatingEvent>, sqlx::Error> {
+    let events = sqlx::query_as!(
+        RatingEvent,
+        r#"
+        SELECT * FROM rating_events
+        WHERE user_id = $1
+        ORDER BY created_at DESC, id
+        LIMIT $2
+        "#,
+        user_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+/// Lists the players rated closest to `rating`, most certain ratings first among equals.
+/// The user and anyone they already have a pending or active duel with are left out.
+pub async fn find_match_candidates(
+    pool: &PgPool,
+    user_id: Uuid,
+    rating: f64,
+    limit: i64,
+) -> Result<Vec<MatchCandidate>, sqlx::Error> {
+    let candidates = sqlx::query_as!(
+        MatchCandidate,
+        r#"
+        SELECT u.id AS user_id, u.username, u.wallet_address,
+               COALESCE(r.rating, 1500) AS "rating!",
+               COALESCE(r.deviation, 350) AS "deviation!",
+               COALESCE(r.volatility, 0.06) AS "volatility!",
+               r.updated_at AS "updated_at?"
+        FROM users u
+        LEFT JOIN player_ratings r ON r.user_id = u.id
+        WHERE u.id <> $1
+          AND NOT EXISTS (
+              SELECT 1 FROM duels d
+              WHERE d.status IN ('pending', 'active')
+                AND ((d.challenger_id = $1 AND d.opponent_id = u.id) OR (d.challenger_id = u.id AND d.opponent_id = $1))
+          )
+        ORDER BY ABS(COALESCE(r.rating, 1500) - $2), COALESCE(r.deviation, 350), u.id
+        LIMIT $3
+        "#,
+        user_id,
+        rating,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(candidates)
+}
+
+/// Lists the ratings of players rated at least once since a time, or ever when `since` is `None`.
+pub async fn rated_players_since(pool: &PgPool, since: Option<DateTime<Utc>>) -> Result<Vec<PlayerRating>, sqlx::Error> {
+    let ratings = sqlx::query_as!(
+        PlayerRating,
+        r#"
+        SELECT * FROM player_ratings r
+        WHERE r.games > 0
+          AND ($1::TIMESTAMPTZ IS NULL OR EXISTS (
+              SELECT 1 FROM rating_events e WHERE e.user_id = r.user_id AND e.created_at >= $1
+          ))
+        "#,
+        since
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ratings)
+}
@@ -0,0 +1,119 @@
+//! Rebalance schedule database queries.
+//! Stores users' target weights and tracks scheduled rebalance runs.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::RebalanceSchedule;
+
+/// Finds a user's rebalance schedule.
+pub async fn find_rebalance_schedule(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Option<RebalanceSchedule>, sqlx::Error> {
+    let schedule = sqlx::query_as!(
+        RebalanceSchedule,
+        "SELECT * FROM rebalance_schedules WHERE user_id = $1",
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(schedule)
+}
+
+/// Creates or replaces a user's rebalance schedule.
+pub async fn upsert_rebalance_schedule(
+    pool: &PgPool,
+    user_id: Uuid,
+    symbols: &[String],
+    weights_bps: &[i32],
+    interval_seconds: i32,
+    drift_threshold_bps: i32,
+    next_run_at: DateTime<Utc>,
+) -> Result<RebalanceSchedule, sqlx::Error> {
+    let now = Utc::now();
+    let schedule = sqlx::query_as!(
+        RebalanceSchedule,
+        r#"
+        INSERT INTO rebalance_schedules (user_id, symbols, weights_bps, interval_seconds, drift_threshold_bps, next_run_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+        ON CONFLICT (user_id) DO UPDATE
+        SET symbols = EXCLUDED.symbols,
+            weights_bps = EXCLUDED.weights_bps,
+            interval_seconds = EXCLUDED.interval_seconds,
+            drift_threshold_bps = EXCLUDED.drift_threshold_bps,
+            next_run_at = EXCLUDED.next_run_at,
+            last_error = NULL,
+            updated_at = EXCLUDED.updated_at
+        RETURNING *
+        "#,
+        user_id,
+        symbols,
+        weights_bps,
+        interval_seconds,
+        drift_threshold_bps,
+        next_run_at,
+        now
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(schedule)
+}
+
+/// Deletes a user's rebalance schedule.
+pub async fn delete_rebalance_schedule(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM rebalance_schedules WHERE user_id = $1",
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lists schedules whose next rebalance is due.
+pub async fn list_due_rebalance_schedules(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<RebalanceSchedule>, sqlx::Error> {
+    let schedules = sqlx::query_as!(
+        RebalanceSchedule,
+        "SELECT * FROM rebalance_schedules WHERE next_run_at <= $1 ORDER BY next_run_at",
+        now
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(schedules)
+}
+
+/// Records the outcome of a scheduled rebalance and schedules the next one.
+pub async fn record_rebalance_run(
+    pool: &PgPool,
+    user_id: Uuid,
+    next_run_at: DateTime<Utc>,
+    last_error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    sqlx::query!(
+        r#"
+        UPDATE rebalance_schedules
+        SET next_run_at = $2, last_error = $3, last_run_at = $4, updated_at = $4
+        WHERE user_id = $1
+        "#,
+        user_id,
+        next_run_at,
+        last_error,
+        now
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,332 @@
+//! Season database queries.
+//! Tracks the current season, archives final standings and resets seasonal progress at rollovers.
+
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::{Season, SeasonStanding};
+use crate::queries::users::STARTING_BALANCE_CENTS;
+
+/// Archived standing with the user's public profile.
+#[derive(Debug, Clone)]
+pub struct SeasonStandingEntry {
+    /// Final standing.
+    pub standing: SeasonStanding,
+    /// User's display name.
+    pub username: Option<String>,
+    /// User's wallet address.
+    pub wallet_address: String,
+}
+
+/// Finds the current season, the one that has not rolled over yet.
+pub async fn find_current_season(pool: &PgPool) -> Result<Option<Season>, sqlx::Error> {
+    let season = sqlx::query_as!(Season, "SELECT * FROM seasons WHERE closed_at IS NULL")
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(season)
+}
+
+/// Finds the current season and locks it for a rollover.
+pub async fn lock_current_season(conn: &mut PgConnection) -> Result<Option<Season>, sqlx::Error> {
+    let season = sqlx::query_as!(Season, "SELECT * FROM seasons WHERE closed_at IS NULL FOR UPDATE")
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(season)
+}
+
+/// Finds a season by its number.
+pub async fn find_season_by_number(pool: &PgPool, number: i32) -> Result<Option<Season>, sqlx::Error> {
+    let season = sqlx::query_as!(Season, "SELECT * FROM seasons WHERE number = $1", number)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(season)
+}
+
+/// Lists all seasons, newest first.
+pub async fn list_seasons(pool: &PgPool) -> Result<Vec<Season>, sqlx::Error> {
+    let seasons = sqlx::query_as!(Season, "SELECT * FROM seasons ORDER BY number DESC")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(seasons)
+}
+
+/// Archives the final standings of a season at the given prices.
+/// Includes every user who earned XP or traded since the season started.
+/// Positions in symbols without a price are valued at their average price.
+/// Returns the number of standings written.
+pub async fn archive_season_standings(
+    conn: &mut PgConnection,
+    season_id: Uuid,
+    starts_at: DateTime<Utc>,
+    symbols: &[String],
+    prices_cents: &[i64],
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO season_standings (
+            season_id, user_id, xp_rank, xp_points, level, portfolio_rank, portfolio_value_cents, return_bps
+        )
+        SELECT $1, v.id,
+               RANK() OVER (ORDER BY v.xp_points DESC)::INTEGER,
+               v.xp_points,
+               v.level,
+               RANK() OVER (ORDER BY v.value_cents DESC)::INTEGER,
+               v.value_cents,
+               ((v.value_cents - $5::BIGINT) * 10000 / $5::BIGINT)::BIGINT
+        FROM (
+            SELECT u.id, u.xp_points, u.level,
+                   (u.cash_balance_cents + COALESCE(SUM(
+                       ROUND(p.quantity::NUMERIC * COALESCE(px.price_cents, p.average_price) / 1000000)
+                   ), 0))::BIGINT AS value_cents
+            FROM users u
+            LEFT JOIN positions p ON p.user_id = u.id
+            LEFT JOIN UNNEST($3::VARCHAR[], $4::BIGINT[]) AS px(symbol, price_cents) ON px.symbol = p.symbol
+            WHERE u.xp_points > 0
+               OR EXISTS (SELECT 1 FROM trades t WHERE t.user_id = u.id AND t.executed_at >= $2)
+            GROUP BY u.id, u.xp_points, u.level, u.cash_balance_cents
+        ) v
+        "#,
+        season_id,
+        starts_at,
+        symbols,
+        prices_cents,
+        STARTING_BALANCE_CENTS
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Records the reward earned by an archived standing.
+pub async fn set_standing_reward(
+    conn: &mut PgConnection,
+    season_id: Uuid,
+    user_id: Uuid,
+    reward_title: &str,
+    reward_xp: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE season_standings SET reward_title = $3, reward_xp = $4 WHERE season_id = $1 AND user_id = $2",
+        season_id,
+        user_id,
+        reward_title,
+        reward_xp
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Lists the archived standings of a season ranked at or above `max_rank`, best first.
+pub async fn list_standings_up_to_rank(
+    conn: &mut PgConnection,
+    season_id: Uuid,
+    max_rank: i32,
+) -> Result<Vec<SeasonStanding>, sqlx::Error> {
+    let standings = sqlx::query_as!(
+        SeasonStanding,
+        "SELECT * FROM season_standings WHERE season_id = $1 AND xp_rank <= $2 ORDER BY xp_rank, user_id",
+        season_id,
+        max_rank
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(standings)
+}
+
+/// Lists a page of a season's archived standings with user profiles, best first.
+pub async fn list_season_standings(
+    pool: &PgPool,
+    season_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SeasonStandingEntry>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT s.season_id, s.user_id, s.xp_rank, s.xp_points, s.level, s.portfolio_rank,
+               s.portfolio_value_cents, s.return_bps, s.reward_title, s.reward_xp,
+               u.username, u.wallet_address
+        FROM season_standings s
+        JOIN users u ON u.id = s.user_id
+        WHERE s.season_id = $1
+        ORDER BY s.xp_rank, s.user_id
+        LIMIT $2 OFFSET $3
+        "#,
+        season_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| SeasonStandingEntry {
+            standing: SeasonStanding {
+                season_id: row.season_id,
+                user_id: row.user_id,
+                xp_rank: row.xp_rank,
+                xp_points: row.xp_points,
+                level: row.level,
+                portfolio_rank: row.portfolio_rank,
+                portfolio_value_cents: row.portfolio_value_cents,
+                return_bps: row.return_bps,
+                reward_title: row.reward_title,
+                reward_xp: row.reward_xp,
+            },
+            username: row.username,
+            wallet_address: row.wallet_address,
+        })
+        .collect())
+}
+
+/// Finds a user's archived standing in a season.
+pub async fn find_season_standing(
+    pool: &PgPool,
+    season_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<SeasonStanding>, sqlx::Error> {
+    let standing = sqlx::query_as!(
+        SeasonStanding,
+        "SELECT * FROM season_standings WHERE season_id = $1 AND user_id = $2",
+        season_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(standing)
+}
+
+/// Locks every user row for the rest of the transaction, in id order.
+/// Held during a rollover so no trade or XP grant lands between the archived standings and the reset.
+pub async fn lock_all_users(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    sqlx::query!("SELECT id FROM users ORDER BY id FOR UPDATE")
+        .fetch_all(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Resets every user's XP, level, paper balances and positions for a new season.
+/// Open positions are closed with fee-free sell trades at the given prices, or their average price
+/// when a symbol has none, so trade history stays complete. XP resets are recorded in the ledger
+/// against the closing season. User rows must already be locked with `lock_all_users`.
+pub async fn reset_season_progress(
+    conn: &mut PgConnection,
+    season_id: Uuid,
+    reset_source: &str,
+    idempotency_key: &str,
+    symbols: &[String],
+    prices_cents: &[i64],
+) -> Result<u64, sqlx::Error> {
+    let now = Utc::now();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO xp_events (
+            id, user_id, source, amount, reference_id, idempotency_key, xp_after, level_after, created_at, season_id
+        )
+        SELECT gen_random_uuid(), id, $2, -xp_points, $1::UUID::TEXT, $3, 0, 1, $4, $1
+        FROM users
+        WHERE xp_points > 0
+        ON CONFLICT (user_id, idempotency_key) DO NOTHING
+        "#,
+        season_id,
+        reset_source,
+        idempotency_key,
+        now
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO trades (
+            id, user_id, symbol, trade_type, quantity, price, total_value, executed_at,
+            reference_price, fee_cents, slippage_cents, bot_id
+        )
+        SELECT gen_random_uuid(), p.user_id, p.symbol, 'sell', p.quantity,
+               COALESCE(px.price_cents, p.average_price),
+               ROUND(p.quantity::NUMERIC * COALESCE(px.price_cents, p.average_price) / 1000000)::BIGINT,
+               $3, COALESCE(px.price_cents, p.average_price), 0, 0, NULL
+        FROM positions p
+        LEFT JOIN UNNEST($1::VARCHAR[], $2::BIGINT[]) AS px(symbol, price_cents) ON px.symbol = p.symbol
+        WHERE p.quantity > 0
+        "#,
+        symbols,
+        prices_cents,
+        now
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!("DELETE FROM positions").execute(&mut *conn).await?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE users
+        SET xp_points = 0, level = 1, cash_balance_cents = $1, portfolio_value_cents = $1, updated_at = $2
+        "#,
+        STARTING_BALANCE_CENTS,
+        now
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Marks a season as rolled over.
+pub async fn close_season(
+    conn: &mut PgConnection,
+    season_id: Uuid,
+    participant_count: i32,
+    closed_at: DateTime<Utc>,
+) -> Result<Season, sqlx::Error> {
+    let season = sqlx::query_as!(
+        Season,
+        "UPDATE seasons SET closed_at = $2, participant_count = $3 WHERE id = $1 RETURNING *",
+        season_id,
+        closed_at,
+        participant_count
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(season)
+}
+
+/// Creates a new current season.
+pub async fn create_season(
+    conn: &mut PgConnection,
+    number: i32,
+    name: &str,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+) -> Result<Season, sqlx::Error> {
+    let season = sqlx::query_as!(
+        Season,
+        r#"
+        INSERT INTO seasons (id, number, name, starts_at, ends_at, created_at)
+        VALUES ($1, $2, $3, $4, $5, $4)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        number,
+        name,
+        starts_at,
+        ends_at
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(season)
+}
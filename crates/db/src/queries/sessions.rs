@@ -0,0 +1,50 @@
+//! User session database queries.
+//! Handles storing and looking up authentication sessions.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::UserSession;
+
+/// Stores a new session for an issued authentication token.
+/// Only the token hash is persisted, never the token itself.
+pub async fn create_session(
+    pool: &PgPool,
+    user_id: Uuid,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<UserSession, sqlx::Error> {
+    let session = sqlx::query_as!(
+        UserSession,
+        r#"
+        INSERT INTO user_sessions (id, user_id, token_hash, expires_at, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        token_hash,
+        expires_at,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(session)
+}
+
+/// Finds a non-expired session by its token hash.
+pub async fn find_active_session(
+    pool: &PgPool,
+    token_hash: &str,
+) -> Result<Option<UserSession>, sqlx::Error> {
+    let session = sqlx::query_as!(
+        UserSession,
+        "SELECT * FROM user_sessions WHERE token_hash = $1 AND expires_at > NOW()",
+        token_hash
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(session)
+}
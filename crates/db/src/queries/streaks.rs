@@ -0,0 +1,106 @@
+//! Activity streak database queries.
+//! Stores users' daily streaks, their freezes and the time zone days are counted in.
+
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{NaiveDate, Utc};
+use crate::models::ActivityStreak;
+
+/// Streak values to store after recording activity.
+#[derive(Debug, Clone, Copy)]
+pub struct StreakChange {
+    /// Consecutive active days.
+    pub current_days: i32,
+    /// Longest streak reached.
+    pub longest_days: i32,
+    /// Latest active day.
+    pub last_active_date: NaiveDate,
+    /// Freezes held.
+    pub freezes: i32,
+    /// Freezes spent by this change.
+    pub freezes_used: i32,
+}
+
+/// Finds a user's streak.
+pub async fn find_streak(executor: impl PgExecutor<'_>, user_id: Uuid) -> Result<Option<ActivityStreak>, sqlx::Error> {
+    let streak = sqlx::query_as!(ActivityStreak, "SELECT * FROM activity_streaks WHERE user_id = $1", user_id)
+        .fetch_optional(executor)
+        .await?;
+
+    Ok(streak)
+}
+
+/// Loads a user's streak and locks it for the rest of the transaction, starting an empty one if needed.
+pub async fn lock_streak(conn: &mut PgConnection, user_id: Uuid) -> Result<ActivityStreak, sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO activity_streaks (user_id, updated_at)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO NOTHING
+        "#,
+        user_id,
+        Utc::now()
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let streak = sqlx::query_as!(
+        ActivityStreak,
+        "SELECT * FROM activity_streaks WHERE user_id = $1 FOR UPDATE",
+        user_id
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(streak)
+}
+
+/// Stores a user's streak after recording activity.
+pub async fn update_streak(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    change: &StreakChange,
+) -> Result<ActivityStreak, sqlx::Error> {
+    let streak = sqlx::query_as!(
+        ActivityStreak,
+        r#"
+        UPDATE activity_streaks
+        SET current_days = $2, longest_days = $3, last_active_date = $4, freezes = $5,
+            freezes_used = freezes_used + $6, updated_at = $7
+        WHERE user_id = $1
+        RETURNING *
+        "#,
+        user_id,
+        change.current_days,
+        change.longest_days,
+        change.last_active_date,
+        change.freezes,
+        change.freezes_used,
+        Utc::now()
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(streak)
+}
+
+/// Sets the time zone a user's days are counted in.
+pub async fn set_time_zone(pool: &PgPool, user_id: Uuid, time_zone: &str) -> Result<ActivityStreak, sqlx::Error> {
+    let streak = sqlx::query_as!(
+        ActivityStreak,
+        r#"
+        INSERT INTO activity_streaks (user_id, time_zone, updated_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id) DO UPDATE
+        SET time_zone = EXCLUDED.time_zone, updated_at = EXCLUDED.updated_at
+        RETURNING *
+        "#,
+        user_id,
+        time_zone,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(streak)
+}
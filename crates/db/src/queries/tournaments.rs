@@ -0,0 +1,597 @@
+//! Tournament database queries.
+//! Manages tournaments, their entrants' isolated accounts and the final rankings.
+
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::{Tournament, TournamentEntry, TournamentPosition, TournamentTrade};
+
+/// Tournament to be created.
+#[derive(Debug, Clone)]
+pub struct NewTournament<'a> {
+    /// Display name.
+    pub name: &'a str,
+    /// Rules or prize description.
+    pub description: Option<&'a str>,
+    /// Paper cash every entrant starts with, in cents.
+    pub starting_balance_cents: i64,
+    /// Symbols entrants may trade.
+    pub allowed_symbols: &'a [String],
+    /// Maximum number of entrants, `None` for unlimited.
+    pub max_participants: Option<i32>,
+    /// When registration opens.
+    pub registration_opens_at: DateTime<Utc>,
+    /// When registration closes.
+    pub registration_closes_at: DateTime<Utc>,
+    /// When trading starts.
+    pub starts_at: DateTime<Utc>,
+    /// When trading ends.
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Tournament trade row to be inserted after an order has been filled.
+#[derive(Debug, Clone)]
+pub struct NewTournamentTrade<'a> {
+    /// Tournament traded in.
+    pub tournament_id: Uuid,
+    /// Entrant who traded.
+    pub user_id: Uuid,
+    /// Trading symbol.
+    pub symbol: &'a str,
+    /// Trade type: "buy" or "sell".
+    pub trade_type: &'a str,
+    /// Quantity in micro units.
+    pub quantity: i64,
+    /// Fill price in cents, including slippage.
+    pub price: i64,
+    /// Quantity times fill price in cents.
+    pub total_value: i64,
+    /// Fee charged in cents.
+    pub fee_cents: i64,
+}
+
+/// Entrant's place in a tournament's standings.
+#[derive(Debug, Clone)]
+pub struct TournamentStanding {
+    /// Entrant.
+    pub user_id: Uuid,
+    /// Entrant's display name.
+    pub username: Option<String>,
+    /// Entrant's wallet address.
+    pub wallet_address: String,
+    /// Rank by account value. Ties share a rank.
+    pub rank: i64,
+    /// Account value in cents.
+    pub value_cents: i64,
+    /// Prize badge, once finalized.
+    pub badge: Option<String>,
+    /// XP granted for the finish, once finalized.
+    pub xp_awarded: i32,
+}
+
+/// Creates a tournament.
+pub async fn create_tournament(pool: &PgPool, tournament: &NewTournament<'_>) -> Result<Tournament, sqlx::Error> {
+    let tournament = sqlx::query_as!(
+        Tournament,
+        r#"
+        INSERT INTO tournaments (
+            id, name, description, starting_balance_cents, allowed_symbols, max_participants,
+            registration_opens_at, registration_closes_at, starts_at, ends_at, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        tournament.name,
+        tournament.description,
+        tournament.starting_balance_cents,
+        tournament.allowed_symbols,
+        tournament.max_participants,
+        tournament.registration_opens_at,
+        tournament.registration_closes_at,
+        tournament.starts_at,
+        tournament.ends_at,
+        Utc::now()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(tournament)
+}
+
+/// Finds a tournament by id.
+pub async fn find_tournament(pool: &PgPool, tournament_id: Uuid) -> Result<Option<Tournament>, sqlx::Error> {
+    let tournament = sqlx::query_as!(Tournament, "SELECT * FROM tournaments WHERE id = $1", tournament_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(tournament)
+}
+
+/// Loads a tournament and locks it for the rest of the transaction.
+/// Serializes registrations against the entry limit and finalization.
+pub async fn lock_tournament(conn: &mut PgConnection, tournament_id: Uuid) -> Result<Option<Tournament>, sqlx::Error> {
+    let tournament = sqlx::query_as!(
+        Tournament,
+        "SELECT * FROM tournaments WHERE id = $1 FOR UPDATE",
+        tournament_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(tournament)
+}
+
+/// Lists tournaments by start time, most recent first.
+/// Finalized tournaments are only included when requested.
+pub async fn list_tournaments(pool: &PgPool, include_finalized: bool) -> Result<Vec<Tournament>, sqlx::Error> {
+    let tournaments = sqlx::query_as!(
+        Tournament,
+        r#"
+        SELECT * FROM tournaments
+        WHERE $1 OR finalized_at IS NULL
+        ORDER BY starts_at DESC
+        "#,
+        include_finalized
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tournaments)
+}
+
+/// Lists tournaments whose trading has ended but which are not finalized yet.
+pub async fn list_due_tournaments(pool: &PgPool, now: DateTime<Utc>) -> Result<Vec<Tournament>, sqlx::Error> {
+    let tournaments = sqlx::query_as!(
+        Tournament,
+        "SELECT * FROM tournaments WHERE finalized_at IS NULL AND ends_at <= $1 ORDER BY ends_at",
+        now
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tournaments)
+}
+
+/// Counts a tournament's entrants.
+pub async fn count_entries(executor: impl PgExecutor<'_>, tournament_id: Uuid) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM tournament_entries WHERE tournament_id = $1"#,
+        tournament_id
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(count)
+}
+
+/// Registers a user with a fresh account, playing for their clan when it has been entered.
+/// Returns `None` when the user is already registered.
+pub async fn create_entry(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    cash_balance_cents: i64,
+) -> Result<Option<TournamentEntry>, sqlx::Error> {
+    let entry = sqlx::query_as!(
+        TournamentEntry,
+        r#"
+        INSERT INTO tournament_entries (tournament_id, user_id, cash_balance_cents, joined_at, clan_id)
+        VALUES ($1, $2, $3, $4, (
+            SELECT m.clan_id FROM clan_members m
+            JOIN tournament_clans tc ON tc.clan_id = m.clan_id AND tc.tournament_id = $1
+            WHERE m.user_id = $2
+        ))
+        ON CONFLICT (tournament_id, user_id) DO NOTHING
+        RETURNING *
+        "#,
+        tournament_id,
+        user_id,
+        cash_balance_cents,
+        Utc::now()
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(entry)
+}
+
+/// Finds a user's entry in a tournament.
+pub async fn find_entry(
+    executor: impl PgExecutor<'_>,
+    tournament_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<TournamentEntry>, sqlx::Error> {
+    let entry = sqlx::query_as!(
+        TournamentEntry,
+        "SELECT * FROM tournament_entries WHERE tournament_id = $1 AND user_id = $2",
+        tournament_id,
+        user_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(entry)
+}
+
+/// Loads a user's entry and locks it for the rest of the transaction.
+/// Prevents concurrent orders from spending the same tournament cash twice.
+pub async fn lock_entry(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<TournamentEntry>, sqlx::Error> {
+    let entry = sqlx::query_as!(
+        TournamentEntry,
+        "SELECT * FROM tournament_entries WHERE tournament_id = $1 AND user_id = $2 FOR UPDATE",
+        tournament_id,
+        user_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(entry)
+}
+
+/// Sets an entrant's tournament cash balance.
+pub async fn update_entry_cash(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    cash_balance_cents: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE tournament_entries SET cash_balance_cents = $3 WHERE tournament_id = $1 AND user_id = $2",
+        tournament_id,
+        user_id,
+        cash_balance_cents
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Loads an entrant's position in a symbol and locks it for the rest of the transaction.
+pub async fn lock_position(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    symbol: &str,
+) -> Result<Option<TournamentPosition>, sqlx::Error> {
+    let position = sqlx::query_as!(
+        TournamentPosition,
+        r#"
+        SELECT * FROM tournament_positions
+        WHERE tournament_id = $1 AND user_id = $2 AND symbol = $3
+        FOR UPDATE
+        "#,
+        tournament_id,
+        user_id,
+        symbol
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(position)
+}
+
+/// Creates or replaces an entrant's position in a symbol.
+pub async fn upsert_position(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    symbol: &str,
+    quantity: i64,
+    average_price: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO tournament_positions (tournament_id, user_id, symbol, quantity, average_price, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (tournament_id, user_id, symbol) DO UPDATE
+        SET quantity = EXCLUDED.quantity,
+            average_price = EXCLUDED.average_price,
+            updated_at = EXCLUDED.updated_at
+        "#,
+        tournament_id,
+        user_id,
+        symbol,
+        quantity,
+        average_price,
+        Utc::now()
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes an entrant's position once it has been fully sold.
+pub async fn delete_position(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    symbol: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM tournament_positions WHERE tournament_id = $1 AND user_id = $2 AND symbol = $3",
+        tournament_id,
+        user_id,
+        symbol
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Lists an entrant's open positions.
+pub async fn list_positions(
+    executor: impl PgExecutor<'_>,
+    tournament_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<TournamentPosition>, sqlx::Error> {
+    let positions = sqlx::query_as!(
+        TournamentPosition,
+        "SELECT * FROM tournament_positions WHERE tournament_id = $1 AND user_id = $2 ORDER BY symbol",
+        tournament_id,
+        user_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(positions)
+}
+
+/// Records a tournament fill.
+pub async fn insert_trade(
+    conn: &mut PgConnection,
+    trade: &NewTournamentTrade<'_>,
+) -> Result<TournamentTrade, sqlx::Error> {
+    let trade = sqlx::query_as!(
+        TournamentTrade,
+        r#"
+        INSERT INTO tournament_trades (
+            id, tournament_id, user_id, symbol, trade_type, quantity, price, total_value, fee_cents, executed_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        trade.tournament_id,
+        trade.user_id,
+        trade.symbol,
+        trade.trade_type,
+        trade.quantity,
+        trade.price,
+        trade.total_value,
+        trade.fee_cents,
+        Utc::now()
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(trade)
+}
+
+/// Lists an entrant's most recent tournament trades, newest first.
+pub async fn list_trades(
+    pool: &PgPool,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<TournamentTrade>, sqlx::Error> {
+    let trades = sqlx::query_as!(
+        TournamentTrade,
+        r#"
+        SELECT * FROM tournament_trades
+        WHERE tournament_id = $1 AND user_id = $2
+        ORDER BY executed_at DESC
+        LIMIT $3
+        "#,
+        tournament_id,
+        user_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(trades)
+}
+
+/// Ranks a tournament's entrants by account value at the given prices, best first.
+/// Positions in symbols without a price are valued at their average price.
+pub async fn live_standings(
+    pool: &PgPool,
+    tournament_id: Uuid,
+    symbols: &[String],
+    prices_cents: &[i64],
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<TournamentStanding>, sqlx::Error> {
+    let standings = sqlx::query_as!(
+        TournamentStanding,
+        r#"
+        SELECT v.user_id AS "user_id!", u.username, u.wallet_address,
+               RANK() OVER (ORDER BY v.value_cents DESC) AS "rank!",
+               v.value_cents AS "value_cents!", NULL::VARCHAR AS badge, 0 AS "xp_awarded!"
+        FROM (
+            SELECT e.user_id,
+                   (e.cash_balance_cents + COALESCE(SUM(
+                       ROUND(p.quantity::NUMERIC * COALESCE(px.price_cents, p.average_price) / 1000000)
+                   ), 0))::BIGINT AS value_cents
+            FROM tournament_entries e
+            LEFT JOIN tournament_positions p ON p.tournament_id = e.tournament_id AND p.user_id = e.user_id
+            LEFT JOIN UNNEST($2::VARCHAR[], $3::BIGINT[]) AS px(symbol, price_cents) ON px.symbol = p.symbol
+            WHERE e.tournament_id = $1
+            GROUP BY e.user_id, e.cash_balance_cents
+        ) v
+        JOIN users u ON u.id = v.user_id
+        ORDER BY 4, v.user_id
+        LIMIT $4 OFFSET $5
+        "#,
+        tournament_id,
+        symbols,
+        prices_cents,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(standings)
+}
+
+/// Lists a finalized tournament's recorded ranking, best first.
+pub async fn final_standings(
+    pool: &PgPool,
+    tournament_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<TournamentStanding>, sqlx::Error> {
+    let standings = sqlx::query_as!(
+        TournamentStanding,
+        r#"
+        SELECT e.user_id, u.username, u.wallet_address,
+               e.final_rank::BIGINT AS "rank!", e.final_value_cents AS "value_cents!", e.badge, e.xp_awarded
+        FROM tournament_entries e
+        JOIN users u ON u.id = e.user_id
+        WHERE e.tournament_id = $1 AND e.final_rank IS NOT NULL
+        ORDER BY e.final_rank, e.user_id
+        LIMIT $2 OFFSET $3
+        "#,
+        tournament_id,
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(standings)
+}
+
+/// Records every entrant's final account value and rank at the given prices.
+/// Returns the number of entrants ranked.
+pub async fn record_final_ranking(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    symbols: &[String],
+    prices_cents: &[i64],
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE tournament_entries e
+        SET final_value_cents = r.value_cents, final_rank = r.rank
+        FROM (
+            SELECT v.user_id, v.value_cents, RANK() OVER (ORDER BY v.value_cents DESC)::INTEGER AS rank
+            FROM (
+                SELECT e.user_id,
+                       (e.cash_balance_cents + COALESCE(SUM(
+                           ROUND(p.quantity::NUMERIC * COALESCE(px.price_cents, p.average_price) / 1000000)
+                       ), 0))::BIGINT AS value_cents
+                FROM tournament_entries e
+                LEFT JOIN tournament_positions p ON p.tournament_id = e.tournament_id AND p.user_id = e.user_id
+                LEFT JOIN UNNEST($2::VARCHAR[], $3::BIGINT[]) AS px(symbol, price_cents) ON px.symbol = p.symbol
+                WHERE e.tournament_id = $1
+                GROUP BY e.user_id, e.cash_balance_cents
+            ) v
+        ) r
+        WHERE e.tournament_id = $1 AND e.user_id = r.user_id
+        "#,
+        tournament_id,
+        symbols,
+        prices_cents
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Lists the entries ranked at or above `max_rank` after the final ranking, best first.
+/// Entrants who never traded are left out so idle entries cannot win prizes.
+pub async fn list_prize_candidates(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    max_rank: i32,
+) -> Result<Vec<TournamentEntry>, sqlx::Error> {
+    let entries = sqlx::query_as!(
+        TournamentEntry,
+        r#"
+        SELECT * FROM tournament_entries
+        WHERE tournament_id = $1 AND final_rank <= $2
+          AND EXISTS (
+              SELECT 1 FROM tournament_trades t
+              WHERE t.tournament_id = tournament_entries.tournament_id AND t.user_id = tournament_entries.user_id
+          )
+        ORDER BY final_rank, user_id
+        "#,
+        tournament_id,
+        max_rank
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Lists the final rank of every entrant who traded, best first.
+/// Used to rate finishers; idle entries are left out like they are for prizes.
+pub async fn list_rated_finishers(conn: &mut PgConnection, tournament_id: Uuid) -> Result<Vec<(Uuid, i64)>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT e.user_id, e.final_rank::BIGINT AS "rank!"
+        FROM tournament_entries e
+        WHERE e.tournament_id = $1 AND e.final_rank IS NOT NULL
+          AND EXISTS (
+              SELECT 1 FROM tournament_trades t WHERE t.tournament_id = e.tournament_id AND t.user_id = e.user_id
+          )
+        ORDER BY e.final_rank, e.user_id
+        "#,
+        tournament_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| (row.user_id, row.rank)).collect())
+}
+
+/// Records the prize earned by an entrant.
+pub async fn set_entry_prize(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    user_id: Uuid,
+    badge: &str,
+    xp_awarded: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE tournament_entries SET badge = $3, xp_awarded = $4 WHERE tournament_id = $1 AND user_id = $2",
+        tournament_id,
+        user_id,
+        badge,
+        xp_awarded
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Marks a tournament as finalized.
+pub async fn mark_finalized(
+    conn: &mut PgConnection,
+    tournament_id: Uuid,
+    finalized_at: DateTime<Utc>,
+) -> Result<Tournament, sqlx::Error> {
+    let tournament = sqlx::query_as!(
+        Tournament,
+        "UPDATE tournaments SET finalized_at = $2 WHERE id = $1 RETURNING *",
+        tournament_id,
+        finalized_at
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(tournament)
+}
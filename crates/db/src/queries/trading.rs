@@ -0,0 +1,233 @@
+//! Paper trading database queries.
+//! Handles trades, positions and cash balance updates during order execution.
+
+use futures_util::stream::BoxStream;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::Utc;
+use crate::models::{Position, Trade, User};
+
+/// Trade row to be inserted after an order has been filled.
+#[derive(Debug, Clone)]
+pub struct NewTrade<'a> {
+    /// User who executed the trade.
+    pub user_id: Uuid,
+    /// Trading symbol.
+    pub symbol: &'a str,
+    /// Trade type: "buy" or "sell".
+    pub trade_type: &'a str,
+    /// Quantity in micro units.
+    pub quantity: i64,
+    /// Fill price in cents, including slippage.
+    pub price: i64,
+    /// Quantity times fill price in cents.
+    pub total_value: i64,
+    /// Oracle price before slippage in cents.
+    pub reference_price: i64,
+    /// Fee charged in cents.
+    pub fee_cents: i64,
+    /// Slippage cost in cents.
+    pub slippage_cents: i64,
+    /// Bot that placed the order, if any.
+    pub bot_id: Option<Uuid>,
+}
+
+/// Loads a user and locks the row for the rest of the transaction.
+/// Prevents concurrent orders from spending the same cash twice.
+pub async fn lock_user(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> Result<Option<User>, sqlx::Error> {
+    let user = sqlx::query_as!(
+        User,
+        "SELECT * FROM users WHERE id = $1 FOR UPDATE",
+        user_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(user)
+}
+
+/// Updates user's cash balance and portfolio value after a fill.
+pub async fn update_user_balances(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    cash_balance_cents: i64,
+    portfolio_value_cents: i64,
+) -> Result<User, sqlx::Error> {
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        UPDATE users
+        SET cash_balance_cents = $2, portfolio_value_cents = $3, updated_at = $4
+        WHERE id = $1
+        RETURNING *
+        "#,
+        user_id,
+        cash_balance_cents,
+        portfolio_value_cents,
+        Utc::now()
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(user)
+}
+
+/// Inserts an executed trade.
+pub async fn insert_trade(
+    conn: &mut PgConnection,
+    trade: &NewTrade<'_>,
+) -> Result<Trade, sqlx::Error> {
+    let trade = sqlx::query_as!(
+        Trade,
+        r#"
+        INSERT INTO trades (id, user_id, symbol, trade_type, quantity, price, total_value, executed_at, reference_price, fee_cents, slippage_cents, bot_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        trade.user_id,
+        trade.symbol,
+        trade.trade_type,
+        trade.quantity,
+        trade.price,
+        trade.total_value,
+        Utc::now(),
+        trade.reference_price,
+        trade.fee_cents,
+        trade.slippage_cents,
+        trade.bot_id
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(trade)
+}
+
+/// Loads a user's position in a symbol and locks it for the rest of the transaction.
+pub async fn lock_position(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    symbol: &str,
+) -> Result<Option<Position>, sqlx::Error> {
+    let position = sqlx::query_as!(
+        Position,
+        "SELECT * FROM positions WHERE user_id = $1 AND symbol = $2 FOR UPDATE",
+        user_id,
+        symbol
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(position)
+}
+
+/// Creates or replaces a user's position in a symbol.
+/// A new position is opened now. Adding to an existing one moves its opening time towards now by
+/// the added share of the quantity, so it stays the quantity-weighted open time; selling keeps it.
+pub async fn upsert_position(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    symbol: &str,
+    quantity: i64,
+    average_price: i64,
+    current_value: i64,
+) -> Result<Position, sqlx::Error> {
+    let position = sqlx::query_as!(
+        Position,
+        r#"
+        INSERT INTO positions (id, user_id, symbol, quantity, average_price, current_value, updated_at, opened_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+        ON CONFLICT (user_id, symbol) DO UPDATE
+        SET quantity = EXCLUDED.quantity,
+            average_price = EXCLUDED.average_price,
+            current_value = EXCLUDED.current_value,
+            updated_at = EXCLUDED.updated_at,
+            opened_at = CASE
+                WHEN EXCLUDED.quantity > positions.quantity THEN positions.opened_at
+                    + (EXCLUDED.opened_at - positions.opened_at)
+                    * ((EXCLUDED.quantity - positions.quantity)::FLOAT8 / EXCLUDED.quantity)
+                ELSE positions.opened_at
+            END
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        symbol,
+        quantity,
+        average_price,
+        current_value,
+        Utc::now()
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(position)
+}
+
+/// Removes a position once it has been fully sold.
+pub async fn delete_position(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    symbol: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "DELETE FROM positions WHERE user_id = $1 AND symbol = $2",
+        user_id,
+        symbol
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Lists all open positions of a user.
+/// Accepts either the pool or an open transaction.
+pub async fn list_positions(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+) -> Result<Vec<Position>, sqlx::Error> {
+    let positions = sqlx::query_as!(
+        Position,
+        "SELECT * FROM positions WHERE user_id = $1 ORDER BY symbol",
+        user_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(positions)
+}
+
+/// Lists a user's most recent trades, newest first.
+pub async fn list_trades(
+    pool: &PgPool,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<Trade>, sqlx::Error> {
+    let trades = sqlx::query_as!(
+        Trade,
+        "SELECT * FROM trades WHERE user_id = $1 ORDER BY executed_at DESC LIMIT $2",
+        user_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(trades)
+}
+
+/// Streams all of a user's trades, oldest first, without loading them into memory.
+pub fn stream_trades(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> BoxStream<'_, Result<Trade, sqlx::Error>> {
+    sqlx::query_as!(
+        Trade,
+        "SELECT * FROM trades WHERE user_id = $1 ORDER BY executed_at, id",
+        user_id
+    )
+    .fetch(pool)
+}
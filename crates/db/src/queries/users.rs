@@ -1,11 +1,14 @@
 //! User-related database queries.
 //! Handles user creation, authentication, and profile management.
 
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 use chrono::Utc;
 use crate::models::User;
 
+/// Paper cash every user starts a season with, in cents ($10,000).
+pub const STARTING_BALANCE_CENTS: i64 = 1_000_000;
+
 /// Creates a new user account with wallet address.
 /// Initializes user with default XP, level, and starting cash balance.
 pub async fn create_user(
@@ -28,8 +31,8 @@ pub async fn create_user(
         username,
         0i32,       // xp_points (now i32)
         1i16,       // level (i16)
-        1000000i64, // portfolio_value_cents (i64) - $10,000 in cents
-        1000000i64, // cash_balance_cents (i64) - $10,000 in cents
+        STARTING_BALANCE_CENTS, // portfolio_value_cents (i64) - $10,000 in cents
+        STARTING_BALANCE_CENTS, // cash_balance_cents (i64) - $10,000 in cents
         now,
         now
     )
@@ -57,7 +60,7 @@ pub async fn find_user_by_wallet(
 
 /// Finds a user by their unique ID.
 pub async fn find_user_by_id(
-    pool: &PgPool,
+    executor: impl PgExecutor<'_>,
     user_id: Uuid,
 ) -> Result<Option<User>, sqlx::Error> {
     let user = sqlx::query_as!(
@@ -65,38 +68,8 @@ pub async fn find_user_by_id(
         "SELECT * FROM users WHERE id = $1",
         user_id
     )
-    .fetch_optional(pool)
+    .fetch_optional(executor)
     .await?;
 
     Ok(user)
 }
-
-
-/// Updates user's XP points and recalculates level.
-/// Used for gamification features when users complete actions.
-pub async fn update_user_xp(
-    pool: &PgPool,
-    user_id: Uuid,
-    xp_points: i64,
-) -> Result<User, sqlx::Error> {
-    let level = game::calculate_level_from_xp(xp_points as u32);
-    let now = Utc::now();
-    
-    let user = sqlx::query_as!(
-        User,
-        r#"
-        UPDATE users 
-        SET xp_points = $2, level = $3, updated_at = $4
-        WHERE id = $1
-        RETURNING *
-        "#,
-        user_id,
-        xp_points as i32,
-        level as i16,
-        now
-    )
-    .fetch_one(pool)
-    .await?;
-
-    Ok(user)
-}
\ No newline at end of file
@@ -0,0 +1,186 @@
+//! Watchlist database queries.
+//! Handles a user's named lists of watched symbols.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::Utc;
+use crate::models::Watchlist;
+
+/// Creates a watchlist.
+pub async fn create_watchlist(
+    pool: &PgPool,
+    user_id: Uuid,
+    name: &str,
+    symbols: &[String],
+) -> Result<Watchlist, sqlx::Error> {
+    let now = Utc::now();
+    let watchlist = sqlx::query_as!(
+        Watchlist,
+        r#"
+        INSERT INTO watchlists (id, user_id, name, symbols, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $5)
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        name,
+        symbols,
+        now
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(watchlist)
+}
+
+/// Counts the watchlists owned by a user.
+pub async fn count_watchlists(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<i64, sqlx::Error> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM watchlists WHERE user_id = $1"#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Lists a user's watchlists, oldest first.
+pub async fn list_watchlists(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> Result<Vec<Watchlist>, sqlx::Error> {
+    let watchlists = sqlx::query_as!(
+        Watchlist,
+        "SELECT * FROM watchlists WHERE user_id = $1 ORDER BY created_at",
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(watchlists)
+}
+
+/// Finds a user's watchlist.
+pub async fn find_watchlist(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<Watchlist>, sqlx::Error> {
+    let watchlist = sqlx::query_as!(
+        Watchlist,
+        "SELECT * FROM watchlists WHERE id = $1 AND user_id = $2",
+        id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(watchlist)
+}
+
+/// Renames a watchlist and/or replaces its symbols. Fields that are `None` are left unchanged.
+/// Returns `None` when the watchlist does not exist or belongs to another user.
+pub async fn update_watchlist(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+    name: Option<&str>,
+    symbols: Option<&[String]>,
+) -> Result<Option<Watchlist>, sqlx::Error> {
+    let watchlist = sqlx::query_as!(
+        Watchlist,
+        r#"
+        UPDATE watchlists
+        SET name = COALESCE($3, name),
+            symbols = COALESCE($4, symbols),
+            updated_at = $5
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+        id,
+        user_id,
+        name,
+        symbols as Option<&[String]>,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(watchlist)
+}
+
+/// Appends a symbol to a watchlist unless it is already on it.
+/// Returns `None` when the watchlist does not exist or belongs to another user.
+pub async fn add_watchlist_symbol(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+    symbol: &str,
+) -> Result<Option<Watchlist>, sqlx::Error> {
+    let watchlist = sqlx::query_as!(
+        Watchlist,
+        r#"
+        UPDATE watchlists
+        SET symbols = CASE WHEN $3 = ANY(symbols) THEN symbols ELSE array_append(symbols, $3) END,
+            updated_at = $4
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+        id,
+        user_id,
+        symbol,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(watchlist)
+}
+
+/// Removes a symbol from a watchlist.
+/// Returns `None` when the watchlist does not exist or belongs to another user.
+pub async fn remove_watchlist_symbol(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+    symbol: &str,
+) -> Result<Option<Watchlist>, sqlx::Error> {
+    let watchlist = sqlx::query_as!(
+        Watchlist,
+        r#"
+        UPDATE watchlists
+        SET symbols = array_remove(symbols, $3), updated_at = $4
+        WHERE id = $1 AND user_id = $2
+        RETURNING *
+        "#,
+        id,
+        user_id,
+        symbol,
+        Utc::now()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(watchlist)
+}
+
+/// Deletes a user's watchlist. Returns `false` when there was nothing to delete.
+pub async fn delete_watchlist(
+    pool: &PgPool,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        "DELETE FROM watchlists WHERE id = $1 AND user_id = $2",
+        id,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
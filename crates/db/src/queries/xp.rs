@@ -0,0 +1,175 @@
+//! XP ledger database queries.
+//! Records XP changes as events and keeps the user's total and level in step with them.
+
+use sqlx::{Connection, PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::models::XpEvent;
+
+/// XP change to record for a user.
+#[derive(Debug, Clone, Default)]
+pub struct XpGrant<'a> {
+    /// What changed the XP, e.g. "trade" or "adjustment".
+    pub source: &'a str,
+    /// XP to add. Negative amounts deduct XP, never below zero.
+    pub amount: i32,
+    /// Entity behind the change, e.g. a trade id.
+    pub reference_id: Option<&'a str>,
+    /// Key that makes the grant apply at most once per user.
+    pub idempotency_key: Option<&'a str>,
+}
+
+/// Records an XP event and applies it to the user's total and level in the current season.
+/// Runs in its own transaction, or a savepoint when `conn` is already in one.
+/// Returns `None` when a grant with the same idempotency key was already applied.
+pub async fn grant_xp(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    grant: &XpGrant<'_>,
+) -> Result<Option<XpEvent>, sqlx::Error> {
+    let mut tx = conn.begin().await?;
+    let now = Utc::now();
+
+    // Lock the user so concurrent grants apply one after another
+    let xp_points = sqlx::query_scalar!(
+        "SELECT xp_points FROM users WHERE id = $1 FOR UPDATE",
+        user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let xp_after = xp_points.max(0).saturating_add(grant.amount).max(0);
+    let level_after = game::calculate_level_from_xp(xp_after as u32) as i16;
+
+    let event = sqlx::query_as!(
+        XpEvent,
+        r#"
+        INSERT INTO xp_events (
+            id, user_id, source, amount, reference_id, idempotency_key, xp_after, level_after, created_at, season_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, (SELECT id FROM seasons WHERE closed_at IS NULL))
+        ON CONFLICT (user_id, idempotency_key) DO NOTHING
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        grant.source,
+        xp_after - xp_points.max(0),
+        grant.reference_id,
+        grant.idempotency_key,
+        xp_after,
+        level_after,
+        now
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(event) = event else {
+        return Ok(None);
+    };
+
+    sqlx::query!(
+        "UPDATE users SET xp_points = $2, level = $3, updated_at = $4 WHERE id = $1",
+        user_id,
+        xp_after,
+        level_after,
+        now
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(event))
+}
+
+/// Recomputes every user's stored level from their XP with the active level curve.
+/// Run at startup so levels stay correct when the curve changes. Returns the number of users updated.
+pub async fn sync_user_levels(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let users = sqlx::query!("SELECT id, xp_points, level FROM users")
+        .fetch_all(pool)
+        .await?;
+
+    let (mut ids, mut xp_points, mut levels) = (Vec::new(), Vec::new(), Vec::new());
+    for user in users {
+        let level = game::calculate_level_from_xp(game::safe_xp_conversion(user.xp_points)) as i16;
+        if level != user.level {
+            ids.push(user.id);
+            xp_points.push(user.xp_points);
+            levels.push(level);
+        }
+    }
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    // Users whose XP changed since they were read already have a level from the same curve
+    let result = sqlx::query!(
+        r#"
+        UPDATE users u
+        SET level = t.level
+        FROM UNNEST($1::UUID[], $2::INTEGER[], $3::SMALLINT[]) AS t(id, xp_points, level)
+        WHERE u.id = t.id AND u.xp_points = t.xp_points
+        "#,
+        &ids,
+        &xp_points,
+        &levels
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Lists a user's XP events, newest first.
+/// Only events ordered after the `(created_at, id)` cursor `before` are returned when it is given, for paging.
+/// A cursor without an id skips every event at its time.
+pub async fn list_xp_events(
+    pool: &PgPool,
+    user_id: Uuid,
+    before: Option<(DateTime<Utc>, Option<Uuid>)>,
+    limit: i64,
+) -> Result<Vec<XpEvent>, sqlx::Error> {
+    let (before, before_id) = before.unzip();
+    let events = sqlx::query_as!(
+        XpEvent,
+        r#"
+        SELECT * FROM xp_events
+        WHERE user_id = $1
+          AND ($2::TIMESTAMPTZ IS NULL OR created_at < $2 OR (created_at = $2 AND id < $3::UUID))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $4
+        "#,
+        user_id,
+        before,
+        before_id.flatten(),
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+/// Counts a user's XP awards from a source since a time and sums the XP they granted.
+/// Deductions are ignored so reversals do not free up daily allowance.
+pub async fn xp_activity_since(
+    executor: impl PgExecutor<'_>,
+    user_id: Uuid,
+    source: &str,
+    since: DateTime<Utc>,
+) -> Result<(i64, i64), sqlx::Error> {
+    let activity = sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "awards!", COALESCE(SUM(amount), 0)::BIGINT AS "xp!"
+        FROM xp_events
+        WHERE user_id = $1 AND source = $2 AND created_at >= $3 AND amount > 0
+        "#,
+        user_id,
+        source,
+        since
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok((activity.awards, activity.xp))
+}
@@ -0,0 +1,213 @@
+//! Achievement catalog and evaluator.
+//! Achievements are plain data; each has one criterion checked against a player's running stats.
+
+/// Player statistics achievement criteria are checked against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayerProgress {
+    /// Trades executed.
+    pub trade_count: i64,
+    /// Notional traded, in cents.
+    pub volume_cents: i64,
+    /// Sells that realized a profit.
+    pub profitable_closes: i64,
+    /// Profit realized across all sells, in cents.
+    pub realized_pnl_cents: i64,
+    /// Best return of a single sell on its cost, in basis points.
+    pub best_return_bps: i64,
+    /// Longest a position was held before selling, in seconds.
+    pub longest_hold_seconds: i64,
+    /// Longest activity streak reached, in days.
+    pub longest_login_streak_days: i64,
+    /// Total XP earned.
+    pub xp: i64,
+    /// Current level.
+    pub level: i64,
+}
+
+/// Condition that unlocks an achievement once its target is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    /// Number of trades executed.
+    TradeCount(i64),
+    /// Notional traded, in cents.
+    VolumeCents(i64),
+    /// Number of sells that realized a profit.
+    ProfitableCloses(i64),
+    /// Profit realized across all sells, in cents.
+    RealizedPnlCents(i64),
+    /// Return of a single sell on its cost, in basis points.
+    SingleReturnBps(i64),
+    /// Days a position was held before selling.
+    HoldDays(i64),
+    /// Activity streak length, in days.
+    LoginStreakDays(i64),
+    /// Total XP earned.
+    Xp(i64),
+    /// Level reached.
+    Level(i64),
+}
+
+impl Criterion {
+    /// Returns the player's current value and the target, in the criterion's unit.
+    pub fn progress(&self, player: &PlayerProgress) -> (i64, i64) {
+        match *self {
+            Criterion::TradeCount(target) => (player.trade_count, target),
+            Criterion::VolumeCents(target) => (player.volume_cents, target),
+            Criterion::ProfitableCloses(target) => (player.profitable_closes, target),
+            Criterion::RealizedPnlCents(target) => (player.realized_pnl_cents, target),
+            Criterion::SingleReturnBps(target) => (player.best_return_bps, target),
+            Criterion::HoldDays(target) => (player.longest_hold_seconds / 86_400, target),
+            Criterion::LoginStreakDays(target) => (player.longest_login_streak_days, target),
+            Criterion::Xp(target) => (player.xp, target),
+            Criterion::Level(target) => (player.level, target),
+        }
+    }
+
+    /// Returns whether the player has reached the target.
+    pub fn is_met(&self, player: &PlayerProgress) -> bool {
+        let (current, target) = self.progress(player);
+        current >= target
+    }
+}
+
+/// Collectible achievement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Achievement {
+    /// Stable key stored with unlocks.
+    pub id: &'static str,
+    /// Display name.
+    pub name: &'static str,
+    /// What the player has to do.
+    pub description: &'static str,
+    /// Badge rarity: "bronze", "silver", "gold" or "platinum".
+    pub badge: &'static str,
+    /// XP granted when the achievement unlocks.
+    pub xp_reward: u32,
+    /// Condition that unlocks the achievement.
+    pub criterion: Criterion,
+}
+
+/// Source of XP granted for unlocking achievements.
+pub const ACHIEVEMENT_XP_SOURCE: &str = "achievement";
+
+/// All achievements, in display order.
+pub const ACHIEVEMENTS: [Achievement; 13] = [
+    Achievement {
+        id: "first_blood",
+        name: "First Blood",
+        description: "Execute your first trade",
+        badge: "bronze",
+        xp_reward: 50,
+        criterion: Criterion::TradeCount(1),
+    },
+    Achievement {
+        id: "active_trader",
+        name: "Active Trader",
+        description: "Execute 100 trades",
+        badge: "silver",
+        xp_reward: 150,
+        criterion: Criterion::TradeCount(100),
+    },
+    Achievement {
+        id: "whale",
+        name: "Whale",
+        description: "Trade $1,000,000 in total volume",
+        badge: "gold",
+        xp_reward: 300,
+        criterion: Criterion::VolumeCents(100_000_000),
+    },
+    Achievement {
+        id: "in_the_green",
+        name: "In the Green",
+        description: "Sell a position at a profit",
+        badge: "bronze",
+        xp_reward: 50,
+        criterion: Criterion::ProfitableCloses(1),
+    },
+    Achievement {
+        id: "consistent_winner",
+        name: "Consistent Winner",
+        description: "Sell at a profit 25 times",
+        badge: "silver",
+        xp_reward: 200,
+        criterion: Criterion::ProfitableCloses(25),
+    },
+    Achievement {
+        id: "big_earner",
+        name: "Big Earner",
+        description: "Realize $10,000 in total profit",
+        badge: "gold",
+        xp_reward: 300,
+        criterion: Criterion::RealizedPnlCents(1_000_000),
+    },
+    Achievement {
+        id: "ten_x_trader",
+        name: "10x Trader",
+        description: "Sell a position for ten times what it cost",
+        badge: "platinum",
+        xp_reward: 500,
+        criterion: Criterion::SingleReturnBps(90_000),
+    },
+    Achievement {
+        id: "diamond_hands",
+        name: "Diamond Hands",
+        description: "Hold a position for 30 days before selling",
+        badge: "gold",
+        xp_reward: 250,
+        criterion: Criterion::HoldDays(30),
+    },
+    Achievement {
+        id: "regular",
+        name: "Regular",
+        description: "Sign in 7 days in a row",
+        badge: "bronze",
+        xp_reward: 100,
+        criterion: Criterion::LoginStreakDays(7),
+    },
+    Achievement {
+        id: "dedicated",
+        name: "Dedicated",
+        description: "Sign in 30 days in a row",
+        badge: "gold",
+        xp_reward: 300,
+        criterion: Criterion::LoginStreakDays(30),
+    },
+    Achievement {
+        id: "xp_hunter",
+        name: "XP Hunter",
+        description: "Earn 10,000 XP",
+        badge: "silver",
+        xp_reward: 0,
+        criterion: Criterion::Xp(10_000),
+    },
+    Achievement {
+        id: "rising_star",
+        name: "Rising Star",
+        description: "Reach level 5",
+        badge: "silver",
+        xp_reward: 100,
+        criterion: Criterion::Level(5),
+    },
+    Achievement {
+        id: "veteran",
+        name: "Veteran",
+        description: "Reach level 20",
+        badge: "platinum",
+        xp_reward: 500,
+        criterion: Criterion::Level(20),
+    },
+];
+
+/// Returns an achievement by id.
+pub fn achievement(id: &str) -> Option<&'static Achievement> {
+    ACHIEVEMENTS.iter().find(|achievement| achievement.id == id)
+}
+
+/// Returns the achievements a player qualifies for but has not unlocked yet.
+pub fn newly_unlocked(player: &PlayerProgress, unlocked: &[String]) -> Vec<&'static Achievement> {
+    ACHIEVEMENTS
+        .iter()
+        .filter(|achievement| !unlocked.iter().any(|id| id == achievement.id))
+        .filter(|achievement| achievement.criterion.is_met(player))
+        .collect()
+}
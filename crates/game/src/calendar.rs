@@ -0,0 +1,24 @@
+//! Calendar arithmetic on Unix timestamps.
+//! Weeks start on Monday 00:00 UTC wherever the game counts them, for quests and leagues alike.
+
+/// Seconds in a day.
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Days between the Unix epoch, a Thursday, and the Monday before it.
+const EPOCH_WEEKDAY_OFFSET: i64 = 3;
+
+/// Returns the index of the week containing a Unix timestamp.
+/// Week 0 starts on Monday 1969-12-29.
+pub fn week_index(unix: i64) -> i64 {
+    (unix.div_euclid(SECONDS_PER_DAY) + EPOCH_WEEKDAY_OFFSET).div_euclid(7)
+}
+
+/// Returns the start of a week by index: Monday 00:00 UTC, as a Unix timestamp.
+pub fn week_index_start(index: i64) -> i64 {
+    (index * 7 - EPOCH_WEEKDAY_OFFSET) * SECONDS_PER_DAY
+}
+
+/// Returns the start of the week containing a Unix timestamp: Monday 00:00 UTC, as a Unix timestamp.
+pub fn week_start(unix: i64) -> i64 {
+    week_index_start(week_index(unix))
+}
@@ -0,0 +1,104 @@
+//! Clan rules.
+//! Defines member roles and what each may do, and aggregates members' activity into clan standings.
+
+use crate::leaderboards::{LeaderboardMetric, TraderActivity};
+
+/// Most members a clan can have.
+pub const MAX_CLAN_MEMBERS: i64 = 50;
+
+/// Length of generated invite codes.
+pub const INVITE_CODE_LENGTH: usize = 8;
+
+/// Members with a ranked return a clan needs to appear on the return leaderboard.
+pub const MIN_RETURN_MEMBERS: usize = 3;
+
+/// Member's role within a clan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ClanRole {
+    /// Regular member.
+    Member,
+    /// Helps run the clan: invites, removes members and enters tournaments.
+    Officer,
+    /// Founder or current leader; exactly one per clan.
+    Owner,
+}
+
+impl ClanRole {
+    /// All roles, lowest first.
+    pub const ALL: [ClanRole; 3] = [ClanRole::Member, ClanRole::Officer, ClanRole::Owner];
+
+    /// Returns the role name stored and shown to clients.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClanRole::Member => "member",
+            ClanRole::Officer => "officer",
+            ClanRole::Owner => "owner",
+        }
+    }
+
+    /// Parses a role name.
+    pub fn parse(value: &str) -> Option<ClanRole> {
+        Self::ALL.into_iter().find(|role| role.as_str() == value)
+    }
+
+    /// Returns whether the role can manage invites and enter the clan into tournaments.
+    pub fn can_manage(&self) -> bool {
+        *self >= ClanRole::Officer
+    }
+
+    /// Returns whether the role can remove a member with `target` role.
+    /// Officers can only remove members; the owner can remove anyone else.
+    pub fn can_kick(&self, target: ClanRole) -> bool {
+        self.can_manage() && *self > target
+    }
+
+    /// Returns whether the role can change other members' roles.
+    pub fn can_assign_roles(&self) -> bool {
+        *self == ClanRole::Owner
+    }
+}
+
+/// What clans are ranked by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClanMetric {
+    /// Total XP earned by the members.
+    Xp,
+    /// Mean portfolio return of the members with a ranked return.
+    Return,
+}
+
+impl ClanMetric {
+    /// All metrics.
+    pub const ALL: [ClanMetric; 2] = [ClanMetric::Xp, ClanMetric::Return];
+
+    /// Returns the metric name used in URLs and storage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClanMetric::Xp => "xp",
+            ClanMetric::Return => "return",
+        }
+    }
+
+    /// Parses a metric name.
+    pub fn parse(value: &str) -> Option<ClanMetric> {
+        Self::ALL.into_iter().find(|metric| metric.as_str() == value)
+    }
+
+    /// Scores a clan from its members' activity, or returns `None` if it is not eligible:
+    /// no XP earned, or fewer than `MIN_RETURN_MEMBERS` members with a ranked return.
+    pub fn score(&self, members: &[TraderActivity]) -> Option<f64> {
+        match self {
+            ClanMetric::Xp => {
+                let xp: i64 = members.iter().map(|member| member.xp).sum();
+                (xp > 0).then_some(xp as f64)
+            }
+            ClanMetric::Return => {
+                let returns: Vec<f64> = members
+                    .iter()
+                    .filter_map(|member| LeaderboardMetric::Roi.score(member))
+                    .collect();
+                (returns.len() >= MIN_RETURN_MEMBERS).then(|| returns.iter().sum::<f64>() / returns.len() as f64)
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Head-to-head duel rules.
+//! Two players trade identical paper accounts for a fixed time; the higher final value wins the XP stakes.
+
+/// Source of XP escrowed and paid out for duels.
+pub const DUEL_XP_SOURCE: &str = "duel";
+
+/// Duel length used when the challenger does not pick one, in hours.
+pub const DEFAULT_DUEL_HOURS: i32 = 24;
+
+/// Shortest duel that can be proposed, in hours.
+pub const MIN_DUEL_HOURS: i32 = 1;
+
+/// Longest duel that can be proposed, in hours.
+pub const MAX_DUEL_HOURS: i32 = 168;
+
+/// How long a challenge waits for an answer before it expires, in hours.
+pub const CHALLENGE_TTL_HOURS: i64 = 24;
+
+/// Largest XP stake each player can put up.
+pub const MAX_DUEL_STAKE_XP: i32 = 1000;
+
+/// Stage of a duel's lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DuelStatus {
+    /// Waiting for the opponent to answer.
+    Pending,
+    /// The opponent turned the challenge down.
+    Declined,
+    /// The challenge was not answered in time.
+    Expired,
+    /// Both players are trading.
+    Active,
+    /// Trading has ended and the stakes have been paid out.
+    Settled,
+}
+
+impl DuelStatus {
+    /// All statuses, in lifecycle order.
+    pub const ALL: [DuelStatus; 5] = [
+        DuelStatus::Pending,
+        DuelStatus::Declined,
+        DuelStatus::Expired,
+        DuelStatus::Active,
+        DuelStatus::Settled,
+    ];
+
+    /// Returns the status name stored and shown to clients.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DuelStatus::Pending => "pending",
+            DuelStatus::Declined => "declined",
+            DuelStatus::Expired => "expired",
+            DuelStatus::Active => "active",
+            DuelStatus::Settled => "settled",
+        }
+    }
+
+    /// Parses a status name.
+    pub fn parse(value: &str) -> Option<DuelStatus> {
+        Self::ALL.iter().copied().find(|status| status.as_str() == value)
+    }
+}
+
+/// Result of a settled duel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuelResult {
+    /// The challenger finished with the higher account value.
+    ChallengerWon,
+    /// The opponent finished with the higher account value.
+    OpponentWon,
+    /// Both accounts finished at the same value.
+    Draw,
+}
+
+impl DuelResult {
+    /// Decides a duel from both players' final account values.
+    pub fn decide(challenger_value_cents: i64, opponent_value_cents: i64) -> DuelResult {
+        match challenger_value_cents.cmp(&opponent_value_cents) {
+            std::cmp::Ordering::Greater => DuelResult::ChallengerWon,
+            std::cmp::Ordering::Less => DuelResult::OpponentWon,
+            std::cmp::Ordering::Equal => DuelResult::Draw,
+        }
+    }
+
+    /// Returns the challenger's game score: 1.0 for a win, 0.5 for a draw and 0.0 for a loss.
+    pub fn challenger_score(&self) -> f64 {
+        match self {
+            DuelResult::ChallengerWon => 1.0,
+            DuelResult::OpponentWon => 0.0,
+            DuelResult::Draw => 0.5,
+        }
+    }
+
+    /// Returns the XP paid out of escrow to the challenger and the opponent.
+    /// The winner takes both stakes; a draw returns each stake to its owner.
+    pub fn payouts(&self, stake_xp: i32) -> (i32, i32) {
+        match self {
+            DuelResult::ChallengerWon => (stake_xp * 2, 0),
+            DuelResult::OpponentWon => (0, stake_xp * 2),
+            DuelResult::Draw => (stake_xp, stake_xp),
+        }
+    }
+}
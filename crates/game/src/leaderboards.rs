@@ -0,0 +1,163 @@
+//! Leaderboard metrics and eligibility rules.
+//! Scores players on XP, portfolio performance and skill rating and ranks them with ties sharing a rank.
+
+use crate::ratings::MIN_RATED_GAMES;
+
+/// Trades a player must make within the window to be ranked on portfolio metrics.
+pub const MIN_LEADERBOARD_TRADES: i64 = 3;
+
+/// Hourly returns required before a Sharpe ratio is considered meaningful.
+pub const MIN_SHARPE_PERIODS: i64 = 24;
+
+/// Length of the periods portfolio returns are measured over, in seconds.
+pub const RETURN_PERIOD_SECONDS: i64 = 3600;
+
+/// Return periods per year, used to annualize Sharpe ratios.
+const RETURN_PERIODS_PER_YEAR: f64 = 24.0 * 365.0;
+
+/// What a leaderboard ranks players by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LeaderboardMetric {
+    /// XP earned.
+    Xp,
+    /// Compounded return on the portfolio.
+    Roi,
+    /// Absolute change in portfolio value.
+    Pnl,
+    /// Annualized Sharpe ratio of hourly returns.
+    Sharpe,
+    /// Conservative Glicko-2 skill rating from duels and tournaments.
+    Skill,
+}
+
+impl LeaderboardMetric {
+    /// All metrics.
+    pub const ALL: [LeaderboardMetric; 5] = [
+        LeaderboardMetric::Xp,
+        LeaderboardMetric::Roi,
+        LeaderboardMetric::Pnl,
+        LeaderboardMetric::Sharpe,
+        LeaderboardMetric::Skill,
+    ];
+
+    /// Returns the metric name stored with leaderboard entries.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardMetric::Xp => "xp",
+            LeaderboardMetric::Roi => "roi",
+            LeaderboardMetric::Pnl => "pnl",
+            LeaderboardMetric::Sharpe => "sharpe",
+            LeaderboardMetric::Skill => "skill",
+        }
+    }
+
+    /// Parses a metric name.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|metric| metric.as_str() == value)
+    }
+
+    /// Returns the player's score, or `None` when they are not eligible.
+    /// XP is in points, ROI a fraction, P&L in cents, Sharpe a ratio and skill a rating.
+    pub fn score(&self, activity: &TraderActivity) -> Option<f64> {
+        match self {
+            LeaderboardMetric::Xp => return (activity.xp > 0).then_some(activity.xp as f64),
+            LeaderboardMetric::Skill => {
+                return activity.rating.filter(|_| activity.rated_games >= MIN_RATED_GAMES);
+            }
+            _ => {}
+        }
+        if activity.trades < MIN_LEADERBOARD_TRADES || activity.periods < 1 {
+            return None;
+        }
+
+        match self {
+            LeaderboardMetric::Roi => Some(activity.log_return.exp_m1()),
+            LeaderboardMetric::Pnl => Some(activity.pnl_cents as f64),
+            LeaderboardMetric::Sharpe => sharpe_ratio(activity),
+            LeaderboardMetric::Xp | LeaderboardMetric::Skill => None,
+        }
+    }
+}
+
+/// Time window a leaderboard covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LeaderboardWindow {
+    /// The last seven days.
+    Week,
+    /// The current season.
+    Season,
+    /// All time, compounding returns across seasons.
+    All,
+}
+
+impl LeaderboardWindow {
+    /// All windows.
+    pub const ALL: [LeaderboardWindow; 3] = [
+        LeaderboardWindow::Week,
+        LeaderboardWindow::Season,
+        LeaderboardWindow::All,
+    ];
+
+    /// Returns the window name stored with leaderboard entries.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardWindow::Week => "week",
+            LeaderboardWindow::Season => "season",
+            LeaderboardWindow::All => "all",
+        }
+    }
+
+    /// Parses a window name.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|window| window.as_str() == value)
+    }
+}
+
+/// Player activity within a leaderboard window.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TraderActivity {
+    /// XP earned.
+    pub xp: i64,
+    /// Trades executed.
+    pub trades: i64,
+    /// Return periods with a portfolio value at both ends.
+    pub periods: i64,
+    /// Change in portfolio value, in cents.
+    pub pnl_cents: i64,
+    /// Sum of the periods' log returns.
+    pub log_return: f64,
+    /// Mean log return per period.
+    pub mean_return: f64,
+    /// Sample standard deviation of the periods' log returns.
+    pub stddev_return: Option<f64>,
+    /// Conservative skill rating, for players rated within the window.
+    pub rating: Option<f64>,
+    /// Rated games played in total.
+    pub rated_games: i64,
+}
+
+/// Returns the annualized Sharpe ratio of a player's returns, assuming a zero risk-free rate.
+/// Returns `None` with too few periods or no volatility.
+pub fn sharpe_ratio(activity: &TraderActivity) -> Option<f64> {
+    if activity.periods < MIN_SHARPE_PERIODS {
+        return None;
+    }
+    let stddev = activity.stddev_return.filter(|stddev| *stddev > f64::EPSILON)?;
+    Some(activity.mean_return / stddev * RETURN_PERIODS_PER_YEAR.sqrt())
+}
+
+/// Sorts scored entries best first and assigns competition ranks, so ties share a rank.
+pub fn rank_scores<T>(mut scored: Vec<(T, f64)>) -> Vec<(T, i32, f64)> {
+    scored.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let mut ranked: Vec<(T, i32, f64)> = Vec::with_capacity(scored.len());
+    for (position, (item, score)) in scored.into_iter().enumerate() {
+        let rank = match ranked.last() {
+            Some((_, previous_rank, previous_score)) if *previous_score == score => *previous_rank,
+            _ => position as i32 + 1,
+        };
+        ranked.push((item, rank, score));
+    }
+
+    ranked
+}
@@ -0,0 +1,134 @@
+//! Weekly leagues.
+//! Players compete in groups of about thirty within a division; each week the best are promoted
+//! to the next division and the worst relegated to the one below.
+
+use crate::calendar::SECONDS_PER_DAY;
+use crate::leaderboards::{LeaderboardMetric, TraderActivity};
+
+/// Players placed in a league group before a new group is opened.
+pub const GROUP_SIZE: i64 = 30;
+
+/// Top finishers of a group promoted at the end of the week.
+pub const PROMOTION_SLOTS: i64 = 7;
+
+/// Bottom finishers of a group relegated at the end of the week.
+pub const RELEGATION_SLOTS: i64 = 5;
+
+/// Length of a league week, in seconds.
+pub const WEEK_SECONDS: i64 = 7 * SECONDS_PER_DAY;
+
+/// League division, from Bronze at tier 0 upwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Division {
+    /// Position in the ladder, 0 being the lowest.
+    pub tier: i16,
+    /// Display name.
+    pub name: &'static str,
+}
+
+/// Divisions from lowest to highest.
+pub const DIVISIONS: [Division; 10] = [
+    Division { tier: 0, name: "Bronze" },
+    Division { tier: 1, name: "Silver" },
+    Division { tier: 2, name: "Gold" },
+    Division { tier: 3, name: "Sapphire" },
+    Division { tier: 4, name: "Ruby" },
+    Division { tier: 5, name: "Emerald" },
+    Division { tier: 6, name: "Amethyst" },
+    Division { tier: 7, name: "Pearl" },
+    Division { tier: 8, name: "Obsidian" },
+    Division { tier: 9, name: "Diamond" },
+];
+
+/// Highest division tier.
+pub const TOP_TIER: i16 = DIVISIONS.len() as i16 - 1;
+
+/// Returns the division at a tier, clamped to the ladder.
+pub fn division(tier: i16) -> &'static Division {
+    &DIVISIONS[tier.clamp(0, TOP_TIER) as usize]
+}
+
+/// What league groups rank players by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LeagueMetric {
+    /// XP earned during the week.
+    Xp,
+    /// Portfolio return during the week.
+    Return,
+}
+
+impl LeagueMetric {
+    /// All metrics.
+    pub const ALL: [LeagueMetric; 2] = [LeagueMetric::Xp, LeagueMetric::Return];
+
+    /// Returns the metric name stored with league groups.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeagueMetric::Xp => "xp",
+            LeagueMetric::Return => "return",
+        }
+    }
+
+    /// Parses a metric name.
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|metric| metric.as_str() == value)
+    }
+
+    /// Scores a player's activity during the week, or returns `None` if they have not been
+    /// active enough to be ranked: no XP earned, or too few trades for a return.
+    pub fn score(&self, activity: &TraderActivity) -> Option<f64> {
+        match self {
+            LeagueMetric::Xp => (activity.xp > 0).then_some(activity.xp as f64),
+            LeagueMetric::Return => LeaderboardMetric::Roi.score(activity),
+        }
+    }
+}
+
+/// Where a player goes after a league week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LeagueOutcome {
+    /// Moves up one division.
+    Promoted,
+    /// Stays in the same division.
+    Stayed,
+    /// Moves down one division.
+    Relegated,
+}
+
+impl LeagueOutcome {
+    /// Returns the outcome name stored with league members.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeagueOutcome::Promoted => "promoted",
+            LeagueOutcome::Stayed => "stayed",
+            LeagueOutcome::Relegated => "relegated",
+        }
+    }
+
+    /// Returns the division tier a player in `tier` moves to.
+    pub fn next_tier(&self, tier: i16) -> i16 {
+        match self {
+            LeagueOutcome::Promoted => (tier + 1).min(TOP_TIER),
+            LeagueOutcome::Stayed => tier,
+            LeagueOutcome::Relegated => (tier - 1).max(0),
+        }
+    }
+}
+
+/// Decides a player's outcome from their final rank in a group of `members` players.
+/// `score` is `None` for players who were not active enough to be scored; they are always
+/// relegated. Only players with a positive score can be promoted, and promotion takes precedence
+/// over relegation in small groups. Nobody is promoted out of the top division or relegated out
+/// of the bottom one.
+pub fn league_outcome(tier: i16, rank: i64, members: i64, score: Option<f64>) -> LeagueOutcome {
+    let scored = score.is_some_and(|score| score > 0.0);
+    if tier < TOP_TIER && scored && rank <= PROMOTION_SLOTS {
+        LeagueOutcome::Promoted
+    } else if tier > 0
+        && (score.is_none() || (rank > PROMOTION_SLOTS && rank > members - RELEGATION_SLOTS))
+    {
+        LeagueOutcome::Relegated
+    } else {
+        LeagueOutcome::Stayed
+    }
+}
@@ -0,0 +1,309 @@
+//! Level curves mapping XP to levels.
+//! Provides linear, exponential and table-driven curves and the process-wide curve selected from config.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Highest level a user can reach. Matches the range of the `users.level` SMALLINT column.
+pub const MAX_LEVEL: u16 = i16::MAX as u16;
+
+/// XP per level of the default linear curve.
+pub const DEFAULT_XP_PER_LEVEL: u64 = 1000;
+
+/// Curve used by the free level functions, set once at startup.
+static ACTIVE_CURVE: OnceLock<Box<dyn LevelCurve>> = OnceLock::new();
+
+/// Maps XP totals to levels.
+/// Implementations only define the XP needed for each level; lookups are derived from it.
+pub trait LevelCurve: fmt::Debug + Send + Sync {
+    /// Total XP required to reach `level`. Level 1 requires 0 and totals never decrease.
+    fn xp_for_level(&self, level: u16) -> u64;
+
+    /// Highest level reachable on this curve.
+    fn max_level(&self) -> u16 {
+        MAX_LEVEL
+    }
+
+    /// Level reached with an XP total.
+    fn level_for_xp(&self, xp: u64) -> u16 {
+        // Highest level whose requirement has been met
+        let (mut low, mut high) = (1, self.max_level());
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if self.xp_for_level(mid) <= xp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
+
+    /// Total XP required to reach the level after `level`, or 0 at the maximum level.
+    fn xp_required_for_next_level(&self, level: u16) -> u64 {
+        if level >= self.max_level() {
+            return 0;
+        }
+        self.xp_for_level(level.max(1) + 1)
+    }
+
+    /// Progress from the current level towards the next, between 0.0 and 1.0.
+    fn level_progress(&self, xp: u64) -> f32 {
+        let level = self.level_for_xp(xp);
+        if level >= self.max_level() {
+            return 1.0;
+        }
+
+        let level_xp = self.xp_for_level(level);
+        let span = self.xp_for_level(level + 1).saturating_sub(level_xp);
+        if span == 0 {
+            return 1.0;
+        }
+        (xp.saturating_sub(level_xp) as f64 / span as f64).min(1.0) as f32
+    }
+}
+
+/// Same XP for every level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinearCurve {
+    /// XP needed to advance one level.
+    pub xp_per_level: u64,
+}
+
+impl Default for LinearCurve {
+    fn default() -> Self {
+        Self { xp_per_level: DEFAULT_XP_PER_LEVEL }
+    }
+}
+
+impl LevelCurve for LinearCurve {
+    fn xp_for_level(&self, level: u16) -> u64 {
+        u64::from(level.saturating_sub(1)).saturating_mul(self.xp_per_level)
+    }
+}
+
+/// Each level needs `growth` times the XP of the previous one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialCurve {
+    /// XP needed to advance from level 1 to level 2.
+    pub base_xp: u64,
+    /// Factor by which each level's requirement grows, at least 1.0.
+    pub growth: f64,
+}
+
+impl LevelCurve for ExponentialCurve {
+    fn xp_for_level(&self, level: u16) -> u64 {
+        let steps = i32::from(level.saturating_sub(1));
+        if self.growth <= 1.0 {
+            return (steps as u64).saturating_mul(self.base_xp);
+        }
+
+        // Geometric series; float to integer casts saturate once totals outgrow u64
+        let total = self.base_xp as f64 * (self.growth.powi(steps) - 1.0) / (self.growth - 1.0);
+        total.round() as u64
+    }
+}
+
+/// Explicit XP totals per level, for hand-tuned progression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCurve {
+    /// Total XP required for levels 2, 3, ... in order.
+    thresholds: Vec<u64>,
+}
+
+impl TableCurve {
+    /// Creates a curve from the XP totals of levels 2 onwards.
+    /// The table must be non-empty, strictly increasing and no longer than the level range.
+    pub fn new(thresholds: Vec<u64>) -> Result<Self, LevelCurveError> {
+        if thresholds.is_empty() || thresholds.len() >= usize::from(MAX_LEVEL) {
+            return Err(LevelCurveError::InvalidTable);
+        }
+        if thresholds.windows(2).any(|pair| pair[0] >= pair[1]) || thresholds[0] == 0 {
+            return Err(LevelCurveError::InvalidTable);
+        }
+        Ok(Self { thresholds })
+    }
+}
+
+impl LevelCurve for TableCurve {
+    fn xp_for_level(&self, level: u16) -> u64 {
+        match level {
+            0 | 1 => 0,
+            _ => self.thresholds[usize::from(level.min(self.max_level())) - 2],
+        }
+    }
+
+    fn max_level(&self) -> u16 {
+        self.thresholds.len() as u16 + 1
+    }
+}
+
+/// Why a level curve configuration was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LevelCurveError {
+    /// The curve kind is not "linear", "exponential" or "table".
+    UnknownKind(String),
+    /// A parameter is missing or not a valid number.
+    InvalidParameter(String),
+    /// Table thresholds are empty, not strictly increasing or too many.
+    InvalidTable,
+}
+
+impl fmt::Display for LevelCurveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelCurveError::UnknownKind(kind) => write!(f, "unknown level curve '{}'", kind),
+            LevelCurveError::InvalidParameter(parameter) => write!(f, "invalid level curve parameter '{}'", parameter),
+            LevelCurveError::InvalidTable => {
+                write!(f, "level table must list increasing XP totals for at most {} levels", MAX_LEVEL - 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelCurveError {}
+
+/// Parses a curve from its config string.
+/// Formats: `linear:<xp per level>`, `exponential:<base xp>:<growth>` and `table:<xp>,<xp>,...`
+/// where the table lists the total XP of levels 2 onwards.
+pub fn parse_level_curve(spec: &str) -> Result<Box<dyn LevelCurve>, LevelCurveError> {
+    let mut parts = spec.trim().split(':');
+    let kind = parts.next().unwrap_or_default().to_lowercase();
+    let mut parameter = |name: &str| {
+        parts
+            .next()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| LevelCurveError::InvalidParameter(name.to_string()))
+            .map(str::to_string)
+    };
+
+    match kind.as_str() {
+        "linear" => {
+            let xp_per_level = parse_number::<u64>(&parameter("xp per level")?)?;
+            if xp_per_level == 0 {
+                return Err(LevelCurveError::InvalidParameter(xp_per_level.to_string()));
+            }
+            Ok(Box::new(LinearCurve { xp_per_level }))
+        }
+        "exponential" => {
+            let base_xp = parse_number::<u64>(&parameter("base xp")?)?;
+            let growth = parse_number::<f64>(&parameter("growth")?)?;
+            if base_xp == 0 || !growth.is_finite() || growth < 1.0 {
+                return Err(LevelCurveError::InvalidParameter(format!("{}:{}", base_xp, growth)));
+            }
+            Ok(Box::new(ExponentialCurve { base_xp, growth }))
+        }
+        "table" => {
+            let thresholds = parameter("thresholds")?
+                .split(',')
+                .map(|value| parse_number::<u64>(value.trim()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Box::new(TableCurve::new(thresholds)?))
+        }
+        _ => Err(LevelCurveError::UnknownKind(kind)),
+    }
+}
+
+/// Parses a numeric curve parameter.
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, LevelCurveError> {
+    value
+        .parse()
+        .map_err(|_| LevelCurveError::InvalidParameter(value.to_string()))
+}
+
+/// Installs the curve used by the free level functions.
+/// Only the first call takes effect; returns `false` if a curve was already in use.
+pub fn set_level_curve(curve: Box<dyn LevelCurve>) -> bool {
+    ACTIVE_CURVE.set(curve).is_ok()
+}
+
+/// Returns the active curve, the default linear curve if none was installed.
+pub fn level_curve() -> &'static dyn LevelCurve {
+    ACTIVE_CURVE
+        .get_or_init(|| Box::new(LinearCurve::default()))
+        .as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_round_trip_at_their_thresholds() {
+        let curves: Vec<Box<dyn LevelCurve>> = vec![
+            Box::new(LinearCurve::default()),
+            Box::new(ExponentialCurve { base_xp: 100, growth: 1.5 }),
+            Box::new(TableCurve::new(vec![100, 250, 500, 1000]).unwrap()),
+        ];
+
+        for curve in &curves {
+            for level in 1..=curve.max_level().min(30) {
+                let xp = curve.xp_for_level(level);
+                assert_eq!(curve.level_for_xp(xp), level, "{:?} at level {}", curve, level);
+                if level > 1 {
+                    assert_eq!(curve.level_for_xp(xp - 1), level - 1, "{:?} below level {}", curve, level);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn levels_clamp_at_the_maximum() {
+        let table = TableCurve::new(vec![100, 250, 500]).unwrap();
+        assert_eq!(table.max_level(), 4);
+        assert_eq!(table.level_for_xp(u64::MAX), 4);
+        assert_eq!(table.xp_for_level(10), 500);
+        assert_eq!(table.xp_required_for_next_level(4), 0);
+        assert_eq!(table.level_progress(10_000), 1.0);
+
+        let linear = LinearCurve::default();
+        assert_eq!(linear.level_for_xp(u64::MAX), MAX_LEVEL);
+        assert_eq!(linear.xp_required_for_next_level(MAX_LEVEL), 0);
+    }
+
+    #[test]
+    fn exponential_totals_saturate_instead_of_overflowing() {
+        let curve = ExponentialCurve { base_xp: 1000, growth: 2.0 };
+        assert_eq!(curve.xp_for_level(2), 1000);
+        assert_eq!(curve.xp_for_level(3), 3000);
+        assert_eq!(curve.xp_for_level(100), u64::MAX);
+        assert_eq!(curve.xp_for_level(MAX_LEVEL), u64::MAX);
+
+        // Every level past the overflow needs the same saturated total, so the lookup stays monotonic
+        let last_reachable = curve.level_for_xp(u64::MAX - 1);
+        assert!(curve.xp_for_level(last_reachable) < u64::MAX);
+        assert_eq!(curve.level_for_xp(u64::MAX), MAX_LEVEL);
+    }
+
+    #[test]
+    fn tables_must_be_strictly_increasing() {
+        assert_eq!(TableCurve::new(vec![]), Err(LevelCurveError::InvalidTable));
+        assert_eq!(TableCurve::new(vec![0, 100]), Err(LevelCurveError::InvalidTable));
+        assert_eq!(TableCurve::new(vec![100, 100, 200]), Err(LevelCurveError::InvalidTable));
+        assert_eq!(TableCurve::new(vec![100, 300, 200]), Err(LevelCurveError::InvalidTable));
+        assert_eq!(
+            TableCurve::new(vec![1; usize::from(MAX_LEVEL)]),
+            Err(LevelCurveError::InvalidTable)
+        );
+        assert!(TableCurve::new(vec![100, 200, 300]).is_ok());
+    }
+
+    #[test]
+    fn malformed_specs_are_rejected() {
+        let invalid = |spec: &str| parse_level_curve(spec).unwrap_err();
+
+        assert_eq!(invalid("quadratic:10"), LevelCurveError::UnknownKind("quadratic".to_string()));
+        assert_eq!(invalid(""), LevelCurveError::UnknownKind(String::new()));
+        assert_eq!(invalid("linear"), LevelCurveError::InvalidParameter("xp per level".to_string()));
+        assert_eq!(invalid("linear:abc"), LevelCurveError::InvalidParameter("abc".to_string()));
+        assert_eq!(invalid("linear:0"), LevelCurveError::InvalidParameter("0".to_string()));
+        assert_eq!(invalid("exponential:100"), LevelCurveError::InvalidParameter("growth".to_string()));
+        assert_eq!(invalid("exponential:100:0.5"), LevelCurveError::InvalidParameter("100:0.5".to_string()));
+        assert_eq!(invalid("table:100,x"), LevelCurveError::InvalidParameter("x".to_string()));
+        assert_eq!(invalid("table:200,100"), LevelCurveError::InvalidTable);
+
+        assert_eq!(parse_level_curve(" Linear:500 ").unwrap().xp_for_level(3), 1000);
+        assert_eq!(parse_level_curve("table:100, 250").unwrap().max_level(), 3);
+    }
+}
@@ -1,11 +1,42 @@
 //! Gamification logic for Vectra DEX.
 //! Handles XP calculations, level progression, and reward systems.
 
+pub mod achievements;
+pub mod calendar;
+pub mod clans;
+pub mod duels;
+pub mod leaderboards;
+pub mod leagues;
+pub mod levels;
+pub mod quests;
+pub mod ratings;
+pub mod rules;
+pub mod seasons;
+pub mod streaks;
+pub mod tournaments;
+
+pub use achievements::{achievement, newly_unlocked, Achievement, Criterion, PlayerProgress, ACHIEVEMENTS};
+pub use clans::{ClanMetric, ClanRole};
+pub use duels::{DuelResult, DuelStatus};
+pub use leaderboards::{rank_scores, LeaderboardMetric, LeaderboardWindow, TraderActivity};
+pub use leagues::{division, league_outcome, Division, LeagueMetric, LeagueOutcome, DIVISIONS};
+pub use levels::{
+    level_curve, parse_level_curve, set_level_curve, ExponentialCurve, LevelCurve, LevelCurveError, LinearCurve,
+    TableCurve, MAX_LEVEL,
+};
+pub use quests::{active_quests, quest_template, QuestGoal, QuestPeriod, QuestTemplate, QUEST_TEMPLATES};
+pub use ratings::{expected_score, match_quality, placement_results, GameResult, Rating};
+pub use rules::{
+    awards_for_event, limit_award, limit_boosted_award, xp_rule, AwardScope, GameEvent, XpAward, XpRule, XP_RULES,
+};
+pub use seasons::{season_reward, SeasonReward, SEASON_REWARDS};
+pub use streaks::{streak_multiplier_percent, Streak, StreakUpdate};
+pub use tournaments::{tournament_prize, TournamentPhase, TournamentPrize, TournamentSchedule, TOURNAMENT_PRIZES};
+
 /// Calculates user level based on XP points.
-/// Simple formula: level = (XP / 1000) + 1, capped at level 255.
-pub fn calculate_level_from_xp(xp_points: u32) -> u8 {
-    let level = (xp_points / 1000) + 1;
-    std::cmp::min(level as u8, 255)
+/// Uses the active level curve, capped at `MAX_LEVEL`.
+pub fn calculate_level_from_xp(xp_points: u32) -> u16 {
+    level_curve().level_for_xp(u64::from(xp_points))
 }
 
 /// Helper function to safely convert i32 XP to u32 for calculations.
@@ -18,44 +49,20 @@ pub fn safe_xp_conversion(xp_points: i32) -> u32 {
     }
 }
 
-/// Calculates XP required for next level.
-/// Takes current level (u8) and returns XP needed as u32.
-pub fn xp_required_for_next_level(current_level: u8) -> u32 {
-    if current_level >= 255 {
-        return 0; // Max level reached
-    }
-    (current_level as u32 + 1) * 1000
+/// Calculates total XP required to reach the level after `current_level`.
+/// Returns 0 once the maximum level has been reached.
+pub fn xp_required_for_next_level(current_level: u16) -> u64 {
+    level_curve().xp_required_for_next_level(current_level)
 }
 
 /// Calculates XP progress towards next level (0.0 to 1.0).
 /// Returns progress percentage as a float between 0.0 and 1.0.
 pub fn level_progress(xp_points: u32) -> f32 {
-    let current_level = calculate_level_from_xp(xp_points);
-    
-    // Handle max level case
-    if current_level >= 255 {
-        return 1.0;
-    }
-    
-    let current_level_xp = (current_level.saturating_sub(1)) as u32 * 1000;
-    let next_level_xp = current_level as u32 * 1000;
-    
-    // Calculate progress within current level
-    let progress_in_level = xp_points.saturating_sub(current_level_xp);
-    let xp_needed_for_level = next_level_xp - current_level_xp;
-    
-    if xp_needed_for_level == 0 {
-        return 1.0; // Edge case protection
-    }
-    
-    (progress_in_level as f32 / xp_needed_for_level as f32).min(1.0)
+    level_curve().level_progress(u64::from(xp_points))
 }
 
 /// Calculates total XP required to reach a specific level.
 /// Useful for displaying level requirements in UI.
-pub fn xp_for_level(level: u8) -> u32 {
-    if level <= 1 {
-        return 0;
-    }
-    (level.saturating_sub(1)) as u32 * 1000
+pub fn xp_for_level(level: u16) -> u64 {
+    level_curve().xp_for_level(level)
 }
@@ -0,0 +1,262 @@
+//! Quest templates and rotation.
+//! Every daily and weekly period draws the same quests for all users from a seeded shuffle of the templates.
+
+use crate::calendar::{self, SECONDS_PER_DAY};
+use crate::rules::GameEvent;
+
+/// Number of quests active per day.
+pub const DAILY_QUEST_COUNT: usize = 3;
+
+/// Number of quests active per week.
+pub const WEEKLY_QUEST_COUNT: usize = 2;
+
+/// Source of XP granted for claimed quests.
+pub const QUEST_XP_SOURCE: &str = "quest";
+
+/// How long a quest stays active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuestPeriod {
+    /// One UTC day.
+    Daily,
+    /// One week starting Monday 00:00 UTC.
+    Weekly,
+}
+
+impl QuestPeriod {
+    /// Returns the period name stored with quest progress.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuestPeriod::Daily => "daily",
+            QuestPeriod::Weekly => "weekly",
+        }
+    }
+
+    /// Returns how many quests are active per period.
+    pub fn quest_count(&self) -> usize {
+        match self {
+            QuestPeriod::Daily => DAILY_QUEST_COUNT,
+            QuestPeriod::Weekly => WEEKLY_QUEST_COUNT,
+        }
+    }
+
+    /// Returns the index of the period containing a Unix timestamp.
+    pub fn index_at(&self, unix_seconds: i64) -> i64 {
+        match self {
+            QuestPeriod::Daily => unix_seconds.div_euclid(SECONDS_PER_DAY),
+            QuestPeriod::Weekly => calendar::week_index(unix_seconds),
+        }
+    }
+
+    /// Returns the start and end of a period as Unix timestamps, end exclusive.
+    pub fn bounds(&self, index: i64) -> (i64, i64) {
+        match self {
+            QuestPeriod::Daily => (index * SECONDS_PER_DAY, (index + 1) * SECONDS_PER_DAY),
+            QuestPeriod::Weekly => (calendar::week_index_start(index), calendar::week_index_start(index + 1)),
+        }
+    }
+}
+
+/// What a quest asks the player to do within its period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestGoal {
+    /// Execute a number of trades.
+    Trades(i64),
+    /// Trade a notional volume, in cents.
+    VolumeCents(i64),
+    /// Sell at a profit a number of times.
+    ProfitableCloses(i64),
+    /// Hold an open position in a symbol for a number of hours within the period.
+    HoldSymbol {
+        /// Symbol to hold.
+        symbol: &'static str,
+        /// Hours the position must have been open since the period started.
+        hours: i64,
+    },
+    /// Be ahead of the market average over the period so far, checked when the quest is claimed.
+    BeatMarket,
+}
+
+impl QuestGoal {
+    /// Returns the progress value at which the quest is complete.
+    pub fn target(&self) -> i64 {
+        match *self {
+            QuestGoal::Trades(target) | QuestGoal::VolumeCents(target) | QuestGoal::ProfitableCloses(target) => target,
+            QuestGoal::HoldSymbol { hours, .. } => hours,
+            QuestGoal::BeatMarket => 1,
+        }
+    }
+
+    /// Returns whether progress is accumulated from events rather than checked against current state.
+    pub fn is_event_tracked(&self) -> bool {
+        matches!(self, QuestGoal::Trades(_) | QuestGoal::VolumeCents(_) | QuestGoal::ProfitableCloses(_))
+    }
+
+    /// Returns how much an event advances the quest.
+    pub fn event_progress(&self, event: &GameEvent) -> i64 {
+        match (self, event) {
+            (QuestGoal::Trades(_), GameEvent::TradeExecuted { .. }) => 1,
+            (QuestGoal::VolumeCents(_), GameEvent::TradeExecuted { notional_cents }) => *notional_cents,
+            (QuestGoal::ProfitableCloses(_), GameEvent::PositionClosed { realized_pnl_cents, .. })
+                if *realized_pnl_cents > 0 =>
+            {
+                1
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Quest that can be drawn into a period's rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuestTemplate {
+    /// Stable key stored with progress.
+    pub id: &'static str,
+    /// Display title.
+    pub title: &'static str,
+    /// What the player has to do.
+    pub description: &'static str,
+    /// Period the quest is drawn for.
+    pub period: QuestPeriod,
+    /// XP granted when the quest is claimed, exempt from daily limits.
+    pub xp_reward: u32,
+    /// What the quest asks for.
+    pub goal: QuestGoal,
+}
+
+/// All quest templates.
+pub const QUEST_TEMPLATES: [QuestTemplate; 12] = [
+    QuestTemplate {
+        id: "daily_trades_3",
+        title: "Warm-up",
+        description: "Make 3 trades",
+        period: QuestPeriod::Daily,
+        xp_reward: 50,
+        goal: QuestGoal::Trades(3),
+    },
+    QuestTemplate {
+        id: "daily_trades_10",
+        title: "Busy Day",
+        description: "Make 10 trades",
+        period: QuestPeriod::Daily,
+        xp_reward: 100,
+        goal: QuestGoal::Trades(10),
+    },
+    QuestTemplate {
+        id: "daily_volume_1k",
+        title: "Market Mover",
+        description: "Trade $1,000 in volume",
+        period: QuestPeriod::Daily,
+        xp_reward: 60,
+        goal: QuestGoal::VolumeCents(100_000),
+    },
+    QuestTemplate {
+        id: "daily_profit_1",
+        title: "Take Profit",
+        description: "Sell a position at a profit",
+        period: QuestPeriod::Daily,
+        xp_reward: 75,
+        goal: QuestGoal::ProfitableCloses(1),
+    },
+    QuestTemplate {
+        id: "daily_hold_btc",
+        title: "HODL",
+        description: "Hold BTC for 12 hours today",
+        period: QuestPeriod::Daily,
+        xp_reward: 60,
+        goal: QuestGoal::HoldSymbol { symbol: "BTC", hours: 12 },
+    },
+    QuestTemplate {
+        id: "daily_hold_eth",
+        title: "Ether Hands",
+        description: "Hold ETH for 12 hours today",
+        period: QuestPeriod::Daily,
+        xp_reward: 60,
+        goal: QuestGoal::HoldSymbol { symbol: "ETH", hours: 12 },
+    },
+    QuestTemplate {
+        id: "daily_beat_market",
+        title: "Beat the Market",
+        description: "Be ahead of the market average today when you claim",
+        period: QuestPeriod::Daily,
+        xp_reward: 100,
+        goal: QuestGoal::BeatMarket,
+    },
+    QuestTemplate {
+        id: "weekly_trades_25",
+        title: "Grinder",
+        description: "Make 25 trades this week",
+        period: QuestPeriod::Weekly,
+        xp_reward: 250,
+        goal: QuestGoal::Trades(25),
+    },
+    QuestTemplate {
+        id: "weekly_volume_25k",
+        title: "High Roller",
+        description: "Trade $25,000 in volume this week",
+        period: QuestPeriod::Weekly,
+        xp_reward: 300,
+        goal: QuestGoal::VolumeCents(2_500_000),
+    },
+    QuestTemplate {
+        id: "weekly_profit_5",
+        title: "Sharpshooter",
+        description: "Sell at a profit 5 times this week",
+        period: QuestPeriod::Weekly,
+        xp_reward: 300,
+        goal: QuestGoal::ProfitableCloses(5),
+    },
+    QuestTemplate {
+        id: "weekly_hold_btc",
+        title: "Diamond Week",
+        description: "Hold BTC for 3 days this week",
+        period: QuestPeriod::Weekly,
+        xp_reward: 250,
+        goal: QuestGoal::HoldSymbol { symbol: "BTC", hours: 72 },
+    },
+    QuestTemplate {
+        id: "weekly_beat_market",
+        title: "Outperformer",
+        description: "Be ahead of the market average this week when you claim",
+        period: QuestPeriod::Weekly,
+        xp_reward: 400,
+        goal: QuestGoal::BeatMarket,
+    },
+];
+
+/// Returns a quest template by id.
+pub fn quest_template(id: &str) -> Option<&'static QuestTemplate> {
+    QUEST_TEMPLATES.iter().find(|template| template.id == id)
+}
+
+/// Returns the quests active in a period, in display order.
+/// The draw depends only on the period, so every user sees the same quests.
+pub fn active_quests(period: QuestPeriod, index: i64) -> Vec<&'static QuestTemplate> {
+    let seed = mix(fnv1a(period.as_str()) ^ index as u64);
+    let mut candidates: Vec<(u64, &'static QuestTemplate)> = QUEST_TEMPLATES
+        .iter()
+        .filter(|template| template.period == period)
+        .map(|template| (mix(seed ^ fnv1a(template.id)), template))
+        .collect();
+    candidates.sort_by_key(|(rank, _)| *rank);
+
+    candidates
+        .into_iter()
+        .take(period.quest_count())
+        .map(|(_, template)| template)
+        .collect()
+}
+
+/// Stable string hash, independent of the process and platform.
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// SplitMix64 finalizer, spreading similar inputs across the whole range.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
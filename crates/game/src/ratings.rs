@@ -0,0 +1,280 @@
+//! Glicko-2 skill ratings.
+//! Rates players from duel outcomes and tournament placements so rank reflects skill rather than grinding.
+
+use std::f64::consts::PI;
+
+/// Rating new players start with.
+pub const DEFAULT_RATING: f64 = 1500.0;
+
+/// Rating deviation new players start with, also the most uncertain a rating can become.
+pub const DEFAULT_DEVIATION: f64 = 350.0;
+
+/// Volatility new players start with.
+pub const DEFAULT_VOLATILITY: f64 = 0.06;
+
+/// Lowest rating deviation, so established ratings keep moving.
+pub const MIN_DEVIATION: f64 = 30.0;
+
+/// System constant limiting how quickly volatility changes.
+pub const SYSTEM_TAU: f64 = 0.5;
+
+/// Length of an idle rating period, in days. Deviation grows once per idle period.
+pub const RATING_PERIOD_DAYS: f64 = 7.0;
+
+/// Rated games a player needs before appearing on the skill leaderboard.
+pub const MIN_RATED_GAMES: i64 = 5;
+
+/// Entrants above and below a tournament finisher they are rated against.
+pub const PLACEMENT_NEIGHBOURS: usize = 10;
+
+/// Rating source for duel outcomes.
+pub const DUEL_RATING_SOURCE: &str = "duel";
+
+/// Rating source for tournament placements.
+pub const TOURNAMENT_RATING_SOURCE: &str = "tournament";
+
+/// Factor between the Glicko and Glicko-2 scales.
+const GLICKO2_SCALE: f64 = 173.7178;
+
+/// Precision of the volatility iteration.
+const CONVERGENCE_TOLERANCE: f64 = 0.000001;
+
+/// Player's skill estimate on the Glicko scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    /// Estimated skill.
+    pub rating: f64,
+    /// Uncertainty of the estimate; the true skill is within two deviations with 95% confidence.
+    pub deviation: f64,
+    /// Expected fluctuation of the player's performance.
+    pub volatility: f64,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            rating: DEFAULT_RATING,
+            deviation: DEFAULT_DEVIATION,
+            volatility: DEFAULT_VOLATILITY,
+        }
+    }
+}
+
+/// Outcome of one game against an opponent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameResult {
+    /// Opponent's rating before the game.
+    pub opponent: Rating,
+    /// 1.0 for a win, 0.5 for a draw and 0.0 for a loss.
+    pub score: f64,
+}
+
+impl Rating {
+    /// Returns the lower bound of the 95% confidence interval, used to rank players.
+    pub fn conservative(&self) -> f64 {
+        self.rating - 2.0 * self.deviation
+    }
+
+    /// Returns the rating after `periods` rating periods without games.
+    /// Only the deviation grows; it never exceeds that of a new player.
+    pub fn decayed(&self, periods: f64) -> Rating {
+        if periods <= 0.0 {
+            return *self;
+        }
+        let phi = self.deviation / GLICKO2_SCALE;
+        let phi = (phi * phi + periods * self.volatility * self.volatility).sqrt();
+        Rating {
+            deviation: (phi * GLICKO2_SCALE).min(DEFAULT_DEVIATION),
+            ..*self
+        }
+    }
+
+    /// Returns the rating after one rating period with the given results.
+    /// With no results this is the same as one idle period.
+    pub fn update(&self, results: &[GameResult]) -> Rating {
+        if results.is_empty() {
+            return self.decayed(1.0);
+        }
+
+        let mu = (self.rating - DEFAULT_RATING) / GLICKO2_SCALE;
+        let phi = self.deviation / GLICKO2_SCALE;
+
+        let mut inverse_variance = 0.0;
+        let mut improvement = 0.0;
+        for result in results {
+            let mu_j = (result.opponent.rating - DEFAULT_RATING) / GLICKO2_SCALE;
+            let g_j = g(result.opponent.deviation / GLICKO2_SCALE);
+            let expected = expectation(mu, mu_j, g_j);
+            inverse_variance += g_j * g_j * expected * (1.0 - expected);
+            improvement += g_j * (result.score - expected);
+        }
+        let variance = 1.0 / inverse_variance;
+        let delta = variance * improvement;
+
+        let volatility = next_volatility(phi, self.volatility, variance, delta);
+        let phi_star = (phi * phi + volatility * volatility).sqrt();
+        let phi_next = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / variance).sqrt();
+        let mu_next = mu + phi_next * phi_next * improvement;
+
+        Rating {
+            rating: mu_next * GLICKO2_SCALE + DEFAULT_RATING,
+            deviation: (phi_next * GLICKO2_SCALE).clamp(MIN_DEVIATION, DEFAULT_DEVIATION),
+            volatility,
+        }
+    }
+}
+
+/// Returns the probability that `player` beats `opponent`, accounting for both players' uncertainty.
+pub fn expected_score(player: &Rating, opponent: &Rating) -> f64 {
+    let mu = (player.rating - DEFAULT_RATING) / GLICKO2_SCALE;
+    let mu_j = (opponent.rating - DEFAULT_RATING) / GLICKO2_SCALE;
+    let phi = (player.deviation.powi(2) + opponent.deviation.powi(2)).sqrt() / GLICKO2_SCALE;
+    expectation(mu, mu_j, g(phi))
+}
+
+/// Returns how balanced a pairing is, from 0.0 (one-sided) to 1.0 (a coin flip).
+pub fn match_quality(player: &Rating, opponent: &Rating) -> f64 {
+    1.0 - (2.0 * expected_score(player, opponent) - 1.0).abs()
+}
+
+/// Turns tournament placements into head-to-head results.
+/// `ranks` must be sorted best first. Each finisher is scored against up to `PLACEMENT_NEIGHBOURS`
+/// entrants on either side: a win over worse ranks, a draw on shared ranks and a loss to better ranks.
+/// Returns, for every finisher, the indices of their opponents with their scores.
+pub fn placement_results(ranks: &[i64]) -> Vec<Vec<(usize, f64)>> {
+    (0..ranks.len())
+        .map(|i| {
+            let first = i.saturating_sub(PLACEMENT_NEIGHBOURS);
+            let last = (i + PLACEMENT_NEIGHBOURS).min(ranks.len().saturating_sub(1));
+            (first..=last)
+                .filter(|j| *j != i)
+                .map(|j| {
+                    let score = match ranks[i].cmp(&ranks[j]) {
+                        std::cmp::Ordering::Less => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Greater => 0.0,
+                    };
+                    (j, score)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Weighs an opponent's result by how certain their rating is.
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+}
+
+/// Expected score against an opponent on the Glicko-2 scale.
+fn expectation(mu: f64, mu_j: f64, g_j: f64) -> f64 {
+    1.0 / (1.0 + (-g_j * (mu - mu_j)).exp())
+}
+
+/// Solves for the new volatility with the Illinois algorithm.
+fn next_volatility(phi: f64, sigma: f64, variance: f64, delta: f64) -> f64 {
+    let a = (sigma * sigma).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        let denominator = phi * phi + variance + ex;
+        ex * (delta * delta - phi * phi - variance - ex) / (2.0 * denominator * denominator)
+            - (x - a) / (SYSTEM_TAU * SYSTEM_TAU)
+    };
+
+    let mut low = a;
+    let mut high = if delta * delta > phi * phi + variance {
+        (delta * delta - phi * phi - variance).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * SYSTEM_TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * SYSTEM_TAU
+    };
+
+    let mut f_low = f(low);
+    let mut f_high = f(high);
+    while (high - low).abs() > CONVERGENCE_TOLERANCE {
+        let next = low + (low - high) * f_low / (f_high - f_low);
+        let f_next = f(next);
+        if f_next * f_high <= 0.0 {
+            low = high;
+            f_low = f_high;
+        } else {
+            f_low /= 2.0;
+        }
+        high = next;
+        f_high = f_next;
+    }
+
+    (low / 2.0).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rating(rating: f64, deviation: f64) -> Rating {
+        Rating {
+            rating,
+            deviation,
+            ..Rating::default()
+        }
+    }
+
+    #[test]
+    fn update_matches_glickmans_worked_example() {
+        let player = rating(1500.0, 200.0);
+        let results = [
+            GameResult { opponent: rating(1400.0, 30.0), score: 1.0 },
+            GameResult { opponent: rating(1550.0, 100.0), score: 0.0 },
+            GameResult { opponent: rating(1700.0, 300.0), score: 0.0 },
+        ];
+
+        let updated = player.update(&results);
+
+        assert!((updated.rating - 1464.06).abs() < 0.01, "rating {}", updated.rating);
+        assert!((updated.deviation - 151.52).abs() < 0.01, "deviation {}", updated.deviation);
+        assert!((updated.volatility - 0.05999).abs() < 0.00001, "volatility {}", updated.volatility);
+    }
+
+    #[test]
+    fn idle_periods_only_grow_the_deviation() {
+        let player = rating(1720.0, 80.0);
+
+        let idle = player.update(&[]);
+        assert_eq!(idle, player.decayed(1.0));
+        assert_eq!(idle.rating, player.rating);
+        assert_eq!(idle.volatility, player.volatility);
+        assert!(idle.deviation > player.deviation);
+
+        let longer = player.decayed(10.0);
+        assert!(longer.deviation > idle.deviation);
+        assert_eq!(player.decayed(0.0), player);
+        assert_eq!(player.decayed(1_000_000.0).deviation, DEFAULT_DEVIATION);
+    }
+
+    #[test]
+    fn placements_score_neighbours_by_rank() {
+        let results = placement_results(&[1, 2, 2, 4]);
+
+        assert_eq!(results[0], vec![(1, 1.0), (2, 1.0), (3, 1.0)]);
+        assert_eq!(results[1], vec![(0, 0.0), (2, 0.5), (3, 1.0)]);
+        assert_eq!(results[2], vec![(0, 0.0), (1, 0.5), (3, 1.0)]);
+        assert_eq!(results[3], vec![(0, 0.0), (1, 0.0), (2, 0.0)]);
+        assert!(placement_results(&[]).is_empty());
+        assert_eq!(placement_results(&[1]), vec![Vec::new()]);
+    }
+
+    #[test]
+    fn placements_only_pair_nearby_finishers() {
+        let ranks: Vec<i64> = (1..=30).collect();
+        let results = placement_results(&ranks);
+
+        assert_eq!(results[0].len(), PLACEMENT_NEIGHBOURS);
+        assert_eq!(results[15].len(), 2 * PLACEMENT_NEIGHBOURS);
+        assert_eq!(results[15].first(), Some(&(15 - PLACEMENT_NEIGHBOURS, 0.0)));
+        assert_eq!(results[15].last(), Some(&(15 + PLACEMENT_NEIGHBOURS, 1.0)));
+        assert_eq!(results[29].len(), PLACEMENT_NEIGHBOURS);
+    }
+}
@@ -0,0 +1,210 @@
+//! XP rules mapping domain events to XP awards.
+//! Applies per-source daily caps and diminishing returns to repeated actions, and streak multipliers.
+
+/// Seconds in a day, used for holding periods.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Domain event that can earn XP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A buy or sell was executed.
+    TradeExecuted {
+        /// Quantity times fill price, in cents.
+        notional_cents: i64,
+    },
+    /// Part or all of a position was sold.
+    PositionClosed {
+        /// Proceeds after fees minus the cost of the sold quantity, in cents.
+        realized_pnl_cents: i64,
+        /// Cost of the sold quantity at the average purchase price, in cents.
+        cost_cents: i64,
+        /// How long the position had been open, in seconds.
+        held_seconds: i64,
+    },
+    /// The user signed in.
+    DailyLogin,
+}
+
+/// How often an award may be granted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AwardScope {
+    /// Once per user, ever.
+    Once,
+    /// Once per user per day in their time zone.
+    Daily,
+    /// Once per triggering event, e.g. per trade.
+    PerEvent,
+}
+
+/// XP an event earns before daily limits are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XpAward {
+    /// Rule the award falls under, recorded as the XP event source.
+    pub source: &'static str,
+    /// XP before diminishing returns and caps.
+    pub base_xp: u32,
+    /// How often the award may be granted.
+    pub scope: AwardScope,
+}
+
+/// Daily limits of an XP source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XpRule {
+    /// XP event source the rule applies to.
+    pub source: &'static str,
+    /// Maximum XP from this source per day in the user's time zone.
+    pub daily_cap: u32,
+    /// Awards per day granted at full value before returns diminish.
+    pub full_value_per_day: u32,
+    /// Percent of the previous award's value kept by each further award that day.
+    pub decay_percent: u32,
+}
+
+/// Source of the one-off first trade bonus.
+pub const FIRST_TRADE: &str = "first_trade";
+/// Source of XP for trading volume.
+pub const TRADE_VOLUME: &str = "trade_volume";
+/// Source of XP for selling at a profit.
+pub const PROFITABLE_CLOSE: &str = "profitable_close";
+/// Source of XP for signing in.
+pub const DAILY_LOGIN: &str = "daily_login";
+/// Source of XP for holding positions before selling.
+pub const HOLDING_PERIOD: &str = "holding_period";
+
+/// Daily limits per XP source.
+/// Quest rewards are not listed: each quest pays its fixed reward once per period.
+pub const XP_RULES: [XpRule; 5] = [
+    XpRule { source: FIRST_TRADE, daily_cap: 100, full_value_per_day: 1, decay_percent: 0 },
+    XpRule { source: TRADE_VOLUME, daily_cap: 250, full_value_per_day: 10, decay_percent: 50 },
+    XpRule { source: PROFITABLE_CLOSE, daily_cap: 200, full_value_per_day: 5, decay_percent: 50 },
+    XpRule { source: DAILY_LOGIN, daily_cap: 10, full_value_per_day: 1, decay_percent: 0 },
+    XpRule { source: HOLDING_PERIOD, daily_cap: 150, full_value_per_day: 5, decay_percent: 50 },
+];
+
+/// XP per trade by minimum notional in cents, highest tier first.
+const VOLUME_TIERS: [(i64, u32); 5] = [
+    (10_000_000, 50),
+    (1_000_000, 35),
+    (100_000, 20),
+    (10_000, 10),
+    (0, 5),
+];
+
+/// XP per profitable close by minimum realized profit in cents, highest tier first.
+const PROFIT_TIERS: [(i64, u32); 4] = [
+    (500_000, 75),
+    (50_000, 50),
+    (5_000, 30),
+    (1, 15),
+];
+
+/// XP per close by minimum days held, highest tier first.
+const HOLDING_TIERS: [(i64, u32); 3] = [
+    (30, 75),
+    (7, 30),
+    (1, 10),
+];
+
+/// XP for the first trade a user makes.
+const FIRST_TRADE_XP: u32 = 100;
+
+/// XP for the first sign-in of a day.
+const DAILY_LOGIN_XP: u32 = 10;
+
+/// Returns the XP awards an event earns, before daily limits.
+pub fn awards_for_event(event: &GameEvent) -> Vec<XpAward> {
+    let mut awards = Vec::new();
+    match *event {
+        GameEvent::TradeExecuted { notional_cents } => {
+            awards.push(XpAward { source: FIRST_TRADE, base_xp: FIRST_TRADE_XP, scope: AwardScope::Once });
+            if let Some(base_xp) = tier_xp(&VOLUME_TIERS, notional_cents) {
+                awards.push(XpAward { source: TRADE_VOLUME, base_xp, scope: AwardScope::PerEvent });
+            }
+        }
+        GameEvent::PositionClosed { realized_pnl_cents, held_seconds, .. } => {
+            if let Some(base_xp) = tier_xp(&PROFIT_TIERS, realized_pnl_cents) {
+                awards.push(XpAward { source: PROFITABLE_CLOSE, base_xp, scope: AwardScope::PerEvent });
+            }
+            if let Some(base_xp) = tier_xp(&HOLDING_TIERS, held_seconds / SECONDS_PER_DAY) {
+                awards.push(XpAward { source: HOLDING_PERIOD, base_xp, scope: AwardScope::PerEvent });
+            }
+        }
+        GameEvent::DailyLogin => {
+            awards.push(XpAward { source: DAILY_LOGIN, base_xp: DAILY_LOGIN_XP, scope: AwardScope::Daily });
+        }
+    }
+    awards
+}
+
+/// Returns the daily limits of an XP source.
+pub fn xp_rule(source: &str) -> Option<&'static XpRule> {
+    XP_RULES.iter().find(|rule| rule.source == source)
+}
+
+/// Applies diminishing returns and the daily cap to an award.
+/// `awards_today` and `xp_today` describe what the source already granted the user today.
+pub fn limit_award(award: &XpAward, awards_today: u32, xp_today: u32) -> u32 {
+    limit_boosted_award(award, awards_today, xp_today, 100)
+}
+
+/// Applies diminishing returns and the daily cap to an award boosted by a streak multiplier in percent.
+/// The multiplier scales both the award and the daily cap; one-off awards are never boosted.
+pub fn limit_boosted_award(award: &XpAward, awards_today: u32, xp_today: u32, multiplier_percent: u32) -> u32 {
+    let multiplier_percent = match award.scope {
+        AwardScope::Once => 100,
+        _ => multiplier_percent.max(100),
+    };
+    let Some(rule) = xp_rule(award.source) else {
+        return award.base_xp * multiplier_percent / 100;
+    };
+
+    // Each award past the full-value allowance keeps decay_percent of the one before
+    let decays = (awards_today + 1).saturating_sub(rule.full_value_per_day);
+    let mut xp = award.base_xp;
+    for _ in 0..decays {
+        if xp == 0 {
+            break;
+        }
+        xp = xp * rule.decay_percent.min(100) / 100;
+    }
+
+    let daily_cap = rule.daily_cap * multiplier_percent / 100;
+    (xp * multiplier_percent / 100).min(daily_cap.saturating_sub(xp_today))
+}
+
+/// Returns the XP of the highest tier a value reaches.
+fn tier_xp(tiers: &[(i64, u32)], value: i64) -> Option<u32> {
+    tiers
+        .iter()
+        .find(|(minimum, _)| value >= *minimum)
+        .map(|(_, xp)| *xp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_off_awards_are_not_boosted() {
+        let award = XpAward { source: FIRST_TRADE, base_xp: FIRST_TRADE_XP, scope: AwardScope::Once };
+
+        assert_eq!(limit_boosted_award(&award, 0, 0, 200), FIRST_TRADE_XP);
+    }
+
+    #[test]
+    fn streak_multiplier_raises_award_and_cap() {
+        let award = XpAward { source: DAILY_LOGIN, base_xp: DAILY_LOGIN_XP, scope: AwardScope::Daily };
+
+        assert_eq!(limit_boosted_award(&award, 0, 0, 150), 15);
+        assert_eq!(limit_boosted_award(&award, 0, 0, 100), 10);
+    }
+
+    #[test]
+    fn awards_decay_and_stop_at_the_daily_cap() {
+        let award = XpAward { source: TRADE_VOLUME, base_xp: 20, scope: AwardScope::PerEvent };
+
+        assert_eq!(limit_boosted_award(&award, 10, 0, 100), 10);
+        assert_eq!(limit_boosted_award(&award, 0, 240, 100), 10);
+        assert_eq!(limit_boosted_award(&award, 0, 250, 100), 0);
+    }
+}
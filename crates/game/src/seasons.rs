@@ -0,0 +1,35 @@
+//! Season rewards.
+//! Maps final season ranks to reward titles and the XP head start they grant in the next season.
+
+/// Default length of a season, in days.
+pub const DEFAULT_SEASON_DAYS: i64 = 30;
+
+/// Source of XP granted for season finishes.
+pub const SEASON_REWARD_XP_SOURCE: &str = "season_reward";
+
+/// Source of the ledger entries that reset XP at a season rollover.
+pub const SEASON_RESET_XP_SOURCE: &str = "season_reset";
+
+/// Reward for finishing a season at or above a rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonReward {
+    /// Lowest final XP rank that earns the reward.
+    pub max_rank: i64,
+    /// Title shown with the archived standing.
+    pub title: &'static str,
+    /// XP granted at the start of the next season.
+    pub xp_bonus: u32,
+}
+
+/// Season rewards, best first.
+pub const SEASON_REWARDS: [SeasonReward; 4] = [
+    SeasonReward { max_rank: 1, title: "Champion", xp_bonus: 1000 },
+    SeasonReward { max_rank: 3, title: "Podium", xp_bonus: 600 },
+    SeasonReward { max_rank: 10, title: "Top 10", xp_bonus: 300 },
+    SeasonReward { max_rank: 100, title: "Top 100", xp_bonus: 100 },
+];
+
+/// Returns the reward for a final XP rank, if it earns one.
+pub fn season_reward(rank: i64) -> Option<&'static SeasonReward> {
+    SEASON_REWARDS.iter().find(|reward| rank >= 1 && rank <= reward.max_rank)
+}
@@ -0,0 +1,186 @@
+//! Activity streaks.
+//! Counts consecutive active days, spends earned freezes to cover missed days and scales XP for long streaks.
+
+/// Streak days between earned freezes; a freeze is earned every time the streak reaches a multiple.
+pub const FREEZE_EARN_DAYS: i32 = 7;
+
+/// Most freezes a player can hold at once.
+pub const MAX_STREAK_FREEZES: i32 = 2;
+
+/// XP multipliers in percent by minimum streak length in days, longest first.
+pub const STREAK_MULTIPLIERS: [(i32, u32); 4] = [(30, 200), (14, 150), (7, 125), (3, 110)];
+
+/// Player's activity streak. Days are numbered consecutively, e.g. days since the epoch in the
+/// player's time zone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streak {
+    /// Consecutive active days ending on `last_active_day`, counting days covered by freezes.
+    pub current_days: i32,
+    /// Longest streak reached.
+    pub longest_days: i32,
+    /// Last day the player was active.
+    pub last_active_day: Option<i64>,
+    /// Freezes held.
+    pub freezes: i32,
+}
+
+/// Result of recording a day of activity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreakUpdate {
+    /// Streak after the activity.
+    pub streak: Streak,
+    /// Whether this was the first activity of the day.
+    pub new_day: bool,
+    /// Freezes spent covering missed days.
+    pub freezes_used: i32,
+    /// Freezes earned by reaching a milestone.
+    pub freezes_earned: i32,
+    /// Whether missed days ended the previous streak.
+    pub broken: bool,
+}
+
+impl Streak {
+    /// Returns the days missed since the last activity, not counting `today`.
+    pub fn missed_days(&self, today: i64) -> i64 {
+        self.last_active_day
+            .map(|last| (today - last - 1).max(0))
+            .unwrap_or(0)
+    }
+
+    /// Returns whether the streak can still be continued on `today`, spending freezes on missed days.
+    pub fn is_alive(&self, today: i64) -> bool {
+        self.current_days > 0 && self.missed_days(today) <= i64::from(self.freezes)
+    }
+
+    /// Returns the streak length as of `today`: zero once it can no longer be continued.
+    pub fn days_as_of(&self, today: i64) -> i32 {
+        if self.is_alive(today) { self.current_days } else { 0 }
+    }
+
+    /// Records activity on `today`.
+    /// Missed days are covered by freezes when enough are held, otherwise the streak restarts.
+    /// Activity on a day before the last active one, e.g. after a time zone change, is ignored.
+    pub fn record_activity(&self, today: i64) -> StreakUpdate {
+        let unchanged = StreakUpdate {
+            streak: *self,
+            new_day: false,
+            freezes_used: 0,
+            freezes_earned: 0,
+            broken: false,
+        };
+        if self.last_active_day.is_some_and(|last| today <= last) {
+            return unchanged;
+        }
+
+        let missed = self.missed_days(today);
+        let mut streak = *self;
+        let mut freezes_used = 0;
+        let mut broken = false;
+        if self.current_days > 0 && self.is_alive(today) {
+            freezes_used = missed as i32;
+            streak.freezes -= freezes_used;
+            streak.current_days += freezes_used + 1;
+        } else {
+            broken = self.current_days > 0;
+            streak.current_days = 1;
+        }
+        streak.last_active_day = Some(today);
+        streak.longest_days = streak.longest_days.max(streak.current_days);
+
+        // Every multiple of FREEZE_EARN_DAYS passed on the way earns a freeze
+        let milestones = streak.current_days / FREEZE_EARN_DAYS - (streak.current_days - freezes_used - 1) / FREEZE_EARN_DAYS;
+        let freezes_earned = milestones.min(MAX_STREAK_FREEZES - streak.freezes).max(0);
+        streak.freezes += freezes_earned;
+
+        StreakUpdate {
+            streak,
+            new_day: true,
+            freezes_used,
+            freezes_earned,
+            broken,
+        }
+    }
+}
+
+/// Returns the XP multiplier in percent for a streak length; 100 for short streaks.
+pub fn streak_multiplier_percent(streak_days: i32) -> u32 {
+    STREAK_MULTIPLIERS
+        .iter()
+        .find(|(minimum, _)| streak_days >= *minimum)
+        .map(|(_, percent)| *percent)
+        .unwrap_or(100)
+}
+
+/// Returns the next streak length that raises the multiplier, with that multiplier in percent.
+pub fn next_streak_multiplier(streak_days: i32) -> Option<(i32, u32)> {
+    STREAK_MULTIPLIERS
+        .iter()
+        .rev()
+        .find(|(minimum, _)| streak_days < *minimum)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn streak(current_days: i32, last_active_day: i64, freezes: i32) -> Streak {
+        Streak {
+            current_days,
+            longest_days: current_days,
+            last_active_day: Some(last_active_day),
+            freezes,
+        }
+    }
+
+    #[test]
+    fn freezes_cover_missed_days_when_enough_are_held() {
+        let update = streak(5, 10, 2).record_activity(13);
+
+        assert!(update.new_day);
+        assert!(!update.broken);
+        assert_eq!(update.freezes_used, 2);
+        assert_eq!(update.streak.current_days, 8);
+        assert_eq!(update.streak.longest_days, 8);
+        // Day 7 was passed on the way and earns a freeze back
+        assert_eq!(update.freezes_earned, 1);
+        assert_eq!(update.streak.freezes, 1);
+    }
+
+    #[test]
+    fn streak_restarts_when_too_few_freezes_are_held() {
+        let update = streak(5, 10, 1).record_activity(13);
+
+        assert!(update.new_day);
+        assert!(update.broken);
+        assert_eq!(update.freezes_used, 0);
+        assert_eq!(update.streak.current_days, 1);
+        assert_eq!(update.streak.longest_days, 5);
+        assert_eq!(update.streak.freezes, 1);
+        assert_eq!(update.streak.last_active_day, Some(13));
+    }
+
+    #[test]
+    fn activity_on_an_earlier_day_is_ignored() {
+        let before = streak(5, 10, 1);
+
+        for today in [9, 10] {
+            let update = before.record_activity(today);
+            assert!(!update.new_day);
+            assert_eq!(update.streak, before);
+        }
+    }
+
+    #[test]
+    fn milestone_freezes_stop_at_the_maximum() {
+        let update = streak(6, 10, MAX_STREAK_FREEZES).record_activity(11);
+        assert_eq!(update.streak.current_days, FREEZE_EARN_DAYS);
+        assert_eq!(update.freezes_earned, 0);
+        assert_eq!(update.streak.freezes, MAX_STREAK_FREEZES);
+
+        let update = streak(13, 10, MAX_STREAK_FREEZES - 1).record_activity(11);
+        assert_eq!(update.streak.current_days, 2 * FREEZE_EARN_DAYS);
+        assert_eq!(update.freezes_earned, 1);
+        assert_eq!(update.streak.freezes, MAX_STREAK_FREEZES);
+    }
+}
@@ -0,0 +1,133 @@
+//! Tournament phases and prizes.
+//! Tournaments move through registration, trading and finalization; top finishers earn badges and XP.
+
+use std::fmt;
+
+/// Source of XP granted for tournament finishes.
+pub const TOURNAMENT_XP_SOURCE: &str = "tournament";
+
+/// Stage of a tournament's lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TournamentPhase {
+    /// Registration has not opened yet.
+    Upcoming,
+    /// Registration is open and trading has not started.
+    Registration,
+    /// Trading is open. Registration may still be open.
+    Live,
+    /// Trading has ended and the final ranking is pending.
+    Ended,
+    /// The final ranking has been recorded and prizes granted.
+    Finalized,
+}
+
+impl TournamentPhase {
+    /// Returns the phase name shown to clients.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TournamentPhase::Upcoming => "upcoming",
+            TournamentPhase::Registration => "registration",
+            TournamentPhase::Live => "live",
+            TournamentPhase::Ended => "ended",
+            TournamentPhase::Finalized => "finalized",
+        }
+    }
+}
+
+/// Tournament schedule as Unix timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TournamentSchedule {
+    /// When registration opens.
+    pub registration_opens_at: i64,
+    /// When registration closes. May be after trading starts to allow late entries.
+    pub registration_closes_at: i64,
+    /// When trading starts.
+    pub starts_at: i64,
+    /// When trading ends.
+    pub ends_at: i64,
+}
+
+/// Reason a tournament schedule is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// Registration must open before it closes.
+    RegistrationWindow,
+    /// Trading must start before it ends.
+    TradingWindow,
+    /// Registration must open before trading starts and close before trading ends.
+    RegistrationOrder,
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::RegistrationWindow => write!(f, "registration must open before it closes"),
+            ScheduleError::TradingWindow => write!(f, "trading must start before it ends"),
+            ScheduleError::RegistrationOrder => {
+                write!(f, "registration must open before trading starts and close before trading ends")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+impl TournamentSchedule {
+    /// Checks that the windows are in order.
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        if self.registration_opens_at >= self.registration_closes_at {
+            return Err(ScheduleError::RegistrationWindow);
+        }
+        if self.starts_at >= self.ends_at {
+            return Err(ScheduleError::TradingWindow);
+        }
+        if self.registration_opens_at > self.starts_at || self.registration_closes_at > self.ends_at {
+            return Err(ScheduleError::RegistrationOrder);
+        }
+        Ok(())
+    }
+
+    /// Returns the phase at a time. Finalization is tracked separately from the schedule.
+    pub fn phase_at(&self, now: i64, finalized: bool) -> TournamentPhase {
+        if finalized {
+            TournamentPhase::Finalized
+        } else if now >= self.ends_at {
+            TournamentPhase::Ended
+        } else if now >= self.starts_at {
+            TournamentPhase::Live
+        } else if now >= self.registration_opens_at {
+            TournamentPhase::Registration
+        } else {
+            TournamentPhase::Upcoming
+        }
+    }
+
+    /// Returns whether players can register at a time.
+    pub fn is_registration_open(&self, now: i64) -> bool {
+        now >= self.registration_opens_at && now < self.registration_closes_at
+    }
+}
+
+/// Prize for finishing a tournament at or above a rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TournamentPrize {
+    /// Lowest final rank that earns the prize.
+    pub max_rank: i64,
+    /// Badge awarded: "gold", "silver", "bronze" or "finalist".
+    pub badge: &'static str,
+    /// XP granted for the finish.
+    pub xp_bonus: u32,
+}
+
+/// Tournament prizes, best first.
+pub const TOURNAMENT_PRIZES: [TournamentPrize; 4] = [
+    TournamentPrize { max_rank: 1, badge: "gold", xp_bonus: 500 },
+    TournamentPrize { max_rank: 2, badge: "silver", xp_bonus: 300 },
+    TournamentPrize { max_rank: 3, badge: "bronze", xp_bonus: 200 },
+    TournamentPrize { max_rank: 10, badge: "finalist", xp_bonus: 50 },
+];
+
+/// Returns the prize for a final rank, if it earns one.
+pub fn tournament_prize(rank: i64) -> Option<&'static TournamentPrize> {
+    TOURNAMENT_PRIZES.iter().find(|prize| rank >= 1 && rank <= prize.max_rank)
+}
@@ -0,0 +1,34 @@
+-- Trading fee and slippage model for paper trading
+-- Adds per-symbol fee schedules and records fee/slippage line items on each trade
+
+-- Fee and slippage configuration per symbol ('*' is the default schedule)
+CREATE TABLE fee_schedules (
+    symbol VARCHAR(10) PRIMARY KEY,
+    maker_fee_bps INTEGER NOT NULL,              -- Fee for liquidity-adding fills, in basis points
+    taker_fee_bps INTEGER NOT NULL,              -- Fee for liquidity-taking fills, in basis points
+    min_fee_cents BIGINT NOT NULL,               -- Minimum fee charged per trade, in cents
+    slippage_base_bps INTEGER NOT NULL,          -- Slippage applied to every fill, in basis points
+    slippage_impact_bps INTEGER NOT NULL,        -- Extra slippage per slippage_depth_cents of notional
+    slippage_depth_cents BIGINT NOT NULL,        -- Notional at which the full impact applies, in cents
+    max_slippage_bps INTEGER NOT NULL,           -- Upper bound for total slippage, in basis points
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE fee_schedules
+ADD CONSTRAINT check_fee_bps_range CHECK (maker_fee_bps >= 0 AND taker_fee_bps >= 0 AND maker_fee_bps <= 10000 AND taker_fee_bps <= 10000),
+ADD CONSTRAINT check_min_fee_cents_positive CHECK (min_fee_cents >= 0),
+ADD CONSTRAINT check_slippage_bps_range CHECK (slippage_base_bps >= 0 AND slippage_impact_bps >= 0 AND max_slippage_bps >= 0 AND max_slippage_bps <= 10000),
+ADD CONSTRAINT check_slippage_depth_cents_positive CHECK (slippage_depth_cents > 0);
+
+-- Default schedule used when a symbol has no specific entry
+INSERT INTO fee_schedules (symbol, maker_fee_bps, taker_fee_bps, min_fee_cents, slippage_base_bps, slippage_impact_bps, slippage_depth_cents, max_slippage_bps)
+VALUES ('*', 10, 20, 10, 2, 10, 10000000, 100);
+
+-- Fee and slippage line items on every executed trade
+ALTER TABLE trades
+ADD COLUMN reference_price BIGINT NOT NULL DEFAULT 0,   -- Oracle price before slippage, in cents
+ADD COLUMN fee_cents BIGINT NOT NULL DEFAULT 0,         -- Trading fee charged, in cents
+ADD COLUMN slippage_cents BIGINT NOT NULL DEFAULT 0;    -- Cost of slippage versus reference price, in cents
+
+COMMENT ON COLUMN trades.price IS 'Fill price in cents, including slippage';
+COMMENT ON COLUMN trades.total_value IS 'Quantity times fill price in cents, excluding fees';
@@ -0,0 +1,41 @@
+-- Asset catalog with per-symbol trading rules
+-- Every order is validated against the asset's status, lot size and minimum notional
+
+CREATE TABLE assets (
+    symbol VARCHAR(10) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    decimals SMALLINT NOT NULL,                  -- On-chain token decimals
+    tick_size_cents BIGINT NOT NULL,             -- Price increment, in cents
+    lot_size BIGINT NOT NULL,                    -- Quantity increment, in micro units
+    min_notional_cents BIGINT NOT NULL,          -- Minimum order value, in cents
+    status VARCHAR(10) NOT NULL DEFAULT 'active',
+    chain VARCHAR(32),                           -- Chain the asset lives on (e.g. 'ethereum')
+    contract_address VARCHAR(42),                -- Token contract, NULL for native assets
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE assets
+ADD CONSTRAINT check_asset_status CHECK (status IN ('active', 'halted', 'delisted')),
+ADD CONSTRAINT check_asset_decimals_range CHECK (decimals >= 0 AND decimals <= 36),
+ADD CONSTRAINT check_tick_size_cents_positive CHECK (tick_size_cents > 0),
+ADD CONSTRAINT check_lot_size_positive CHECK (lot_size > 0),
+ADD CONSTRAINT check_min_notional_cents_positive CHECK (min_notional_cents >= 0);
+
+-- Index for listing tradable assets
+CREATE INDEX idx_assets_status ON assets(status);
+
+-- Seed the markets served by the simulated oracle
+INSERT INTO assets (symbol, name, decimals, tick_size_cents, lot_size, min_notional_cents, status, chain, contract_address) VALUES
+    ('BTC', 'Bitcoin', 8, 1, 1, 100, 'active', 'bitcoin', NULL),
+    ('ETH', 'Ethereum', 18, 1, 1, 100, 'active', 'ethereum', NULL),
+    ('SOL', 'Solana', 9, 1, 1000, 100, 'active', 'solana', NULL),
+    ('AVAX', 'Avalanche', 18, 1, 1000, 100, 'active', 'avalanche', NULL),
+    ('LINK', 'Chainlink', 18, 1, 10000, 100, 'active', 'ethereum', '0x514910771AF9Ca656af840dff83E8264EcF986CA');
+
+-- Trades and positions must reference a cataloged asset
+ALTER TABLE trades
+ADD CONSTRAINT fk_trades_symbol FOREIGN KEY (symbol) REFERENCES assets(symbol);
+
+ALTER TABLE positions
+ADD CONSTRAINT fk_positions_symbol FOREIGN KEY (symbol) REFERENCES assets(symbol);
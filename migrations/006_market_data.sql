@@ -0,0 +1,37 @@
+-- Market data history for charts
+-- Persists raw oracle ticks and OHLCV candles rolled up from them
+
+-- Raw price updates published by the oracle
+CREATE TABLE price_ticks (
+    id BIGSERIAL PRIMARY KEY,
+    symbol VARCHAR(10) NOT NULL REFERENCES assets(symbol) ON DELETE CASCADE,
+    price_cents BIGINT NOT NULL,                 -- Price in cents
+    observed_at TIMESTAMPTZ NOT NULL
+);
+
+-- Index for per-symbol range scans and latest price lookups
+CREATE INDEX idx_price_ticks_symbol_observed_at ON price_ticks(symbol, observed_at);
+-- Index for rollups and retention cleanup
+CREATE INDEX idx_price_ticks_observed_at ON price_ticks(observed_at);
+
+-- OHLCV candles per symbol and timeframe ('1m', '5m', '1h', '1d')
+CREATE TABLE candles (
+    symbol VARCHAR(10) NOT NULL REFERENCES assets(symbol) ON DELETE CASCADE,
+    timeframe VARCHAR(3) NOT NULL,
+    open_time TIMESTAMPTZ NOT NULL,              -- Start of the candle, aligned to the timeframe
+    open BIGINT NOT NULL,                        -- Prices in cents
+    high BIGINT NOT NULL,
+    low BIGINT NOT NULL,
+    close BIGINT NOT NULL,
+    volume BIGINT NOT NULL DEFAULT 0,            -- Traded quantity in micro units
+    -- Primary key doubles as the index for range queries
+    PRIMARY KEY (symbol, timeframe, open_time)
+);
+
+ALTER TABLE candles
+ADD CONSTRAINT check_candle_timeframe CHECK (timeframe IN ('1m', '5m', '1h', '1d')),
+ADD CONSTRAINT check_candle_prices CHECK (low > 0 AND low <= open AND low <= close AND high >= open AND high >= close),
+ADD CONSTRAINT check_candle_volume_positive CHECK (volume >= 0);
+
+-- Index for 24h ticker aggregation across all symbols
+CREATE INDEX idx_candles_timeframe_open_time ON candles(timeframe, open_time);
@@ -0,0 +1,51 @@
+-- Server-side trading bots for paper accounts
+-- DCA bots buy a fixed amount on an interval; grid bots trade as the price crosses grid lines
+
+CREATE TABLE bots (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    kind VARCHAR(4) NOT NULL,                    -- 'dca' or 'grid'
+    symbol VARCHAR(10) NOT NULL REFERENCES assets(symbol),
+    status VARCHAR(10) NOT NULL DEFAULT 'active',
+    -- DCA settings
+    amount_cents BIGINT,                         -- Amount spent per buy, in cents
+    interval_seconds INTEGER,                    -- Time between buys
+    next_run_at TIMESTAMPTZ,                     -- When the next buy is due
+    -- Grid settings
+    grid_lower_cents BIGINT,                     -- Lowest grid line, in cents
+    grid_upper_cents BIGINT,                     -- Highest grid line, in cents
+    grid_levels SMALLINT,                        -- Number of evenly spaced grid lines
+    grid_quantity BIGINT,                        -- Quantity traded per crossed line, in micro units
+    grid_level SMALLINT,                         -- Grid lines at or below the last seen price
+    last_run_at TIMESTAMPTZ,                     -- When the bot last placed an order
+    last_error TEXT,                             -- Why the last order failed, if it did
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE bots
+ADD CONSTRAINT check_bot_kind CHECK (kind IN ('dca', 'grid')),
+ADD CONSTRAINT check_bot_status CHECK (status IN ('active', 'paused')),
+ADD CONSTRAINT check_dca_settings CHECK (
+    kind <> 'dca' OR (amount_cents > 0 AND interval_seconds >= 60 AND next_run_at IS NOT NULL)
+),
+ADD CONSTRAINT check_grid_settings CHECK (
+    kind <> 'grid' OR (
+        grid_lower_cents > 0 AND grid_upper_cents > grid_lower_cents
+        AND grid_levels >= 2 AND grid_levels <= 100
+        AND grid_quantity > 0
+        AND grid_level >= 0 AND grid_level <= grid_levels
+    )
+);
+
+-- Index for listing a user's bots
+CREATE INDEX idx_bots_user_id ON bots(user_id);
+-- Index for the scheduler's due-bot scan
+CREATE INDEX idx_bots_status_kind_next_run_at ON bots(status, kind, next_run_at);
+
+-- Trades placed by a bot keep a reference to it for per-bot P&L
+ALTER TABLE trades
+ADD COLUMN bot_id UUID REFERENCES bots(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_trades_bot_id ON trades(bot_id) WHERE bot_id IS NOT NULL;
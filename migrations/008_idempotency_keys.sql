@@ -0,0 +1,21 @@
+-- Idempotency keys for mutating requests
+-- Retries with the same Idempotency-Key replay the stored response instead of running twice
+
+CREATE TABLE idempotency_keys (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    key VARCHAR(255) NOT NULL,                   -- Client-supplied Idempotency-Key header
+    request_hash CHAR(64) NOT NULL,              -- SHA-256 of method, path and body
+    response_status SMALLINT,                    -- NULL while the original request is in flight
+    response_body BYTEA,
+    content_type VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    -- Keys are scoped per user
+    PRIMARY KEY (user_id, key)
+);
+
+ALTER TABLE idempotency_keys
+ADD CONSTRAINT check_response_status_range CHECK (response_status IS NULL OR (response_status >= 100 AND response_status <= 599));
+
+-- Index for expiry cleanup
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys(expires_at);
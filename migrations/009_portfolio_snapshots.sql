@@ -0,0 +1,18 @@
+-- Portfolio value history
+-- Periodic snapshots of every user's portfolio value and cash for equity curves
+
+CREATE TABLE portfolio_snapshots (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    captured_at TIMESTAMPTZ NOT NULL,
+    portfolio_value_cents BIGINT NOT NULL,       -- Cash plus positions at oracle prices, in cents
+    cash_balance_cents BIGINT NOT NULL,          -- Cash at the time of the snapshot, in cents
+    -- Primary key doubles as the index for per-user range queries
+    PRIMARY KEY (user_id, captured_at)
+);
+
+ALTER TABLE portfolio_snapshots
+ADD CONSTRAINT check_snapshot_portfolio_value_positive CHECK (portfolio_value_cents >= 0),
+ADD CONSTRAINT check_snapshot_cash_balance_positive CHECK (cash_balance_cents >= 0);
+
+-- Index for cross-user queries such as leaderboards
+CREATE INDEX idx_portfolio_snapshots_captured_at ON portfolio_snapshots(captured_at);
@@ -0,0 +1,23 @@
+-- Scheduled portfolio rebalancing
+-- Stores each user's target weights and how often to rebalance towards them
+
+CREATE TABLE rebalance_schedules (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    symbols VARCHAR(10)[] NOT NULL,              -- Target symbols, parallel to weights_bps
+    weights_bps INTEGER[] NOT NULL,              -- Target weights in basis points; cash gets the rest
+    interval_seconds INTEGER NOT NULL,           -- Time between rebalances
+    drift_threshold_bps INTEGER NOT NULL DEFAULT 0,  -- Skip runs while no weight is further off than this
+    next_run_at TIMESTAMPTZ NOT NULL,
+    last_run_at TIMESTAMPTZ,
+    last_error TEXT,                             -- Why the last run failed, if it did
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE rebalance_schedules
+ADD CONSTRAINT check_rebalance_targets_parallel CHECK (cardinality(symbols) = cardinality(weights_bps)),
+ADD CONSTRAINT check_rebalance_interval_seconds CHECK (interval_seconds >= 3600),
+ADD CONSTRAINT check_rebalance_drift_threshold_bps CHECK (drift_threshold_bps >= 0 AND drift_threshold_bps <= 10000);
+
+-- Index for the scheduler's due scan
+CREATE INDEX idx_rebalance_schedules_next_run_at ON rebalance_schedules(next_run_at);
@@ -0,0 +1,70 @@
+-- Price alerts with delivery to an in-app inbox and user-registered webhooks
+-- Alerts are evaluated on every oracle tick of their symbol
+
+CREATE TABLE alerts (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    symbol VARCHAR(10) NOT NULL REFERENCES assets(symbol),
+    condition VARCHAR(7) NOT NULL,               -- 'above', 'below' or 'percent'
+    target_price_cents BIGINT,                   -- Level crossed by 'above' and 'below' alerts, in cents
+    percent_bps INTEGER,                         -- Move from the reference price that triggers 'percent' alerts
+    reference_price_cents BIGINT,                -- Price 'percent' moves are measured from, in cents
+    recurring BOOLEAN NOT NULL DEFAULT FALSE,    -- Recurring alerts re-arm after triggering, one-shot alerts deactivate
+    armed BOOLEAN NOT NULL DEFAULT TRUE,         -- Level alerts arm once the price is on the near side of the level
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    note VARCHAR(140),                           -- Optional text included in notifications
+    trigger_count INTEGER NOT NULL DEFAULT 0,
+    last_triggered_at TIMESTAMPTZ,
+    last_triggered_price_cents BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE alerts
+ADD CONSTRAINT check_alert_condition CHECK (condition IN ('above', 'below', 'percent')),
+ADD CONSTRAINT check_level_alert_settings CHECK (
+    condition = 'percent' OR target_price_cents > 0
+),
+ADD CONSTRAINT check_percent_alert_settings CHECK (
+    condition <> 'percent' OR (percent_bps > 0 AND reference_price_cents > 0)
+);
+
+-- Index for listing a user's alerts
+CREATE INDEX idx_alerts_user_id ON alerts(user_id);
+-- Index for evaluating the active alerts of a ticking symbol
+CREATE INDEX idx_alerts_active_symbol ON alerts(symbol) WHERE is_active;
+
+-- In-app notification inbox
+CREATE TABLE notifications (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(30) NOT NULL,                   -- What produced the notification, e.g. 'price_alert'
+    title VARCHAR(100) NOT NULL,
+    message TEXT NOT NULL,
+    alert_id UUID REFERENCES alerts(id) ON DELETE SET NULL,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Index for reading a user's inbox newest first
+CREATE INDEX idx_notifications_user_id_created_at ON notifications(user_id, created_at DESC);
+
+-- Endpoints that receive signed notification payloads
+CREATE TABLE webhooks (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    url VARCHAR(2048) NOT NULL,
+    secret CHAR(64) NOT NULL,                    -- HMAC-SHA256 signing key, hex encoded
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,     -- Disabled after repeated delivery failures
+    failure_count INTEGER NOT NULL DEFAULT 0,    -- Consecutive failed deliveries
+    last_delivery_at TIMESTAMPTZ,
+    last_status SMALLINT,                        -- HTTP status of the last delivery, NULL if unreachable
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE webhooks
+ADD CONSTRAINT check_webhook_url CHECK (url LIKE 'http://%' OR url LIKE 'https://%'),
+ADD CONSTRAINT check_failure_count_positive CHECK (failure_count >= 0);
+
+-- Index for loading a user's webhooks
+CREATE INDEX idx_webhooks_user_id ON webhooks(user_id);
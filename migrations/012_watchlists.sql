@@ -0,0 +1,14 @@
+-- Named per-user watchlists of symbols, kept in display order
+
+CREATE TABLE watchlists (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    symbols VARCHAR(10)[] NOT NULL DEFAULT '{}', -- Watched symbols in display order
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+ALTER TABLE watchlists
+ADD CONSTRAINT check_watchlist_size CHECK (COALESCE(array_length(symbols, 1), 0) <= 50);
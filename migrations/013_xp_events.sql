@@ -0,0 +1,36 @@
+-- Append-only ledger of XP changes; users.xp_points is the running total of a user's events
+
+CREATE TABLE xp_events (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source VARCHAR(50) NOT NULL, -- What changed the XP, e.g. "trade", "achievement" or "adjustment"
+    amount INTEGER NOT NULL, -- XP applied; negative for reversals and deductions
+    reference_id VARCHAR(100), -- Entity behind the change, e.g. a trade id
+    idempotency_key VARCHAR(150), -- Events with the same key are applied once per user
+    xp_after INTEGER NOT NULL, -- User's XP total after the event
+    level_after SMALLINT NOT NULL, -- User's level after the event
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, idempotency_key)
+);
+
+CREATE INDEX idx_xp_events_user_created ON xp_events(user_id, created_at DESC);
+
+ALTER TABLE xp_events
+ADD CONSTRAINT check_xp_after_non_negative CHECK (xp_after >= 0);
+
+-- Events are never edited; mistakes are corrected with a reversing event
+CREATE FUNCTION reject_xp_event_update() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'xp_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER xp_events_append_only
+BEFORE UPDATE ON xp_events
+FOR EACH ROW EXECUTE FUNCTION reject_xp_event_update();
+
+-- Open the ledger with each user's existing total so events add up to xp_points
+INSERT INTO xp_events (id, user_id, source, amount, idempotency_key, xp_after, level_after, created_at)
+SELECT gen_random_uuid(), id, 'opening_balance', xp_points, 'opening_balance', xp_points, level, NOW()
+FROM users
+WHERE xp_points > 0;
@@ -0,0 +1,7 @@
+-- Track when each position was opened so holding periods can earn XP
+
+ALTER TABLE positions
+ADD COLUMN opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(); -- Set when the position is created, kept while it stays open
+
+-- Existing positions have no better estimate than their last update
+UPDATE positions SET opened_at = updated_at;
@@ -0,0 +1,28 @@
+-- Running per-user statistics for achievement criteria, and unlocked achievements
+
+CREATE TABLE player_stats (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    trade_count BIGINT NOT NULL DEFAULT 0,
+    volume_cents BIGINT NOT NULL DEFAULT 0,            -- Notional traded, in cents
+    profitable_closes BIGINT NOT NULL DEFAULT 0,       -- Sells that realized a profit
+    realized_pnl_cents BIGINT NOT NULL DEFAULT 0,      -- Profit realized across all sells, in cents
+    best_return_bps BIGINT NOT NULL DEFAULT 0,         -- Best single-sell return on cost, in basis points
+    longest_hold_seconds BIGINT NOT NULL DEFAULT 0,    -- Longest a position was held before selling
+    login_streak_days INTEGER NOT NULL DEFAULT 0,      -- Consecutive days with a sign-in, up to last_login_date
+    longest_login_streak_days INTEGER NOT NULL DEFAULT 0,
+    last_login_date DATE,                              -- UTC day of the latest sign-in
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Start trade statistics from existing history
+INSERT INTO player_stats (user_id, trade_count, volume_cents)
+SELECT user_id, COUNT(*), SUM(total_value)
+FROM trades
+GROUP BY user_id;
+
+CREATE TABLE user_achievements (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    achievement_id VARCHAR(50) NOT NULL, -- Catalog key, e.g. "first_blood"
+    unlocked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, achievement_id)
+);
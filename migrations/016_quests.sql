@@ -0,0 +1,16 @@
+-- Per-user progress and claims of rotating daily and weekly quests
+
+CREATE TABLE quest_progress (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    quest_id VARCHAR(50) NOT NULL,        -- Template key, e.g. "daily_trades_3"
+    period VARCHAR(10) NOT NULL,          -- "daily" or "weekly"
+    period_start TIMESTAMPTZ NOT NULL,    -- Start of the rotation period the quest was active in
+    progress BIGINT NOT NULL DEFAULT 0,   -- Accumulated progress, in the goal's unit
+    claimed_at TIMESTAMPTZ,               -- When the reward was claimed
+    xp_awarded INTEGER,                   -- XP granted on claim, after daily limits
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, quest_id, period_start)
+);
+
+ALTER TABLE quest_progress
+ADD CONSTRAINT check_quest_period CHECK (period IN ('daily', 'weekly'));
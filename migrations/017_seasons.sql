@@ -0,0 +1,46 @@
+-- Seasons scope XP, levels and paper balances; rollovers archive final standings
+
+CREATE TABLE seasons (
+    id UUID PRIMARY KEY,
+    number INTEGER NOT NULL UNIQUE,
+    name VARCHAR(50) NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,                  -- Scheduled rollover time
+    closed_at TIMESTAMPTZ,                         -- When the season rolled over, NULL while current
+    participant_count INTEGER NOT NULL DEFAULT 0,  -- Users in the archived standings
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE seasons
+ADD CONSTRAINT check_season_dates CHECK (ends_at > starts_at);
+
+-- At most one season is current at a time
+CREATE UNIQUE INDEX idx_seasons_current ON seasons ((closed_at IS NULL)) WHERE closed_at IS NULL;
+
+INSERT INTO seasons (id, number, name, starts_at, ends_at)
+SELECT gen_random_uuid(), 1, 'Season 1', COALESCE(MIN(created_at), NOW()), NOW() + INTERVAL '30 days'
+FROM users;
+
+-- Final standings of closed seasons
+CREATE TABLE season_standings (
+    season_id UUID NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    xp_rank INTEGER NOT NULL,              -- Rank by seasonal XP, ties share a rank
+    xp_points INTEGER NOT NULL,
+    level SMALLINT NOT NULL,
+    portfolio_rank INTEGER NOT NULL,       -- Rank by final portfolio value
+    portfolio_value_cents BIGINT NOT NULL, -- Final portfolio value at oracle prices, in cents
+    return_bps BIGINT NOT NULL,            -- Return on the starting balance, in basis points
+    reward_title VARCHAR(30),              -- Season reward earned, e.g. "Champion"
+    reward_xp INTEGER NOT NULL DEFAULT 0,  -- XP granted in the next season for the finish
+    PRIMARY KEY (season_id, user_id)
+);
+
+CREATE INDEX idx_season_standings_rank ON season_standings(season_id, xp_rank);
+
+-- XP events belong to the season they were earned in
+ALTER TABLE xp_events ADD COLUMN season_id UUID REFERENCES seasons(id);
+UPDATE xp_events SET season_id = (SELECT id FROM seasons WHERE number = 1);
+
+COMMENT ON COLUMN users.xp_points IS 'XP earned in the current season (maps to Rust i32, range: 0 to 2,147,483,647)';
+COMMENT ON COLUMN users.level IS 'Level in the current season (maps to Rust i16, range: 1 to 32767)';
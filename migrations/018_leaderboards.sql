@@ -0,0 +1,23 @@
+-- Materialized leaderboards, recomputed periodically so reads are cheap
+
+CREATE TABLE leaderboard_entries (
+    metric VARCHAR(10) NOT NULL,            -- "xp", "roi", "pnl" or "sharpe"
+    period VARCHAR(10) NOT NULL,            -- "week", "season" or "all"
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,                  -- Ties share a rank
+    score DOUBLE PRECISION NOT NULL,        -- XP points, ROI fraction, P&L in cents or Sharpe ratio
+    computed_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (metric, period, user_id)
+);
+
+ALTER TABLE leaderboard_entries
+ADD CONSTRAINT check_leaderboard_metric CHECK (metric IN ('xp', 'roi', 'pnl', 'sharpe'));
+
+ALTER TABLE leaderboard_entries
+ADD CONSTRAINT check_leaderboard_period CHECK (period IN ('week', 'season', 'all'));
+
+CREATE INDEX idx_leaderboard_entries_rank ON leaderboard_entries(metric, period, rank);
+
+-- Windowed XP sums and trade counts
+CREATE INDEX idx_xp_events_created_at ON xp_events(created_at);
+CREATE INDEX idx_trades_user_executed_at ON trades(user_id, executed_at);
@@ -0,0 +1,86 @@
+-- Trading tournaments with isolated paper accounts and final rankings
+
+CREATE TABLE tournaments (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    description TEXT,
+    starting_balance_cents BIGINT NOT NULL,     -- Paper cash every entrant starts with, in cents
+    allowed_symbols VARCHAR(10)[] NOT NULL,     -- Symbols entrants may trade
+    max_participants INTEGER,                   -- NULL for unlimited entries
+    registration_opens_at TIMESTAMPTZ NOT NULL,
+    registration_closes_at TIMESTAMPTZ NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,             -- Trading opens
+    ends_at TIMESTAMPTZ NOT NULL,               -- Trading closes
+    finalized_at TIMESTAMPTZ,                   -- When the final ranking was recorded
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE tournaments
+ADD CONSTRAINT check_tournament_balance CHECK (starting_balance_cents > 0);
+
+ALTER TABLE tournaments
+ADD CONSTRAINT check_tournament_symbols CHECK (cardinality(allowed_symbols) > 0);
+
+ALTER TABLE tournaments
+ADD CONSTRAINT check_tournament_max_participants CHECK (max_participants IS NULL OR max_participants > 0);
+
+ALTER TABLE tournaments
+ADD CONSTRAINT check_tournament_schedule CHECK (
+    registration_opens_at < registration_closes_at
+    AND starts_at < ends_at
+    AND registration_opens_at <= starts_at
+    AND registration_closes_at <= ends_at
+);
+
+CREATE INDEX idx_tournaments_ends_at ON tournaments(ends_at) WHERE finalized_at IS NULL;
+
+-- Entrants and their isolated cash balance; final results are filled in on finalization
+CREATE TABLE tournament_entries (
+    tournament_id UUID NOT NULL REFERENCES tournaments(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    cash_balance_cents BIGINT NOT NULL,
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    final_rank INTEGER,                         -- Rank by final account value, ties share a rank
+    final_value_cents BIGINT,                   -- Final account value at oracle prices, in cents
+    badge VARCHAR(20),                          -- Prize badge, e.g. "gold"
+    xp_awarded INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (tournament_id, user_id)
+);
+
+ALTER TABLE tournament_entries
+ADD CONSTRAINT check_tournament_cash CHECK (cash_balance_cents >= 0);
+
+-- Holdings within a tournament account
+CREATE TABLE tournament_positions (
+    tournament_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    symbol VARCHAR(10) NOT NULL,
+    quantity BIGINT NOT NULL,                   -- Micro units
+    average_price BIGINT NOT NULL,              -- Cents
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tournament_id, user_id, symbol),
+    FOREIGN KEY (tournament_id, user_id) REFERENCES tournament_entries(tournament_id, user_id) ON DELETE CASCADE
+);
+
+ALTER TABLE tournament_positions
+ADD CONSTRAINT check_tournament_position_quantity CHECK (quantity > 0);
+
+-- Fills within a tournament account
+CREATE TABLE tournament_trades (
+    id UUID PRIMARY KEY,
+    tournament_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    symbol VARCHAR(10) NOT NULL,
+    trade_type VARCHAR(4) NOT NULL,
+    quantity BIGINT NOT NULL,                   -- Micro units
+    price BIGINT NOT NULL,                      -- Fill price including slippage, in cents
+    total_value BIGINT NOT NULL,                -- Quantity times price, excluding fees, in cents
+    fee_cents BIGINT NOT NULL,
+    executed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (tournament_id, user_id) REFERENCES tournament_entries(tournament_id, user_id) ON DELETE CASCADE
+);
+
+ALTER TABLE tournament_trades
+ADD CONSTRAINT check_tournament_trade_type CHECK (trade_type IN ('buy', 'sell'));
+
+CREATE INDEX idx_tournament_trades_entry ON tournament_trades(tournament_id, user_id, executed_at DESC);
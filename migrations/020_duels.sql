@@ -0,0 +1,95 @@
+-- Head-to-head trading duels with isolated paper accounts and escrowed XP stakes
+
+CREATE TABLE duels (
+    id UUID PRIMARY KEY,
+    challenger_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    opponent_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status VARCHAR(10) NOT NULL DEFAULT 'pending',
+    starting_balance_cents BIGINT NOT NULL,     -- Paper cash both players start with, in cents
+    allowed_symbols VARCHAR(10)[] NOT NULL,     -- Symbols both players may trade
+    duration_hours INTEGER NOT NULL,            -- Trading time once accepted
+    stake_xp INTEGER NOT NULL DEFAULT 0,        -- XP each player puts up; the winner takes both
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,            -- Challenge expires unanswered after this
+    responded_at TIMESTAMPTZ,                   -- When the opponent accepted or declined
+    starts_at TIMESTAMPTZ,                      -- Trading opens, set on acceptance
+    ends_at TIMESTAMPTZ,                        -- Trading closes, set on acceptance
+    settled_at TIMESTAMPTZ,
+    winner_id UUID REFERENCES users(id) ON DELETE SET NULL, -- NULL for a draw
+    challenger_final_value_cents BIGINT,
+    opponent_final_value_cents BIGINT
+);
+
+ALTER TABLE duels
+ADD CONSTRAINT check_duel_players CHECK (challenger_id <> opponent_id);
+
+ALTER TABLE duels
+ADD CONSTRAINT check_duel_status CHECK (status IN ('pending', 'declined', 'expired', 'active', 'settled'));
+
+ALTER TABLE duels
+ADD CONSTRAINT check_duel_balance CHECK (starting_balance_cents > 0);
+
+ALTER TABLE duels
+ADD CONSTRAINT check_duel_symbols CHECK (cardinality(allowed_symbols) > 0);
+
+ALTER TABLE duels
+ADD CONSTRAINT check_duel_duration CHECK (duration_hours > 0);
+
+ALTER TABLE duels
+ADD CONSTRAINT check_duel_stake CHECK (stake_xp >= 0);
+
+-- At most one open duel between the same two players, whoever challenged
+CREATE UNIQUE INDEX idx_duels_open_pair ON duels (
+    LEAST(challenger_id, opponent_id), GREATEST(challenger_id, opponent_id)
+) WHERE status IN ('pending', 'active');
+
+CREATE INDEX idx_duels_challenger ON duels(challenger_id, created_at DESC);
+CREATE INDEX idx_duels_opponent ON duels(opponent_id, created_at DESC);
+CREATE INDEX idx_duels_pending_expiry ON duels(expires_at) WHERE status = 'pending';
+CREATE INDEX idx_duels_active_end ON duels(ends_at) WHERE status = 'active';
+
+-- Each player's isolated cash balance, created when the duel is accepted
+CREATE TABLE duel_accounts (
+    duel_id UUID NOT NULL REFERENCES duels(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    cash_balance_cents BIGINT NOT NULL,
+    PRIMARY KEY (duel_id, user_id)
+);
+
+ALTER TABLE duel_accounts
+ADD CONSTRAINT check_duel_cash CHECK (cash_balance_cents >= 0);
+
+-- Holdings within a duel account
+CREATE TABLE duel_positions (
+    duel_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    symbol VARCHAR(10) NOT NULL,
+    quantity BIGINT NOT NULL,                   -- Micro units
+    average_price BIGINT NOT NULL,              -- Cents
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (duel_id, user_id, symbol),
+    FOREIGN KEY (duel_id, user_id) REFERENCES duel_accounts(duel_id, user_id) ON DELETE CASCADE
+);
+
+ALTER TABLE duel_positions
+ADD CONSTRAINT check_duel_position_quantity CHECK (quantity > 0);
+
+-- Fills within a duel account
+CREATE TABLE duel_trades (
+    id UUID PRIMARY KEY,
+    duel_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    symbol VARCHAR(10) NOT NULL,
+    trade_type VARCHAR(4) NOT NULL,
+    quantity BIGINT NOT NULL,                   -- Micro units
+    price BIGINT NOT NULL,                      -- Fill price including slippage, in cents
+    total_value BIGINT NOT NULL,                -- Quantity times price, excluding fees, in cents
+    fee_cents BIGINT NOT NULL,
+    executed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (duel_id, user_id) REFERENCES duel_accounts(duel_id, user_id) ON DELETE CASCADE
+);
+
+ALTER TABLE duel_trades
+ADD CONSTRAINT check_duel_trade_type CHECK (trade_type IN ('buy', 'sell'));
+
+CREATE INDEX idx_duel_trades_account ON duel_trades(duel_id, user_id, executed_at DESC);
@@ -0,0 +1,47 @@
+-- Glicko-2 skill ratings from duel outcomes and tournament placements
+
+CREATE TABLE player_ratings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    rating FLOAT8 NOT NULL DEFAULT 1500,
+    deviation FLOAT8 NOT NULL DEFAULT 350,      -- Uncertainty of the rating
+    volatility FLOAT8 NOT NULL DEFAULT 0.06,    -- Expected fluctuation in performance
+    games INTEGER NOT NULL DEFAULT 0,           -- Rated games played
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE player_ratings
+ADD CONSTRAINT check_rating_deviation CHECK (deviation > 0);
+
+ALTER TABLE player_ratings
+ADD CONSTRAINT check_rating_volatility CHECK (volatility > 0);
+
+CREATE INDEX idx_player_ratings_rating ON player_ratings(rating);
+
+-- One row per rating update, so a player's rating history can be charted
+CREATE TABLE rating_events (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source VARCHAR(20) NOT NULL,                -- 'duel' or 'tournament'
+    reference_id UUID NOT NULL,                 -- Duel or tournament that was rated
+    games INTEGER NOT NULL,                     -- Head-to-head results in the update
+    score FLOAT8 NOT NULL,                      -- Points scored: 1 per win, 0.5 per draw
+    expected_score FLOAT8 NOT NULL,             -- Points expected from the ratings beforehand
+    rating_before FLOAT8 NOT NULL,
+    rating_after FLOAT8 NOT NULL,
+    deviation_after FLOAT8 NOT NULL,
+    volatility_after FLOAT8 NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, source, reference_id)
+);
+
+ALTER TABLE rating_events
+ADD CONSTRAINT check_rating_event_source CHECK (source IN ('duel', 'tournament'));
+
+CREATE INDEX idx_rating_events_user ON rating_events(user_id, created_at DESC);
+CREATE INDEX idx_rating_events_created_at ON rating_events(created_at);
+
+-- Rank players by conservative rating on the skill leaderboard
+ALTER TABLE leaderboard_entries DROP CONSTRAINT IF EXISTS check_leaderboard_metric;
+
+ALTER TABLE leaderboard_entries
+ADD CONSTRAINT check_leaderboard_metric CHECK (metric IN ('xp', 'roi', 'pnl', 'sharpe', 'skill'));
@@ -0,0 +1,38 @@
+-- Weekly leagues: groups of about thirty players per division with promotion and relegation
+
+CREATE TABLE league_groups (
+    id UUID PRIMARY KEY,
+    week_start TIMESTAMPTZ NOT NULL,            -- Monday 00:00 UTC the league week starts
+    division SMALLINT NOT NULL,                 -- Division tier, 0 being Bronze
+    metric VARCHAR(10) NOT NULL,                -- What members are ranked by: 'xp' or 'return'
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finalized_at TIMESTAMPTZ                    -- Set once promotions and relegations are applied
+);
+
+ALTER TABLE league_groups
+ADD CONSTRAINT check_league_division CHECK (division >= 0);
+
+ALTER TABLE league_groups
+ADD CONSTRAINT check_league_metric CHECK (metric IN ('xp', 'return'));
+
+CREATE INDEX idx_league_groups_week ON league_groups(week_start, division);
+CREATE INDEX idx_league_groups_unfinalized ON league_groups(week_start) WHERE finalized_at IS NULL;
+
+-- Players in a group, one group per player and week
+CREATE TABLE league_members (
+    group_id UUID NOT NULL REFERENCES league_groups(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    week_start TIMESTAMPTZ NOT NULL,            -- Copied from the group to keep one group per week
+    score FLOAT8,                               -- XP or return this week, NULL until active
+    final_rank INTEGER,                         -- Set when the week is finalized
+    outcome VARCHAR(10),                        -- 'promoted', 'stayed' or 'relegated'
+    next_division SMALLINT,                     -- Division the player starts the next week in
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id),
+    UNIQUE (user_id, week_start)
+);
+
+ALTER TABLE league_members
+ADD CONSTRAINT check_league_outcome CHECK (outcome IN ('promoted', 'stayed', 'relegated'));
+
+CREATE INDEX idx_league_members_user ON league_members(user_id, week_start DESC);
@@ -0,0 +1,24 @@
+-- Daily activity streaks with freezes that cover missed days
+
+CREATE TABLE activity_streaks (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    current_days INTEGER NOT NULL DEFAULT 0,    -- Consecutive active days up to last_active_date, including frozen days
+    longest_days INTEGER NOT NULL DEFAULT 0,
+    last_active_date DATE,                      -- Latest day with a sign-in or trade, in the user's time zone
+    freezes INTEGER NOT NULL DEFAULT 0,         -- Freezes held, each covering one missed day
+    freezes_used INTEGER NOT NULL DEFAULT 0,    -- Freezes spent so far
+    time_zone VARCHAR(64) NOT NULL DEFAULT 'UTC', -- IANA time zone days are counted in
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE activity_streaks
+ADD CONSTRAINT check_streak_days CHECK (current_days >= 0 AND longest_days >= current_days);
+
+ALTER TABLE activity_streaks
+ADD CONSTRAINT check_streak_freezes CHECK (freezes >= 0 AND freezes_used >= 0);
+
+-- Start streaks from the existing sign-in streaks
+INSERT INTO activity_streaks (user_id, current_days, longest_days, last_active_date)
+SELECT user_id, login_streak_days, GREATEST(longest_login_streak_days, login_streak_days), last_login_date
+FROM player_stats
+WHERE last_login_date IS NOT NULL;
@@ -0,0 +1,70 @@
+-- Clans: player teams with roles, invite codes, aggregate standings and clan tournament entries
+
+CREATE TABLE clans (
+    id UUID PRIMARY KEY,
+    name VARCHAR(50) NOT NULL,
+    tag VARCHAR(5) NOT NULL,                    -- Short uppercase tag shown next to member names
+    description TEXT,
+    invite_code VARCHAR(16) NOT NULL UNIQUE,    -- Shared by officers to let players join
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE clans
+ADD CONSTRAINT check_clan_tag CHECK (tag ~ '^[A-Z0-9]{2,5}$');
+
+CREATE UNIQUE INDEX idx_clans_name ON clans(LOWER(name));
+CREATE UNIQUE INDEX idx_clans_tag ON clans(tag);
+
+-- Clan members, one clan per player
+CREATE TABLE clan_members (
+    clan_id UUID NOT NULL REFERENCES clans(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL UNIQUE REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(10) NOT NULL,                  -- 'owner', 'officer' or 'member'
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (clan_id, user_id)
+);
+
+ALTER TABLE clan_members
+ADD CONSTRAINT check_clan_role CHECK (role IN ('owner', 'officer', 'member'));
+
+-- Exactly one owner per clan is enforced by the service; at most one by the index
+CREATE UNIQUE INDEX idx_clan_members_owner ON clan_members(clan_id) WHERE role = 'owner';
+
+-- Materialized clan leaderboards, recomputed with the player leaderboards
+CREATE TABLE clan_leaderboard_entries (
+    metric VARCHAR(10) NOT NULL,                -- "xp" or "return"
+    period VARCHAR(10) NOT NULL,                -- "week", "season" or "all"
+    clan_id UUID NOT NULL REFERENCES clans(id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,                      -- Ties share a rank
+    score DOUBLE PRECISION NOT NULL,            -- Total XP points or mean ROI fraction
+    members INTEGER NOT NULL,                   -- Members when computed
+    computed_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (metric, period, clan_id)
+);
+
+ALTER TABLE clan_leaderboard_entries
+ADD CONSTRAINT check_clan_leaderboard_metric CHECK (metric IN ('xp', 'return'));
+
+ALTER TABLE clan_leaderboard_entries
+ADD CONSTRAINT check_clan_leaderboard_period CHECK (period IN ('week', 'season', 'all'));
+
+CREATE INDEX idx_clan_leaderboard_entries_rank ON clan_leaderboard_entries(metric, period, rank);
+
+-- Clans entered into a tournament; clans are ranked by their entrants' mean account value
+CREATE TABLE tournament_clans (
+    tournament_id UUID NOT NULL REFERENCES tournaments(id) ON DELETE CASCADE,
+    clan_id UUID NOT NULL REFERENCES clans(id) ON DELETE CASCADE,
+    entered_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    entered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    final_rank INTEGER,                         -- Set on finalization, ties share a rank
+    final_value_cents BIGINT,                   -- Mean final account value of the clan's entrants, in cents
+    PRIMARY KEY (tournament_id, clan_id)
+);
+
+-- Clan an entrant played for, set when their clan is entered into the tournament
+ALTER TABLE tournament_entries
+ADD COLUMN clan_id UUID REFERENCES clans(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_tournament_entries_clan ON tournament_entries(tournament_id, clan_id) WHERE clan_id IS NOT NULL;
@@ -0,0 +1,8 @@
+-- In-flight leases for idempotency keys
+-- A claimed key whose request never stored a response can be taken over once its lease lapses,
+-- instead of staying "in progress" until the key expires
+
+ALTER TABLE idempotency_keys
+ADD COLUMN locked_until TIMESTAMPTZ;            -- NULL once the response is stored
+
+CREATE INDEX idx_idempotency_keys_locked_until ON idempotency_keys(locked_until) WHERE response_status IS NULL;
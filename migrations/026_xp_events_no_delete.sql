@@ -0,0 +1,21 @@
+-- XP events can no longer be deleted either; the ledger must keep adding up to users.xp_points
+-- Rows removed along with their user (ON DELETE CASCADE) are still allowed
+
+CREATE OR REPLACE FUNCTION reject_xp_event_update() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' AND NOT EXISTS (SELECT 1 FROM users WHERE id = OLD.user_id) THEN
+        RETURN OLD;
+    END IF;
+    RAISE EXCEPTION 'xp_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER xp_events_append_only ON xp_events;
+
+CREATE TRIGGER xp_events_append_only
+BEFORE UPDATE OR DELETE ON xp_events
+FOR EACH ROW EXECUTE FUNCTION reject_xp_event_update();
+
+-- Keyset paging of a user's history orders by (created_at, id)
+DROP INDEX idx_xp_events_user_created;
+CREATE INDEX idx_xp_events_user_created ON xp_events(user_id, created_at DESC, id DESC);
@@ -0,0 +1,22 @@
+-- Outbox of gamification events raised by user actions
+-- Events are stored with the action that raised them and consumed in order by the progression job,
+-- so none are lost when the job falls behind or the server restarts
+
+CREATE TABLE user_events (
+    id BIGSERIAL PRIMARY KEY,                   -- Consumption order
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL,                  -- 'trade_executed', 'position_closed' or 'daily_login'
+    notional_cents BIGINT,                      -- Trade notional, for trade_executed
+    realized_pnl_cents BIGINT,                  -- Realized P&L after fees, for position_closed
+    cost_cents BIGINT,                          -- Cost of the sold quantity, for position_closed
+    held_seconds BIGINT,                        -- How long the position was open, for position_closed
+    reference_id VARCHAR(100),                  -- Entity behind the event, e.g. a trade id
+    occurred_at TIMESTAMPTZ NOT NULL,
+    processed_at TIMESTAMPTZ                    -- Set once the progression job has claimed the event
+);
+
+ALTER TABLE user_events
+ADD CONSTRAINT check_user_event_kind CHECK (kind IN ('trade_executed', 'position_closed', 'daily_login'));
+
+CREATE INDEX idx_user_events_pending ON user_events(id) WHERE processed_at IS NULL;
+CREATE INDEX idx_user_events_processed ON user_events(processed_at) WHERE processed_at IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Sign-in streaks are counted by activity_streaks in each user's time zone
+
+ALTER TABLE player_stats
+DROP COLUMN login_streak_days,
+DROP COLUMN longest_login_streak_days,
+DROP COLUMN last_login_date;
@@ -0,0 +1,6 @@
+-- User events are marked processed in the transaction that applies them instead of when claimed,
+-- and events whose processing failed are retried with backoff
+
+ALTER TABLE user_events
+ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0,     -- Failed processing attempts
+ADD COLUMN retry_at TIMESTAMPTZ;                    -- Earliest retry after a failure
@@ -0,0 +1,6 @@
+-- Claim tokens for idempotency keys
+-- Each claim gets a fresh token, so a request whose key was taken over after its lease lapsed
+-- can no longer renew, complete or overwrite the new holder's claim
+
+ALTER TABLE idempotency_keys
+ADD COLUMN claim_token UUID;                    -- Token of the request currently holding the key
@@ -0,0 +1,22 @@
+-- Level-based trading fee tiers
+-- Moves the fee discounts unlocked by levels into configuration next to the fee schedules
+
+-- Fee tier unlocked at each level ('Bronze' at level 1 is the base tier every user starts in)
+CREATE TABLE fee_tiers (
+    min_level SMALLINT PRIMARY KEY,              -- Lowest level in the tier
+    name VARCHAR(32) NOT NULL UNIQUE,            -- Display name of the tier
+    discount_percent INTEGER NOT NULL,           -- Discount on maker and taker fees, in percent
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE fee_tiers
+ADD CONSTRAINT check_fee_tier_min_level_positive CHECK (min_level >= 1),
+ADD CONSTRAINT check_fee_tier_discount_range CHECK (discount_percent >= 0 AND discount_percent <= 100);
+
+INSERT INTO fee_tiers (min_level, name, discount_percent)
+VALUES
+    (1, 'Bronze', 0),
+    (5, 'Silver', 10),
+    (10, 'Gold', 20),
+    (20, 'Platinum', 30),
+    (40, 'Diamond', 40);